@@ -26,6 +26,9 @@ pub enum BanksClientError {
         logs: Vec<String>,
         units_consumed: u64,
     },
+
+    #[error("evm simulation error: {0}")]
+    EvmSimulationError(String),
 }
 
 impl BanksClientError {
@@ -50,6 +53,7 @@ impl From<BanksClientError> for io::Error {
             BanksClientError::SimulationError { err, .. } => {
                 Self::new(io::ErrorKind::Other, err.to_string())
             }
+            BanksClientError::EvmSimulationError(err) => Self::new(io::ErrorKind::Other, err),
         }
     }
 }
@@ -68,6 +72,9 @@ impl From<BanksClientError> for TransportError {
             }
             BanksClientError::TransactionError(err) => Self::TransactionError(err),
             BanksClientError::SimulationError { err, .. } => Self::TransactionError(err),
+            BanksClientError::EvmSimulationError(err) => {
+                Self::IoError(io::Error::new(io::ErrorKind::Other, err))
+            }
         }
     }
 }
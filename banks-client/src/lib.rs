@@ -7,12 +7,16 @@
 
 pub use {
     crate::error::BanksClientError,
-    solana_banks_interface::{BanksClient as TarpcClient, TransactionStatus},
+    solana_banks_interface::{
+        BanksClient as TarpcClient, EvmTransactionSimulationDetails, TransactionStatus,
+    },
 };
 use {
     borsh::BorshDeserialize,
+    evm_state::{AccountState, SignedTransaction, TransactionReceipt, H160, H256, U256},
     futures::{future::join_all, Future, FutureExt, TryFutureExt},
     solana_banks_interface::{BanksRequest, BanksResponse, BanksTransactionResultWithSimulation},
+    solana_evm_loader_program::instructions::FeePayerType,
     solana_program::{
         clock::Slot, fee_calculator::FeeCalculator, hash::Hash, program_pack::Pack, pubkey::Pubkey,
         rent::Rent, sysvar::Sysvar,
@@ -21,7 +25,7 @@ use {
         account::{from_account, Account},
         commitment_config::CommitmentLevel,
         message::Message,
-        signature::Signature,
+        signature::{Keypair, Signature, Signer},
         transaction::{self, Transaction},
     },
     tarpc::{
@@ -149,6 +153,53 @@ impl BanksClient {
             .map_err(Into::into)
     }
 
+    pub fn get_evm_account_with_commitment_and_context(
+        &mut self,
+        ctx: Context,
+        address: H160,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<Option<AccountState>, BanksClientError>> + '_ {
+        self.inner
+            .get_evm_account_with_commitment_and_context(ctx, address, commitment)
+            .map_err(Into::into)
+    }
+
+    pub fn get_evm_storage_with_commitment_and_context(
+        &mut self,
+        ctx: Context,
+        address: H160,
+        index: H256,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<Option<H256>, BanksClientError>> + '_ {
+        self.inner
+            .get_evm_storage_with_commitment_and_context(ctx, address, index, commitment)
+            .map_err(Into::into)
+    }
+
+    pub fn get_evm_receipt_with_commitment_and_context(
+        &mut self,
+        ctx: Context,
+        hash: H256,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<Option<TransactionReceipt>, BanksClientError>> + '_ {
+        self.inner
+            .get_evm_receipt_with_commitment_and_context(ctx, hash, commitment)
+            .map_err(Into::into)
+    }
+
+    pub fn simulate_evm_transaction_with_commitment_and_context(
+        &mut self,
+        ctx: Context,
+        transaction: SignedTransaction,
+        commitment: CommitmentLevel,
+    ) -> impl Future<
+        Output = Result<Result<EvmTransactionSimulationDetails, String>, BanksClientError>,
+    > + '_ {
+        self.inner
+            .simulate_evm_transaction_with_commitment_and_context(ctx, transaction, commitment)
+            .map_err(Into::into)
+    }
+
     /// Send a transaction and return immediately. The server will resend the
     /// transaction until either it is accepted by the cluster or the transaction's
     /// blockhash expires.
@@ -410,6 +461,131 @@ impl BanksClient {
         statuses.into_iter().collect()
     }
 
+    /// Return the evm account at the given address at the slot corresponding to the given
+    /// commitment level. If the account is not found, None is returned.
+    pub fn get_evm_account_with_commitment(
+        &mut self,
+        address: H160,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<Option<AccountState>, BanksClientError>> + '_ {
+        self.get_evm_account_with_commitment_and_context(context::current(), address, commitment)
+    }
+
+    /// Return the evm account at the given address at the time of the most recent root slot.
+    /// If the account is not found, None is returned.
+    pub fn get_evm_account(
+        &mut self,
+        address: H160,
+    ) -> impl Future<Output = Result<Option<AccountState>, BanksClientError>> + '_ {
+        self.get_evm_account_with_commitment(address, CommitmentLevel::default())
+    }
+
+    /// Return the balance in wei of an evm account at the given address at the time
+    /// of the most recent root slot.
+    pub fn get_evm_balance(
+        &mut self,
+        address: H160,
+    ) -> impl Future<Output = Result<U256, BanksClientError>> + '_ {
+        self.get_evm_account(address)
+            .map(|result| Ok(result?.map(|x| x.balance).unwrap_or_default()))
+    }
+
+    /// Return the nonce of an evm account at the given address at the time
+    /// of the most recent root slot.
+    pub fn get_evm_nonce(
+        &mut self,
+        address: H160,
+    ) -> impl Future<Output = Result<U256, BanksClientError>> + '_ {
+        self.get_evm_account(address)
+            .map(|result| Ok(result?.map(|x| x.nonce).unwrap_or_default()))
+    }
+
+    /// Return the contract code of an evm account at the given address at the time
+    /// of the most recent root slot.
+    pub fn get_evm_code(
+        &mut self,
+        address: H160,
+    ) -> impl Future<Output = Result<Vec<u8>, BanksClientError>> + '_ {
+        self.get_evm_account(address)
+            .map(|result| Ok(result?.map(|x| x.code.into()).unwrap_or_default()))
+    }
+
+    /// Return the value of evm contract storage at the given index at the time
+    /// of the most recent root slot.
+    pub fn get_evm_storage(
+        &mut self,
+        address: H160,
+        index: H256,
+    ) -> impl Future<Output = Result<H256, BanksClientError>> + '_ {
+        self.get_evm_storage_with_commitment_and_context(
+            context::current(),
+            address,
+            index,
+            CommitmentLevel::default(),
+        )
+        .map(|result| Ok(result?.unwrap_or_default()))
+    }
+
+    /// Return the receipt of an evm transaction with the given hash. Return None if the
+    /// transaction is not found, receipts are only kept for blocks, that are not rooted yet.
+    pub fn get_evm_receipt(
+        &mut self,
+        hash: H256,
+    ) -> impl Future<Output = Result<Option<TransactionReceipt>, BanksClientError>> + '_ {
+        self.get_evm_receipt_with_commitment_and_context(
+            context::current(),
+            hash,
+            CommitmentLevel::default(),
+        )
+    }
+
+    /// Execute an evm transaction on top of the most recent root slot, without committing it.
+    pub fn simulate_evm_transaction(
+        &mut self,
+        transaction: SignedTransaction,
+    ) -> impl Future<Output = Result<EvmTransactionSimulationDetails, BanksClientError>> + '_ {
+        self.simulate_evm_transaction_with_commitment_and_context(
+            context::current(),
+            transaction,
+            CommitmentLevel::default(),
+        )
+        .map(|result| result?.map_err(BanksClientError::EvmSimulationError))
+    }
+
+    /// Wrap an evm transaction into a native transaction, signed and paid by `payer`,
+    /// and return its hash after the transaction has been finalized or rejected.
+    /// Evm fee is paid by the sender of evm transaction, and collected by `payer`.
+    pub async fn process_evm_transaction(
+        &mut self,
+        payer: &Keypair,
+        transaction: SignedTransaction,
+    ) -> Result<H256, BanksClientError> {
+        let hash = transaction.tx_id_hash();
+        let instruction = match transaction {
+            SignedTransaction::Legacy(tx) => solana_evm_loader_program::send_raw_tx(
+                payer.pubkey(),
+                tx,
+                Some(payer.pubkey()),
+                FeePayerType::Evm,
+            ),
+            SignedTransaction::Typed(tx) => solana_evm_loader_program::send_raw_typed_tx(
+                payer.pubkey(),
+                tx,
+                Some(payer.pubkey()),
+                FeePayerType::Evm,
+            ),
+        };
+        let recent_blockhash = self.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[payer],
+            recent_blockhash,
+        );
+        self.process_transaction(transaction).await?;
+        Ok(hash)
+    }
+
     pub fn get_latest_blockhash(
         &mut self,
     ) -> impl Future<Output = Result<Hash, BanksClientError>> + '_ {
@@ -1,6 +1,7 @@
 #![allow(deprecated)]
 
 use {
+    evm_state::{AccountState, SignedTransaction, TransactionReceipt, H160, H256},
     serde::{Deserialize, Serialize},
     solana_sdk::{
         account::Account,
@@ -43,6 +44,15 @@ pub struct BanksTransactionResultWithSimulation {
     pub simulation_details: Option<TransactionSimulationDetails>,
 }
 
+/// Result of evm transaction, that was executed without committing its changes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EvmTransactionSimulationDetails {
+    pub exit_reason: evm_state::ExitReason,
+    pub exit_data: Vec<u8>,
+    pub used_gas: u64,
+    pub logs: Vec<evm_state::Log>,
+}
+
 #[tarpc::service]
 pub trait Banks {
     async fn send_transaction_with_context(transaction: Transaction);
@@ -77,6 +87,25 @@ pub trait Banks {
         commitment: CommitmentLevel,
         message: Message,
     ) -> Option<u64>;
+    async fn get_evm_account_with_commitment_and_context(
+        address: H160,
+        commitment: CommitmentLevel,
+    ) -> Option<AccountState>;
+    async fn get_evm_storage_with_commitment_and_context(
+        address: H160,
+        index: H256,
+        commitment: CommitmentLevel,
+    ) -> Option<H256>;
+    /// Only receipts of blocks, that are not rooted yet, are available; use rpc for older ones.
+    async fn get_evm_receipt_with_commitment_and_context(
+        hash: H256,
+        commitment: CommitmentLevel,
+    ) -> Option<TransactionReceipt>;
+    /// Returns error message, if transaction cannot be executed (e.g. invalid nonce or signature).
+    async fn simulate_evm_transaction_with_commitment_and_context(
+        transaction: SignedTransaction,
+        commitment: CommitmentLevel,
+    ) -> Result<EvmTransactionSimulationDetails, String>;
 }
 
 #[cfg(test)]
@@ -1,24 +1,33 @@
 use {
     bincode::{deserialize, serialize},
     crossbeam_channel::{unbounded, Receiver, Sender},
+    evm_state::{
+        AccountProvider, AccountState, ChainContext, EvmConfig, EvmState, Executor,
+        SignedTransaction, TransactionReceipt, H160, H256,
+    },
     futures::{future, prelude::stream::StreamExt},
     solana_banks_interface::{
         Banks, BanksRequest, BanksResponse, BanksTransactionResultWithSimulation,
-        TransactionConfirmationStatus, TransactionSimulationDetails, TransactionStatus,
+        EvmTransactionSimulationDetails, TransactionConfirmationStatus,
+        TransactionSimulationDetails, TransactionStatus,
     },
     solana_client::connection_cache::ConnectionCache,
+    solana_evm_loader_program::precompiles::{
+        simulation_entrypoint, NativeFeatures, PrecompileSet,
+    },
     solana_runtime::{
         bank::{Bank, TransactionSimulationResult},
         bank_forks::BankForks,
         commitment::BlockCommitmentCache,
     },
     solana_sdk::{
-        account::Account,
+        account::{Account, ReadableAccount},
         clock::Slot,
         commitment_config::CommitmentLevel,
         feature_set::FeatureSet,
         fee_calculator::FeeCalculator,
         hash::Hash,
+        keyed_account::KeyedAccount,
         message::{Message, SanitizedMessage},
         pubkey::Pubkey,
         signature::Signature,
@@ -29,6 +38,7 @@ use {
         tpu_info::NullTpuInfo,
     },
     std::{
+        cell::RefCell,
         convert::TryFrom,
         io,
         net::{Ipv4Addr, SocketAddr},
@@ -158,6 +168,72 @@ fn verify_transaction(
     Ok(())
 }
 
+/// Find receipt of evm transaction, that was executed in `bank` or one of its parents.
+/// Find receipt in `bank` and its unrooted parents.
+/// Rooted banks are squashed, and their receipts are kept only in blockstore,
+/// which is not available to banks server.
+fn find_evm_receipt(bank: &Arc<Bank>, hash: H256) -> Option<TransactionReceipt> {
+    std::iter::once(bank.clone())
+        .chain(bank.parents())
+        .find_map(|bank| match &*bank.evm_state.read().unwrap() {
+            EvmState::Incomming(incomming) => incomming.find_transaction_receipt(hash).cloned(),
+            EvmState::Committed(committed) => committed
+                .state
+                .committed_transactions
+                .iter()
+                .find(|(tx_hash, _)| *tx_hash == hash)
+                .map(|(_, receipt)| receipt.clone()),
+        })
+}
+
+/// Execute evm transaction on top of `bank` state, without committing its changes.
+fn simulate_evm_transaction(
+    bank: &Bank,
+    transaction: SignedTransaction,
+) -> Result<EvmTransactionSimulationDetails, String> {
+    let evm_state = match bank.evm_state.read().unwrap().clone() {
+        EvmState::Incomming(incomming) => incomming,
+        EvmState::Committed(committed) => {
+            committed.next_incomming(bank.clock().unix_timestamp as u64)
+        }
+    };
+    let mut executor = Executor::with_config(
+        evm_state,
+        ChainContext::new(bank.evm_hashes()),
+        EvmConfig::new(bank.evm_chain_id, bank.evm_burn_fee_activated()),
+        bank.evm_executor_feature_set(),
+    );
+
+    let evm_state_balance = bank
+        .get_account(&solana_sdk::evm_state::id())
+        .unwrap_or_default()
+        .lamports();
+    // Simulation does not have access to native accounts, so only immutable precompiles are available.
+    let evm_account = RefCell::new(solana_evm_loader_program::create_state_account(
+        evm_state_balance,
+    ));
+    let evm_keyed_account = KeyedAccount::new(&solana_sdk::evm_state::ID, false, &evm_account);
+    let precompiles = simulation_entrypoint(
+        PrecompileSet::from_feature_set(executor.support_precompile(), &bank.feature_set),
+        NativeFeatures::from_feature_set(&bank.feature_set),
+        &evm_keyed_account,
+        &[],
+    );
+
+    let result = match transaction {
+        SignedTransaction::Legacy(tx) => executor.transaction_execute(tx, true, precompiles),
+        SignedTransaction::Typed(tx) => executor.transaction_execute_typed(tx, true, precompiles),
+    }
+    .map_err(|e| e.to_string())?;
+
+    Ok(EvmTransactionSimulationDetails {
+        exit_reason: result.exit_reason,
+        exit_data: result.exit_data,
+        used_gas: result.used_gas,
+        logs: result.tx_logs,
+    })
+}
+
 #[tarpc::server]
 impl Banks for BanksServer {
     async fn send_transaction_with_context(self, _: Context, transaction: Transaction) {
@@ -349,6 +425,47 @@ impl Banks for BanksServer {
         let sanitized_message = SanitizedMessage::try_from(message).ok()?;
         bank.get_fee_for_message(&sanitized_message)
     }
+
+    async fn get_evm_account_with_commitment_and_context(
+        self,
+        _: Context,
+        address: H160,
+        commitment: CommitmentLevel,
+    ) -> Option<AccountState> {
+        let bank = self.bank(commitment);
+        let evm_state = bank.evm_state.read().unwrap();
+        evm_state.get_account_state(address)
+    }
+
+    async fn get_evm_storage_with_commitment_and_context(
+        self,
+        _: Context,
+        address: H160,
+        index: H256,
+        commitment: CommitmentLevel,
+    ) -> Option<H256> {
+        let bank = self.bank(commitment);
+        let evm_state = bank.evm_state.read().unwrap();
+        evm_state.get_storage(address, index)
+    }
+
+    async fn get_evm_receipt_with_commitment_and_context(
+        self,
+        _: Context,
+        hash: H256,
+        commitment: CommitmentLevel,
+    ) -> Option<TransactionReceipt> {
+        find_evm_receipt(&self.bank(commitment), hash)
+    }
+
+    async fn simulate_evm_transaction_with_commitment_and_context(
+        self,
+        _: Context,
+        transaction: SignedTransaction,
+        commitment: CommitmentLevel,
+    ) -> Result<EvmTransactionSimulationDetails, String> {
+        simulate_evm_transaction(&self.bank(commitment), transaction)
+    }
 }
 
 pub async fn start_local_server(
@@ -5363,6 +5363,11 @@ impl RpcClient {
             .await
     }
 
+    pub async fn get_evm_code(&self, address: &evm_state::Address) -> ClientResult<evm_rpc::Bytes> {
+        self.send(RpcRequest::EthGetCode, json!([*address, "latest"]))
+            .await
+    }
+
     pub async fn get_evm_transaction_receipt(
         &self,
         hash: &evm_state::H256,
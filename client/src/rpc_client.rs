@@ -4074,6 +4074,10 @@ impl RpcClient {
         self.invoke(self.rpc_client.get_evm_balance(address))
     }
 
+    pub fn get_evm_code(&self, address: &evm_state::Address) -> ClientResult<evm_rpc::Bytes> {
+        self.invoke(self.rpc_client.get_evm_code(address))
+    }
+
     pub fn get_evm_transaction_receipt(
         &self,
         hash: &evm_state::H256,
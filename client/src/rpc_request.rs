@@ -133,6 +133,8 @@ pub enum RpcRequest {
     EthEstimateGas,
     EthGetLogs,
     EthSyncing,
+    EthGasPrice,
+    EthMaxPriorityFeePerGas,
     EthTraceCall,
     EthTraceCallMany,
     EthTraceReplayTransaction,
@@ -240,6 +242,8 @@ impl fmt::Display for RpcRequest {
             RpcRequest::EthEstimateGas => "eth_estimateGas",
             RpcRequest::EthGetLogs => "eth_getLogs",
             RpcRequest::EthSyncing => "eth_syncing",
+            RpcRequest::EthGasPrice => "eth_gasPrice",
+            RpcRequest::EthMaxPriorityFeePerGas => "eth_maxPriorityFeePerGas",
             RpcRequest::GetVelasAccountsByOperationalKey => "getVelasAccountsByOperationalKey",
             RpcRequest::GetVelasAccountsByOwnerKey => "getVelasAccountsByOwnerKey",
             RpcRequest::GetVelasRelyingPartiesByOwnerKey => "getVelasRelyingPartiesByOwnerKey",
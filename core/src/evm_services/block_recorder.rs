@@ -47,8 +47,11 @@ impl EvmRecorderService {
         blockstore: &Arc<Blockstore>,
     ) -> Result<(), RecvTimeoutError> {
         let block = evm_records_receiver.recv_timeout(Duration::from_secs(1))?;
+        debug!("Writing evm block num = {}", block.header.block_number);
+        blockstore
+            .write_evm_logs_index(&block)
+            .expect("Expected database write to succed");
         let block_header = block.header;
-        debug!("Writing evm block num = {}", block_header.block_number);
         blockstore
             .write_evm_block_header(&block_header)
             .expect("Expected database write to succed");
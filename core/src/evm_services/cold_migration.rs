@@ -0,0 +1,92 @@
+//! The `cold_migration` service moves states of evm archive, that are older than hot window,
+//! into the cold tier of archive.
+
+use {
+    evm_state::Storage,
+    solana_measure::measure::Measure,
+    solana_runtime::bank_forks::BankForks,
+    solana_sdk::clock::Slot,
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+/// Two days of slots, at 400ms per slot.
+pub const DEFAULT_EVM_ARCHIVE_HOT_SLOTS: Slot = 432_000;
+
+const MIGRATION_INTERVAL: Duration = Duration::from_secs(60);
+const EXIT_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+/// Maximum number of slots, that are migrated between checks of exit signal.
+const MAX_SLOTS_PER_BATCH: usize = 1_000;
+
+pub struct EvmColdMigrationService {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl EvmColdMigrationService {
+    pub fn new(
+        archive: Storage,
+        bank_forks: Arc<RwLock<BankForks>>,
+        hot_slots: Slot,
+        exit: &Arc<AtomicBool>,
+    ) -> Self {
+        let exit = exit.clone();
+        let thread_hdl = Builder::new()
+            .name("evm-cold-migration".to_string())
+            .spawn(move || {
+                let mut last_migration: Option<Instant> = None;
+                loop {
+                    if exit.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    if last_migration.map_or(true, |at| at.elapsed() >= MIGRATION_INTERVAL) {
+                        let root = bank_forks.read().unwrap().root();
+                        Self::migrate(&archive, root.saturating_sub(hot_slots), &exit);
+                        last_migration = Some(Instant::now());
+                    }
+                    thread::sleep(EXIT_CHECK_INTERVAL);
+                }
+            })
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    fn migrate(archive: &Storage, before_slot: Slot, exit: &AtomicBool) {
+        while !exit.load(Ordering::Relaxed) {
+            let mut migrate_time = Measure::start("evm_cold_migration");
+            let stats = match archive.migrate_to_cold(before_slot, MAX_SLOTS_PER_BATCH) {
+                Ok(stats) => stats,
+                Err(e) => {
+                    error!(
+                        "Cannot migrate evm archive before slot: {}, error: {}",
+                        before_slot, e
+                    );
+                    return;
+                }
+            };
+            migrate_time.stop();
+
+            if stats.slots == 0 {
+                return;
+            }
+            datapoint_info!(
+                "evm-cold-migration",
+                ("before_slot", before_slot, i64),
+                ("slots", stats.slots, i64),
+                ("roots", stats.roots, i64),
+                ("nodes", stats.nodes, i64),
+                ("bytes", stats.bytes, i64),
+                ("migrate_us", migrate_time.as_us(), i64),
+            );
+        }
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
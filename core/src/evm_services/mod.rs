@@ -1,5 +1,9 @@
 pub mod block_recorder;
+pub mod cold_migration;
+pub mod pruning_service;
 pub mod state_recorder;
 // pub use transaction_recorder;
 pub use block_recorder::*;
+pub use cold_migration::*;
+pub use pruning_service::*;
 pub use state_recorder::*;
@@ -0,0 +1,202 @@
+//! The `pruning_service` keeps evm state of rooted slots for bounded retention window,
+//! and purges states that become older than that window.
+//! It also removes roots, that were kept by readers after purge, once readers unpin them.
+//!
+//! Retained slots are tracked only in memory, so after restart retention window starts from zero.
+
+use {
+    evm_state::{AccountProvider, Storage},
+    solana_measure::measure::Measure,
+    solana_runtime::bank_forks::BankForks,
+    solana_sdk::clock::Slot,
+    std::{
+        collections::VecDeque,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+const PRUNING_INTERVAL: Duration = Duration::from_millis(1000);
+
+pub struct EvmPruningService {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl EvmPruningService {
+    /// Enables history of rooted states in evm storage, and keeps last `retention_slots` of it.
+    pub fn new(
+        bank_forks: Arc<RwLock<BankForks>>,
+        retention_slots: Slot,
+        exit: &Arc<AtomicBool>,
+    ) -> Self {
+        let storage = bank_forks
+            .read()
+            .unwrap()
+            .root_bank()
+            .evm_state
+            .read()
+            .unwrap()
+            .kvs()
+            .clone();
+        if !storage.gc_enabled() {
+            warn!("Evm state storage has no gc enabled, evm history is not bounded");
+        }
+        storage.history().enable();
+
+        let exit = exit.clone();
+        let thread_hdl = Builder::new()
+            .name("evm-pruning".to_string())
+            .spawn(move || {
+                let mut rooted_blocks = VecDeque::new();
+                loop {
+                    if exit.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    Self::remove_released_roots(&storage);
+                    Self::prune(&bank_forks, &storage, retention_slots, &mut rooted_blocks);
+                    thread::sleep(PRUNING_INTERVAL);
+                }
+            })
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    fn remove_released_roots(storage: &Storage) {
+        match storage.remove_released_roots() {
+            Ok(0) => {}
+            Ok(removed_roots) => {
+                datapoint_info!(
+                    "evm-pruning-service-released",
+                    ("removed_roots", removed_roots, i64),
+                );
+            }
+            Err(e) => error!("Cannot remove unpinned evm state roots, error: {}", e),
+        }
+    }
+
+    /// Purge states of slots, older than `retention_slots` before current root.
+    ///
+    /// `rooted_blocks` tracks evm block numbers of observed roots,
+    /// to report oldest evm block whose state is still available.
+    fn prune(
+        bank_forks: &RwLock<BankForks>,
+        storage: &Storage,
+        retention_slots: Slot,
+        rooted_blocks: &mut VecDeque<(Slot, u64)>,
+    ) {
+        let root_bank = bank_forks.read().unwrap().root_bank();
+        let root = root_bank.slot();
+        if rooted_blocks.back().map(|(slot, _)| *slot) == Some(root) {
+            return;
+        }
+        let block = root_bank.evm_state.read().unwrap().block_number();
+        drop(root_bank);
+        rooted_blocks.push_back((root, block));
+
+        let mut purge_time = Measure::start("evm_pruning_purge");
+        let cutoff = root.saturating_sub(retention_slots);
+        let (purged_slots, removed_roots) = match storage.purge_retained_slots(cutoff) {
+            Ok(result) => result,
+            Err(e) => {
+                error!(
+                    "Cannot purge evm state before slot: {}, error: {}",
+                    cutoff, e
+                );
+                (0, 0)
+            }
+        };
+        purge_time.stop();
+
+        while rooted_blocks.len() > 1 && rooted_blocks[0].0 < cutoff {
+            rooted_blocks.pop_front();
+        }
+        let oldest_block = rooted_blocks[0].1;
+        storage.history().set_oldest_block(oldest_block);
+
+        datapoint_info!(
+            "evm-pruning-service",
+            ("root", root, i64),
+            ("cutoff_slot", cutoff, i64),
+            ("oldest_block", oldest_block, i64),
+            ("purged_slots", purged_slots, i64),
+            ("removed_roots", removed_roots, i64),
+            ("retained_slots", storage.history().retained_slots(), i64),
+            ("purge_us", purge_time.as_us(), i64),
+        );
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_runtime::{
+            accounts_background_service::AbsRequestSender, bank::Bank,
+            genesis_utils::create_genesis_config,
+        },
+        solana_sdk::pubkey::Pubkey,
+    };
+
+    fn set_root(bank_forks: &RwLock<BankForks>, slot: Slot) {
+        let mut bank_forks = bank_forks.write().unwrap();
+        let parent = bank_forks.get(slot - 1).unwrap();
+        bank_forks.insert(Bank::new_from_parent(&parent, &Pubkey::default(), slot));
+        bank_forks.set_root(slot, &AbsRequestSender::default(), None);
+    }
+
+    #[test]
+    fn prune_slots_behind_retention_window() {
+        let genesis_config_info = create_genesis_config(10_000);
+        let bank0 = Bank::new_for_tests(&genesis_config_info.genesis_config);
+        let bank_forks = RwLock::new(BankForks::new(bank0));
+        let storage = bank_forks
+            .read()
+            .unwrap()
+            .root_bank()
+            .evm_state
+            .read()
+            .unwrap()
+            .kvs()
+            .clone();
+        storage.history().enable();
+
+        for slot in 1..=10 {
+            set_root(&bank_forks, slot);
+        }
+        for slot in 0..=10 {
+            storage.history().retain(slot);
+        }
+        let mut rooted_blocks = VecDeque::new();
+        EvmPruningService::prune(&bank_forks, &storage, 3, &mut rooted_blocks);
+
+        assert_eq!(storage.history().retained_slots(), 4);
+        assert!(storage.history().is_pruned(6));
+        assert!(!storage.history().is_pruned(7));
+        let root_block = rooted_blocks[0].1;
+        assert_eq!(storage.history().oldest_block(), Some(root_block));
+
+        // nothing to do, until root is changed
+        EvmPruningService::prune(&bank_forks, &storage, 3, &mut rooted_blocks);
+        assert_eq!(rooted_blocks.len(), 1);
+
+        for slot in 11..=12 {
+            set_root(&bank_forks, slot);
+            storage.history().retain(slot);
+        }
+        EvmPruningService::prune(&bank_forks, &storage, 3, &mut rooted_blocks);
+        assert_eq!(storage.history().retained_slots(), 4);
+        assert!(storage.history().is_pruned(8));
+        assert!(!storage.history().is_pruned(9));
+        // block of slot 10 is still inside retention window
+        assert_eq!(rooted_blocks.len(), 2);
+        assert_eq!(storage.history().oldest_block(), Some(root_block));
+    }
+}
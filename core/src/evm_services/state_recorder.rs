@@ -9,9 +9,10 @@ use std::{
 };
 
 use evm_state::{ChangedState, Storage, H256};
+use solana_sdk::clock::Slot;
 
-pub type EvmStateRecorderReceiver = Receiver<(H256, ChangedState)>;
-pub type EvmStateRecorderSender = Sender<(H256, ChangedState)>;
+pub type EvmStateRecorderReceiver = Receiver<(Slot, H256, ChangedState)>;
+pub type EvmStateRecorderSender = Sender<(Slot, H256, ChangedState)>;
 
 pub struct EvmStateRecorderService {
     thread_hdl: JoinHandle<()>,
@@ -47,7 +48,7 @@ impl EvmStateRecorderService {
     ) -> Result<(), RecvTimeoutError> {
         // TODO: use changed nodes as state_updates, instead of changed accounts (to avoid recalculation of hashes)
 
-        let (state_root, state_updates) =
+        let (slot, state_root, state_updates) =
             evm_records_receiver.recv_timeout(Duration::from_secs(1))?;
         if !storage.check_root_exist(state_root) {
             warn!(
@@ -57,7 +58,14 @@ impl EvmStateRecorderService {
             );
             return Ok(());
         }
-        storage.flush_changes(state_root, state_updates);
+        let new_root = storage.flush_changes(state_root, state_updates);
+        // Tiered archive keeps root until it is migrated to cold tier, noop for archive without gc.
+        if let Err(e) = storage.register_slot(slot, new_root, false) {
+            warn!(
+                "Cannot register archive root, slot:{} root:{}, error:{}",
+                slot, new_root, e
+            );
+        }
 
         Ok(())
     }
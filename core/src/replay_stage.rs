@@ -30,7 +30,10 @@ use {
     crossbeam_channel::{Receiver, RecvTimeoutError, Sender},
     solana_client::rpc_response::SlotUpdate,
     solana_entry::entry::VerifyRecyclers,
-    solana_geyser_plugin_manager::block_metadata_notifier_interface::BlockMetadataNotifierLock,
+    solana_geyser_plugin_manager::{
+        block_metadata_notifier_interface::BlockMetadataNotifierLock,
+        evm_notifier_interface::EvmNotifierLock,
+    },
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
         block_error::BlockError,
@@ -376,6 +379,7 @@ impl ReplayStage {
         voting_sender: Sender<VoteOp>,
         drop_bank_sender: Sender<Vec<Arc<Bank>>>,
         block_metadata_notifier: Option<BlockMetadataNotifierLock>,
+        evm_notifier: Option<EvmNotifierLock>,
         transaction_cost_metrics_sender: Option<TransactionCostMetricsSender>,
     ) -> Self {
         let ReplayStageConfig {
@@ -491,6 +495,7 @@ impl ReplayStage {
                         &mut duplicate_slots_to_repair,
                         &ancestor_hashes_replay_update_sender,
                         block_metadata_notifier.clone(),
+                        evm_notifier.clone(),
                         transaction_cost_metrics_sender.as_ref(),
                         &mut replay_timing,
                     );
@@ -2182,6 +2187,7 @@ impl ReplayStage {
         duplicate_slots_to_repair: &mut DuplicateSlotsToRepair,
         ancestor_hashes_replay_update_sender: &AncestorHashesReplayUpdateSender,
         block_metadata_notifier: Option<BlockMetadataNotifierLock>,
+        evm_notifier: Option<EvmNotifierLock>,
         transaction_cost_metrics_sender: Option<&TransactionCostMetricsSender>,
         replay_timing: &mut ReplayTiming,
     ) -> bool {
@@ -2346,6 +2352,13 @@ impl ReplayStage {
                     evm_block_recorder_sender.as_ref(),
                     evm_state_recorder_sender.as_ref(),
                 );
+                if let Some(ref evm_notifier) = evm_notifier {
+                    if let Some(block) = bank.evm_block() {
+                        let state_change = bank.evm_state_change();
+                        let evm_notifier = evm_notifier.read().unwrap();
+                        evm_notifier.notify_evm_block(bank.slot(), &block, state_change.as_ref());
+                    }
+                }
                 Self::record_rewards(&bank, rewards_recorder_sender);
                 if let Some(ref block_metadata_notifier) = block_metadata_notifier {
                     let block_metadata_notifier = block_metadata_notifier.read().unwrap();
@@ -66,6 +66,10 @@ impl SnapshotPackagerService {
                 if let Some(snapshot_gossip_manager) = snapshot_gossip_manager.as_mut() {
                     snapshot_gossip_manager.push_starting_snapshot_hashes(starting_snapshot_hashes);
                 }
+                // Slot and evm root of the last archived full snapshot, incremental snapshots
+                // on top of it contain only evm state delta, if it is enabled.
+                let evm_state_delta = snapshot_config.incremental_snapshot_evm_state_delta;
+                let mut last_full_snapshot_evm_root: Option<(Slot, evm_state::H256)> = None;
 
                 loop {
                     if exit.load(Ordering::Relaxed) {
@@ -77,7 +81,14 @@ impl SnapshotPackagerService {
                         std::thread::sleep(Duration::from_millis(100));
                         continue;
                     }
-                    let snapshot_package = snapshot_package.unwrap();
+                    let mut snapshot_package = snapshot_package.unwrap();
+                    if let SnapshotType::IncrementalSnapshot(base_slot) =
+                        snapshot_package.snapshot_type
+                    {
+                        snapshot_package.evm_base_root = last_full_snapshot_evm_root
+                            .filter(|(slot, _)| evm_state_delta && *slot == base_slot)
+                            .map(|(_, evm_root)| evm_root);
+                    }
 
                     // Archiving the snapshot package is not allowed to fail.
                     // AccountsBackgroundService calls `clean_accounts()` with a value for
@@ -89,6 +100,11 @@ impl SnapshotPackagerService {
                     )
                     .expect("failed to archive snapshot package");
 
+                    if snapshot_package.snapshot_type == SnapshotType::FullSnapshot {
+                        last_full_snapshot_evm_root =
+                            Some((snapshot_package.slot(), snapshot_package.evm_root));
+                    }
+
                     if let Some(snapshot_gossip_manager) = snapshot_gossip_manager.as_mut() {
                         snapshot_gossip_manager.push_snapshot_hash(
                             snapshot_package.snapshot_type,
@@ -333,6 +349,7 @@ mod tests {
             evm_root: evm_state::empty_trie_hash(),
             evm_db: evm_state::storage::Storage::create_temporary()
                 .expect("Unable to create temporary EVM state storage"),
+            evm_base_root: None,
             bank,
             snapshot_type: SnapshotType::FullSnapshot,
         };
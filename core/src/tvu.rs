@@ -31,7 +31,10 @@ use {
     },
     crossbeam_channel::{bounded, unbounded, Receiver, RecvTimeoutError},
     solana_client::connection_cache::ConnectionCache,
-    solana_geyser_plugin_manager::block_metadata_notifier_interface::BlockMetadataNotifierLock,
+    solana_geyser_plugin_manager::{
+        block_metadata_notifier_interface::BlockMetadataNotifierLock,
+        evm_notifier_interface::EvmNotifierLock,
+    },
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
         blockstore::Blockstore, blockstore_processor::TransactionStatusSender,
@@ -158,6 +161,7 @@ impl Tvu {
         accounts_package_channel: (AccountsPackageSender, AccountsPackageReceiver),
         last_full_snapshot_slot: Option<Slot>,
         block_metadata_notifier: Option<BlockMetadataNotifierLock>,
+        evm_notifier: Option<EvmNotifierLock>,
         wait_to_vote_slot: Option<Slot>,
         pruned_banks_receiver: DroppedSlotsReceiver,
         connection_cache: &Arc<ConnectionCache>,
@@ -350,6 +354,7 @@ impl Tvu {
             voting_sender,
             drop_bank_sender,
             block_metadata_notifier,
+            evm_notifier,
             transaction_cost_metrics_sender,
         );
 
@@ -550,6 +555,7 @@ pub mod tests {
             None,
             None,
             None,
+            None,
             pruned_banks_receiver,
             &Arc::new(ConnectionCache::default()),
         );
@@ -9,7 +9,8 @@ use {
         completed_data_sets_service::CompletedDataSetsService,
         consensus::{reconcile_blockstore_roots_with_tower, Tower},
         evm_services::{
-            EvmRecorderSender, EvmRecorderService, EvmStateRecorderSender, EvmStateRecorderService,
+            EvmColdMigrationService, EvmPruningService, EvmRecorderSender, EvmRecorderService,
+            EvmStateRecorderSender, EvmStateRecorderService, DEFAULT_EVM_ARCHIVE_HOT_SLOTS,
         },
         rewards_recorder_service::{RewardsRecorderSender, RewardsRecorderService},
         sample_performance_service::SamplePerformanceService,
@@ -179,6 +180,10 @@ pub struct ValidatorConfig {
     pub ledger_column_options: LedgerColumnOptions,
     pub enable_quic_servers: bool,
     pub verify_evm_state: bool,
+    /// Number of slots behind root, for which evm state is kept, unbounded by default.
+    pub evm_state_retention_slots: Option<Slot>,
+    /// Number of slots behind root, for which evm archive is kept in hot tier.
+    pub evm_state_archive_hot_slots: Slot,
     pub jaeger_collector_url: Option<String>,
 }
 
@@ -244,6 +249,8 @@ impl Default for ValidatorConfig {
             accounts_shrink_ratio: AccountShrinkThreshold::default(),
             accounts_db_config: None,
             verify_evm_state: false,
+            evm_state_retention_slots: None,
+            evm_state_archive_hot_slots: DEFAULT_EVM_ARCHIVE_HOT_SLOTS,
             jaeger_collector_url: None,
             wait_to_vote_slot: None,
             ledger_column_options: LedgerColumnOptions::default(),
@@ -348,6 +355,8 @@ pub struct Validator {
     sample_performance_service: Option<SamplePerformanceService>,
     evm_block_recorder_service: Option<EvmRecorderService>,
     evm_state_recorder_service: Option<EvmStateRecorderService>,
+    evm_pruning_service: Option<EvmPruningService>,
+    evm_cold_migration_service: Option<EvmColdMigrationService>,
     stats_reporter_service: StatsReporterService,
     gossip_service: GossipService,
     serve_repair_service: ServeRepairService,
@@ -501,6 +510,10 @@ impl Validator {
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_block_metadata_notifier());
 
+        let evm_notifier = geyser_plugin_service
+            .as_ref()
+            .and_then(|geyser_plugin_service| geyser_plugin_service.get_evm_notifier());
+
         info!(
             "Geyser plugin: accounts_update_notifier: {} transaction_notifier: {}",
             accounts_update_notifier.is_some(),
@@ -799,6 +812,22 @@ impl Validator {
             (None, None, None, None, None)
         };
 
+        let evm_pruning_service = config.evm_state_retention_slots.map(|retention_slots| {
+            EvmPruningService::new(bank_forks.clone(), retention_slots, &exit)
+        });
+
+        let evm_cold_migration_service = evm_state_archive
+            .as_ref()
+            .filter(|archive| archive.cold_tier().is_some())
+            .map(|archive| {
+                EvmColdMigrationService::new(
+                    archive.clone(),
+                    bank_forks.clone(),
+                    config.evm_state_archive_hot_slots,
+                    &exit,
+                )
+            });
+
         let evm_state_rpc_service = match (
             config.evm_state_rpc_addr.as_ref(),
             config.evm_state_rpc_config.as_ref(),
@@ -1036,6 +1065,7 @@ impl Validator {
             accounts_package_channel,
             last_full_snapshot_slot,
             block_metadata_notifier,
+            evm_notifier,
             config.wait_to_vote_slot,
             pruned_banks_receiver,
             &connection_cache,
@@ -1100,6 +1130,8 @@ impl Validator {
             completed_data_sets_service,
             evm_block_recorder_service,
             evm_state_recorder_service,
+            evm_pruning_service,
+            evm_cold_migration_service,
             tpu,
             tvu,
             poh_recorder,
@@ -1222,6 +1254,16 @@ impl Validator {
                 .expect("evm_state_recorder_service");
         }
 
+        if let Some(evm_pruning_service) = self.evm_pruning_service {
+            evm_pruning_service.join().expect("evm_pruning_service");
+        }
+
+        if let Some(evm_cold_migration_service) = self.evm_cold_migration_service {
+            evm_cold_migration_service
+                .join()
+                .expect("evm_cold_migration_service");
+        }
+
         if let Some(s) = self.snapshot_packager_service {
             s.join().expect("snapshot_packager_service");
         }
@@ -1,4 +1,7 @@
-use {evm_state::BlockNum, solana_storage_bigtable::DEFAULT_INSTANCE_NAME, std::path::PathBuf};
+use {
+    crate::ledger::LedgerSpec, evm_state::BlockNum, solana_storage_bigtable::DEFAULT_INSTANCE_NAME,
+    std::path::PathBuf,
+};
 
 const DEFAULT_BIGTABLE_LIMIT: &str = "150000";
 
@@ -15,11 +18,40 @@ pub struct Cli {
     #[clap(long, value_name = "STRING", default_value = DEFAULT_INSTANCE_NAME)]
     pub instance: String,
 
+    /// Ledger to use instead of Bigtable Instance: "bigtable:INSTANCE", "blockstore:DIR" or "files:DIR"
+    #[clap(long, value_name = "SPEC")]
+    pub ledger: Option<LedgerSpec>,
+
     /// Enables additional structured output to stdout for use in embedded environment
     #[clap(long, value_name = "BOOL")]
     pub embed: bool,
 }
 
+impl Cli {
+    pub fn ledger_spec(&self) -> LedgerSpec {
+        bigtable_or(&self.ledger, &self.creds, &self.instance)
+    }
+}
+
+/// Bigtable `instance` is used only if `ledger` is not set,
+/// while `creds` are also used by `bigtable:INSTANCE` ledger, that has no credentials in spec.
+fn bigtable_or(ledger: &Option<LedgerSpec>, creds: &Option<String>, instance: &str) -> LedgerSpec {
+    match ledger.clone() {
+        Some(LedgerSpec::Bigtable {
+            creds: None,
+            instance,
+        }) => LedgerSpec::Bigtable {
+            creds: creds.clone(),
+            instance,
+        },
+        Some(ledger) => ledger,
+        None => LedgerSpec::Bigtable {
+            creds: creds.clone(),
+            instance: instance.to_string(),
+        },
+    }
+}
+
 #[derive(clap::Subcommand)]
 pub enum Command {
     /// Finds missing ranges of EVM Blocks
@@ -40,7 +72,7 @@ pub enum Command {
     /// Compares difference of Native Block sets
     CompareNative(CompareNativeArgs),
 
-    /// Uploads blocks to Bigtable from .json file
+    /// Uploads blocks to Ledger from .json file
     Upload(UploadArgs),
 
     /// Copies sequence of EVM Blocks from Source to Destination Ledger
@@ -156,20 +188,46 @@ pub struct CompareNativeArgs {
     pub limit: usize,
 
     /// Google credentials JSON filepath of the "Credible Ledger"
-    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath)]
-    pub credible_ledger_creds: String,
+    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath, required_unless_present = "credible_ledger")]
+    pub credible_ledger_creds: Option<String>,
 
     /// "Credible Ledger" Instance
     #[clap(long, value_name = "STRING", default_value = DEFAULT_INSTANCE_NAME)]
     pub credible_ledger_instance: String,
 
+    /// "Credible Ledger" to use instead of Bigtable Instance
+    #[clap(long, value_name = "SPEC")]
+    pub credible_ledger: Option<LedgerSpec>,
+
     /// Google credentials JSON filepath of the "Deceptive Ledger"
-    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath)]
-    pub dubious_ledger_creds: String,
+    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath, required_unless_present = "dubious_ledger")]
+    pub dubious_ledger_creds: Option<String>,
 
     /// "Deceptive Ledger" Instance
     #[clap(long, value_name = "STRING", default_value = DEFAULT_INSTANCE_NAME)]
     pub dubious_ledger_instance: String,
+
+    /// "Deceptive Ledger" to use instead of Bigtable Instance
+    #[clap(long, value_name = "SPEC")]
+    pub dubious_ledger: Option<LedgerSpec>,
+}
+
+impl CompareNativeArgs {
+    pub fn credible_ledger_spec(&self) -> LedgerSpec {
+        bigtable_or(
+            &self.credible_ledger,
+            &self.credible_ledger_creds,
+            &self.credible_ledger_instance,
+        )
+    }
+
+    pub fn dubious_ledger_spec(&self) -> LedgerSpec {
+        bigtable_or(
+            &self.dubious_ledger,
+            &self.dubious_ledger_creds,
+            &self.dubious_ledger_instance,
+        )
+    }
 }
 
 #[derive(clap::Args)]
@@ -190,20 +248,38 @@ pub struct RepeatEvmArgs {
     pub limit: u64,
 
     /// Google credentials JSON filepath of the Source Ledger
-    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath)]
-    pub src_creds: String,
+    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath, required_unless_present = "src_ledger")]
+    pub src_creds: Option<String>,
 
     /// Source Ledger Instance
     #[clap(long, value_name = "STRING", default_value = DEFAULT_INSTANCE_NAME)]
     pub src_instance: String,
 
+    /// Source Ledger to use instead of Bigtable Instance
+    #[clap(long, value_name = "SPEC")]
+    pub src_ledger: Option<LedgerSpec>,
+
     /// Google credentials JSON filepath of the Destination Ledger
-    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath)]
-    pub dst_creds: String,
+    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath, required_unless_present = "dst_ledger")]
+    pub dst_creds: Option<String>,
 
     /// Destination Ledger Instance
     #[clap(long, value_name = "STRING", default_value = DEFAULT_INSTANCE_NAME)]
     pub dst_instance: String,
+
+    /// Destination Ledger to use instead of Bigtable Instance
+    #[clap(long, value_name = "SPEC")]
+    pub dst_ledger: Option<LedgerSpec>,
+}
+
+impl RepeatEvmArgs {
+    pub fn src_ledger_spec(&self) -> LedgerSpec {
+        bigtable_or(&self.src_ledger, &self.src_creds, &self.src_instance)
+    }
+
+    pub fn dst_ledger_spec(&self) -> LedgerSpec {
+        bigtable_or(&self.dst_ledger, &self.dst_creds, &self.dst_instance)
+    }
 }
 
 #[derive(clap::Args)]
@@ -217,20 +293,38 @@ pub struct RepeatNativeArgs {
     pub end_slot: u64,
 
     /// Google credentials JSON filepath of the Source Ledger
-    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath)]
-    pub src_creds: String,
+    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath, required_unless_present = "src_ledger")]
+    pub src_creds: Option<String>,
 
     /// Source Ledger Instance
     #[clap(long, value_name = "STRING", default_value = DEFAULT_INSTANCE_NAME)]
     pub src_instance: String,
 
+    /// Source Ledger to use instead of Bigtable Instance
+    #[clap(long, value_name = "SPEC")]
+    pub src_ledger: Option<LedgerSpec>,
+
     /// Google credentials JSON filepath of the Destination Ledger
-    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath)]
-    pub dst_creds: String,
+    #[clap(long, value_name = "FILE_PATH", value_hint = clap::ValueHint::FilePath, required_unless_present = "dst_ledger")]
+    pub dst_creds: Option<String>,
 
     /// Destination Ledger Instance
     #[clap(long, value_name = "STRING", default_value = DEFAULT_INSTANCE_NAME)]
     pub dst_instance: String,
+
+    /// Destination Ledger to use instead of Bigtable Instance
+    #[clap(long, value_name = "SPEC")]
+    pub dst_ledger: Option<LedgerSpec>,
+}
+
+impl RepeatNativeArgs {
+    pub fn src_ledger_spec(&self) -> LedgerSpec {
+        bigtable_or(&self.src_ledger, &self.src_creds, &self.src_instance)
+    }
+
+    pub fn dst_ledger_spec(&self) -> LedgerSpec {
+        bigtable_or(&self.dst_ledger, &self.dst_creds, &self.dst_instance)
+    }
 }
 
 #[derive(clap::Args)]
@@ -264,3 +358,31 @@ pub struct CompletionArgs {
     #[clap(long, value_name = "STRING")]
     pub shell: clap_complete::Shell,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bigtable_ledger_uses_creds() {
+        let creds = Some("creds.json".to_string());
+        assert_eq!(
+            bigtable_or(&None, &creds, "default"),
+            LedgerSpec::Bigtable {
+                creds: creds.clone(),
+                instance: "default".to_string()
+            }
+        );
+        assert_eq!(
+            bigtable_or(&Some("bigtable:other".parse().unwrap()), &creds, "default"),
+            LedgerSpec::Bigtable {
+                creds: creds.clone(),
+                instance: "other".to_string()
+            }
+        );
+        assert_eq!(
+            bigtable_or(&Some("files:./blocks".parse().unwrap()), &creds, "default"),
+            LedgerSpec::Files(PathBuf::from("./blocks"))
+        );
+    }
+}
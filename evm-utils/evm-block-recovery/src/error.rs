@@ -1,5 +1,6 @@
 use {
-    crate::routines::repeat::BlockMessage, evm_state::Block,
+    crate::{ledger::LedgerSpec, routines::repeat::BlockMessage},
+    evm_state::Block,
     solana_transaction_status::ConfirmedBlock,
 };
 
@@ -42,30 +43,29 @@ pub enum AppError {
     EndSlotLessThanStartSlot,
 
     /////////////////////////////////////////////////////////////////////////////////////
-    // Errors of LedgerStorage
+    // Errors of Ledger
     /////////////////////////////////////////////////////////////////////////////////////
-    #[error("Unable to initialize `LedgerStorage` with creds={creds_path:?}, instance={instance}")]
+    #[error("Unable to open ledger {spec}")]
     OpenLedger {
         #[source]
-        source: solana_storage_bigtable::Error,
-        creds_path: Option<String>,
-        instance: String,
+        source: crate::ledger::Error,
+        spec: LedgerSpec,
     },
 
     #[error("Unable to get EVM Block")]
-    GetEvmBlock(#[source] solana_storage_bigtable::Error),
+    GetEvmBlock(#[source] crate::ledger::Error),
 
     #[error("Unable to get EVM Block Header {number}")]
     GetEvmBlockHeader {
         #[source]
-        source: solana_storage_bigtable::Error,
+        source: crate::ledger::Error,
         number: u64,
     },
 
     #[error("Unable to get EVM Confirmed Block IDs: start_block={start_block}, limit={limit}")]
     GetEvmBlockNums {
         #[source]
-        source: solana_storage_bigtable::Error,
+        source: crate::ledger::Error,
         start_block: u64,
         limit: usize,
     },
@@ -73,20 +73,20 @@ pub enum AppError {
     #[error("Unable to get native block {block}")]
     GetNativeBlock {
         #[source]
-        source: solana_storage_bigtable::Error,
+        source: crate::ledger::Error,
         block: u64,
     },
 
     #[error("Unable to get Native Confirmed Block IDs start_block={start_block}, limit={limit}")]
     GetNativeBlocks {
         #[source]
-        source: solana_storage_bigtable::Error,
+        source: crate::ledger::Error,
         start_block: u64,
         limit: usize,
     },
 
-    #[error("Unable to write block to bigtable")]
-    UploadEvmBlock(#[source] solana_storage_bigtable::Error),
+    #[error("Unable to write block to ledger")]
+    UploadEvmBlock(#[source] crate::ledger::Error),
 
     /////////////////////////////////////////////////////////////////////////////////////
     // IO Errors
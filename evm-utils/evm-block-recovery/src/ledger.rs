@@ -1,39 +1,240 @@
+mod blockstore;
+mod files;
+
+pub use {self::blockstore::BlockstoreLedger, files::FilesLedger};
 use {
     crate::error::AppError,
+    evm_state::{Block, BlockHeader, BlockNum},
+    solana_sdk::clock::Slot,
     solana_storage_bigtable::{LedgerStorage, LedgerStorageConfig, DEFAULT_APP_PROFILE_ID},
-    solana_transaction_status::ConfirmedBlock,
-    std::time::Duration,
+    solana_transaction_status::{ConfirmedBlock, VersionedConfirmedBlock},
+    std::{fmt, path::PathBuf, str::FromStr, time::Duration},
 };
 
-pub async fn with_params(
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Block {0} not found")]
+    BlockNotFound(u64),
+
+    #[error("Operation `{operation}` is not supported by {storage} storage")]
+    Unsupported {
+        storage: &'static str,
+        operation: &'static str,
+    },
+
+    #[error(transparent)]
+    Bigtable(solana_storage_bigtable::Error),
+
+    #[error(transparent)]
+    Blockstore(#[from] solana_ledger::blockstore_db::BlockstoreError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    ProtobufDecode(#[from] prost::DecodeError),
+
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error),
+}
+
+impl From<solana_storage_bigtable::Error> for Error {
+    fn from(error: solana_storage_bigtable::Error) -> Self {
+        match error {
+            solana_storage_bigtable::Error::BlockNotFound(block) => Error::BlockNotFound(block),
+            error => Error::Bigtable(error),
+        }
+    }
+}
+
+/// Location of ledger, in form of `bigtable:INSTANCE`, `blockstore:DIR` or `files:DIR`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerSpec {
+    /// Google BigTable instance, credentials are taken from `GOOGLE_APPLICATION_CREDENTIALS`
+    /// if not set
+    Bigtable {
+        creds: Option<String>,
+        instance: String,
+    },
+    /// Rocksdb ledger of validator
+    Blockstore(PathBuf),
+    /// Directory with EVM Blocks as `.json` and Native Blocks as `.pb` files
+    Files(PathBuf),
+}
+
+impl FromStr for LedgerSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("bigtable", instance)) if !instance.is_empty() => Ok(LedgerSpec::Bigtable {
+                creds: None,
+                instance: instance.to_string(),
+            }),
+            Some(("blockstore", path)) if !path.is_empty() => {
+                Ok(LedgerSpec::Blockstore(PathBuf::from(path)))
+            }
+            Some(("files", path)) if !path.is_empty() => Ok(LedgerSpec::Files(PathBuf::from(path))),
+            _ => Err(format!(
+                r#"invalid ledger "{s}", expected "bigtable:INSTANCE", "blockstore:DIR" or "files:DIR""#
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LedgerSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerSpec::Bigtable { creds, instance } => {
+                write!(f, "bigtable:{instance} (creds={creds:?})")
+            }
+            LedgerSpec::Blockstore(path) => write!(f, "blockstore:{}", path.display()),
+            LedgerSpec::Files(path) => write!(f, "files:{}", path.display()),
+        }
+    }
+}
+
+/// Open ledger for reading, blockstore of running validator can be opened too.
+pub async fn open(spec: LedgerSpec) -> std::result::Result<Ledger, AppError> {
+    open_with_access(spec, false).await
+}
+
+/// Open ledger for reading and writing of blocks.
+pub async fn open_writable(spec: LedgerSpec) -> std::result::Result<Ledger, AppError> {
+    open_with_access(spec, true).await
+}
+
+async fn open_with_access(
+    spec: LedgerSpec,
+    writable: bool,
+) -> std::result::Result<Ledger, AppError> {
+    log::info!("Opening ledger {spec}, writable = {writable}");
+    let ledger = match &spec {
+        LedgerSpec::Bigtable { creds, instance } => {
+            with_params(creds.clone(), instance.clone(), writable)
+                .await
+                .map(Ledger::Bigtable)
+        }
+        LedgerSpec::Blockstore(path) => {
+            BlockstoreLedger::open(path, writable).map(Ledger::Blockstore)
+        }
+        LedgerSpec::Files(path) => FilesLedger::open(path, writable).map(Ledger::Files),
+    };
+    ledger.map_err(|source| AppError::OpenLedger { source, spec })
+}
+
+async fn with_params(
     creds_path: Option<String>,
     instance: String,
-) -> Result<LedgerStorage, AppError> {
+    writable: bool,
+) -> Result<LedgerStorage> {
     log::info!(
         "Creating LedgerStorage: creds_path='{:?}', instance='{}'",
         creds_path,
         instance
     );
     let config = LedgerStorageConfig {
-        read_only: false,
+        read_only: !writable,
         timeout: None,
-        credential_type: solana_storage_bigtable::CredentialType::Filepath(creds_path.clone()),
-        instance_name: instance.clone(),
+        credential_type: solana_storage_bigtable::CredentialType::Filepath(creds_path),
+        instance_name: instance,
         app_profile_id: DEFAULT_APP_PROFILE_ID.to_string(),
     };
-    LedgerStorage::new_with_config(config)
-        .await
-        .map_err(|source| AppError::OpenLedger {
-            source,
-            creds_path,
-            instance,
-        })
+    Ok(LedgerStorage::new_with_config(config).await?)
+}
+
+/// Storage of Native and EVM Blocks, which routines read from and write to
+pub enum Ledger {
+    Bigtable(LedgerStorage),
+    Blockstore(BlockstoreLedger),
+    Files(FilesLedger),
 }
 
-// struct BTCreds {
-//     creds_path: Option<String>,
-//     instance: String,
-// }
+impl Ledger {
+    /// Returns up to `limit` numbers of confirmed EVM Blocks, starting from `start_block`
+    pub async fn get_evm_confirmed_full_blocks_nums(
+        &self,
+        start_block: BlockNum,
+        limit: usize,
+    ) -> Result<Vec<BlockNum>> {
+        match self {
+            Ledger::Bigtable(ledger) => Ok(ledger
+                .get_evm_confirmed_full_blocks_nums(start_block, limit)
+                .await?),
+            Ledger::Blockstore(ledger) => {
+                ledger.get_evm_confirmed_full_blocks_nums(start_block, limit)
+            }
+            Ledger::Files(ledger) => ledger.get_evm_confirmed_full_blocks_nums(start_block, limit),
+        }
+    }
+
+    pub async fn get_evm_confirmed_full_block(&self, block_num: BlockNum) -> Result<Block> {
+        match self {
+            Ledger::Bigtable(ledger) => Ok(ledger.get_evm_confirmed_full_block(block_num).await?),
+            Ledger::Blockstore(ledger) => ledger.get_evm_confirmed_full_block(block_num),
+            Ledger::Files(ledger) => ledger.get_evm_confirmed_full_block(block_num),
+        }
+    }
+
+    pub async fn get_evm_confirmed_block_header(&self, block_num: BlockNum) -> Result<BlockHeader> {
+        match self {
+            Ledger::Bigtable(ledger) => {
+                Ok(ledger.get_evm_confirmed_block_header(block_num).await?)
+            }
+            Ledger::Blockstore(ledger) => ledger
+                .get_evm_confirmed_full_block(block_num)
+                .map(|block| block.header),
+            Ledger::Files(ledger) => ledger
+                .get_evm_confirmed_full_block(block_num)
+                .map(|block| block.header),
+        }
+    }
+
+    pub async fn upload_evm_block(&self, block_num: BlockNum, block: Block) -> Result<()> {
+        match self {
+            Ledger::Bigtable(ledger) => Ok(ledger.upload_evm_block(block_num, block).await?),
+            Ledger::Blockstore(ledger) => ledger.upload_evm_block(block),
+            Ledger::Files(ledger) => ledger.upload_evm_block(block_num, block),
+        }
+    }
+
+    /// Returns up to `limit` numbers of confirmed Native Blocks, starting from `start_slot`
+    pub async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        match self {
+            Ledger::Bigtable(ledger) => Ok(ledger.get_confirmed_blocks(start_slot, limit).await?),
+            Ledger::Blockstore(ledger) => ledger.get_confirmed_blocks(start_slot, limit),
+            Ledger::Files(ledger) => ledger.get_confirmed_blocks(start_slot, limit),
+        }
+    }
+
+    pub async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        match self {
+            Ledger::Bigtable(ledger) => Ok(ledger.get_confirmed_block(slot).await?),
+            Ledger::Blockstore(ledger) => ledger.get_confirmed_block(slot),
+            Ledger::Files(ledger) => ledger.get_confirmed_block(slot),
+        }
+    }
+
+    pub async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        block: VersionedConfirmedBlock,
+    ) -> Result<()> {
+        match self {
+            Ledger::Bigtable(ledger) => Ok(ledger.upload_confirmed_block(slot, block).await?),
+            Ledger::Blockstore(_) => Err(Error::Unsupported {
+                storage: "blockstore",
+                operation: "upload_confirmed_block",
+            }),
+            Ledger::Files(ledger) => ledger.upload_confirmed_block(slot, block),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum Fetched<T> {
@@ -41,14 +242,11 @@ pub enum Fetched<T> {
     BlockNotFound,
 }
 
-fn to_fetched<T>(
-    result: solana_storage_bigtable::Result<T>,
-    slot: u64,
-) -> Result<Fetched<T>, AppError> {
+fn to_fetched<T>(result: Result<T>, slot: u64) -> std::result::Result<Fetched<T>, AppError> {
     match result {
         Ok(block) => Ok(Fetched::BlockFound(block)),
         Err(error) => match error {
-            solana_storage_bigtable::Error::BlockNotFound(_) => Ok(Fetched::BlockNotFound),
+            Error::BlockNotFound(_) => Ok(Fetched::BlockNotFound),
             other_error => Err(AppError::GetNativeBlock {
                 source: other_error,
                 block: slot,
@@ -57,18 +255,17 @@ fn to_fetched<T>(
     }
 }
 
-/// Tries to fetch native block from bigtable `ledger` with configurable retry
+/// Tries to fetch native block from `ledger` with configurable retry
 ///
 /// * `slot` - number of block to fetch
 /// * `num_retries` - number of retries
 /// * `pause` - function which generates pause duration for nth retry
 pub async fn get_native_block_obsessively<P: Fn(u64) -> Duration>(
-    ledger: &mut LedgerStorage,
+    ledger: &Ledger,
     slot: u64,
     num_retries: u64,
     pause: P,
-    // reinstantiate_ledger: Option<BTCreds>
-) -> Result<Fetched<ConfirmedBlock>, AppError> {
+) -> std::result::Result<Fetched<ConfirmedBlock>, AppError> {
     let mut result = to_fetched(ledger.get_confirmed_block(slot).await, slot);
 
     if result.is_err() {
@@ -97,3 +294,30 @@ pub async fn get_native_block_obsessively<P: Fn(u64) -> Duration>(
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ledger_spec() {
+        assert_eq!(
+            "bigtable:velas-ledger".parse(),
+            Ok(LedgerSpec::Bigtable {
+                creds: None,
+                instance: "velas-ledger".to_string()
+            })
+        );
+        assert_eq!(
+            "blockstore:/mnt/ledger".parse(),
+            Ok(LedgerSpec::Blockstore(PathBuf::from("/mnt/ledger")))
+        );
+        assert_eq!(
+            "files:./blocks".parse(),
+            Ok(LedgerSpec::Files(PathBuf::from("./blocks")))
+        );
+        assert!("files:".parse::<LedgerSpec>().is_err());
+        assert!("/mnt/ledger".parse::<LedgerSpec>().is_err());
+        assert!("s3:bucket".parse::<LedgerSpec>().is_err());
+    }
+}
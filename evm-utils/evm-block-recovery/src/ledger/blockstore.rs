@@ -0,0 +1,100 @@
+use {
+    super::{Error, Result},
+    evm_state::{Block, BlockNum},
+    solana_ledger::{
+        blockstore::Blockstore,
+        blockstore_db::{AccessType, BlockstoreError, BlockstoreOptions},
+    },
+    solana_sdk::clock::Slot,
+    solana_transaction_status::ConfirmedBlock,
+    std::path::Path,
+};
+
+/// Local rocksdb ledger, e.g. copied from a validator.
+///
+/// Only rooted blocks are visible, and Native Blocks can't be written, because they are stored as
+/// shreds.
+pub struct BlockstoreLedger {
+    blockstore: Blockstore,
+}
+
+impl BlockstoreLedger {
+    /// Read only ledger is opened as secondary, if it is already opened by validator.
+    pub fn open(path: &Path, writable: bool) -> Result<Self> {
+        let access_type = if writable {
+            AccessType::PrimaryOnly
+        } else {
+            AccessType::TryPrimaryThenSecondary
+        };
+        let blockstore = Blockstore::open_with_options(
+            path,
+            BlockstoreOptions {
+                access_type,
+                ..BlockstoreOptions::default()
+            },
+        )?;
+        Ok(Self { blockstore })
+    }
+
+    pub fn get_evm_confirmed_full_blocks_nums(
+        &self,
+        start_block: BlockNum,
+        limit: usize,
+    ) -> Result<Vec<BlockNum>> {
+        let mut blocks: Vec<BlockNum> = vec![];
+        for ((block_num, _slot), header) in self.blockstore.evm_blocks_iterator(start_block)? {
+            if blocks.len() >= limit {
+                break;
+            }
+            // Same block number can be produced on different forks.
+            if blocks.last() != Some(&block_num)
+                && self.blockstore.is_root(header.native_chain_slot)
+            {
+                blocks.push(block_num);
+            }
+        }
+        Ok(blocks)
+    }
+
+    pub fn get_evm_confirmed_full_block(&self, block_num: BlockNum) -> Result<Block> {
+        match self.blockstore.get_evm_block(block_num) {
+            Ok((block, true)) => Ok(block),
+            Ok((_, false)) | Err(BlockstoreError::SlotCleanedUp) => {
+                Err(Error::BlockNotFound(block_num))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn upload_evm_block(&self, block: Block) -> Result<()> {
+        self.blockstore.write_evm_logs_index(&block)?;
+        self.blockstore.write_evm_block_header(&block.header)?;
+        for (hash, tx) in block.transactions {
+            self.blockstore.write_evm_transaction(
+                block.header.block_number,
+                block.header.native_chain_slot,
+                hash,
+                tx,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        Ok(self
+            .blockstore
+            .rooted_slot_iterator(start_slot)?
+            .take(limit)
+            .collect())
+    }
+
+    pub fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        match self.blockstore.get_rooted_block(slot, false) {
+            Ok(block) => Ok(block.into()),
+            Err(BlockstoreError::SlotNotRooted)
+            | Err(BlockstoreError::SlotCleanedUp)
+            | Err(BlockstoreError::SlotUnavailable) => Err(Error::BlockNotFound(slot)),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use {
+    super::{Error, Result},
+    evm_state::{Block, BlockNum},
+    prost::Message,
+    solana_sdk::clock::Slot,
+    solana_storage_proto::convert::generated,
+    solana_transaction_status::{ConfirmedBlock, VersionedConfirmedBlock},
+    std::{
+        fs,
+        io::ErrorKind,
+        path::{Path, PathBuf},
+    },
+};
+
+const EVM_BLOCKS_DIR: &str = "evm-full-blocks";
+const EVM_BLOCK_EXTENSION: &str = "json";
+const NATIVE_BLOCKS_DIR: &str = "blocks";
+const NATIVE_BLOCK_EXTENSION: &str = "pb";
+
+/// Directory of plain files: EVM Blocks are stored as `evm-full-blocks/<NUM>.json`,
+/// in the same format as `upload` command accepts, and Native Blocks as `blocks/<SLOT>.pb`,
+/// in the same protobuf format as BigTable stores.
+pub struct FilesLedger {
+    path: PathBuf,
+}
+
+impl FilesLedger {
+    /// Open directory of blocks, block directories are created only if ledger is `writable`,
+    /// read-only ledger should already exist.
+    pub fn open(path: &Path, writable: bool) -> Result<Self> {
+        if writable {
+            fs::create_dir_all(path.join(EVM_BLOCKS_DIR))?;
+            fs::create_dir_all(path.join(NATIVE_BLOCKS_DIR))?;
+        } else if !path.is_dir() {
+            return Err(std::io::Error::new(
+                ErrorKind::NotFound,
+                format!("ledger directory {} not found", path.display()),
+            )
+            .into());
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn file_path(&self, dir: &str, num: u64, extension: &str) -> PathBuf {
+        // Zero padding keeps files ordered, when listed by other tools.
+        self.path.join(dir).join(format!("{num:020}.{extension}"))
+    }
+
+    fn list(&self, dir: &str, extension: &str, start: u64, limit: usize) -> Result<Vec<u64>> {
+        let entries = match fs::read_dir(self.path.join(dir)) {
+            Ok(entries) => entries,
+            // Ledger may contain only one kind of blocks.
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(error.into()),
+        };
+        let mut nums = vec![];
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(extension) {
+                continue;
+            }
+            match path
+                .file_stem()
+                .and_then(|stem| stem.to_str()?.parse::<u64>().ok())
+            {
+                Some(num) if num >= start => nums.push(num),
+                Some(_) => (),
+                None => log::warn!("Skipping unknown file {}", path.display()),
+            }
+        }
+        nums.sort_unstable();
+        nums.truncate(limit);
+        Ok(nums)
+    }
+
+    fn read(&self, dir: &str, num: u64, extension: &str) -> Result<Vec<u8>> {
+        fs::read(self.file_path(dir, num, extension)).map_err(|error| match error.kind() {
+            ErrorKind::NotFound => Error::BlockNotFound(num),
+            _ => error.into(),
+        })
+    }
+
+    pub fn get_evm_confirmed_full_blocks_nums(
+        &self,
+        start_block: BlockNum,
+        limit: usize,
+    ) -> Result<Vec<BlockNum>> {
+        self.list(EVM_BLOCKS_DIR, EVM_BLOCK_EXTENSION, start_block, limit)
+    }
+
+    pub fn get_evm_confirmed_full_block(&self, block_num: BlockNum) -> Result<Block> {
+        let content = self.read(EVM_BLOCKS_DIR, block_num, EVM_BLOCK_EXTENSION)?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    pub fn upload_evm_block(&self, block_num: BlockNum, block: Block) -> Result<()> {
+        let content = serde_json::to_vec(&block)?;
+        fs::write(
+            self.file_path(EVM_BLOCKS_DIR, block_num, EVM_BLOCK_EXTENSION),
+            content,
+        )?;
+        Ok(())
+    }
+
+    pub fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        self.list(NATIVE_BLOCKS_DIR, NATIVE_BLOCK_EXTENSION, start_slot, limit)
+    }
+
+    pub fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        let content = self.read(NATIVE_BLOCKS_DIR, slot, NATIVE_BLOCK_EXTENSION)?;
+        let block = generated::ConfirmedBlock::decode(content.as_slice())?;
+        Ok(block.try_into()?)
+    }
+
+    pub fn upload_confirmed_block(&self, slot: Slot, block: VersionedConfirmedBlock) -> Result<()> {
+        let block: generated::ConfirmedBlock = block.into();
+        fs::write(
+            self.file_path(NATIVE_BLOCKS_DIR, slot, NATIVE_BLOCK_EXTENSION),
+            block.encode_to_vec(),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        evm_state::{BlockHeader, BlockVersion, H256},
+    };
+
+    fn evm_block(block_number: BlockNum) -> Block {
+        Block {
+            header: BlockHeader::new(
+                H256::repeat_byte(0x11),
+                300_000_000,
+                H256::repeat_byte(0x22),
+                block_number,
+                0,
+                1_600_000_000 + block_number,
+                block_number * 2,
+                H256::repeat_byte(0x33),
+                [].iter(),
+                BlockVersion::VersionConsistentHashes,
+            ),
+            transactions: vec![],
+        }
+    }
+
+    fn native_block(slot: Slot) -> VersionedConfirmedBlock {
+        VersionedConfirmedBlock {
+            previous_blockhash: solana_sdk::hash::Hash::new_unique().to_string(),
+            blockhash: solana_sdk::hash::Hash::new_unique().to_string(),
+            parent_slot: slot - 1,
+            transactions: vec![],
+            rewards: vec![],
+            block_time: Some(1_600_000_000 + slot as i64),
+            block_height: Some(slot),
+        }
+    }
+
+    #[test]
+    fn test_files_ledger_evm_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = FilesLedger::open(dir.path(), true).unwrap();
+
+        for block_number in [12, 10, 11, 15] {
+            ledger
+                .upload_evm_block(block_number, evm_block(block_number))
+                .unwrap();
+        }
+        fs::write(dir.path().join(EVM_BLOCKS_DIR).join("README"), "").unwrap();
+
+        assert_eq!(
+            ledger.get_evm_confirmed_full_blocks_nums(11, 10).unwrap(),
+            vec![11, 12, 15]
+        );
+        assert_eq!(
+            ledger.get_evm_confirmed_full_blocks_nums(0, 2).unwrap(),
+            vec![10, 11]
+        );
+        assert_eq!(
+            ledger.get_evm_confirmed_full_block(12).unwrap(),
+            evm_block(12)
+        );
+        assert!(matches!(
+            ledger.get_evm_confirmed_full_block(13),
+            Err(Error::BlockNotFound(13))
+        ));
+    }
+
+    #[test]
+    fn test_files_ledger_native_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = FilesLedger::open(dir.path(), true).unwrap();
+
+        for slot in [7, 5] {
+            ledger
+                .upload_confirmed_block(slot, native_block(slot))
+                .unwrap();
+        }
+        let block = native_block(9);
+        ledger.upload_confirmed_block(9, block.clone()).unwrap();
+
+        assert_eq!(ledger.get_confirmed_blocks(0, 10).unwrap(), vec![5, 7, 9]);
+        assert_eq!(
+            ledger.get_confirmed_block(9).unwrap(),
+            ConfirmedBlock::from(block)
+        );
+        assert!(matches!(
+            ledger.get_confirmed_block(8),
+            Err(Error::BlockNotFound(8))
+        ));
+    }
+
+    #[test]
+    fn test_files_ledger_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        assert!(matches!(
+            FilesLedger::open(&missing, false),
+            Err(Error::Io(error)) if error.kind() == ErrorKind::NotFound
+        ));
+        assert!(!missing.exists());
+
+        let ledger = FilesLedger::open(dir.path(), false).unwrap();
+        assert!(!dir.path().join(EVM_BLOCKS_DIR).exists());
+        assert_eq!(
+            ledger.get_evm_confirmed_full_blocks_nums(0, 10).unwrap(),
+            vec![]
+        );
+        assert_eq!(ledger.get_confirmed_blocks(0, 10).unwrap(), vec![]);
+    }
+}
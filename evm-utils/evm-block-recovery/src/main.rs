@@ -43,14 +43,15 @@ async fn main() {
         }
     }
 
+    let ledger = cli.ledger_spec();
     let execution_result = match cli.subcommand {
-        FindEvm(args) => report(cli.embed, find_evm(cli.creds, cli.instance, args).await),
-        FindNative(args) => report(cli.embed, find_native(cli.creds, cli.instance, args).await),
-        RestoreChain(args) => restore_chain(cli.creds, cli.instance, args).await,
-        CheckNative(args) => check_native(cli.creds, cli.instance, args).await,
-        CheckEvm(args) => check_evm(cli.creds, cli.instance, args).await,
+        FindEvm(args) => report(cli.embed, find_evm(ledger, args).await),
+        FindNative(args) => report(cli.embed, find_native(ledger, args).await),
+        RestoreChain(args) => restore_chain(ledger, args).await,
+        CheckNative(args) => check_native(ledger, args).await,
+        CheckEvm(args) => check_evm(ledger, args).await,
         CompareNative(args) => compare_native(args).await,
-        Upload(args) => upload(cli.creds, cli.instance, args).await,
+        Upload(args) => upload(ledger, args).await,
         RepeatEvm(args) => repeat_evm(args).await,
         RepeatNative(args) => repeat_native(args).await,
         ScanEvmStateRoots(ref args) => scan_evm_state_roots::command(args).await,
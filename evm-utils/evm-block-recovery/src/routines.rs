@@ -11,7 +11,7 @@ pub(crate) mod upload;
 // it's convenient, as all potentially required dependencies have been imported
 pub(crate) mod scratchpad;
 
-use crate::error::AppError;
+use crate::{error::AppError, ledger::Ledger};
 pub use {
     check_evm::check_evm,
     check_native::check_native,
@@ -24,7 +24,7 @@ pub use {
 };
 
 async fn write_blocks_collection(
-    ledger: &solana_storage_bigtable::LedgerStorage,
+    ledger: &Ledger,
     blocks: Vec<evm_state::Block>,
 ) -> Result<(), AppError> {
     for block in blocks {
@@ -1,13 +1,13 @@
-use crate::{cli::CheckEvmArgs, error::RoutineResult, ledger};
+use crate::{
+    cli::CheckEvmArgs,
+    error::RoutineResult,
+    ledger::{self, LedgerSpec},
+};
 
-pub async fn check_evm(
-    creds: Option<String>,
-    instance: String,
-    args: CheckEvmArgs,
-) -> RoutineResult {
+pub async fn check_evm(ledger: LedgerSpec, args: CheckEvmArgs) -> RoutineResult {
     let CheckEvmArgs { block_number } = args;
 
-    let ledger = ledger::with_params(creds, instance).await?;
+    let ledger = ledger::open(ledger).await?;
 
     let evm_block = ledger.get_evm_confirmed_full_block(block_number).await;
 
@@ -2,16 +2,12 @@ use crate::{
     cli::CheckNativeArgs,
     error::{AppError, RoutineResult},
     extensions::NativeBlockExt,
-    ledger,
+    ledger::{self, LedgerSpec},
 };
 
-pub async fn check_native(
-    creds: Option<String>,
-    instance: String,
-    args: CheckNativeArgs,
-) -> RoutineResult {
+pub async fn check_native(ledger: LedgerSpec, args: CheckNativeArgs) -> RoutineResult {
     let CheckNativeArgs { slot } = args;
-    let ledger = ledger::with_params(creds, instance).await?;
+    let ledger = ledger::open(ledger).await?;
 
     let native_block =
         ledger
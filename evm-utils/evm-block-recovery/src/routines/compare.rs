@@ -8,20 +8,13 @@ use {
 };
 
 pub async fn compare_native(args: CompareNativeArgs) -> RoutineResult {
-    let CompareNativeArgs {
-        start_slot,
-        limit,
-        credible_ledger_creds,
-        credible_ledger_instance,
-        dubious_ledger_creds,
-        dubious_ledger_instance,
-    } = args;
+    let credible_ledger = ledger::open(args.credible_ledger_spec()).await?;
 
-    let credible_ledger =
-        ledger::with_params(Some(credible_ledger_creds), credible_ledger_instance).await?;
+    let dubious_ledger = ledger::open(args.dubious_ledger_spec()).await?;
 
-    let dubious_ledger =
-        ledger::with_params(Some(dubious_ledger_creds), dubious_ledger_instance).await?;
+    let CompareNativeArgs {
+        start_slot, limit, ..
+    } = args;
 
     log::info!("Getting credible blocks set: start_slot={start_slot}, limit={limit}");
 
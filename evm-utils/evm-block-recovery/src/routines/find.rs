@@ -3,7 +3,7 @@ use {
     crate::{
         cli::{FindEvmArgs, FindNativeArgs},
         error::AppError,
-        ledger,
+        ledger::{self, LedgerSpec},
         routines::BlockRange,
     },
     std::time::Duration,
@@ -24,7 +24,7 @@ fn retry_pause(n: u64) -> Duration {
     Duration::from_millis(ms)
 }
 
-pub async fn find_evm(creds: Option<String>, instance: String, args: FindEvmArgs) -> FindResult {
+pub async fn find_evm(ledger: LedgerSpec, args: FindEvmArgs) -> FindResult {
     let FindEvmArgs {
         start_block,
         end_block,
@@ -37,7 +37,7 @@ pub async fn find_evm(creds: Option<String>, instance: String, args: FindEvmArgs
     log::info!("Looking for missing EVM Blocks");
     log::info!("start_block={start_block}, end_block={end_block}, bigtable_limit={bigtable_limit}");
 
-    let ledger = ledger::with_params(creds, instance).await?;
+    let ledger = ledger::open(ledger).await?;
 
     let mut start_block = start_block;
     let mut blocks = vec![];
@@ -63,7 +63,7 @@ pub async fn find_evm(creds: Option<String>, instance: String, args: FindEvmArgs
         } else {
             // we reach the end just after last successfull query
             log::debug!(
-                "Bigtable didn't return anything for range #{start_block}..#{end_block_to_query}"
+                "Ledger didn't return anything for range #{start_block}..#{end_block_to_query}"
             );
             break;
         };
@@ -100,11 +100,7 @@ pub async fn find_evm(creds: Option<String>, instance: String, args: FindEvmArgs
     }
 }
 
-pub async fn find_native(
-    creds: Option<String>,
-    instance: String,
-    args: FindNativeArgs,
-) -> FindResult {
+pub async fn find_native(ledger: LedgerSpec, args: FindNativeArgs) -> FindResult {
     let FindNativeArgs {
         start_block,
         end_block,
@@ -119,7 +115,7 @@ pub async fn find_native(
     log::info!("Looking for missing Native Blocks");
     log::info!("start_slot={start_slot}, end_slot={end_slot}, bigtable_limit={bigtable_limit}");
 
-    let mut ledger = ledger::with_params(creds, instance).await?;
+    let ledger = ledger::open(ledger).await?;
 
     let mut slots = vec![];
 
@@ -140,7 +136,7 @@ pub async fn find_native(
             *block
         } else {
             // we reach the end just after last successfull query
-            log::debug!("Bigtable didn't return anything for range #{start_slot}..#{end_slot}");
+            log::debug!("Ledger didn't return anything for range #{start_slot}..#{end_slot}");
             break;
         };
 
@@ -184,10 +180,10 @@ pub async fn find_native(
         let slot_curr = range.last() + 1;
 
         let block_prev =
-            ledger::get_native_block_obsessively(&mut ledger, slot_prev, 15, retry_pause).await?;
+            ledger::get_native_block_obsessively(&ledger, slot_prev, 15, retry_pause).await?;
 
         let block_curr =
-            ledger::get_native_block_obsessively(&mut ledger, slot_curr, 15, retry_pause).await?;
+            ledger::get_native_block_obsessively(&ledger, slot_curr, 15, retry_pause).await?;
 
         match (block_prev, block_curr) {
             (BlockFound(block_prev), BlockFound(block_curr)) => {
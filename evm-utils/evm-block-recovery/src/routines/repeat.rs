@@ -41,19 +41,16 @@ pub struct BlockMessage<B> {
 }
 
 pub async fn repeat_evm(args: RepeatEvmArgs) -> RoutineResult {
+    let src = ledger::open(args.src_ledger_spec()).await?;
+
+    let dst = ledger::open_writable(args.dst_ledger_spec()).await?;
+
     let RepeatEvmArgs {
         block_number,
         limit,
-        src_creds,
-        src_instance,
-        dst_creds,
-        dst_instance,
+        ..
     } = args;
 
-    let src = ledger::with_params(Some(src_creds), src_instance).await?;
-
-    let dst = ledger::with_params(Some(dst_creds), dst_instance).await?;
-
     if limit == 1 {
         log::info!("Repeat EVM Block {}", block_number)
     } else {
@@ -140,19 +137,16 @@ pub async fn repeat_evm(args: RepeatEvmArgs) -> RoutineResult {
 }
 
 pub async fn repeat_native(args: RepeatNativeArgs) -> RoutineResult {
+    let src = ledger::open(args.src_ledger_spec()).await?;
+
+    let dst = ledger::open_writable(args.dst_ledger_spec()).await?;
+
     let RepeatNativeArgs {
         start_slot,
         end_slot,
-        src_creds,
-        src_instance,
-        dst_creds,
-        dst_instance,
+        ..
     } = args;
 
-    let src = ledger::with_params(Some(src_creds), src_instance).await?;
-
-    let dst = ledger::with_params(Some(dst_creds), dst_instance).await?;
-
     if end_slot < start_slot {
         return Err(AppError::EndSlotLessThanStartSlot);
     }
@@ -4,7 +4,7 @@ use {
         cli::RestoreChainArgs,
         error::{AppError, RoutineResult},
         extensions::NativeBlockExt,
-        ledger,
+        ledger::{self, LedgerSpec},
     },
     evm_rpc::RPCTransaction,
     evm_state::{Block, BlockHeader, TransactionInReceipt, H256},
@@ -17,11 +17,7 @@ use {
 
 pub const SECONDS_PER_HOUR: i64 = 60 * 60;
 
-pub async fn restore_chain(
-    creds: Option<String>,
-    instance: String,
-    args: RestoreChainArgs,
-) -> RoutineResult {
+pub async fn restore_chain(ledger: LedgerSpec, args: RestoreChainArgs) -> RoutineResult {
     let RestoreChainArgs {
         first_block,
         last_block,
@@ -33,7 +29,11 @@ pub async fn restore_chain(
         hrs_offset,
     } = args;
 
-    let ledger = ledger::with_params(creds, instance).await?;
+    let ledger = if modify_ledger {
+        ledger::open_writable(ledger).await?
+    } else {
+        ledger::open(ledger).await?
+    };
 
     let rpc_client = RpcClient::new(archive_url);
 
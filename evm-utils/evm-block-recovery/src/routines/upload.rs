@@ -3,14 +3,14 @@ use {
     crate::{
         cli::UploadArgs,
         error::{AppError, RoutineResult},
-        ledger,
+        ledger::{self, LedgerSpec},
     },
     evm_state::Block,
 };
 
-pub async fn upload(creds: Option<String>, instance: String, args: UploadArgs) -> RoutineResult {
+pub async fn upload(ledger: LedgerSpec, args: UploadArgs) -> RoutineResult {
     let UploadArgs { collection } = args;
-    let ledger = ledger::with_params(creds, instance).await?;
+    let ledger = ledger::open_writable(ledger).await?;
 
     log::info!("Reading file: '{}'...", &collection);
     let content = std::fs::read_to_string(&collection).map_err(AppError::ReadFile)?;
@@ -0,0 +1,385 @@
+//! Bundles of evm transactions, that are executed atomically by single native instruction.
+//!
+//! Bundle bypasses mempool, so transactions should be ordered and have correct nonces,
+//! if any of transactions fail, none of them is applied.
+//! Transactions of bundle pass the same checks, as transactions imported into mempool,
+//! and senders should have enough balance to pay for all their transactions in bundle.
+
+use {
+    crate::{from_client_error, pool::write_big_tx_storage, EvmBridge, EvmResult},
+    borsh::BorshSerialize,
+    evm_rpc::{
+        error::{into_native_error, Error},
+        trace::{TraceMeta, TraceResultsWithTransactionHash},
+        RPCTransaction,
+    },
+    evm_state::{H160, H256, U256},
+    log::*,
+    serde_json::json,
+    solana_client::{rpc_config::RpcSendTransactionConfig, rpc_request::RpcRequest},
+    solana_evm_loader_program::{
+        instructions::{FeePayerType, MAX_TRANSACTIONS_IN_BATCH},
+        scope::{evm, solana},
+    },
+    solana_sdk::{
+        commitment_config::{CommitmentConfig, CommitmentLevel},
+        instruction::AccountMeta,
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+    },
+    std::collections::{BTreeMap, HashSet},
+    tracing_attributes::instrument,
+};
+
+/// Transactions of a single sender in bundle.
+#[derive(Debug)]
+struct SenderTransactions {
+    first_nonce: U256,
+    first_hash: H256,
+    /// Value and gas, that sender pays for its transactions.
+    cost: Option<U256>,
+}
+
+/// Checks, that are done before any request to the node.
+/// Returns transactions grouped by sender, and fee payer of bundle.
+fn check_transactions(
+    bridge: &EvmBridge,
+    txs: &[evm::SignedTransaction],
+) -> EvmResult<(BTreeMap<H160, SenderTransactions>, FeePayerType)> {
+    if txs.is_empty() || txs.len() > MAX_TRANSACTIONS_IN_BATCH {
+        return Err(Error::InvalidBundleSize {
+            len: txs.len(),
+            max: MAX_TRANSACTIONS_IN_BATCH,
+        });
+    }
+    // Bridge pays for gas only if it pays for every transaction, same as for pooled transactions.
+    let native_fee = txs.iter().all(|tx| bridge.should_pay_for_gas(tx));
+    let mut senders: BTreeMap<H160, SenderTransactions> = BTreeMap::new();
+    let mut last_nonces: BTreeMap<H160, U256> = BTreeMap::new();
+    for tx in txs {
+        if tx.chain_id() != Some(bridge.evm_chain_id) {
+            return Err(Error::WrongChainId {
+                chain_id: bridge.evm_chain_id,
+                tx_chain_id: tx.chain_id(),
+            });
+        }
+        if tx.gas_price() < bridge.min_gas_price {
+            return Err(Error::GasPriceTooLow {
+                need: bridge.min_gas_price,
+            });
+        }
+        let sender = tx
+            .caller()
+            .map_err(|source| Error::EvmStateError { source })?;
+        let nonce = tx.nonce();
+        if let Some(last_nonce) = last_nonces.insert(sender, nonce) {
+            if nonce != last_nonce + 1 {
+                return Err(Error::InvalidBundleNonce {
+                    tx_hash: tx.tx_id_hash(),
+                    nonce,
+                    expected: last_nonce + 1,
+                });
+            }
+        }
+        let gas = if native_fee {
+            Some(U256::zero())
+        } else {
+            tx.gas_limit().checked_mul(tx.gas_price())
+        };
+        let entry = senders.entry(sender).or_insert_with(|| SenderTransactions {
+            first_nonce: nonce,
+            first_hash: tx.tx_id_hash(),
+            cost: Some(U256::zero()),
+        });
+        entry.cost = entry
+            .cost
+            .zip(gas)
+            .and_then(|(cost, gas)| cost.checked_add(gas)?.checked_add(tx.value()));
+    }
+    let fee_type = if native_fee {
+        FeePayerType::Native
+    } else {
+        FeePayerType::Evm
+    };
+    Ok((senders, fee_type))
+}
+
+/// Checks nonces and balances of senders on the latest state of the node.
+///
+/// Bundle is executed atomically, so nonce of the first transaction of each sender
+/// should follow its transactions, that are already sent to the node or pooled on the bridge.
+async fn check_senders(
+    bridge: &EvmBridge,
+    senders: &BTreeMap<H160, SenderTransactions>,
+) -> EvmResult<()> {
+    for (sender, txs) in senders {
+        let expected = match bridge.pool.transaction_count(sender) {
+            Some(nonce) => nonce,
+            None => bridge
+                .rpc_client
+                .get_evm_transaction_count(sender)
+                .await
+                .map_err(from_client_error)?,
+        };
+        if txs.first_nonce != expected {
+            return Err(Error::InvalidBundleNonce {
+                tx_hash: txs.first_hash,
+                nonce: txs.first_nonce,
+                expected,
+            });
+        }
+        let balance = bridge
+            .rpc_client
+            .get_evm_balance(sender)
+            .await
+            .map_err(from_client_error)?;
+        let need = txs.cost.unwrap_or_else(U256::max_value);
+        if need > balance {
+            return Err(Error::InsufficientBundleBalance {
+                sender: *sender,
+                need,
+                balance,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Executes transactions of bundle one by one on the latest state of the node.
+async fn simulate_bundle(
+    bridge: &EvmBridge,
+    txs: &[evm::SignedTransaction],
+    meta_keys: &HashSet<Pubkey>,
+) -> EvmResult<()> {
+    let meta = TraceMeta {
+        meta_keys: Some(meta_keys.iter().map(ToString::to_string).collect()),
+        ..Default::default()
+    };
+    let tx_traces = txs
+        .iter()
+        .map(|tx| {
+            let rpc_tx = RPCTransaction::from_transaction(tx.clone().into())?;
+            Ok((rpc_tx, vec!["trace".to_string()], Some(meta.clone())))
+        })
+        .collect::<EvmResult<Vec<_>>>()?;
+    let results: Vec<TraceResultsWithTransactionHash> = bridge
+        .rpc_client
+        .send(RpcRequest::EthTraceCallMany, json!([tx_traces, "latest"]))
+        .await
+        .map_err(from_client_error)?;
+    for (tx, result) in txs.iter().zip(results) {
+        let error = result
+            .trace
+            .into_iter()
+            .find(|trace| trace.trace_address.is_empty())
+            .and_then(|trace| trace.error);
+        if let Some(reason) = error {
+            return Err(Error::BundleSimulationFailed {
+                tx_hash: tx.tx_id_hash(),
+                reason,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Send all transactions of bundle in single native transaction.
+/// If bundle doesn't fit into native transaction, it is written into storage account first,
+/// so big bundles are always simulated before anything is written.
+#[instrument(skip(bridge))]
+pub async fn send_bundle(
+    bridge: &EvmBridge,
+    txs: Vec<evm::SignedTransaction>,
+    meta_keys: HashSet<Pubkey>,
+) -> EvmResult<Vec<H256>> {
+    let (senders, fee_type) = check_transactions(bridge, &txs)?;
+    check_senders(bridge, &senders).await?;
+    let hashes: Vec<H256> = txs.iter().map(|tx| tx.tx_id_hash()).collect();
+
+    let mut bytes = vec![];
+    BorshSerialize::serialize(&txs, &mut bytes)
+        .map_err(|e| into_native_error(e, bridge.verbose_errors))?;
+
+    let payer_pubkey = bridge.key.pubkey();
+    let storage = Keypair::new();
+    let is_big = bytes.len() > evm::TX_MTU;
+    let mut ix = if is_big {
+        simulate_bundle(bridge, &txs, &meta_keys).await?;
+        debug!(
+            "Sending bundle of {} transactions by chunks, storage = {}",
+            txs.len(),
+            storage.pubkey()
+        );
+        write_big_tx_storage(bridge, &bridge.key, &storage, &bytes, true).await?;
+        solana_evm_loader_program::big_tx_execute_batch(
+            storage.pubkey(),
+            Some(&payer_pubkey),
+            fee_type,
+        )
+    } else {
+        solana_evm_loader_program::send_raw_batch(payer_pubkey, txs, Some(payer_pubkey), fee_type)
+    };
+    // Add meta accounts as additional arguments
+    for account in meta_keys {
+        ix.accounts.push(AccountMeta::new(account, false))
+    }
+
+    let (blockhash, _height) = bridge
+        .rpc_client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+        .await
+        .map_err(|e| into_native_error(e, bridge.verbose_errors))?
+        .value;
+    let execute_tx = if is_big {
+        solana::Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer_pubkey),
+            &[&bridge.key, &storage],
+            blockhash,
+        )
+    } else {
+        solana::Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer_pubkey),
+            &[&bridge.key],
+            blockhash,
+        )
+    };
+
+    let signature = bridge
+        .rpc_client
+        .send_transaction_with_config(
+            &execute_tx,
+            RpcSendTransactionConfig {
+                preflight_commitment: Some(CommitmentLevel::Processed),
+                skip_preflight: !bridge.simulate,
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(from_client_error)?;
+    info!(
+        "Bundle of {} transactions was sent, signature = {}",
+        hashes.len(),
+        signature
+    );
+
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{AsyncRpcClient, BridgeErpcImpl, BridgeMeta, EthPool, SystemClock},
+        evm_rpc::{bridge::BridgeERPC, Bytes},
+        std::sync::Arc,
+    };
+
+    const CHAIN_ID: u64 = 111;
+
+    fn test_bridge() -> BridgeMeta {
+        Arc::new(EvmBridge {
+            evm_chain_id: CHAIN_ID,
+            key: Keypair::new(),
+            accounts: Default::default(),
+            rpc_client: AsyncRpcClient::new("".to_string()),
+            verbose_errors: true,
+            borsh_encoding: false,
+            simulate: false,
+            max_logs_blocks: 0u64,
+            pool: EthPool::new(SystemClock),
+            pending_filters: Default::default(),
+            min_gas_price: 10.into(),
+            whitelist: vec![],
+            batch_state_map: Default::default(),
+            max_batch_duration: None,
+        })
+        .into()
+    }
+
+    fn raw_tx(nonce: u32, gas_price: u32, chain_id: u64) -> Bytes {
+        let secret_key = evm::SecretKey::from_slice(&[1; 32]).unwrap();
+        let tx: evm::SignedTransaction = evm::UnsignedTransaction {
+            nonce: nonce.into(),
+            gas_price: gas_price.into(),
+            gas_limit: 300000.into(),
+            action: evm::TransactionAction::Create,
+            value: 0.into(),
+            input: vec![],
+        }
+        .sign(&secret_key, Some(chain_id))
+        .into();
+        Bytes(tx.encode_raw())
+    }
+
+    // Valid bundle requires running node, so only checks, done before sending, are tested.
+    #[tokio::test]
+    async fn send_bundle_rejects_invalid_bundles() {
+        let bridge = test_bridge();
+        let rpc = BridgeErpcImpl {};
+
+        assert!(matches!(
+            rpc.send_bundle(bridge.clone(), vec![], None).await,
+            Err(Error::InvalidBundleSize { len: 0, .. })
+        ));
+        let too_big = (0..=MAX_TRANSACTIONS_IN_BATCH as u32)
+            .map(|nonce| raw_tx(nonce, 10, CHAIN_ID))
+            .collect();
+        assert!(matches!(
+            rpc.send_bundle(bridge.clone(), too_big, None).await,
+            Err(Error::InvalidBundleSize { len, max: MAX_TRANSACTIONS_IN_BATCH })
+                if len == MAX_TRANSACTIONS_IN_BATCH + 1
+        ));
+
+        // every transaction of bundle is checked, not only the first one
+        let wrong_chain = vec![raw_tx(0, 10, CHAIN_ID), raw_tx(1, 10, CHAIN_ID + 1)];
+        assert!(matches!(
+            rpc.send_bundle(bridge.clone(), wrong_chain, None).await,
+            Err(Error::WrongChainId { .. })
+        ));
+        let cheap = vec![raw_tx(0, 10, CHAIN_ID), raw_tx(1, 9, CHAIN_ID)];
+        assert!(matches!(
+            rpc.send_bundle(bridge.clone(), cheap, None).await,
+            Err(Error::GasPriceTooLow { .. })
+        ));
+        // transactions of the same sender should follow each other
+        let gap = vec![raw_tx(0, 10, CHAIN_ID), raw_tx(2, 10, CHAIN_ID)];
+        assert!(matches!(
+            rpc.send_bundle(bridge.clone(), gap, None).await,
+            Err(Error::InvalidBundleNonce { nonce, expected, .. })
+                if nonce == 2.into() && expected == 1.into()
+        ));
+
+        let malformed = vec![raw_tx(0, 10, CHAIN_ID), Bytes(vec![0xc1, 0x00])];
+        assert!(matches!(
+            rpc.send_bundle(bridge.clone(), malformed, None).await,
+            Err(Error::RlpError { .. })
+        ));
+        let invalid_meta_key = Some(vec!["not a pubkey".to_string()]);
+        assert!(rpc
+            .send_bundle(bridge, vec![raw_tx(0, 10, CHAIN_ID)], invalid_meta_key)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn bundle_cost_is_summed_by_sender() {
+        let bridge = test_bridge();
+        let txs: Vec<evm::SignedTransaction> = (0..3)
+            .map(|nonce| {
+                evm::SignedTransaction::decode_raw(&raw_tx(nonce, 10, CHAIN_ID).0).unwrap()
+            })
+            .collect();
+        let sender = txs[0].caller().unwrap();
+
+        let (senders, fee_type) = check_transactions(&bridge, &txs).unwrap();
+        assert!(matches!(fee_type, FeePayerType::Evm));
+        assert_eq!(senders.len(), 1);
+        let sender_txs = &senders[&sender];
+        assert_eq!(sender_txs.first_nonce, 0.into());
+        assert_eq!(sender_txs.first_hash, txs[0].tx_id_hash());
+        assert_eq!(sender_txs.cost, Some(U256::from(3 * 300_000 * 10u64)));
+    }
+}
@@ -1,11 +1,15 @@
 use {
+    crate::pool::DEFAULT_PRICE_BUMP_PERCENT,
     clap::ValueHint,
     log::{info, warn},
     primitive_types::U256,
     solana_cli_config::Config,
     solana_evm_loader_program::scope::evm::LAMPORTS_TO_GWEI_PRICE,
     solana_sdk::fee_calculator::DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE,
-    std::{net::SocketAddr, time::Duration},
+    std::{
+        net::{IpAddr, SocketAddr},
+        time::Duration,
+    },
 };
 
 #[derive(clap::Parser, Debug)]
@@ -30,6 +34,10 @@ pub struct BridgeCli {
     #[arg(long, default_value_t = default_min_gas_price(), value_parser = parse_min_gas_price, value_name = "GWEI")]
     pub min_gas_price: U256,
 
+    /// Minimal gas price increase of transaction, that replaces pooled or deployed transaction with the same nonce
+    #[arg(long, value_name = "PERCENT", default_value_t = DEFAULT_PRICE_BUMP_PERCENT)]
+    pub price_bump_percent: u64,
+
     /// Print full details in RPC error message, and ignore original message
     #[arg(long)]
     pub verbose_errors: bool,
@@ -42,7 +50,8 @@ pub struct BridgeCli {
     #[arg(long)]
     pub no_simulate: bool,
 
-    /// Maximum number of blocks to return in eth_getLogs RPC call
+    /// Maximum number of blocks to return in eth_getLogs RPC call,
+    /// filters by address or first topic are limited by node instead
     #[arg(long, value_name = "NUM", default_value_t = 500)]
     pub max_logs_block_count: u64,
 
@@ -57,6 +66,12 @@ pub struct BridgeCli {
     /// Maximum number of seconds to process batched jsonrpc requests
     #[arg(long, value_parser = parse_seconds_duration, value_name = "SECONDS")]
     pub rpc_max_batch_time: Option<Duration>,
+
+    /// Address of reverse proxy in front of the bridge, may be specified multiple times.
+    /// Clients are identified by the last address in X-Forwarded-For header that is not a trusted proxy,
+    /// so bridge port must be reachable only through trusted proxies
+    #[arg(long, value_name = "IP_ADDRESS")]
+    pub rpc_trusted_proxy: Vec<IpAddr>,
 }
 
 fn default_native_keypair() -> String {
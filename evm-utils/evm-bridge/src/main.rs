@@ -1,6 +1,9 @@
+mod bundle;
 mod cli;
 mod middleware;
+mod pending_filters;
 mod pool;
+mod pubsub;
 mod rpc_client;
 mod tx_filter;
 
@@ -19,12 +22,15 @@ use {
     evm_state::*,
     jsonrpc_core::BoxFuture,
     jsonrpc_http_server::{jsonrpc_core::*, *},
+    jsonrpc_pubsub::{PubSubHandler, Session},
     log::*,
     middleware::ProxyMiddleware,
+    pending_filters::PendingFilters,
     pool::{
         worker_cleaner, worker_deploy, worker_signature_checker, EthPool, PooledTransaction,
         SystemClock,
     },
+    pubsub::{BridgePubSub, BridgePubSubImpl, BridgeSession, SubscriptionMiddleware},
     rlp::Encodable,
     rpc_client::AsyncRpcClient,
     secp256k1::Message,
@@ -33,24 +39,32 @@ use {
     solana_client::{
         client_error::{ClientError, ClientErrorKind},
         rpc_config::*,
-        rpc_request::RpcResponseErrorData,
+        rpc_request::{RpcResponseErrorData, MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS},
         rpc_response::{Response as RpcResponse, *},
     },
     solana_evm_loader_program::{instructions::FeePayerType, scope::*},
-    solana_rpc::rpc::{BatchId, BatchStateMap},
+    solana_rpc::{
+        rpc::{BatchId, BatchStateMap},
+        rpc_service::forwarded_client_ip,
+    },
     solana_sdk::{
         clock::MS_PER_TICK,
+        commitment_config::CommitmentConfig,
         instruction::{AccountMeta, Instruction},
         pubkey::Pubkey,
+        signature::Signature,
         signer::Signer,
         signers::Signers,
         system_instruction,
         transaction::TransactionError,
     },
+    solana_transaction_status::TransactionConfirmationStatus,
     std::{
-        collections::{HashMap, HashSet},
+        collections::{BTreeMap, HashMap, HashSet},
         fs::File,
         future::ready,
+        net::IpAddr,
+        ops::Deref,
         result::Result as StdResult,
         str::FromStr,
         sync::Arc,
@@ -177,12 +191,40 @@ pub struct EvmBridge {
     simulate: bool,
     max_logs_blocks: u64,
     pool: EthPool<SystemClock>,
+    pending_filters: PendingFilters,
     min_gas_price: U256,
     whitelist: Vec<TxFilter>,
     pub batch_state_map: BatchStateMap,
     max_batch_duration: Option<Duration>,
 }
 
+/// Metadata of RPC request, bridge shared between requests, and address of client.
+#[derive(Debug, Clone)]
+pub struct BridgeMeta {
+    pub bridge: Arc<EvmBridge>,
+    /// Address of client, known only if request came through trusted proxy.
+    pub client_ip: Option<IpAddr>,
+}
+
+impl Metadata for BridgeMeta {}
+
+impl From<Arc<EvmBridge>> for BridgeMeta {
+    fn from(bridge: Arc<EvmBridge>) -> Self {
+        Self {
+            bridge,
+            client_ip: None,
+        }
+    }
+}
+
+impl Deref for BridgeMeta {
+    type Target = EvmBridge;
+
+    fn deref(&self) -> &EvmBridge {
+        &self.bridge
+    }
+}
+
 impl EvmBridge {
     fn new(
         evm_chain_id: u64,
@@ -194,6 +236,7 @@ impl EvmBridge {
         simulate: bool,
         max_logs_blocks: u64,
         min_gas_price: U256,
+        price_bump_percent: u64,
     ) -> Self {
         info!("EVM chain id {}", evm_chain_id);
 
@@ -214,7 +257,7 @@ impl EvmBridge {
         let key = solana_sdk::signature::read_keypair_file(keypath).unwrap();
 
         info!("Creating mempool...");
-        let pool = EthPool::new(SystemClock);
+        let pool = EthPool::with_price_bump(SystemClock, price_bump_percent);
 
         Self {
             evm_chain_id,
@@ -226,6 +269,7 @@ impl EvmBridge {
             simulate,
             max_logs_blocks,
             pool,
+            pending_filters: Default::default(),
             min_gas_price,
             whitelist: vec![],
             batch_state_map: Default::default(),
@@ -257,10 +301,14 @@ impl EvmBridge {
     }
 
     /// Wrap evm tx into solana, optionally add meta keys, to solana signature.
-    async fn send_tx(&self, tx: evm::Transaction, meta_keys: HashSet<Pubkey>) -> EvmResult<H256> {
+    async fn send_tx(
+        &self,
+        tx: evm::SignedTransaction,
+        meta_keys: HashSet<Pubkey>,
+    ) -> EvmResult<H256> {
         let (sender, mut receiver) = mpsc::channel::<EvmResult<H256>>(1);
 
-        if tx.gas_price < self.min_gas_price {
+        if tx.gas_price() < self.min_gas_price {
             return Err(Error::GasPriceTooLow {
                 need: self.min_gas_price,
             });
@@ -270,7 +318,7 @@ impl EvmBridge {
             .map_err(|source| evm_rpc::Error::EvmStateError { source })?;
         let tx = match self.pool.import(tx) {
             // tx was already processed on this bridge, return hash.
-            Err(txpool::Error::AlreadyImported(h)) => return Ok(h),
+            Err(::txpool::Error::AlreadyImported(h)) => return Ok(h),
             Ok(tx) => tx,
             Err(source) => {
                 let details = format!("{source}");
@@ -300,62 +348,92 @@ impl EvmBridge {
         Ok(block_num)
     }
 
-    pub async fn is_transaction_landed(&self, hash: &H256) -> Option<bool> {
-        async fn is_receipt_exists(bridge: &EvmBridge, hash: &H256) -> Option<bool> {
-            bridge
-                .rpc_client
-                .get_evm_transaction_receipt(hash)
-                .await
-                .ok()
-                .flatten()
-                .map(|_receipt| true)
+    /// Checks receipt of evm transaction, falling back to the already requested
+    /// status of its native transaction.
+    pub async fn is_transaction_landed(
+        &self,
+        hash: &H256,
+        signature_status: Option<&solana_sdk::transaction::Result<()>>,
+    ) -> Option<bool> {
+        let receipt_exists = self
+            .rpc_client
+            .get_evm_transaction_receipt(hash)
+            .await
+            .ok()
+            .flatten()
+            .map(|_receipt| true);
+
+        match receipt_exists {
+            Some(b) => Some(b),
+            None => signature_status
+                .and_then(|result| result.as_ref().ok())
+                .map(|()| true),
         }
+    }
 
-        async fn is_signature_exists(bridge: &EvmBridge, hash: &H256) -> Option<bool> {
-            match bridge.pool.signature_of_cached_transaction(hash) {
-                Some(signature) => bridge
-                    .rpc_client
-                    .get_signature_status(&signature)
-                    .await
-                    .ok()
-                    .flatten()
-                    .and_then(|result| result.ok())
-                    .map(|()| true),
-                None => None,
+    /// Statuses of native transactions, only if they reached confirmed commitment,
+    /// so lifecycle transitions, that are based on them, can't be reverted by fork.
+    /// Signatures are requested in batches, result keeps the order of `signatures`.
+    pub async fn confirmed_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Vec<Option<solana_sdk::transaction::Result<()>>> {
+        let mut statuses = Vec::with_capacity(signatures.len());
+        for chunk in signatures.chunks(MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS) {
+            match self.rpc_client.get_signature_statuses(chunk).await {
+                Ok(RpcResponse { value, .. }) => statuses.extend(value.into_iter().map(|status| {
+                    status
+                        .filter(|status| status.satisfies_commitment(CommitmentConfig::confirmed()))
+                        .map(|status| status.status)
+                })),
+                Err(err) => {
+                    warn!("Unable to get statuses of signatures: {}", err);
+                    statuses.extend(chunk.iter().map(|_| None));
+                }
             }
         }
-
-        match is_receipt_exists(self, hash).await {
-            Some(b) => Some(b),
-            None => is_signature_exists(self, hash).await,
-        }
+        statuses
     }
 
     fn make_send_tx_instructions(
         &self,
-        tx: &Transaction,
+        tx: &evm::SignedTransaction,
         meta_keys: &HashSet<Pubkey>,
     ) -> Vec<Instruction> {
         let mut native_fee_used = false;
-        let mut ix = if self.borsh_encoding {
-            let mut fee_type = FeePayerType::Evm;
-            if self.should_pay_for_gas(tx) {
-                fee_type = FeePayerType::Native;
-                native_fee_used = true;
-                info!("Using Native fee for tx: {}", tx.tx_id_hash());
+        let mut ix = match tx {
+            evm::SignedTransaction::Legacy(tx) if !self.borsh_encoding => {
+                solana_evm_loader_program::send_raw_tx_old(
+                    self.key.pubkey(),
+                    tx.clone(),
+                    Some(self.key.pubkey()),
+                )
+            }
+            _ => {
+                let mut fee_type = FeePayerType::Evm;
+                if self.should_pay_for_gas(tx) {
+                    fee_type = FeePayerType::Native;
+                    native_fee_used = true;
+                    info!("Using Native fee for tx: {}", tx.tx_id_hash());
+                }
+                match tx {
+                    evm::SignedTransaction::Legacy(tx) => solana_evm_loader_program::send_raw_tx(
+                        self.key.pubkey(),
+                        tx.clone(),
+                        Some(self.key.pubkey()),
+                        fee_type,
+                    ),
+                    // Typed transactions are supported only by borsh encoded instructions.
+                    evm::SignedTransaction::Typed(tx) => {
+                        solana_evm_loader_program::send_raw_typed_tx(
+                            self.key.pubkey(),
+                            tx.clone(),
+                            Some(self.key.pubkey()),
+                            fee_type,
+                        )
+                    }
+                }
             }
-            solana_evm_loader_program::send_raw_tx(
-                self.key.pubkey(),
-                tx.clone(),
-                Some(self.key.pubkey()),
-                fee_type,
-            )
-        } else {
-            solana_evm_loader_program::send_raw_tx_old(
-                self.key.pubkey(),
-                tx.clone(),
-                Some(self.key.pubkey()),
-            )
         };
 
         // Add meta accounts as additional arguments
@@ -376,19 +454,32 @@ impl EvmBridge {
 
     fn make_send_big_tx_instructions(
         &self,
-        tx: &Transaction,
+        tx: &evm::SignedTransaction,
         storage_pubkey: Pubkey,
         payer_pubkey: Pubkey,
     ) -> Vec<Instruction> {
         let mut native_fee_used = false;
-        let ix = if self.borsh_encoding {
+        let is_typed = matches!(tx, evm::SignedTransaction::Typed(_));
+        let ix = if self.borsh_encoding || is_typed {
             let mut fee_type = FeePayerType::Evm;
             if self.should_pay_for_gas(tx) {
                 fee_type = FeePayerType::Native;
                 native_fee_used = true;
                 info!("Using Native fee for tx: {}", tx.tx_id_hash());
             }
-            solana_evm_loader_program::big_tx_execute(storage_pubkey, Some(&payer_pubkey), fee_type)
+            if is_typed {
+                solana_evm_loader_program::big_tx_execute_typed(
+                    storage_pubkey,
+                    Some(&payer_pubkey),
+                    fee_type,
+                )
+            } else {
+                solana_evm_loader_program::big_tx_execute(
+                    storage_pubkey,
+                    Some(&payer_pubkey),
+                    fee_type,
+                )
+            }
         } else {
             solana_evm_loader_program::big_tx_execute_old(storage_pubkey, Some(&payer_pubkey))
         };
@@ -403,7 +494,31 @@ impl EvmBridge {
         }
     }
 
-    fn should_pay_for_gas(&self, tx: &Transaction) -> bool {
+    fn decode_raw_tx(&self, bytes: &Bytes) -> EvmResult<evm::SignedTransaction> {
+        let tx = match bytes.0.first() {
+            // Legacy transactions are rlp lists, typed ones starts with transaction type.
+            Some(byte) if *byte >= 0xc0 => {
+                let tx: compatibility::Transaction =
+                    rlp::decode(&bytes.0).with_context(|_| RlpError {
+                        struct_name: "RawTransaction".to_string(),
+                        input_data: hex::encode(&bytes.0),
+                    })?;
+                let tx: evm::Transaction = tx.into();
+
+                let unsigned_tx: evm::UnsignedTransaction = tx.clone().into();
+                let hash = unsigned_tx.signing_hash(Some(self.evm_chain_id));
+                debug!("loaded tx_hash = {:?}", hash);
+                evm::SignedTransaction::Legacy(tx)
+            }
+            _ => evm::SignedTransaction::decode_raw(&bytes.0).with_context(|_| RlpError {
+                struct_name: "RawTypedTransaction".to_string(),
+                input_data: hex::encode(&bytes.0),
+            })?,
+        };
+        Ok(tx)
+    }
+
+    fn should_pay_for_gas(&self, tx: &evm::SignedTransaction) -> bool {
         !self.whitelist.is_empty() && self.whitelist.iter().any(|f| f.is_match(tx))
     }
 }
@@ -412,7 +527,7 @@ impl EvmBridge {
 pub struct BridgeErpcImpl;
 
 impl BridgeERPC for BridgeErpcImpl {
-    type Metadata = Arc<EvmBridge>;
+    type Metadata = BridgeMeta;
 
     #[instrument]
     fn accounts(&self, meta: Self::Metadata) -> EvmResult<Vec<Address>> {
@@ -529,7 +644,7 @@ impl BridgeERPC for BridgeErpcImpl {
 
             let tx = tx_create.sign(secret_key, Some(meta.evm_chain_id));
 
-            meta.send_tx(tx, meta_keys).await
+            meta.send_tx(tx.into(), meta_keys).await
         };
 
         Box::pin(future)
@@ -551,20 +666,11 @@ impl BridgeERPC for BridgeErpcImpl {
                 .collect::<StdResult<HashSet<_>, _>>()
                 .map_err(|e| into_native_error(e, meta.verbose_errors))?;
 
-            let tx: compatibility::Transaction =
-                rlp::decode(&bytes.0).with_context(|_| RlpError {
-                    struct_name: "RawTransaction".to_string(),
-                    input_data: hex::encode(&bytes.0),
-                })?;
-            let tx: evm::Transaction = tx.into();
+            let tx = meta.decode_raw_tx(&bytes)?;
 
             // TODO: Check chain_id.
             // TODO: check gas price.
 
-            let unsigned_tx: evm::UnsignedTransaction = tx.clone().into();
-            let hash = unsigned_tx.signing_hash(Some(meta.evm_chain_id));
-            debug!("loaded tx_hash = {:?}", hash);
-
             meta.send_tx(tx, meta_keys).await
         };
 
@@ -575,12 +681,78 @@ impl BridgeERPC for BridgeErpcImpl {
     fn compilers(&self, _meta: Self::Metadata) -> EvmResult<Vec<String>> {
         Ok(vec![])
     }
+
+    #[instrument]
+    fn send_bundle(
+        &self,
+        meta: Self::Metadata,
+        txs: Vec<Bytes>,
+        meta_keys: Option<Vec<String>>,
+    ) -> BoxFuture<EvmResult<Vec<H256>>> {
+        let future = async move {
+            debug!("send_bundle of {} transactions", txs.len());
+            let meta_keys = meta_keys
+                .into_iter()
+                .flatten()
+                .map(|s| solana_sdk::pubkey::Pubkey::from_str(&s))
+                .collect::<StdResult<HashSet<_>, _>>()
+                .map_err(|e| into_native_error(e, meta.verbose_errors))?;
+
+            let txs = txs
+                .iter()
+                .map(|bytes| meta.decode_raw_tx(bytes))
+                .collect::<EvmResult<Vec<_>>>()?;
+
+            bundle::send_bundle(&meta, txs, meta_keys).await
+        };
+
+        Box::pin(future)
+    }
+
+    #[instrument]
+    fn transaction_lifecycle(
+        &self,
+        meta: Self::Metadata,
+        tx_hash: H256,
+    ) -> BoxFuture<EvmResult<Option<RPCTransactionLifecycle>>> {
+        let future = async move {
+            let lifecycle = match meta.pool.transaction_lifecycle(&tx_hash) {
+                Some(lifecycle) => lifecycle,
+                None => return Ok(None),
+            };
+
+            let status = match lifecycle.execute_signature() {
+                Some(signature) => meta
+                    .rpc_client
+                    .get_signature_statuses(&[signature])
+                    .await
+                    .map_err(from_client_error)?
+                    .value
+                    .pop()
+                    .flatten(),
+                None => None,
+            };
+            let commitment = status.as_ref().map(|status| {
+                match status.confirmation_status() {
+                    TransactionConfirmationStatus::Processed => "processed",
+                    TransactionConfirmationStatus::Confirmed => "confirmed",
+                    TransactionConfirmationStatus::Finalized => "finalized",
+                }
+                .to_string()
+            });
+
+            // State transitions are made by signature checker at confirmed commitment,
+            // so lifecycle is only reported here.
+            Ok(Some(lifecycle.to_rpc(tx_hash, commitment)))
+        };
+        Box::pin(future)
+    }
 }
 
 #[derive(Debug)]
 pub struct GeneralErpcProxy;
 impl GeneralERPC for GeneralErpcProxy {
-    type Metadata = Arc<EvmBridge>;
+    type Metadata = BridgeMeta;
 
     #[instrument]
     fn network_id(&self, meta: Self::Metadata) -> EvmResult<String> {
@@ -647,15 +819,38 @@ impl GeneralERPC for GeneralErpcProxy {
     }
 
     #[instrument]
-    fn gas_price(&self, meta: Self::Metadata) -> EvmResult<Gas> {
-        Ok(meta.min_gas_price)
+    fn gas_price(&self, meta: Self::Metadata) -> BoxFuture<EvmResult<Gas>> {
+        Box::pin(async move {
+            // Bridge rejects transactions cheaper than `min_gas_price`.
+            let gas_price = meta
+                .rpc_client
+                .get_evm_gas_price()
+                .await
+                .map_err(from_client_error)?;
+            Ok(gas_price.max(meta.min_gas_price))
+        })
+    }
+
+    #[instrument]
+    fn max_priority_fee_per_gas(&self, meta: Self::Metadata) -> BoxFuture<EvmResult<Gas>> {
+        Box::pin(async move {
+            let priority_fee = meta
+                .rpc_client
+                .get_evm_max_priority_fee_per_gas()
+                .await
+                .map_err(from_client_error)?;
+            let min_priority_fee = meta
+                .min_gas_price
+                .saturating_sub(evm_state::BURN_GAS_PRICE.into());
+            Ok(priority_fee.max(min_priority_fee))
+        })
     }
 }
 
 #[derive(Debug)]
 pub struct ChainErpcProxy;
 impl ChainERPC for ChainErpcProxy {
-    type Metadata = Arc<EvmBridge>;
+    type Metadata = BridgeMeta;
 
     #[instrument]
     // The same as get_slot
@@ -684,6 +879,28 @@ impl ChainERPC for ChainErpcProxy {
         Box::pin(ready(Err(evm_rpc::Error::ProxyRequest)))
     }
 
+    #[instrument]
+    fn fee_history(
+        &self,
+        _meta: Self::Metadata,
+        _block_count: Hex<u64>,
+        _newest_block: BlockId,
+        _reward_percentiles: Option<Vec<f64>>,
+    ) -> BoxFuture<EvmResult<RPCFeeHistory>> {
+        Box::pin(ready(Err(evm_rpc::Error::ProxyRequest)))
+    }
+
+    #[instrument]
+    fn proof(
+        &self,
+        _meta: Self::Metadata,
+        _address: Address,
+        _storage_keys: Vec<H256>,
+        _block: Option<BlockId>,
+    ) -> BoxFuture<EvmResult<RPCAccountProof>> {
+        Box::pin(ready(Err(evm_rpc::Error::ProxyRequest)))
+    }
+
     #[instrument]
     fn transaction_count(
         &self,
@@ -859,6 +1076,17 @@ impl ChainERPC for ChainErpcProxy {
                 });
             }
 
+            // Node answers indexed filters without range limit, and limit number of logs instead.
+            if log_filter.is_indexable() {
+                log_filter.from_block = Some(starting_block.into());
+                log_filter.to_block = Some(ending_block.into());
+                return meta
+                    .rpc_client
+                    .get_evm_logs(&log_filter)
+                    .await
+                    .map_err(from_client_error);
+            }
+
             // request more than we can provide
             if ending_block > starting_block + meta.max_logs_blocks {
                 return Err(Error::InvalidBlocksRange {
@@ -942,6 +1170,131 @@ impl ChainERPC for ChainErpcProxy {
     }
 }
 
+#[derive(Debug)]
+pub struct FilterErpcProxy;
+impl FilterERPC for FilterErpcProxy {
+    type Metadata = BridgeMeta;
+
+    #[instrument]
+    fn new_filter(
+        &self,
+        _meta: Self::Metadata,
+        _log_filter: RPCLogFilter,
+    ) -> BoxFuture<EvmResult<U256>> {
+        Box::pin(ready(Err(evm_rpc::Error::ProxyRequest)))
+    }
+
+    #[instrument]
+    fn new_block_filter(&self, _meta: Self::Metadata) -> EvmResult<U256> {
+        Err(evm_rpc::Error::ProxyRequest)
+    }
+
+    #[instrument]
+    fn new_pending_transaction_filter(&self, meta: Self::Metadata) -> EvmResult<U256> {
+        meta.pending_filters
+            .install(meta.pool.subscribe_pending_transactions(), meta.client_ip)
+    }
+
+    #[instrument]
+    fn uninstall_filter(&self, meta: Self::Metadata, id: U256) -> EvmResult<bool> {
+        if !PendingFilters::is_local(id) {
+            return Err(evm_rpc::Error::ProxyRequest);
+        }
+        Ok(meta.pending_filters.uninstall(id))
+    }
+
+    #[instrument]
+    fn filter_changes(
+        &self,
+        meta: Self::Metadata,
+        id: U256,
+    ) -> BoxFuture<EvmResult<evm_rpc::Either<Vec<H256>, Vec<RPCLog>>>> {
+        if !PendingFilters::is_local(id) {
+            return Box::pin(ready(Err(evm_rpc::Error::ProxyRequest)));
+        }
+        Box::pin(ready(
+            meta.pending_filters.poll(id).map(evm_rpc::Either::Left),
+        ))
+    }
+
+    #[instrument]
+    fn filter_logs(&self, _meta: Self::Metadata, id: U256) -> BoxFuture<EvmResult<Vec<RPCLog>>> {
+        if !PendingFilters::is_local(id) {
+            return Box::pin(ready(Err(evm_rpc::Error::ProxyRequest)));
+        }
+        // Pending transaction filter has no logs.
+        Box::pin(ready(Err(evm_rpc::Error::FilterNotFound { id })))
+    }
+}
+
+/// Groups pooled transactions by sender and nonce, like in geth `txpool_*` methods.
+fn group_by_nonce<T>(
+    txs: &BTreeMap<Address, Vec<Arc<PooledTransaction>>>,
+    f: impl Fn(&PooledTransaction) -> EvmResult<T>,
+) -> EvmResult<BTreeMap<Address, BTreeMap<String, T>>> {
+    txs.iter()
+        .map(|(sender, txs)| {
+            let txs = txs
+                .iter()
+                .map(|tx| Ok((tx.nonce().to_string(), f(tx)?)))
+                .collect::<EvmResult<_>>()?;
+            Ok((*sender, txs))
+        })
+        .collect()
+}
+
+fn to_rpc_transaction(tx: &PooledTransaction) -> EvmResult<RPCTransaction> {
+    RPCTransaction::from_transaction(tx.inner.clone().into())
+}
+
+fn inspect_transaction(tx: &PooledTransaction) -> EvmResult<String> {
+    let to = match tx.action() {
+        TransactionAction::Call(address) => format!("{:?}", address),
+        TransactionAction::Create => "contract creation".to_string(),
+    };
+    Ok(format!(
+        "{}: {} wei + {} gas × {} wei",
+        to,
+        tx.value(),
+        tx.gas_limit(),
+        tx.gas_price()
+    ))
+}
+
+#[derive(Debug)]
+pub struct TxPoolErpcImpl;
+impl TxPoolERPC for TxPoolErpcImpl {
+    type Metadata = BridgeMeta;
+
+    #[instrument]
+    fn content(&self, meta: Self::Metadata) -> EvmResult<RPCTxPoolContent> {
+        let content = meta.pool.content();
+        Ok(RPCTxPoolContent {
+            pending: group_by_nonce(&content.pending, to_rpc_transaction)?,
+            queued: group_by_nonce(&content.queued, to_rpc_transaction)?,
+        })
+    }
+
+    #[instrument]
+    fn inspect(&self, meta: Self::Metadata) -> EvmResult<RPCTxPoolInspect> {
+        let content = meta.pool.content();
+        Ok(RPCTxPoolInspect {
+            pending: group_by_nonce(&content.pending, inspect_transaction)?,
+            queued: group_by_nonce(&content.queued, inspect_transaction)?,
+        })
+    }
+
+    #[instrument]
+    fn status(&self, meta: Self::Metadata) -> EvmResult<RPCTxPoolStatus> {
+        let content = meta.pool.content();
+        let count = |txs: &BTreeMap<_, Vec<_>>| txs.values().map(Vec::len).sum::<usize>();
+        Ok(RPCTxPoolStatus {
+            pending: Hex(count(&content.pending)),
+            queued: Hex(count(&content.queued)),
+        })
+    }
+}
+
 pub(crate) fn from_client_error(client_error: ClientError) -> evm_rpc::Error {
     let client_error_kind = client_error.kind();
     match client_error_kind {
@@ -1041,6 +1394,7 @@ async fn main() -> StdResult<(), Box<dyn std::error::Error>> {
         !args.no_simulate,
         args.max_logs_block_count,
         min_gas_price,
+        args.price_bump_percent,
     );
     meta.set_whitelist(whitelist);
     meta.set_max_batch_duration(args.rpc_max_batch_time);
@@ -1055,6 +1409,10 @@ async fn main() -> StdResult<(), Box<dyn std::error::Error>> {
     io.extend_with(ether_chain.to_delegate());
     let ether_general = GeneralErpcProxy;
     io.extend_with(ether_general.to_delegate());
+    let ether_filter = FilterErpcProxy;
+    io.extend_with(ether_filter.to_delegate());
+    let ether_txpool = TxPoolErpcImpl;
+    io.extend_with(ether_txpool.to_delegate());
 
     let mempool_worker = worker_deploy(meta.clone());
 
@@ -1064,25 +1422,38 @@ async fn main() -> StdResult<(), Box<dyn std::error::Error>> {
 
     info!("Creating server with: {}", binding_address);
     let meta_clone = meta.clone();
-    let server = ServerBuilder::with_meta_extractor(
-        io.clone(),
-        move |_req: &hyper::Request<hyper::Body>| meta_clone.clone(),
-    )
-    .cors(DomainsValidation::AllowOnly(vec![
-        AccessControlAllowOrigin::Any,
-    ]))
-    .threads(4)
-    .cors_max_age(86400)
-    .start_http(&binding_address)
-    .expect("Unable to start EVM bridge server");
+    let trusted_proxies = args.rpc_trusted_proxy;
+    let meta_extractor = move |req: &hyper::Request<hyper::Body>| BridgeMeta {
+        bridge: meta_clone.clone(),
+        client_ip: forwarded_client_ip(req, &trusted_proxies),
+    };
+    let server = ServerBuilder::with_meta_extractor(io.clone(), meta_extractor)
+        .cors(DomainsValidation::AllowOnly(vec![
+            AccessControlAllowOrigin::Any,
+        ]))
+        .threads(4)
+        .cors_max_age(86400)
+        .start_http(&binding_address)
+        .expect("Unable to start EVM bridge server");
 
     let ws_server = {
         let mut websocket_binding = binding_address;
         websocket_binding.set_port(binding_address.port() + 1);
         info!("Creating websocket server: {}", websocket_binding);
-        jsonrpc_ws_server::ServerBuilder::with_meta_extractor(io, move |_: &_| meta.clone())
-            .start(&websocket_binding)
-            .expect("Unable to start EVM bridge server")
+        let mut ws_io = PubSubHandler::new(MetaIoHandler::with_middleware(
+            SubscriptionMiddleware::new(io),
+        ));
+        ws_io.extend_with(BridgePubSubImpl::new(tokio::runtime::Handle::current()).to_delegate());
+        jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
+            ws_io,
+            move |context: &jsonrpc_ws_server::RequestContext| BridgeSession {
+                bridge: meta.clone(),
+                session_id: context.session_id,
+                session: Some(Arc::new(Session::new(context.sender()))),
+            },
+        )
+        .start(&websocket_binding)
+        .expect("Unable to start EVM bridge server")
     };
 
     let _cleaner = tokio::task::spawn(cleaner);
@@ -1109,10 +1480,11 @@ async fn send_and_confirm_transactions<T: Signers>(
     rpc_client: &AsyncRpcClient,
     mut transactions: Vec<solana::Transaction>,
     signer_keys: &T,
-) -> StdResult<(), anyhow::Error> {
+) -> StdResult<Vec<Signature>, anyhow::Error> {
     const SEND_RETRIES: usize = 5;
     const STATUS_RETRIES: usize = 15;
 
+    let mut confirmed = vec![];
     for _ in 0..SEND_RETRIES {
         // Send all transactions
         let mut transactions_signatures = vec![];
@@ -1162,6 +1534,8 @@ async fn send_and_confirm_transactions<T: Signers>(
                         .unwrap_or(true)
                     {
                         retained.push((transaction, Some(signature)));
+                    } else {
+                        confirmed.push(signature);
                     }
                 } else {
                     retained.push((transaction, signature));
@@ -1170,7 +1544,7 @@ async fn send_and_confirm_transactions<T: Signers>(
             transactions_signatures = retained;
 
             if transactions_signatures.is_empty() {
-                return Ok(());
+                return Ok(confirmed);
             }
         }
 
@@ -1216,6 +1590,7 @@ mod tests {
             simulate: false,
             max_logs_blocks: 0u64,
             pool: EthPool::new(SystemClock),
+            pending_filters: Default::default(),
             min_gas_price: 0.into(),
             whitelist: vec![],
             batch_state_map: Default::default(),
@@ -1225,7 +1600,7 @@ mod tests {
         let rpc = BridgeErpcImpl {};
         let address = Address::from_str("0x141a4802f84bb64c0320917672ef7D92658e964e").unwrap();
         let data = "qwe".as_bytes().to_vec();
-        let res = rpc.sign(bridge, address, data.into()).unwrap();
+        let res = rpc.sign(bridge.into(), address, data.into()).unwrap();
         assert_eq!(res.to_string(), "0xb734e224f0f92d89825f3f69bf03924d7d2f609159d6ce856d37a58d7fcbc8eb6d224fd73f05217025ed015283133c92888211b238272d87ec48347f05ab42a000");
     }
 }
@@ -1,5 +1,5 @@
 use {
-    crate::EvmBridge,
+    crate::BridgeMeta,
     evm_state::rand::{thread_rng, Rng},
     jsonrpc_core::{
         futures_util::future::{Either, FutureExt},
@@ -13,11 +13,11 @@ use {
         middleware::{patch_calls, restore_original_call},
         rpc::BatchId,
     },
-    std::{future::ready, sync::Arc, time::Instant},
+    std::{future::ready, time::Instant},
 };
 
 async fn redirect(
-    meta: Arc<EvmBridge>,
+    meta: BridgeMeta,
     call_json: String,
     jsonrpc: Option<Version>,
     id: Id,
@@ -59,18 +59,18 @@ async fn redirect(
 
 #[derive(Clone, Default)]
 pub struct ProxyMiddleware;
-impl Middleware<Arc<EvmBridge>> for ProxyMiddleware {
+impl Middleware<BridgeMeta> for ProxyMiddleware {
     type Future = FutureResponse;
     type CallFuture = FutureOutput;
 
     fn on_request<F, X>(
         &self,
         request: Request,
-        meta: Arc<EvmBridge>,
+        meta: BridgeMeta,
         next: F,
     ) -> Either<Self::Future, X>
     where
-        F: Fn(Request, Arc<EvmBridge>) -> X + Send + Sync,
+        F: Fn(Request, BridgeMeta) -> X + Send + Sync,
         X: std::future::Future<Output = Option<Response>> + Send + 'static,
     {
         if let Request::Batch(calls) = request {
@@ -92,14 +92,9 @@ impl Middleware<Arc<EvmBridge>> for ProxyMiddleware {
         }
     }
 
-    fn on_call<F, X>(
-        &self,
-        call: Call,
-        meta: Arc<EvmBridge>,
-        next: F,
-    ) -> Either<Self::CallFuture, X>
+    fn on_call<F, X>(&self, call: Call, meta: BridgeMeta, next: F) -> Either<Self::CallFuture, X>
     where
-        F: FnOnce(Call, Arc<EvmBridge>) -> X + Send,
+        F: FnOnce(Call, BridgeMeta) -> X + Send,
         X: std::future::Future<Output = Option<Output>> + Send + 'static,
     {
         let call_json = match serde_json::to_string(&call) {
@@ -0,0 +1,228 @@
+//! `eth_newPendingTransactionFilter`, served from the bridge mempool.
+//!
+//! Node has no evm mempool, so pending transaction filters live on the bridge,
+//! while other filters are proxied to the node.
+//! Ids of bridge filters are random, and allocated from the upper half of `u64`,
+//! while node allocates ids of its filters from the lower half.
+
+use {
+    evm_rpc::error::Error,
+    evm_state::{
+        rand::{thread_rng, Rng},
+        H256, U256,
+    },
+    log::*,
+    std::{
+        collections::{hash_map::Entry, HashMap},
+        net::IpAddr,
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+    tokio::sync::broadcast::{self, error::TryRecvError},
+};
+
+/// Same as geth default `filterTimeout`.
+pub const DEFAULT_FILTER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+pub const DEFAULT_MAX_PENDING_FILTERS: usize = 1000;
+/// Limit for single client, so one client can't exhaust `DEFAULT_MAX_PENDING_FILTERS`.
+pub const DEFAULT_MAX_FILTERS_PER_CLIENT: usize = 100;
+
+const FIRST_FILTER_ID: u64 = 1 << 63;
+
+struct PendingFilter {
+    hashes: broadcast::Receiver<H256>,
+    last_poll: Instant,
+    /// Address of client that installed filter, if it is known.
+    owner: Option<IpAddr>,
+}
+
+pub struct PendingFilters {
+    filters: Mutex<HashMap<u64, PendingFilter>>,
+    max_filters: usize,
+    max_filters_per_client: usize,
+    timeout: Duration,
+}
+
+impl std::fmt::Debug for PendingFilters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingFilters")
+            .field("max_filters", &self.max_filters)
+            .field("max_filters_per_client", &self.max_filters_per_client)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl Default for PendingFilters {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_MAX_PENDING_FILTERS,
+            DEFAULT_MAX_FILTERS_PER_CLIENT,
+            DEFAULT_FILTER_TIMEOUT,
+        )
+    }
+}
+
+impl PendingFilters {
+    pub fn new(max_filters: usize, max_filters_per_client: usize, timeout: Duration) -> Self {
+        Self {
+            filters: Mutex::new(HashMap::new()),
+            max_filters,
+            max_filters_per_client,
+            timeout,
+        }
+    }
+
+    /// Returns true if filter with `id` can be installed by bridge, and should not be proxied.
+    pub fn is_local(id: U256) -> bool {
+        id >= U256::from(FIRST_FILTER_ID) && id <= U256::from(u64::MAX)
+    }
+
+    /// Register new filter, that will return hashes received from `hashes` after installation.
+    ///
+    /// Filters of clients with unknown `owner` are limited only by global limit.
+    pub fn install(
+        &self,
+        hashes: broadcast::Receiver<H256>,
+        owner: Option<IpAddr>,
+    ) -> Result<U256, Error> {
+        let mut filters = self.filters.lock().unwrap();
+        let timeout = self.timeout;
+        filters.retain(|_, filter| filter.last_poll.elapsed() <= timeout);
+        if filters.len() >= self.max_filters {
+            return Err(Error::FiltersLimitReached {
+                limit: self.max_filters,
+            });
+        }
+        if owner.is_some()
+            && filters
+                .values()
+                .filter(|filter| filter.owner == owner)
+                .count()
+                >= self.max_filters_per_client
+        {
+            return Err(Error::FiltersLimitReached {
+                limit: self.max_filters_per_client,
+            });
+        }
+        let mut rng = thread_rng();
+        loop {
+            let id = rng.gen::<u64>() | FIRST_FILTER_ID;
+            if let Entry::Vacant(entry) = filters.entry(id) {
+                entry.insert(PendingFilter {
+                    hashes,
+                    last_poll: Instant::now(),
+                    owner,
+                });
+                return Ok(id.into());
+            }
+        }
+    }
+
+    pub fn uninstall(&self, id: U256) -> bool {
+        if !Self::is_local(id) {
+            return false;
+        }
+        self.filters.lock().unwrap().remove(&id.as_u64()).is_some()
+    }
+
+    /// Returns hashes of transactions imported since last poll, and prolongs filter lifetime.
+    pub fn poll(&self, id: U256) -> Result<Vec<H256>, Error> {
+        let mut filters = self.filters.lock().unwrap();
+        let filter = Self::is_local(id)
+            .then(|| id.as_u64())
+            .and_then(|local_id| filters.get_mut(&local_id))
+            .ok_or(Error::FilterNotFound { id })?;
+        if filter.last_poll.elapsed() > self.timeout {
+            filters.remove(&id.as_u64());
+            return Err(Error::FilterNotFound { id });
+        }
+        filter.last_poll = Instant::now();
+
+        let mut hashes = vec![];
+        loop {
+            match filter.hashes.try_recv() {
+                Ok(hash) => hashes.push(hash),
+                Err(TryRecvError::Lagged(skipped)) => {
+                    warn!(
+                        "Pending transaction filter {} is lagging, skipped {} hashes",
+                        id, skipped
+                    );
+                }
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+            }
+        }
+        Ok(hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_filters_poll_and_limit() {
+        let (sender, _) = broadcast::channel(16);
+        let filters = PendingFilters::new(2, 2, DEFAULT_FILTER_TIMEOUT);
+
+        let first = filters.install(sender.subscribe(), None).unwrap();
+        assert!(PendingFilters::is_local(first));
+        assert!(!PendingFilters::is_local(U256::from(1)));
+
+        sender.send(H256::repeat_byte(1)).unwrap();
+        let second = filters.install(sender.subscribe(), None).unwrap();
+        assert_ne!(first, second);
+        sender.send(H256::repeat_byte(2)).unwrap();
+        assert!(matches!(
+            filters.install(sender.subscribe(), None),
+            Err(Error::FiltersLimitReached { limit: 2 })
+        ));
+
+        assert_eq!(
+            filters.poll(first).unwrap(),
+            vec![H256::repeat_byte(1), H256::repeat_byte(2)]
+        );
+        assert_eq!(filters.poll(first).unwrap(), vec![]);
+        assert_eq!(filters.poll(second).unwrap(), vec![H256::repeat_byte(2)]);
+
+        assert!(filters.uninstall(first));
+        assert!(!filters.uninstall(first));
+        assert!(matches!(
+            filters.poll(first),
+            Err(Error::FilterNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn pending_filters_limit_per_client() {
+        let (sender, _) = broadcast::channel(16);
+        let filters = PendingFilters::new(10, 2, DEFAULT_FILTER_TIMEOUT);
+        let greedy = Some(IpAddr::from([10, 0, 0, 1]));
+        let other = Some(IpAddr::from([10, 0, 0, 2]));
+
+        let first = filters.install(sender.subscribe(), greedy).unwrap();
+        filters.install(sender.subscribe(), greedy).unwrap();
+        assert!(matches!(
+            filters.install(sender.subscribe(), greedy),
+            Err(Error::FiltersLimitReached { limit: 2 })
+        ));
+        // Other clients are not affected.
+        filters.install(sender.subscribe(), other).unwrap();
+        filters.install(sender.subscribe(), None).unwrap();
+
+        assert!(filters.uninstall(first));
+        filters.install(sender.subscribe(), greedy).unwrap();
+    }
+
+    #[test]
+    fn pending_filters_expire() {
+        let (sender, _) = broadcast::channel(16);
+        let filters = PendingFilters::new(10, 10, Duration::from_millis(0));
+        let id = filters.install(sender.subscribe(), None).unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(matches!(
+            filters.poll(id),
+            Err(Error::FilterNotFound { .. })
+        ));
+    }
+}
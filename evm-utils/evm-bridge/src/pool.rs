@@ -1,3 +1,4 @@
+mod lifecycle;
 mod listener;
 
 use {
@@ -5,8 +6,9 @@ use {
     ::tokio::sync::mpsc,
     base64::{engine::general_purpose::STANDARD as BASE64, Engine},
     borsh::BorshSerialize,
-    evm_rpc::{error::into_native_error, Bytes, RPCTransaction},
+    evm_rpc::{error::into_native_error, Bytes, RPCBridgeTxState, RPCNativeTxKind, RPCTransaction},
     evm_state::{Address, TransactionAction, H160, H256, U256},
+    lifecycle::{NativeSignature, RedeployAttempt, TxLifecycle, TxLifecycles},
     listener::PoolListener,
     log::*,
     once_cell::sync::Lazy,
@@ -25,12 +27,12 @@ use {
         system_instruction,
     },
     std::{
-        collections::{HashMap, HashSet},
+        collections::{BTreeMap, HashMap, HashSet},
         ops::Deref,
         sync::{Arc, Mutex},
         time::Duration,
     },
-    tokio::sync::mpsc::error::SendError,
+    tokio::sync::{broadcast, mpsc::error::SendError},
     tracing_attributes::instrument,
     txpool::{
         scoring::Choice, Pool, Readiness, Ready, Scoring, ShouldReplace, VerifiedTransaction,
@@ -42,6 +44,13 @@ type UnixTimeMs = u64;
 /// Loop delay of signature check worker
 const SIG_CHECK_WORKER_PAUSE: Duration = Duration::from_secs(60);
 
+/// Delay after deploy before the signature of transaction, which is not tracked
+/// by post-deploy checks, is requested for the first time
+const SIG_CHECK_DELAY: Duration = Duration::from_secs(30);
+
+/// Delay before the next request of signature, which status is not known yet
+const SIG_RECHECK_DELAY: Duration = Duration::from_secs(300); // = 5 minutes
+
 /// Delay before next loop of cleanup of outdated entries
 /// from hashmap of last deployed transactions
 const CLEANUP_WORKER_PAUSE: Duration = Duration::from_secs(86400); // = 24 hours
@@ -54,12 +63,55 @@ const SENDER_PAUSE: Duration = Duration::from_secs(15);
 /// TODO: adjust value
 const TX_REIMPORT_THRESHOLD: Duration = Duration::from_secs(30);
 
+/// How long lifecycle of transaction is kept after its last update
+const LIFECYCLE_RETENTION: Duration = Duration::from_secs(86400); // = 24 hours
+
+/// Number of transaction hashes, that are buffered for slow pending transaction subscribers
+const PENDING_SUBSCRIPTION_CAPACITY: usize = 1024;
+
+/// Minimal gas price bump (in percents) of transaction, that replaces
+/// transaction with the same nonce, same as in geth
+pub const DEFAULT_PRICE_BUMP_PERCENT: u64 = 10;
+
 #[derive(Debug)]
 pub struct CachedTransaction {
-    evm_tx: evm_state::Transaction,
+    evm_tx: evm_state::SignedTransaction,
+    sender: Address,
     meta_keys: HashSet<Pubkey>,
     cached_at: UnixTimeMs,
     signature: Signature,
+    /// Transaction with the same nonce, that replaced this one, replaced transaction is not redeployed
+    replaced_by: Option<H256>,
+}
+
+/// Deployed transactions, that are waiting for post-deploy checks, indexed by sender and nonce
+#[derive(Debug, Default)]
+struct DeployedTransactions {
+    by_hash: HashMap<H256, CachedTransaction>,
+    /// Last deployed transaction for each sender and nonce
+    by_nonce: HashMap<(Address, U256), H256>,
+}
+
+impl DeployedTransactions {
+    fn insert(&mut self, hash: H256, cached: CachedTransaction) {
+        self.by_nonce
+            .insert((cached.sender, cached.evm_tx.nonce()), hash);
+        self.by_hash.insert(hash, cached);
+    }
+
+    fn remove(&mut self, hash: &H256) -> Option<CachedTransaction> {
+        let cached = self.by_hash.remove(hash)?;
+        let key = (cached.sender, cached.evm_tx.nonce());
+        if self.by_nonce.get(&key) == Some(hash) {
+            self.by_nonce.remove(&key);
+        }
+        Some(cached)
+    }
+
+    fn find_by_nonce(&self, sender: Address, nonce: U256) -> Option<(&H256, &CachedTransaction)> {
+        let hash = self.by_nonce.get(&(sender, nonce))?;
+        self.by_hash.get_key_value(hash)
+    }
 }
 
 /// Abstracting the time source for the testing purposes
@@ -90,6 +142,15 @@ impl<T> Ready<T> for AlwaysReady {
     }
 }
 
+/// Snapshot of pooled transactions, grouped by sender and sorted by nonce
+#[derive(Debug, Default)]
+pub struct PoolContent {
+    /// Transactions, that can be deployed one by one
+    pub pending: BTreeMap<Address, Vec<Arc<PooledTransaction>>>,
+    /// Transactions of paused senders, and transactions after a nonce gap
+    pub queued: BTreeMap<Address, Vec<Arc<PooledTransaction>>>,
+}
+
 #[derive(Debug)]
 pub struct EthPool<C: Clock> {
     /// A pool of transactions, waiting to be deployed
@@ -100,7 +161,16 @@ pub struct EthPool<C: Clock> {
 
     /// List of EVM transactions, which need to be
     /// checked and redeployed in case of error
-    after_deploy_check: Mutex<HashMap<H256, CachedTransaction>>,
+    after_deploy_check: Mutex<DeployedTransactions>,
+
+    /// History of transactions, that was imported into the pool
+    lifecycles: Arc<TxLifecycles>,
+
+    /// Hashes of transactions, that was imported into the pool
+    pending_hashes: broadcast::Sender<H256>,
+
+    /// Replacement policy of transactions with the same nonce
+    scoring: MyScoring,
 
     /// Clock used to determine whether transaction is stalled or ready to be deployed
     clock: C,
@@ -108,20 +178,66 @@ pub struct EthPool<C: Clock> {
 
 impl<C: Clock> EthPool<C> {
     pub fn new(clock: C) -> Self {
+        Self::with_price_bump(clock, DEFAULT_PRICE_BUMP_PERCENT)
+    }
+
+    /// Creates pool, that replaces transaction only by transaction with the same nonce
+    /// and gas price at least `price_bump_percent` percents higher
+    pub fn with_price_bump(clock: C, price_bump_percent: u64) -> Self {
+        let lifecycles = Arc::new(TxLifecycles::default());
+        let (pending_hashes, _) = broadcast::channel(PENDING_SUBSCRIPTION_CAPACITY);
+        let scoring = MyScoring::new(price_bump_percent);
         Self {
-            pool: Mutex::new(Pool::new(PoolListener, MyScoring, Default::default())),
+            pool: Mutex::new(Pool::new(
+                PoolListener::new(lifecycles.clone()),
+                scoring,
+                Default::default(),
+            )),
             last_entry: Mutex::new(HashMap::new()),
-            after_deploy_check: Mutex::new(HashMap::new()),
+            after_deploy_check: Mutex::new(DeployedTransactions::default()),
+            lifecycles,
+            pending_hashes,
+            scoring,
             clock,
         }
     }
 
     /// Imports transaction into the pool
+    ///
+    /// Transaction with the same sender and nonce as already deployed one replaces it
+    /// only if it pays enough, and cancels redeploy of the deployed one.
     pub fn import(
         &self,
         tx: PooledTransaction,
     ) -> Result<Arc<PooledTransaction>, txpool::Error<H256>> {
-        self.pool.lock().unwrap().import(tx, &MyScoring)
+        let hash = tx.hash;
+        let deployed = self.deployed_with_nonce(&tx.sender, tx.nonce());
+        if let Some((deployed_hash, gas_price)) = deployed {
+            if deployed_hash == hash {
+                return Err(txpool::Error::AlreadyImported(hash));
+            }
+            if !self.scoring.is_replacement(gas_price, tx.gas_price()) {
+                return Err(txpool::Error::TooCheapToReplace(deployed_hash, hash));
+            }
+        }
+
+        let imported = self.pool.lock().unwrap().import(tx, &self.scoring)?;
+        self.lifecycles.pooled(hash, self.clock.now());
+        if let Some((deployed_hash, _)) = deployed {
+            info!(
+                "Deployed transaction {} replaced with transaction {}",
+                deployed_hash, hash
+            );
+            self.evict_replaced(&deployed_hash, hash);
+        }
+        // Error only means that there is no subscribers.
+        let _ = self.pending_hashes.send(hash);
+        Ok(imported)
+    }
+
+    /// Subscribes to hashes of transactions, that will be imported into the pool
+    pub fn subscribe_pending_transactions(&self) -> broadcast::Receiver<H256> {
+        self.pending_hashes.subscribe()
     }
 
     /// Prevents pooled transactions from specified sender `address` from processing for certain amount of time
@@ -136,17 +252,49 @@ impl<C: Clock> EthPool<C> {
     }
 
     /// Used for a special case when the transaction was replaced at a time when the worker was already processing it
-    pub fn remove_by_nonce(&self, sender: &Address, nonce: U256) -> Option<Arc<PooledTransaction>> {
-        let hash = {
-            self.pool
-                .lock()
-                .unwrap()
-                .pending_from_sender(AlwaysReady, sender, H256::zero())
-                .find(|tx| &tx.sender == sender && tx.nonce == nonce)
-                .map(|tx| tx.hash)
-        };
+    pub fn find_by_nonce(&self, sender: &Address, nonce: U256) -> Option<Arc<PooledTransaction>> {
+        self.pool
+            .lock()
+            .unwrap()
+            .pending_from_sender(AlwaysReady, sender, H256::zero())
+            .find(|tx| &tx.sender == sender && tx.nonce() == nonce)
+    }
+
+    /// Cancels redeploy of replaced transaction
+    ///
+    /// Already deployed transaction can still land, so it is kept for post-deploy checks,
+    /// and marked as replaced only when replacement lands.
+    pub fn evict_replaced(&self, hash: &H256, by: H256) {
+        let mut after_deploy_check = self.after_deploy_check.lock().unwrap();
+        match after_deploy_check.by_hash.get_mut(hash) {
+            Some(cached) => cached.replaced_by = Some(by),
+            None => self.lifecycles.replaced(hash, by, self.clock.now()),
+        }
+    }
+
+    /// Gets replacement of deployed transaction, if it was replaced
+    pub fn replacement_of(&self, hash: &H256) -> Option<H256> {
+        self.after_deploy_check
+            .lock()
+            .unwrap()
+            .by_hash
+            .get(hash)
+            .and_then(|cached| cached.replaced_by)
+    }
 
-        hash.and_then(|hash| self.remove(&hash))
+    /// Marks deployed transaction as replaced, and drops it from post-deploy checks
+    pub fn transaction_replaced(&self, hash: &H256, by: H256) {
+        self.drop_from_cache(hash);
+        self.lifecycles.replaced(hash, by, self.clock.now())
+    }
+
+    /// Gets hash and gas price of deployed transaction with specified sender and nonce
+    fn deployed_with_nonce(&self, sender: &Address, nonce: U256) -> Option<(H256, U256)> {
+        self.after_deploy_check
+            .lock()
+            .unwrap()
+            .find_by_nonce(*sender, nonce)
+            .map(|(hash, cached)| (*hash, cached.evm_tx.gas_price()))
     }
 
     /// Gets reference to the next transaction in queue ready to be deployed
@@ -175,8 +323,47 @@ impl<C: Clock> EthPool<C> {
             .lock()
             .unwrap()
             .pending_from_sender(AlwaysReady, sender, H256::zero())
-            .max_by_key(|tx| tx.nonce)
-            .map(|tx| tx.nonce + 1)
+            .max_by_key(|tx| tx.nonce())
+            .map(|tx| tx.nonce() + 1)
+    }
+
+    /// Gets all pooled transactions, split into pending and queued ones
+    pub fn content(&self) -> PoolContent {
+        let transactions: Vec<_> = self
+            .pool
+            .lock()
+            .unwrap()
+            .pending(AlwaysReady, H256::zero())
+            .collect();
+
+        let mut by_sender: BTreeMap<Address, Vec<Arc<PooledTransaction>>> = BTreeMap::new();
+        for tx in transactions {
+            by_sender.entry(tx.sender).or_default().push(tx);
+        }
+
+        let now = self.clock.now();
+        let last_entry = self.last_entry.lock().unwrap();
+        let mut content = PoolContent::default();
+        for (sender, mut pending) in by_sender {
+            pending.sort_by_key(|tx| tx.nonce());
+            let paused = matches!(last_entry.get(&sender), Some(stop_before) if now < *stop_before);
+            let ready = if paused {
+                0
+            } else {
+                1 + pending
+                    .windows(2)
+                    .take_while(|txs| txs[1].nonce() == txs[0].nonce() + 1)
+                    .count()
+            };
+            let queued = pending.split_off(ready);
+            if !pending.is_empty() {
+                content.pending.insert(sender, pending);
+            }
+            if !queued.is_empty() {
+                content.queued.insert(sender, queued);
+            }
+        }
+        content
     }
 
     /// Gets transaction from the pool by specified hash
@@ -199,23 +386,27 @@ impl<C: Clock> EthPool<C> {
     /// Adds signature for later tracking of transaction status
     ///
     /// * `hash` - EVM transaction hash
+    /// * `sender` - sender of EVM transaction, used to find its replacements
     /// * `signature` - signature of Solana transaction to be checked for status
     /// * `meta_keys` -
     /// * `evm_tx` - ethereum tx to be redeployed in case of status error
     pub fn schedule_after_deploy_check(
         &self,
         hash: H256,
+        sender: Address,
         signature: Signature,
         meta_keys: HashSet<Pubkey>,
-        evm_tx: evm_state::Transaction,
+        evm_tx: evm_state::SignedTransaction,
     ) {
         let cached_at = self.clock.now();
 
         let cached_tx = CachedTransaction {
             evm_tx,
+            sender,
             meta_keys,
             cached_at,
             signature,
+            replaced_by: None,
         };
 
         self.after_deploy_check
@@ -225,12 +416,13 @@ impl<C: Clock> EthPool<C> {
     }
 
     /// Gets hashes and signatures of transactions needed to be checked for status
-    pub fn get_scheduled_for_check_transactions(&self) -> Vec<(H256, UnixTimeMs)> {
+    pub fn get_scheduled_for_check_transactions(&self) -> Vec<(H256, UnixTimeMs, Signature)> {
         self.after_deploy_check
             .lock()
             .unwrap()
+            .by_hash
             .iter()
-            .map(|(hash, tx)| (*hash, tx.cached_at))
+            .map(|(hash, tx)| (*hash, tx.cached_at, tx.signature))
             .collect()
     }
 
@@ -244,19 +436,80 @@ impl<C: Clock> EthPool<C> {
         self.after_deploy_check.lock().unwrap().remove(hash)
     }
 
-    /// Gets signature of cached transaction
-    pub fn signature_of_cached_transaction(&self, hash: &H256) -> Option<Signature> {
-        self.after_deploy_check
-            .lock()
-            .unwrap()
-            .get(hash)
-            .map(|cached| cached.signature)
+    /// Gets lifecycle of transaction, that was imported into the pool
+    pub fn transaction_lifecycle(&self, hash: &H256) -> Option<TxLifecycle> {
+        self.lifecycles.get(hash)
+    }
+
+    /// Marks transaction as taken by deploy worker
+    pub fn transaction_processing(&self, hash: &H256) {
+        self.lifecycles.processing(hash, self.clock.now())
+    }
+
+    /// Marks transaction, that was left in the pool after recoverable error, as pooled again
+    pub fn transaction_returned(&self, hash: &H256) {
+        self.lifecycles.pooled(*hash, self.clock.now())
+    }
+
+    /// Adds native transaction signature to the lifecycle of transaction
+    pub fn native_signature_sent(&self, hash: &H256, signature: NativeSignature) {
+        self.lifecycles.signature_sent(hash, signature)
+    }
+
+    /// Marks transaction as sent to the network
+    pub fn transaction_deployed(&self, hash: &H256) {
+        let now = self.clock.now();
+        let check_after = now + SIG_CHECK_DELAY.as_millis() as u64;
+        self.lifecycles.deployed(hash, now, check_after)
+    }
+
+    /// Delays the next signature check of deployed transaction
+    pub fn postpone_signature_check(&self, hash: &H256) {
+        let check_after = self.clock.now() + SIG_RECHECK_DELAY.as_millis() as u64;
+        self.lifecycles.postpone_check(hash, check_after)
+    }
+
+    /// Adds redeploy attempt to the lifecycle of transaction
+    pub fn transaction_redeployed(&self, hash: &H256, attempt: RedeployAttempt) {
+        self.lifecycles.redeployed(hash, attempt)
+    }
+
+    /// Marks deployed transaction as landed
+    pub fn transaction_landed(&self, hash: &H256) {
+        self.lifecycles.landed(hash, self.clock.now())
+    }
+
+    /// Marks transaction as failed with specified reason
+    pub fn transaction_failed(&self, hash: &H256, reason: String) {
+        self.lifecycles.failed(hash, self.clock.now(), reason)
+    }
+
+    /// Strips lifecycles, that wasn't updated during retention period, and returns
+    /// the number of elements in the collection before and after the strip
+    pub fn strip_outdated_lifecycles(&self) -> (usize, usize) {
+        let retain_after = self
+            .clock
+            .now()
+            .saturating_sub(LIFECYCLE_RETENTION.as_millis() as u64);
+        self.lifecycles.strip_outdated(retain_after)
+    }
+
+    /// Gets hashes and signatures of deployed transactions, that are not tracked by post-deploy
+    /// checks (e.g. big transactions), so their lifecycle is finished only by status of signature.
+    /// Only transactions, which check deadline has passed, are returned.
+    pub fn deployed_without_check(&self) -> Vec<(H256, Signature)> {
+        let after_deploy_check = self.after_deploy_check.lock().unwrap();
+        self.lifecycles
+            .deployed_signatures(self.clock.now())
+            .into_iter()
+            .filter(|(hash, _)| !after_deploy_check.by_hash.contains_key(hash))
+            .collect()
     }
 }
 
 #[derive(Debug)]
 pub struct PooledTransaction {
-    pub inner: evm::Transaction,
+    pub inner: evm::SignedTransaction,
     pub meta_keys: HashSet<Pubkey>,
     sender: Address,
     hash: H256,
@@ -265,7 +518,7 @@ pub struct PooledTransaction {
 
 impl PooledTransaction {
     pub fn new(
-        transaction: evm::Transaction,
+        transaction: evm::SignedTransaction,
         meta_keys: HashSet<Pubkey>,
         hash_sender: mpsc::Sender<EvmResult<H256>>,
     ) -> Result<Self, evm_state::error::Error> {
@@ -282,7 +535,7 @@ impl PooledTransaction {
     }
 
     pub fn reimported(
-        transaction: evm::Transaction,
+        transaction: evm::SignedTransaction,
         meta_keys: HashSet<Pubkey>,
     ) -> Result<Self, evm_state::error::Error> {
         let hash = transaction.tx_id_hash();
@@ -333,15 +586,36 @@ impl VerifiedTransaction for PooledTransaction {
 }
 
 impl Deref for PooledTransaction {
-    type Target = evm::Transaction;
+    type Target = evm::SignedTransaction;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
     }
 }
 
-#[derive(Debug)]
-pub struct MyScoring;
+#[derive(Debug, Clone, Copy)]
+pub struct MyScoring {
+    /// Minimal gas price increase (in percents) of replacement transaction
+    price_bump_percent: u64,
+}
+
+impl MyScoring {
+    pub fn new(price_bump_percent: u64) -> Self {
+        Self { price_bump_percent }
+    }
+
+    /// Checks that transaction with `new_price` pays enough to replace transaction with `old_price`
+    fn is_replacement(&self, old_price: U256, new_price: U256) -> bool {
+        let bump = old_price.saturating_mul(self.price_bump_percent.into()) / 100;
+        new_price > old_price && new_price >= old_price.saturating_add(bump)
+    }
+}
+
+impl Default for MyScoring {
+    fn default() -> Self {
+        Self::new(DEFAULT_PRICE_BUMP_PERCENT)
+    }
+}
 
 impl Scoring<PooledTransaction> for MyScoring {
     type Score = H256;
@@ -349,12 +623,12 @@ impl Scoring<PooledTransaction> for MyScoring {
     type Event = ();
 
     fn compare(&self, old: &PooledTransaction, other: &PooledTransaction) -> std::cmp::Ordering {
-        old.nonce.cmp(&other.nonce)
+        old.nonce().cmp(&other.nonce())
     }
 
     fn choose(&self, old: &PooledTransaction, new: &PooledTransaction) -> Choice {
-        if old.nonce == new.nonce {
-            if new.gas_price > old.gas_price {
+        if old.nonce() == new.nonce() {
+            if self.is_replacement(old.gas_price(), new.gas_price()) {
                 Choice::ReplaceOld
             } else {
                 Choice::RejectNew
@@ -392,7 +666,7 @@ pub async fn worker_deploy(bridge: Arc<EvmBridge>) {
 
         if let Some(pooled_tx) = tx {
             let hash = pooled_tx.hash;
-            let nonce = pooled_tx.nonce;
+            let nonce = pooled_tx.nonce();
             let sender = pooled_tx.sender;
             let meta_keys = pooled_tx.meta_keys.clone();
             let tx = (*pooled_tx).clone();
@@ -401,6 +675,7 @@ pub async fn worker_deploy(bridge: Arc<EvmBridge>) {
                 &hash, tx
             );
             let cloned_bridge = bridge.clone();
+            bridge.pool.transaction_processing(&hash);
 
             let processed_tx = process_tx(cloned_bridge, tx, hash, sender, meta_keys).await;
 
@@ -420,6 +695,7 @@ pub async fn worker_deploy(bridge: Arc<EvmBridge>) {
                             "Found recoverable error, for tx = {:?}. Error = {}",
                             &hash, &e
                         );
+                        bridge.pool.transaction_returned(&hash);
                         continue;
                     }
 
@@ -427,6 +703,7 @@ pub async fn worker_deploy(bridge: Arc<EvmBridge>) {
                         "Something went wrong in transaction {:?}. Error = {}",
                         &hash, &e
                     );
+                    bridge.pool.transaction_failed(&hash, e.to_string());
                     let _result = pooled_tx.send(Err(e)).await;
                 }
             }
@@ -435,16 +712,15 @@ pub async fn worker_deploy(bridge: Arc<EvmBridge>) {
                 Some(tx) => {
                     info!("Transaction {} removed from the pool", tx.hash)
                 }
-                None => {
-                    match bridge.pool.remove_by_nonce(&sender, nonce) {
-                        Some(dup_tx) => {
-                            info!("Tx was replaced during deploy, duplicate tx with hash = {} removed", dup_tx.hash);
-                        }
-                        None => {
-                            warn!("Transaction from the pool dissapeared mysteriously...")
-                        }
+                None => match bridge.pool.find_by_nonce(&sender, nonce) {
+                    Some(replacement) => {
+                        info!("Tx was replaced during deploy by tx with hash = {}, replaced tx will not be redeployed", replacement.hash);
+                        bridge.pool.evict_replaced(&hash, replacement.hash);
                     }
-                }
+                    None => {
+                        warn!("Transaction from the pool dissapeared mysteriously...")
+                    }
+                },
             }
         } else {
             trace!("Deploy worker is idling...");
@@ -461,6 +737,9 @@ pub async fn worker_cleaner(bridge: Arc<EvmBridge>) {
 
         let (before_strip, after_strip) = bridge.pool.strip_outdated();
         info!("Cleanup of outdated `last deployed` infos. Entries before cleanup: {}, after cleanup: {}", before_strip, after_strip);
+
+        let (before_strip, after_strip) = bridge.pool.strip_outdated_lifecycles();
+        info!("Cleanup of outdated transaction lifecycles. Entries before cleanup: {}, after cleanup: {}", before_strip, after_strip);
     }
 }
 
@@ -472,23 +751,73 @@ pub async fn worker_signature_checker(bridge: Arc<EvmBridge>) {
     loop {
         info!("Worker checks signatures");
 
-        for (hash, generated) in bridge.pool.get_scheduled_for_check_transactions() {
+        let deployed = bridge.pool.deployed_without_check();
+        let signatures: Vec<_> = deployed.iter().map(|(_, signature)| *signature).collect();
+        let statuses = bridge.confirmed_signature_statuses(&signatures).await;
+        for ((hash, _), status) in deployed.into_iter().zip(statuses) {
+            debug!("Checking status of deployed transaction {}", &hash);
+            match status {
+                Some(Ok(())) => bridge.pool.transaction_landed(&hash),
+                Some(Err(err)) => bridge.pool.transaction_failed(&hash, err.to_string()),
+                None => bridge.pool.postpone_signature_check(&hash),
+            }
+        }
+
+        let scheduled = bridge.pool.get_scheduled_for_check_transactions();
+        let signatures: Vec<_> = scheduled
+            .iter()
+            .map(|(_, _, signature)| *signature)
+            .collect();
+        let statuses = bridge.confirmed_signature_statuses(&signatures).await;
+        for ((hash, generated, _), status) in scheduled.into_iter().zip(statuses) {
             debug!("Checking scheduled transaction {}", &hash);
 
             let now = bridge.pool.clock.now();
 
-            match bridge.is_transaction_landed(&hash).await {
+            if let Some(Err(err)) = &status {
+                bridge.pool.transaction_failed(&hash, err.to_string());
+            }
+
+            match bridge.is_transaction_landed(&hash, status.as_ref()).await {
                 Some(true) => {
                     info!("Transaction {} finalized.", &hash);
                     bridge.pool.drop_from_cache(&hash);
+                    bridge.pool.transaction_landed(&hash);
                 }
                 Some(false) | None => {
+                    if let Some(by) = bridge.pool.replacement_of(&hash) {
+                        // Redeploy of replaced transaction is cancelled,
+                        // wait until one of transactions with the same nonce lands.
+                        let replacement_landed = matches!(
+                            bridge.pool.transaction_lifecycle(&by),
+                            Some(lifecycle) if lifecycle.state == RPCBridgeTxState::Landed
+                        );
+                        if replacement_landed {
+                            info!(
+                                "Transaction {} replaced by landed transaction {}",
+                                &hash, &by
+                            );
+                            bridge.pool.transaction_replaced(&hash, by);
+                        } else if now - generated > LIFECYCLE_RETENTION.as_millis() as u64 {
+                            warn!(
+                                "Neither transaction {} nor its replacement {} landed",
+                                &hash, &by
+                            );
+                            bridge.pool.transaction_replaced(&hash, by);
+                        }
+                        continue;
+                    }
                     if now - generated > TX_REIMPORT_THRESHOLD.as_millis() as u64 {
                         info!("Transaction {} needs to redeploy", &hash);
                         let evm_tx = bridge.pool.transaction_for_redeploy(&hash);
                         match evm_tx {
                             Some(cached) => {
                                 warn!("Redeploying transaction {}", &hash);
+                                let mut attempt = RedeployAttempt {
+                                    timestamp: now,
+                                    signature: cached.signature,
+                                    error: None,
+                                };
                                 if let Ok(pooled_tx) =
                                     PooledTransaction::reimported(cached.evm_tx, cached.meta_keys)
                                 {
@@ -504,10 +833,17 @@ pub async fn worker_signature_checker(bridge: Arc<EvmBridge>) {
                                             warn!(
                                                 "Transaction can not be reimported to the pool: {}",
                                                 err
-                                            )
+                                            );
+                                            let reason = format!(
+                                                "Transaction can not be reimported to the pool: {}",
+                                                err
+                                            );
+                                            attempt.error = Some(reason.clone());
+                                            bridge.pool.transaction_failed(&hash, reason);
                                         }
                                     }
                                 }
+                                bridge.pool.transaction_redeployed(&hash, attempt);
                             }
                             None => {
                                 error!("Bug: transaction {} should be present in cache", &hash)
@@ -530,13 +866,12 @@ pub async fn worker_signature_checker(bridge: Arc<EvmBridge>) {
 #[instrument]
 async fn process_tx(
     bridge: Arc<EvmBridge>,
-    tx: evm_state::Transaction,
+    tx: evm_state::SignedTransaction,
     hash: H256,
     sender: H160,
     mut meta_keys: HashSet<Pubkey>,
 ) -> EvmResult<H256> {
-    let mut bytes = vec![];
-    BorshSerialize::serialize(&tx, &mut bytes).unwrap();
+    let bytes = borsh_serialize_tx(&tx).unwrap();
 
     let rpc_tx = RPCTransaction::from_transaction(tx.clone().into())?;
 
@@ -553,6 +888,7 @@ async fn process_tx(
         debug!("Sending tx = {}, by chunks", hash);
         match deploy_big_tx(&bridge, &bridge.key, &tx).await {
             Ok(_tx) => {
+                bridge.pool.transaction_deployed(&hash);
                 return Ok(hash);
             }
             Err(e) => {
@@ -566,17 +902,17 @@ async fn process_tx(
         "Printing tx_info from = {:?}, to = {:?}, nonce = {}, chain_id = {:?}",
         sender,
         tx.address(),
-        tx.nonce,
-        tx.signature.chain_id()
+        tx.nonce(),
+        tx.chain_id()
     );
 
     // Shortcut for swap tokens to native, will add solana account to transaction.
-    if let TransactionAction::Call(addr) = tx.action {
+    if let TransactionAction::Call(addr) = tx.action() {
         use solana_evm_loader_program::precompiles::*;
 
         if addr == *ETH_TO_VLX_ADDR {
             debug!("Found transferToNative transaction");
-            match ETH_TO_VLX_CODE.parse_abi(&tx.input) {
+            match ETH_TO_VLX_CODE.parse_abi(tx.input()) {
                 Ok(pk) => {
                     info!("Adding account to meta = {}", pk);
                     meta_keys.insert(pk);
@@ -626,9 +962,14 @@ async fn process_tx(
         .await
         .map_err(from_client_error)?;
 
+    bridge.pool.native_signature_sent(
+        &hash,
+        NativeSignature::new(signature, RPCNativeTxKind::Execute),
+    );
+    bridge.pool.transaction_deployed(&hash);
     bridge
         .pool
-        .schedule_after_deploy_check(hash, signature, meta_keys, tx);
+        .schedule_after_deploy_check(hash, sender, signature, meta_keys, tx);
 
     Ok(hash)
 }
@@ -637,9 +978,10 @@ async fn process_tx(
 async fn deploy_big_tx(
     bridge: &EvmBridge,
     payer: &solana_sdk::signature::Keypair,
-    tx: &evm::Transaction,
+    tx: &evm::SignedTransaction,
 ) -> EvmResult<()> {
     let payer_pubkey = payer.pubkey();
+    let hash = tx.tx_id_hash();
 
     let storage = solana_sdk::signature::Keypair::new();
     let storage_pubkey = storage.pubkey();
@@ -648,20 +990,97 @@ async fn deploy_big_tx(
 
     debug!("Create new storage {} for EVM tx {:?}", storage_pubkey, tx);
 
-    let tx_bytes = if bridge.borsh_encoding {
-        let mut tx_bytes = vec![];
-        BorshSerialize::serialize(&tx, &mut tx_bytes)
-            .map_err(|e| into_native_error(e, bridge.verbose_errors))?;
-        tx_bytes
+    // Typed transactions can be deployed only using borsh encoding.
+    let borsh_encoding = bridge.borsh_encoding || matches!(tx, evm::SignedTransaction::Typed(_));
+    let tx_bytes = if borsh_encoding {
+        borsh_serialize_tx(tx).map_err(|e| into_native_error(e, bridge.verbose_errors))?
     } else {
-        bincode::serialize(&tx).map_err(|e| into_native_error(e, bridge.verbose_errors))?
+        match tx {
+            evm::SignedTransaction::Legacy(tx) => bincode::serialize(tx),
+            evm::SignedTransaction::Typed(tx) => bincode::serialize(tx),
+        }
+        .map_err(|e| into_native_error(e, bridge.verbose_errors))?
+    };
+
+    let signatures =
+        write_big_tx_storage(bridge, payer, &storage, &tx_bytes, borsh_encoding).await?;
+    for signature in signatures {
+        bridge.pool.native_signature_sent(&hash, signature);
+    }
+
+    let rpc_send_cfg = RpcSendTransactionConfig {
+        skip_preflight: !bridge.simulate,
+        preflight_commitment: Some(CommitmentLevel::Processed),
+        ..Default::default()
     };
 
+    let (blockhash, _height) = bridge
+        .rpc_client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+        .await
+        .map_err(|e| into_native_error(e, bridge.verbose_errors))?
+        .value;
+
+    let instructions = bridge.make_send_big_tx_instructions(tx, storage_pubkey, payer_pubkey);
+    let execute_tx = solana::Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer_pubkey),
+        &signers,
+        blockhash,
+    );
+
+    debug!("Execute EVM transaction at storage {} ...", storage_pubkey);
+
+    match bridge
+        .rpc_client
+        .send_transaction_with_config(&execute_tx, rpc_send_cfg)
+        .await
+    {
+        Ok(signature) => {
+            debug!(
+                "Execute EVM tx at {} was done, signature = {:?}",
+                storage_pubkey, signature
+            );
+            bridge.pool.native_signature_sent(
+                &hash,
+                NativeSignature::with_storage(
+                    signature,
+                    RPCNativeTxKind::ExecuteFromStorage,
+                    storage_pubkey,
+                ),
+            );
+        }
+        Err(e) if e.already_exist_error() => {
+            warn!("Executing EVM tx return AlreadyExist error, handle as executed.");
+        }
+        Err(e) => {
+            error!("Execute EVM tx at {} failed: {}", storage_pubkey, e);
+            return Err(from_client_error(e));
+        }
+    }
+
+    Ok(())
+}
+
+/// Create storage account and write serialized transaction into it by chunks.
+/// Returns signatures of native transactions, that was sent.
+pub(crate) async fn write_big_tx_storage(
+    bridge: &EvmBridge,
+    payer: &solana_sdk::signature::Keypair,
+    storage: &solana_sdk::signature::Keypair,
+    tx_bytes: &[u8],
+    borsh_encoding: bool,
+) -> EvmResult<Vec<NativeSignature>> {
+    let payer_pubkey = payer.pubkey();
+    let storage_pubkey = storage.pubkey();
+    let signers = [payer, storage];
+    let mut signatures = vec![];
+
     debug!(
         "Storage {} : tx bytes size = {}, chunks crc = {:#x}",
         storage_pubkey,
         tx_bytes.len(),
-        TxChunks::new(tx_bytes.as_slice()).crc(),
+        TxChunks::new(tx_bytes).crc(),
     );
 
     let balance = bridge
@@ -685,7 +1104,7 @@ async fn deploy_big_tx(
         &solana_evm_loader_program::ID,
     );
 
-    let allocate_storage_ix = if bridge.borsh_encoding {
+    let allocate_storage_ix = if borsh_encoding {
         solana_evm_loader_program::big_tx_allocate(storage_pubkey, tx_bytes.len())
     } else {
         solana_evm_loader_program::big_tx_allocate_old(storage_pubkey, tx_bytes.len())
@@ -717,7 +1136,12 @@ async fn deploy_big_tx(
             debug!(
                 "Create and allocate {} tx was done, signature = {:?}",
                 storage_pubkey, signature
-            )
+            );
+            signatures.push(NativeSignature::with_storage(
+                signature,
+                RPCNativeTxKind::CreateStorage,
+                storage_pubkey,
+            ));
         }
         Err(e) if e.already_exist_error() => {
             warn!(
@@ -741,7 +1165,7 @@ async fn deploy_big_tx(
         .chunks(evm_state::TX_MTU)
         .enumerate()
         .map(|(i, chunk)| {
-            if bridge.borsh_encoding {
+            if borsh_encoding {
                 solana_evm_loader_program::big_tx_write(
                     storage_pubkey,
                     (i * evm_state::TX_MTU) as u64,
@@ -767,52 +1191,29 @@ async fn deploy_big_tx(
 
     debug!("Write data txs: {:?}", write_data_txs);
 
-    send_and_confirm_transactions(&bridge.rpc_client, write_data_txs, &signers)
-        .await
-        .map(|_| debug!("All write txs for storage {} was done", storage_pubkey))
-        .map_err(|e| {
-            error!("Error on write data to storage {}: {}", storage_pubkey, e);
-            into_native_error(e, bridge.verbose_errors)
-        })?;
-
-    let (blockhash, _height) = bridge
-        .rpc_client
-        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
-        .await
-        .map_err(|e| into_native_error(e, bridge.verbose_errors))?
-        .value;
-
-    let instructions = bridge.make_send_big_tx_instructions(tx, storage_pubkey, payer_pubkey);
-    let execute_tx = solana::Transaction::new_signed_with_payer(
-        &instructions,
-        Some(&payer_pubkey),
-        &signers,
-        blockhash,
-    );
-
-    debug!("Execute EVM transaction at storage {} ...", storage_pubkey);
+    let write_signatures =
+        send_and_confirm_transactions(&bridge.rpc_client, write_data_txs, &signers)
+            .await
+            .map_err(|e| {
+                error!("Error on write data to storage {}: {}", storage_pubkey, e);
+                into_native_error(e, bridge.verbose_errors)
+            })?;
+    debug!("All write txs for storage {} was done", storage_pubkey);
+    signatures.extend(write_signatures.into_iter().map(|signature| {
+        NativeSignature::with_storage(signature, RPCNativeTxKind::WriteStorage, storage_pubkey)
+    }));
+
+    Ok(signatures)
+}
 
-    match bridge
-        .rpc_client
-        .send_transaction_with_config(&execute_tx, rpc_send_cfg)
-        .await
-    {
-        Ok(signature) => {
-            debug!(
-                "Execute EVM tx at {} was done, signature = {:?}",
-                storage_pubkey, signature
-            )
-        }
-        Err(e) if e.already_exist_error() => {
-            warn!("Executing EVM tx return AlreadyExist error, handle as executed.");
-        }
-        Err(e) => {
-            error!("Execute EVM tx at {} failed: {}", storage_pubkey, e);
-            return Err(from_client_error(e));
-        }
+/// Serialize transaction body in the same way as evm_loader expects it in big tx storage.
+fn borsh_serialize_tx(tx: &evm::SignedTransaction) -> std::io::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    match tx {
+        evm::SignedTransaction::Legacy(tx) => BorshSerialize::serialize(tx, &mut bytes)?,
+        evm::SignedTransaction::Typed(tx) => BorshSerialize::serialize(tx, &mut bytes)?,
     }
-
-    Ok(())
+    Ok(bytes)
 }
 
 /// Transactions, deployed with recoverable error result, can be deployed later
@@ -899,7 +1300,11 @@ mod tests {
 
     #[test]
     fn test_pending_queuing() {
-        let mut pool = Pool::new(PoolListener, MyScoring, Default::default());
+        let mut pool = Pool::new(
+            PoolListener::default(),
+            MyScoring::default(),
+            Default::default(),
+        );
 
         import(&mut pool, test_tx(100, 1000, "foo", &SK1));
         import(&mut pool, test_tx(100, 1600, "foo", &SK1));
@@ -910,7 +1315,7 @@ mod tests {
             pool.pending(AlwaysReady, H256::zero())
                 .next()
                 .unwrap()
-                .gas_price,
+                .gas_price(),
             1600.into()
         );
 
@@ -926,7 +1331,11 @@ mod tests {
 
     #[test]
     fn test_readiness() {
-        let mut pool = Pool::new(PoolListener, MyScoring, Default::default());
+        let mut pool = Pool::new(
+            PoolListener::default(),
+            MyScoring::default(),
+            Default::default(),
+        );
 
         import(&mut pool, test_tx(1, 1, "11", &SK1));
         import(&mut pool, test_tx(1, 100, "22", &SK2));
@@ -934,7 +1343,7 @@ mod tests {
         import(&mut pool, test_tx(2, 1, "44", &SK2));
 
         fn only_high_price(tx: &PooledTransaction) -> Readiness {
-            if tx.gas_price > 1.into() {
+            if tx.gas_price() > 1.into() {
                 Readiness::Ready
             } else {
                 Readiness::Stale
@@ -959,7 +1368,7 @@ mod tests {
         pool.import(test_tx(1, 100, "55", &SK3)).unwrap();
 
         let next = pool.pending().unwrap();
-        assert_eq!(next.input, "11".as_bytes());
+        assert_eq!(next.input(), "11".as_bytes());
         assert_eq!(pool.strip_outdated(), (0, 0));
 
         pool.pause_processing(&next.sender, TICK);
@@ -967,14 +1376,14 @@ mod tests {
         assert_eq!(pool.strip_outdated(), (1, 1));
 
         let next = pool.pending().unwrap();
-        assert_eq!(next.input, "33".as_bytes());
+        assert_eq!(next.input(), "33".as_bytes());
 
         pool.pause_processing(&next.sender, TICK);
         pool.remove(&next.hash);
         assert_eq!(pool.strip_outdated(), (2, 2));
 
         let next = pool.pending().unwrap();
-        assert_eq!(next.input, "55".as_bytes());
+        assert_eq!(next.input(), "55".as_bytes());
 
         pool.pause_processing(&next.sender, TICK);
         pool.remove(&next.hash);
@@ -987,7 +1396,7 @@ mod tests {
         assert!(pool.pending().is_some());
 
         let next = pool.pending().unwrap();
-        assert_eq!(next.input, "22".as_bytes());
+        assert_eq!(next.input(), "22".as_bytes());
         assert_eq!(pool.strip_outdated(), (3, 0));
     }
 
@@ -1012,15 +1421,259 @@ mod tests {
         );
         assert!(pool.remove(&next.hash).is_none());
 
-        let removed = pool.remove_by_nonce(&next.sender, next.nonce);
+        let replacement = pool.find_by_nonce(&next.sender, next.nonce());
 
-        assert!(removed.is_some());
+        assert!(replacement.is_some());
+        // Replacement is still waiting for deploy.
         assert_eq!(
             pool.pool.lock().unwrap().light_status().transaction_count,
-            0
+            1
         );
     }
 
+    #[test]
+    fn test_replacement_price_bump() {
+        let test_clock = Arc::new(Mutex::new(TestClock { now: 0 }));
+        let pool = EthPool::new(test_clock.clone());
+
+        pool.import(test_tx(1, 100, "11", &SK1)).unwrap();
+        assert!(matches!(
+            pool.import(test_tx(1, 109, "12", &SK1)),
+            Err(txpool::Error::TooCheapToReplace(..))
+        ));
+        pool.import(test_tx(1, 110, "13", &SK1)).unwrap();
+        assert_eq!(pool.pending().unwrap().input(), "13".as_bytes());
+
+        let pool = EthPool::with_price_bump(test_clock, 0);
+        pool.import(test_tx(1, 100, "11", &SK1)).unwrap();
+        assert!(matches!(
+            pool.import(test_tx(1, 100, "12", &SK1)),
+            Err(txpool::Error::TooCheapToReplace(..))
+        ));
+        pool.import(test_tx(1, 101, "13", &SK1)).unwrap();
+        assert_eq!(pool.pending().unwrap().input(), "13".as_bytes());
+    }
+
+    #[test]
+    fn test_replacing_deployed_transaction() {
+        let test_clock = Arc::new(Mutex::new(TestClock { now: 0 }));
+        let pool = EthPool::new(test_clock.clone());
+        let signature = Signature::new(&[1; 64]);
+
+        let deploy = |pool: &EthPool<_>, tx: &PooledTransaction| {
+            pool.transaction_processing(&tx.hash);
+            pool.transaction_deployed(&tx.hash);
+            pool.schedule_after_deploy_check(
+                tx.hash,
+                tx.sender,
+                signature,
+                HashSet::new(),
+                tx.inner.clone(),
+            );
+        };
+
+        let deployed = pool.import(test_tx(1, 100, "11", &SK1)).unwrap();
+        deploy(&pool, &deployed);
+        pool.remove(&deployed.hash);
+
+        // Same transaction is not deployed twice.
+        let same = PooledTransaction::reimported(deployed.inner.clone(), HashSet::new()).unwrap();
+        assert!(matches!(
+            pool.import(same),
+            Err(txpool::Error::AlreadyImported(hash)) if hash == deployed.hash
+        ));
+        assert!(matches!(
+            pool.import(test_tx(1, 105, "12", &SK1)),
+            Err(txpool::Error::TooCheapToReplace(old, _)) if old == deployed.hash
+        ));
+        assert_eq!(pool.get_scheduled_for_check_transactions().len(), 1);
+
+        test_clock.lock().unwrap().now = 10;
+        let replacement = pool.import(test_tx(1, 200, "13", &SK1)).unwrap();
+        // Replaced transaction can still land, so only its redeploy is cancelled.
+        assert_eq!(pool.get_scheduled_for_check_transactions().len(), 1);
+        assert_eq!(pool.replacement_of(&deployed.hash), Some(replacement.hash));
+        assert_eq!(
+            pool.transaction_lifecycle(&deployed.hash).unwrap().state,
+            RPCBridgeTxState::Deployed
+        );
+        assert_eq!(pool.pending().unwrap().hash, replacement.hash);
+
+        // Replacement is deployed, and next transactions with the same nonce are compared to it.
+        test_clock.lock().unwrap().now = 20;
+        deploy(&pool, &replacement);
+        pool.remove(&replacement.hash);
+        assert!(matches!(
+            pool.import(test_tx(1, 210, "14", &SK1)),
+            Err(txpool::Error::TooCheapToReplace(old, _)) if old == replacement.hash
+        ));
+        pool.transaction_landed(&replacement.hash);
+        pool.transaction_replaced(&deployed.hash, replacement.hash);
+        assert!(pool.transaction_for_redeploy(&deployed.hash).is_none());
+        let lifecycle = pool.transaction_lifecycle(&deployed.hash).unwrap();
+        assert_eq!(lifecycle.state, RPCBridgeTxState::Dropped);
+        assert_eq!(lifecycle.updated_at, 20);
+        assert_eq!(
+            lifecycle.error,
+            Some(format!("Replaced by transaction {:?}", replacement.hash))
+        );
+        assert!(pool.transaction_for_redeploy(&replacement.hash).is_some());
+
+        // Transaction is replaced while deploy worker is processing it.
+        let processing = pool.import(test_tx(2, 100, "21", &SK2)).unwrap();
+        pool.transaction_processing(&processing.hash);
+        let replacement = pool.import(test_tx(2, 150, "22", &SK2)).unwrap();
+        deploy(&pool, &processing);
+        assert!(pool.remove(&processing.hash).is_none());
+        let found = pool
+            .find_by_nonce(&processing.sender, processing.nonce())
+            .unwrap();
+        assert_eq!(found.hash, replacement.hash);
+        pool.evict_replaced(&processing.hash, found.hash);
+
+        assert_eq!(
+            pool.replacement_of(&processing.hash),
+            Some(replacement.hash)
+        );
+        assert_eq!(
+            pool.transaction_lifecycle(&processing.hash).unwrap().state,
+            RPCBridgeTxState::Deployed
+        );
+        assert!(pool.transaction_by_hash(replacement.hash).is_some());
+
+        // Transaction, that wasn't deployed, is replaced immediately.
+        let pooled = pool.import(test_tx(3, 100, "31", &SK3)).unwrap();
+        pool.evict_replaced(&pooled.hash, replacement.hash);
+        assert_eq!(
+            pool.transaction_lifecycle(&pooled.hash).unwrap().state,
+            RPCBridgeTxState::Dropped
+        );
+    }
+
+    #[test]
+    fn test_transaction_lifecycle() {
+        let test_clock = Arc::new(Mutex::new(TestClock { now: 0 }));
+        let pool = EthPool::new(test_clock.clone());
+
+        let replaced = pool.import(test_tx(1, 100, "11", &SK1)).unwrap().hash;
+        let lifecycle = pool.transaction_lifecycle(&replaced).unwrap();
+        assert_eq!(lifecycle.state, RPCBridgeTxState::Pooled);
+
+        test_clock.lock().unwrap().now = 10;
+        let hash = pool.import(test_tx(1, 200, "11", &SK1)).unwrap().hash;
+        let lifecycle = pool.transaction_lifecycle(&replaced).unwrap();
+        assert_eq!(lifecycle.state, RPCBridgeTxState::Dropped);
+        assert_eq!(
+            lifecycle.error,
+            Some(format!("Replaced by transaction {:?}", hash))
+        );
+
+        let next = pool.pending().unwrap();
+        assert_eq!(next.hash, hash);
+        pool.transaction_processing(&hash);
+        let signature = Signature::new(&[1; 64]);
+        pool.native_signature_sent(
+            &hash,
+            NativeSignature::new(signature, RPCNativeTxKind::Execute),
+        );
+        pool.transaction_deployed(&hash);
+        // Removal of deployed transaction from the pool is not a drop.
+        pool.remove(&hash);
+        let lifecycle = pool.transaction_lifecycle(&hash).unwrap();
+        assert_eq!(lifecycle.state, RPCBridgeTxState::Deployed);
+        assert_eq!(lifecycle.execute_signature(), Some(signature));
+        // Deployed transaction without post-deploy check is tracked by its signature,
+        // once its check deadline has passed.
+        assert!(pool.deployed_without_check().is_empty());
+        test_clock.lock().unwrap().now = 10 + SIG_CHECK_DELAY.as_millis() as u64;
+        assert_eq!(pool.deployed_without_check(), vec![(hash, signature)]);
+        pool.postpone_signature_check(&hash);
+        assert!(pool.deployed_without_check().is_empty());
+        test_clock.lock().unwrap().now += SIG_RECHECK_DELAY.as_millis() as u64;
+        assert_eq!(pool.deployed_without_check(), vec![(hash, signature)]);
+        pool.schedule_after_deploy_check(
+            hash,
+            next.sender,
+            signature,
+            HashSet::new(),
+            next.inner.clone(),
+        );
+        assert!(pool.deployed_without_check().is_empty());
+        pool.drop_from_cache(&hash);
+
+        let redeployed_at = test_clock.lock().unwrap().now + 10;
+        test_clock.lock().unwrap().now = redeployed_at;
+        pool.transaction_redeployed(
+            &hash,
+            RedeployAttempt {
+                timestamp: redeployed_at,
+                signature,
+                error: None,
+            },
+        );
+        pool.import(PooledTransaction::reimported(next.inner.clone(), HashSet::new()).unwrap())
+            .unwrap();
+        pool.transaction_processing(&hash);
+        pool.remove(&hash);
+        pool.transaction_failed(&hash, "Something went wrong".to_string());
+
+        let rpc = pool
+            .transaction_lifecycle(&hash)
+            .unwrap()
+            .to_rpc(hash, None);
+        assert_eq!(rpc.state, RPCBridgeTxState::Failed);
+        assert_eq!(rpc.pooled_at, 10);
+        assert_eq!(rpc.native_signatures.len(), 1);
+        assert_eq!(rpc.redeploy_attempts.len(), 1);
+        assert_eq!(rpc.error, Some("Something went wrong".to_string()));
+
+        test_clock.lock().unwrap().now = redeployed_at + LIFECYCLE_RETENTION.as_millis() as u64;
+        assert_eq!(pool.strip_outdated_lifecycles(), (2, 1));
+        assert!(pool.transaction_lifecycle(&replaced).is_none());
+        assert!(pool.transaction_lifecycle(&hash).is_some());
+    }
+
+    #[test]
+    fn test_pool_content() {
+        let test_clock = Arc::new(Mutex::new(TestClock { now: 0 }));
+        let pool = EthPool::new(test_clock.clone());
+        let mut subscription = pool.subscribe_pending_transactions();
+        let sender1 = test_tx(1, 100, "", &SK1).sender;
+        let sender2 = test_tx(1, 100, "", &SK2).sender;
+
+        let tx = test_tx(1, 100, "11", &SK1);
+        let first_hash = tx.hash;
+        pool.import(tx).unwrap();
+        pool.import(test_tx(2, 100, "12", &SK1)).unwrap();
+        pool.import(test_tx(4, 100, "14", &SK1)).unwrap();
+        pool.import(test_tx(1, 100, "21", &SK2)).unwrap();
+        pool.pause_processing(&sender2, Duration::from_millis(100));
+
+        assert_eq!(subscription.try_recv().unwrap(), first_hash);
+        let mut received = 1;
+        while subscription.try_recv().is_ok() {
+            received += 1;
+        }
+        assert_eq!(received, 4);
+
+        let msgs = |txs: &Vec<Arc<PooledTransaction>>| {
+            txs.iter()
+                .map(|tx| String::from_utf8(tx.input().to_vec()).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let content = pool.content();
+        assert_eq!(msgs(&content.pending[&sender1]), vec!["11", "12"]);
+        assert_eq!(msgs(&content.queued[&sender1]), vec!["14"]);
+        assert_eq!(msgs(&content.queued[&sender2]), vec!["21"]);
+        assert!(!content.pending.contains_key(&sender2));
+
+        test_clock.lock().unwrap().now = 100;
+        let content = pool.content();
+        assert_eq!(msgs(&content.pending[&sender2]), vec!["21"]);
+        assert!(!content.queued.contains_key(&sender2));
+    }
+
     fn test_tx(nonce: u32, gas_price: u32, msg: &str, secret_key: &[u8; 32]) -> PooledTransaction {
         let tx_create = evm::UnsignedTransaction {
             nonce: nonce.into(),
@@ -1034,7 +1687,12 @@ mod tests {
         let secret_key: evm_state::SecretKey = evm::SecretKey::from_slice(secret_key).unwrap();
 
         let (tx, _) = mpsc::channel(1);
-        PooledTransaction::new(tx_create.sign(&secret_key, Some(111)), HashSet::new(), tx).unwrap()
+        PooledTransaction::new(
+            tx_create.sign(&secret_key, Some(111)).into(),
+            HashSet::new(),
+            tx,
+        )
+        .unwrap()
     }
 
     fn import(pool: &mut Pool, tx: PooledTransaction) {
@@ -1046,7 +1704,7 @@ mod tests {
         R: Ready<PooledTransaction>,
     {
         pool.pending(ready, H256::zero())
-            .map(|tx| String::from_utf8(tx.input.clone()).unwrap())
+            .map(|tx| String::from_utf8(tx.input().to_vec()).unwrap())
             .collect()
     }
 }
@@ -0,0 +1,230 @@
+use {
+    super::UnixTimeMs,
+    evm_rpc::{
+        RPCBridgeTxState, RPCNativeSignature, RPCNativeTxKind, RPCRedeployAttempt,
+        RPCTransactionLifecycle,
+    },
+    evm_state::H256,
+    solana_sdk::{pubkey::Pubkey, signature::Signature},
+    std::{collections::HashMap, sync::Mutex},
+};
+
+#[derive(Debug, Clone)]
+pub struct NativeSignature {
+    pub signature: Signature,
+    pub kind: RPCNativeTxKind,
+    pub storage_account: Option<Pubkey>,
+}
+
+impl NativeSignature {
+    pub fn new(signature: Signature, kind: RPCNativeTxKind) -> Self {
+        Self {
+            signature,
+            kind,
+            storage_account: None,
+        }
+    }
+
+    pub fn with_storage(signature: Signature, kind: RPCNativeTxKind, storage: Pubkey) -> Self {
+        Self {
+            signature,
+            kind,
+            storage_account: Some(storage),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RedeployAttempt {
+    pub timestamp: UnixTimeMs,
+    pub signature: Signature,
+    pub error: Option<String>,
+}
+
+/// History of single evm transaction, from import into mempool till landing or drop.
+#[derive(Debug, Clone)]
+pub struct TxLifecycle {
+    pub state: RPCBridgeTxState,
+    pub pooled_at: UnixTimeMs,
+    pub updated_at: UnixTimeMs,
+    pub signatures: Vec<NativeSignature>,
+    pub redeploys: Vec<RedeployAttempt>,
+    pub error: Option<String>,
+    /// Signature of deployed transaction isn't checked before this moment.
+    pub check_after: UnixTimeMs,
+}
+
+impl TxLifecycle {
+    /// Signature of last native transaction, that execute evm transaction.
+    pub fn execute_signature(&self) -> Option<Signature> {
+        self.signatures
+            .iter()
+            .rev()
+            .find(|s| {
+                matches!(
+                    s.kind,
+                    RPCNativeTxKind::Execute | RPCNativeTxKind::ExecuteFromStorage
+                )
+            })
+            .map(|s| s.signature)
+    }
+
+    pub fn to_rpc(&self, hash: H256, commitment: Option<String>) -> RPCTransactionLifecycle {
+        RPCTransactionLifecycle {
+            hash,
+            state: self.state,
+            pooled_at: self.pooled_at,
+            native_signatures: self
+                .signatures
+                .iter()
+                .map(|s| RPCNativeSignature {
+                    signature: s.signature.to_string(),
+                    kind: s.kind,
+                    storage_account: s.storage_account.map(|pk| pk.to_string()),
+                })
+                .collect(),
+            redeploy_attempts: self
+                .redeploys
+                .iter()
+                .map(|r| RPCRedeployAttempt {
+                    timestamp: r.timestamp,
+                    signature: r.signature.to_string(),
+                    error: r.error.clone(),
+                })
+                .collect(),
+            commitment,
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// Lifecycles of transactions, that was sent through the bridge.
+#[derive(Debug, Default)]
+pub struct TxLifecycles {
+    entries: Mutex<HashMap<H256, TxLifecycle>>,
+}
+
+impl TxLifecycles {
+    /// Starts new lifecycle, or continues existing one if transaction was reimported.
+    pub fn pooled(&self, hash: H256, now: UnixTimeMs) {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(hash).or_insert_with(|| TxLifecycle {
+            state: RPCBridgeTxState::Pooled,
+            pooled_at: now,
+            updated_at: now,
+            signatures: vec![],
+            redeploys: vec![],
+            error: None,
+            check_after: now,
+        });
+        entry.state = RPCBridgeTxState::Pooled;
+        entry.updated_at = now;
+        entry.error = None;
+    }
+
+    pub fn processing(&self, hash: &H256, now: UnixTimeMs) {
+        self.update(hash, |entry| {
+            entry.state = RPCBridgeTxState::Processing;
+            entry.updated_at = now;
+        })
+    }
+
+    pub fn signature_sent(&self, hash: &H256, signature: NativeSignature) {
+        self.update(hash, |entry| entry.signatures.push(signature))
+    }
+
+    pub fn deployed(&self, hash: &H256, now: UnixTimeMs, check_after: UnixTimeMs) {
+        self.update(hash, |entry| {
+            entry.state = RPCBridgeTxState::Deployed;
+            entry.updated_at = now;
+            entry.check_after = check_after;
+        })
+    }
+
+    /// Postpones signature check of deployed transaction, which status is not known yet.
+    pub fn postpone_check(&self, hash: &H256, check_after: UnixTimeMs) {
+        self.update(hash, |entry| entry.check_after = check_after)
+    }
+
+    pub fn redeployed(&self, hash: &H256, attempt: RedeployAttempt) {
+        self.update(hash, |entry| {
+            entry.updated_at = attempt.timestamp;
+            entry.redeploys.push(attempt);
+        })
+    }
+
+    pub fn landed(&self, hash: &H256, now: UnixTimeMs) {
+        self.update(hash, |entry| {
+            entry.state = RPCBridgeTxState::Landed;
+            entry.updated_at = now;
+        })
+    }
+
+    /// Marks transaction as removed from mempool without deploy.
+    pub fn dropped(&self, hash: &H256, reason: String) {
+        self.update(hash, |entry| {
+            // Transaction can be removed from pool after it was already deployed.
+            if entry.state == RPCBridgeTxState::Pooled {
+                entry.state = RPCBridgeTxState::Dropped;
+                entry.error = Some(reason);
+            }
+        })
+    }
+
+    /// Marks transaction as replaced by transaction with the same nonce, unless it already finished.
+    pub fn replaced(&self, hash: &H256, by: H256, now: UnixTimeMs) {
+        self.update(hash, |entry| {
+            if matches!(
+                entry.state,
+                RPCBridgeTxState::Pooled
+                    | RPCBridgeTxState::Processing
+                    | RPCBridgeTxState::Deployed
+            ) {
+                entry.state = RPCBridgeTxState::Dropped;
+                entry.updated_at = now;
+                entry.error = Some(format!("Replaced by transaction {:?}", by));
+            }
+        })
+    }
+
+    pub fn failed(&self, hash: &H256, now: UnixTimeMs, reason: String) {
+        self.update(hash, |entry| {
+            entry.state = RPCBridgeTxState::Failed;
+            entry.updated_at = now;
+            entry.error = Some(reason);
+        })
+    }
+
+    /// Hashes and execute signatures of transactions, that are deployed but not finished yet,
+    /// and which check deadline has passed.
+    pub fn deployed_signatures(&self, now: UnixTimeMs) -> Vec<(H256, Signature)> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, entry)| {
+                entry.state == RPCBridgeTxState::Deployed && entry.check_after <= now
+            })
+            .filter_map(|(hash, entry)| Some((*hash, entry.execute_signature()?)))
+            .collect()
+    }
+
+    pub fn get(&self, hash: &H256) -> Option<TxLifecycle> {
+        self.entries.lock().unwrap().get(hash).cloned()
+    }
+
+    /// Removes lifecycles, that wasn't updated since `before`, returns number of
+    /// entries before and after the strip.
+    pub fn strip_outdated(&self, before: UnixTimeMs) -> (usize, usize) {
+        let mut entries = self.entries.lock().unwrap();
+        let before_strip = entries.len();
+        entries.retain(|_, entry| entry.updated_at >= before);
+        (before_strip, entries.len())
+    }
+
+    fn update(&self, hash: &H256, f: impl FnOnce(&mut TxLifecycle)) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(hash) {
+            f(entry)
+        }
+    }
+}
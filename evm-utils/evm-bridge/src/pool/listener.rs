@@ -1,5 +1,5 @@
 use {
-    super::PooledTransaction,
+    super::{lifecycle::TxLifecycles, PooledTransaction},
     log::*,
     std::{
         fmt::{Debug, LowerHex},
@@ -9,10 +9,18 @@ use {
     txpool::Listener,
 };
 
-#[derive(Debug)]
-pub struct PoolListener;
+#[derive(Debug, Default)]
+pub struct PoolListener {
+    lifecycles: Arc<TxLifecycles>,
+}
+
 impl PoolListener {
-    fn notify_tx_removed(&self, tx: &Arc<PooledTransaction>) {
+    pub fn new(lifecycles: Arc<TxLifecycles>) -> Self {
+        Self { lifecycles }
+    }
+
+    fn notify_tx_removed(&self, tx: &Arc<PooledTransaction>, reason: String) {
+        self.lifecycles.dropped(&tx.hash, reason);
         if let Ok(handle) = Handle::try_current() {
             let tx = tx.clone();
             handle.spawn(async move {
@@ -41,7 +49,7 @@ impl Listener<PooledTransaction> for PoolListener {
                 "Transaction {} replaced with transaction {}",
                 old.hash, tx.hash
             );
-            self.notify_tx_removed(old)
+            self.notify_tx_removed(old, format!("Replaced by transaction {:?}", tx.hash))
         }
     }
 
@@ -51,26 +59,30 @@ impl Listener<PooledTransaction> for PoolListener {
         reason: &txpool::Error<H>,
     ) {
         debug!("PoolListener::rejected: tx = {:?}, reason = {}", tx, reason);
-        self.notify_tx_removed(tx)
+        self.notify_tx_removed(tx, format!("Rejected by mempool: {}", reason))
     }
 
     fn dropped(&mut self, tx: &Arc<PooledTransaction>, by: Option<&PooledTransaction>) {
         debug!("PoolListener::dropped: tx = {:?}, by = {:?}", tx, by);
-        self.notify_tx_removed(tx)
+        let reason = match by {
+            Some(by) => format!("Replaced by transaction {:?}", by.hash),
+            None => "Dropped from mempool".to_string(),
+        };
+        self.notify_tx_removed(tx, reason)
     }
 
     fn invalid(&mut self, tx: &Arc<PooledTransaction>) {
         debug!("PoolListener::invalid: tx = {:?}", tx);
-        self.notify_tx_removed(tx)
+        self.notify_tx_removed(tx, "Invalid transaction".to_string())
     }
 
     fn canceled(&mut self, tx: &Arc<PooledTransaction>) {
         debug!("PoolListener::canceled: tx = {:?}", tx);
-        self.notify_tx_removed(tx)
+        self.notify_tx_removed(tx, "Removed from mempool".to_string())
     }
 
     fn culled(&mut self, tx: &Arc<PooledTransaction>) {
         debug!("PoolListener::culled: tx = {:?}", tx);
-        self.notify_tx_removed(tx)
+        self.notify_tx_removed(tx, "Culled from mempool".to_string())
     }
 }
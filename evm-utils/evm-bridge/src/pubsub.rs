@@ -0,0 +1,218 @@
+//! Websocket subscriptions, that are served by the bridge itself.
+//!
+//! Any other request to websocket endpoint is handled the same way as http requests.
+
+use {
+    crate::{middleware::ProxyMiddleware, BridgeMeta, EvmBridge},
+    evm_state::H256,
+    jsonrpc_core::{
+        futures_util::future::Either, middleware::NoopCallFuture, Call, Error, ErrorCode,
+        FutureResponse, MetaIoHandler, Metadata, Middleware, Request, Response, Result, Value,
+    },
+    jsonrpc_derive::rpc,
+    jsonrpc_pubsub::{typed::Subscriber, PubSubMetadata, Session, SubscriptionId},
+    log::*,
+    std::{
+        collections::HashMap,
+        future::Future,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    },
+    tokio::{
+        runtime::Handle,
+        sync::{broadcast::error::RecvError, oneshot},
+    },
+};
+
+pub const NEW_PENDING_TRANSACTIONS: &str = "newPendingTransactions";
+
+const SUBSCRIPTION_METHODS: &[&str] = &["eth_subscribe", "eth_unsubscribe"];
+
+#[derive(Clone)]
+pub struct BridgeSession {
+    pub bridge: Arc<EvmBridge>,
+    /// Id of websocket connection, subscriptions are visible only inside connection.
+    pub session_id: u64,
+    pub session: Option<Arc<Session>>,
+}
+
+impl Metadata for BridgeSession {}
+
+impl PubSubMetadata for BridgeSession {
+    fn session(&self) -> Option<Arc<Session>> {
+        self.session.clone()
+    }
+}
+
+/// Passes subscription calls further, and redirects other requests into handler of http endpoint.
+pub struct SubscriptionMiddleware {
+    io: MetaIoHandler<BridgeMeta, ProxyMiddleware>,
+}
+
+impl SubscriptionMiddleware {
+    pub fn new(io: MetaIoHandler<BridgeMeta, ProxyMiddleware>) -> Self {
+        Self { io }
+    }
+}
+
+impl Middleware<BridgeSession> for SubscriptionMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = NoopCallFuture;
+
+    fn on_request<F, X>(
+        &self,
+        request: Request,
+        meta: BridgeSession,
+        next: F,
+    ) -> Either<Self::Future, X>
+    where
+        F: Fn(Request, BridgeSession) -> X + Send + Sync,
+        X: Future<Output = Option<Response>> + Send + 'static,
+    {
+        let is_subscription = matches!(
+            &request,
+            Request::Single(Call::MethodCall(call))
+                if SUBSCRIPTION_METHODS.contains(&call.method.as_str())
+        );
+        if is_subscription {
+            Either::Right(next(request, meta))
+        } else {
+            Either::Left(Box::pin(
+                self.io.handle_rpc_request(request, meta.bridge.into()),
+            ))
+        }
+    }
+}
+
+#[allow(clippy::needless_return)]
+#[rpc(server)]
+pub trait BridgePubSub {
+    type Metadata;
+
+    #[pubsub(subscription = "eth_subscription", subscribe, name = "eth_subscribe")]
+    fn subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<H256>,
+        kind: String,
+        params: Option<Value>,
+    );
+
+    #[pubsub(
+        subscription = "eth_subscription",
+        unsubscribe,
+        name = "eth_unsubscribe"
+    )]
+    fn unsubscribe(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+}
+
+type ActiveSubscriptions = Arc<Mutex<HashMap<(u64, SubscriptionId), oneshot::Sender<()>>>>;
+
+pub struct BridgePubSubImpl {
+    runtime: Handle,
+    next_id: AtomicU64,
+    /// Stop signals of notification tasks, by session and subscription id.
+    active: ActiveSubscriptions,
+}
+
+impl BridgePubSubImpl {
+    /// Notification tasks are spawned on `runtime`, because websocket server has its own threads.
+    pub fn new(runtime: Handle) -> Self {
+        Self {
+            runtime,
+            next_id: AtomicU64::new(1),
+            active: Default::default(),
+        }
+    }
+}
+
+impl BridgePubSub for BridgePubSubImpl {
+    type Metadata = BridgeSession;
+
+    fn subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<H256>,
+        kind: String,
+        _params: Option<Value>,
+    ) {
+        if kind != NEW_PENDING_TRANSACTIONS {
+            // Node subscriptions are available only on websocket endpoint of the node.
+            let _ = subscriber.reject(Error {
+                code: ErrorCode::InvalidParams,
+                message: format!("Unsupported subscription: {}", kind),
+                data: None,
+            });
+            return;
+        }
+
+        let id = SubscriptionId::Number(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let sink = match subscriber.assign_id(id.clone()) {
+            Ok(sink) => sink,
+            Err(()) => return,
+        };
+        let key = (meta.session_id, id.clone());
+        let (stop_sender, mut stop) = oneshot::channel();
+        self.active.lock().unwrap().insert(key.clone(), stop_sender);
+
+        // Stop notifications when connection is closed, even if client never unsubscribed.
+        if let Some(session) = &meta.session {
+            let active = self.active.clone();
+            let key = key.clone();
+            session.on_drop(move || stop_subscription(&active, &key));
+        }
+
+        let mut pending = meta.bridge.pool.subscribe_pending_transactions();
+        let active = self.active.clone();
+        self.runtime.spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut stop => break,
+                    hash = pending.recv() => match hash {
+                        Ok(hash) => {
+                            if sink.notify(Ok(hash)).is_err() {
+                                debug!("Subscriber {:?} disconnected", id);
+                                break;
+                            }
+                        }
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!("Subscriber {:?} missed {} pending transactions", id, skipped);
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                }
+            }
+            active.lock().unwrap().remove(&key);
+        });
+    }
+
+    fn unsubscribe(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        // Subscription can be cancelled only from connection, that created it.
+        let stopped = match meta {
+            Some(meta) => stop_subscription(&self.active, &(meta.session_id, id)),
+            None => false,
+        };
+        if stopped {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid subscription id.".into(),
+                data: None,
+            })
+        }
+    }
+}
+
+/// Sends stop signal to notification task, returns false if subscription wasn't active.
+fn stop_subscription(active: &ActiveSubscriptions, key: &(u64, SubscriptionId)) -> bool {
+    match active.lock().unwrap().remove(key) {
+        Some(stop) => {
+            let _ = stop.send(());
+            true
+        }
+        None => false,
+    }
+}
@@ -215,11 +215,25 @@ impl AsyncRpcClient {
             .await
     }
 
+    pub async fn get_evm_gas_price(&self) -> ClientResult<U256> {
+        self.send(RpcRequest::EthGasPrice, json!([])).await
+    }
+
+    pub async fn get_evm_max_priority_fee_per_gas(&self) -> ClientResult<U256> {
+        self.send(RpcRequest::EthMaxPriorityFeePerGas, json!([]))
+            .await
+    }
+
     pub async fn get_evm_transaction_count(&self, address: &Address) -> ClientResult<U256> {
         self.send(RpcRequest::EthGetTransactionCount, json!([*address]))
             .await
     }
 
+    pub async fn get_evm_balance(&self, address: &Address) -> ClientResult<U256> {
+        self.send(RpcRequest::EthGetBalance, json!([*address]))
+            .await
+    }
+
     pub async fn get_evm_transaction_receipt(
         &self,
         hash: &H256,
@@ -1,6 +1,6 @@
 use {
     evm_rpc::Bytes,
-    evm_state::{Address, Transaction, TransactionAction},
+    evm_state::{Address, SignedTransaction, TransactionAction},
     serde::Deserialize,
 };
 
@@ -13,14 +13,14 @@ pub enum TxFilter {
 }
 
 impl TxFilter {
-    pub fn is_match(&self, tx: &Transaction) -> bool {
+    pub fn is_match(&self, tx: &SignedTransaction) -> bool {
         match self {
             Self::InputStartsWith {
                 contract,
                 input_prefix,
             } => {
-                matches!(tx.action, TransactionAction::Call(addr) if addr == *contract)
-                    && tx.input.starts_with(&input_prefix.0)
+                matches!(tx.action(), TransactionAction::Call(addr) if addr == *contract)
+                    && tx.input().starts_with(&input_prefix.0)
             }
         }
     }
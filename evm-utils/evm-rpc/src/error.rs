@@ -1,7 +1,7 @@
 use {
     crate::{BlockId, Bytes},
     ethabi::StateMutability,
-    evm_state::{ExitError, ExitFatal, ExitRevert, U256},
+    evm_state::{ExitError, ExitFatal, ExitRevert, H160, H256, U256},
     jsonrpc_core::Error as JRpcError,
     rlp::DecoderError,
     rustc_hex::FromHexError,
@@ -35,6 +35,17 @@ pub enum Error {
         ending: u64,
         batch_size: Option<u64>,
     },
+    #[snafu(display(
+        "ServerError(-32005): Query returned more than {} results. Try with this block range [{:#x}, {:#x}]",
+        limit,
+        starting,
+        ending
+    ))]
+    TooManyLogs {
+        limit: usize,
+        starting: u64,
+        ending: u64,
+    },
     #[snafu(display("Tokio runtime error: {}", details))]
     #[snafu(context(suffix(Error)))]
     RuntimeError { details: String },
@@ -73,6 +84,13 @@ pub enum Error {
     #[snafu(display("Failed to find archive state for block {}", block))]
     StateNotFoundForBlock { block: BlockId },
 
+    #[snafu(display(
+        "State for block {} was pruned, oldest available state is for block {}",
+        block,
+        oldest_block
+    ))]
+    StatePruned { block: BlockId, oldest_block: u64 },
+
     #[snafu(display("Failed to process native chain request: {}", source))]
     #[snafu(context(suffix(Error)))]
     ProxyRpcError { source: JRpcError },
@@ -92,6 +110,12 @@ pub enum Error {
     #[snafu(context(suffix(Error)))]
     EvmStateError { source: evm_state::error::Error },
 
+    #[snafu(display("Failed to build merkle proof: {}", source))]
+    #[snafu(context(suffix(Error)))]
+    ProofError {
+        source: evm_state::storage::proof::ProofError,
+    },
+
     #[snafu(display("Method unimplemented"))]
     Unimplemented {},
     #[snafu(display("ServerError(-32005)"))]
@@ -121,6 +145,38 @@ pub enum Error {
     MempoolImport { details: String },
     #[snafu(display("Invalid rpc params"))]
     InvalidParams {},
+    #[snafu(display("Filter not found, id={}", id))]
+    FilterNotFound { id: U256 },
+    #[snafu(display("Too many installed filters, limit={}", limit))]
+    FiltersLimitReached { limit: usize },
+    #[snafu(display("Transaction not found, hash={:?}", tx_hash))]
+    TransactionNotFound { tx_hash: H256 },
+    #[snafu(display("Invalid bundle size={}, expected from 1 to {}", len, max))]
+    InvalidBundleSize { len: usize, max: usize },
+    #[snafu(display(
+        "Invalid nonce of bundle transaction {:?}, nonce={}, expected={}",
+        tx_hash,
+        nonce,
+        expected
+    ))]
+    InvalidBundleNonce {
+        tx_hash: H256,
+        nonce: U256,
+        expected: U256,
+    },
+    #[snafu(display(
+        "Insufficient balance of {:?} for bundle, need={}, balance={}",
+        sender,
+        need,
+        balance
+    ))]
+    InsufficientBundleBalance {
+        sender: H160,
+        need: U256,
+        balance: U256,
+    },
+    #[snafu(display("Bundle transaction {:?} failed in simulation: {}", tx_hash, reason))]
+    BundleSimulationFailed { tx_hash: H256, reason: String },
     // InvalidParams {},
     // UnsupportedTrieQuery,
     // NotFound,
@@ -190,6 +246,9 @@ const GAS_PRICE_TOO_LOW: i64 = 2005;
 const TRANSACTION_REPLACED: i64 = 2006;
 const ARCHIVE_NOT_SUPPORTED_ERROR: i64 = 2007;
 const MEMPOOL_IMPORT: i64 = 2008;
+const FILTER_NOT_FOUND: i64 = 2009;
+const TRANSACTION_NOT_FOUND: i64 = 2010;
+const STATE_PRUNED_RPC_ERROR: i64 = 2011;
 
 const EVM_EXECUTION_ERROR: i64 = 3; // from geth docs
 const ERROR_EVM_BASE_SUBCODE: i64 = 100; //reserved place for evm errors range: 100 - 200
@@ -223,6 +282,9 @@ impl From<Error> for JRpcError {
             Error::EvmStateError { source } => {
                 internal_error_with_details(EVM_STATE_RPC_ERROR, &err, &source)
             }
+            Error::ProofError { source } => {
+                internal_error_with_details(EVM_STATE_RPC_ERROR, &err, &source)
+            }
             Error::NativeRpcError {
                 source: _source,
                 details,
@@ -238,6 +300,7 @@ impl From<Error> for JRpcError {
             Error::BlockNotFound { .. } => internal_error(BLOCK_NOT_FOUND_RPC_ERROR, &err),
             Error::ArchiveNotSupported => internal_error(ARCHIVE_NOT_SUPPORTED_ERROR, &err),
             Error::StateNotFoundForBlock { .. } => internal_error(STATE_NOT_FOUND_RPC_ERROR, &err),
+            Error::StatePruned { .. } => internal_error(STATE_PRUNED_RPC_ERROR, &err),
             Error::KeyNotFound { .. } => internal_error(KEY_NOT_FOUND_RPC_ERROR, &err),
             Error::Unimplemented {} => {
                 let mut error = Self::invalid_request();
@@ -284,10 +347,18 @@ impl From<Error> for JRpcError {
             }
             Error::ServerError {} => internal_error(SERVER_ERROR, &err),
             Error::InvalidBlocksRange { .. } => internal_error(SERVER_ERROR, &err),
+            Error::TooManyLogs { .. } => internal_error(SERVER_ERROR, &err),
             Error::RuntimeError { .. } => internal_error(SERVER_ERROR, &err),
             Error::GasPriceTooLow { .. } => internal_error(GAS_PRICE_TOO_LOW, &err),
             Error::TransactionRemoved {} => internal_error(TRANSACTION_REPLACED, &err),
             Error::MempoolImport { .. } => internal_error(MEMPOOL_IMPORT, &err),
+            Error::FilterNotFound { .. } => internal_error(FILTER_NOT_FOUND, &err),
+            Error::FiltersLimitReached { .. } => internal_error(SERVER_ERROR, &err),
+            Error::TransactionNotFound { .. } => internal_error(TRANSACTION_NOT_FOUND, &err),
+            Error::InvalidBundleSize { .. } => Self::invalid_params(err.to_string()),
+            Error::InvalidBundleNonce { .. } => Self::invalid_params(err.to_string()),
+            Error::InsufficientBundleBalance { .. } => Self::invalid_params(err.to_string()),
+            Error::BundleSimulationFailed { .. } => internal_error(EVM_EXECUTION_ERROR, &err),
         }
     }
 }
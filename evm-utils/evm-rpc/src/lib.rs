@@ -6,15 +6,15 @@ use {
     primitive_types::{H256, U256},
     serde::{Deserialize, Serialize},
     snafu::ResultExt,
-    std::{collections::HashMap, fmt},
+    std::{collections::BTreeMap, fmt},
 };
 
 mod serialize;
 use {
     self::error::EvmStateError,
     evm_state::{
-        Address, Block, BlockHeader, ExitSucceed, Gas, LogFilterTopicEntry, LogWithLocation,
-        TransactionInReceipt,
+        AccessListItem, Address, Block, BlockHeader, ExitSucceed, Gas, LogFilterTopicEntry,
+        LogWithLocation, TransactionInReceipt, TypedTransactionFee,
     },
 };
 
@@ -55,6 +55,24 @@ pub struct RPCLogFilter {
     pub topics: Option<Vec<Option<RPCTopicFilter>>>,
 }
 
+impl RPCLogFilter {
+    /// Returns true if filter contain address or first topic, such filters can be answered
+    /// from logs index of node, without walking every block in range.
+    pub fn is_indexable(&self) -> bool {
+        let has_address = match &self.address {
+            Some(Either::Left(addresses)) => !addresses.is_empty(),
+            Some(Either::Right(_)) => true,
+            None => false,
+        };
+        let has_topic = match self.topics.as_ref().and_then(|topics| topics.first()) {
+            Some(Some(RPCTopicFilter::Or(topics))) => !topics.is_empty(),
+            Some(Some(RPCTopicFilter::Single(_))) => true,
+            Some(None) | None => false,
+        };
+        has_address || has_topic
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RPCLog {
@@ -222,6 +240,42 @@ pub struct RPCTransaction {
     pub r: Option<U256>,
     #[serde(alias = "S")]
     pub s: Option<U256>,
+
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub transaction_type: Option<Hex<u8>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<Hex<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_list: Option<Vec<RPCAccessListItem>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<Gas>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<Gas>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RPCAccessListItem {
+    pub address: Address,
+    pub storage_keys: Vec<H256>,
+}
+
+impl From<AccessListItem> for RPCAccessListItem {
+    fn from(item: AccessListItem) -> Self {
+        RPCAccessListItem {
+            address: item.address,
+            storage_keys: item.storage_keys,
+        }
+    }
+}
+
+impl From<RPCAccessListItem> for AccessListItem {
+    fn from(item: RPCAccessListItem) -> Self {
+        AccessListItem {
+            address: item.address,
+            storage_keys: item.storage_keys,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -239,20 +293,101 @@ pub struct RPCReceipt {
     pub from: Option<Address>,
     pub logs: Vec<RPCLog>,
     pub status: Hex<usize>,
+    #[serde(rename = "type", default)]
+    pub transaction_type: Hex<u8>,
+    #[serde(default)]
+    pub effective_gas_price: Gas,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<jsonrpc_core::Error>,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RPCStorageProof {
+    pub key: H256,
+    pub value: U256,
+    pub proof: Vec<Bytes>,
+}
+
+/// Result of `eth_feeHistory`, fields with per block values are sorted from oldest block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RPCFeeHistory {
+    pub oldest_block: U256,
+    /// Contains one extra entry for the block after the newest one.
+    pub base_fee_per_gas: Vec<U256>,
+    pub gas_used_ratio: Vec<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reward: Option<Vec<Vec<U256>>>,
+}
+
+/// Account and storage merkle proofs, as described in EIP-1186.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RPCAccountProof {
+    pub address: Address,
+    pub account_proof: Vec<Bytes>,
+    pub balance: U256,
+    pub code_hash: H256,
+    pub nonce: U256,
+    pub storage_hash: H256,
+    pub storage_proof: Vec<RPCStorageProof>,
+}
+
+impl From<evm_state::storage::proof::AccountProof> for RPCAccountProof {
+    fn from(proof: evm_state::storage::proof::AccountProof) -> Self {
+        RPCAccountProof {
+            address: proof.address,
+            account_proof: proof.account_proof.into_iter().map(Bytes).collect(),
+            balance: proof.account.balance,
+            code_hash: proof.account.code_hash,
+            nonce: proof.account.nonce,
+            storage_hash: proof.account.storage_root,
+            storage_proof: proof
+                .storage_proofs
+                .into_iter()
+                .map(|storage| RPCStorageProof {
+                    key: storage.key,
+                    value: storage.value,
+                    proof: storage.proof.into_iter().map(Bytes).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Result of default (struct-logger) tracer.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RPCTrace {
-    pub gas: Gas,
+    pub gas: u64,
+    pub failed: bool,
     pub return_value: Bytes,
     pub struct_logs: Vec<RPCStep>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RPCTracer {
+    CallTracer,
+    PrestateTracer,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RPCTracerConfig {
+    /// Used by `callTracer`, skip internal calls.
+    #[serde(default)]
+    pub only_top_call: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct RPCTraceConfig {
+    #[serde(default)]
+    pub enable_memory: bool,
+    /// Deprecated by geth in favor of `enable_memory`, kept for compatibility with old clients.
+    /// Memory is captured only if it is enabled and not disabled.
     #[serde(default)]
     pub disable_memory: bool,
     #[serde(default)]
@@ -260,46 +395,199 @@ pub struct RPCTraceConfig {
     #[serde(default)]
     pub disable_storage: bool,
     #[serde(default)]
-    pub breakpoints: Option<RPCBreakpointConfig>,
+    pub limit: Option<usize>,
+    /// Built-in tracer, struct-logger is used if not set.
+    #[serde(default)]
+    pub tracer: Option<RPCTracer>,
+    #[serde(default)]
+    pub tracer_config: RPCTracerConfig,
+    /// Native accounts passed to transactions, same as `meta_keys` of `trace_*` methods.
+    #[serde(default)]
+    pub meta_keys: Option<Vec<String>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+impl From<&RPCTraceConfig> for evm_state::struct_logger::StructLoggerConfig {
+    fn from(config: &RPCTraceConfig) -> Self {
+        Self {
+            disable_stack: config.disable_stack,
+            disable_storage: config.disable_storage,
+            enable_memory: config.enable_memory && !config.disable_memory,
+            limit: config.limit.filter(|limit| *limit > 0),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct RPCBreakpointConfig {
-    pub source_map: HashMap<H256, RPCSourceMapConfig>,
-    pub breakpoints: String,
+pub struct RPCBlockTrace {
+    pub tx_hash: H256,
+    pub result: RPCTraceResult,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct RPCSourceMapConfig {
-    pub source_map: String,
-    pub source_list: Vec<String>,
+pub struct RPCStep {
+    pub pc: usize,
+    pub op: String,
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    /// Memory splitted into 32 bytes words.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<Bytes>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<H256, H256>>,
+}
+
+impl From<evm_state::struct_logger::StructLog> for RPCStep {
+    fn from(log: evm_state::struct_logger::StructLog) -> Self {
+        Self {
+            pc: log.pc,
+            op: evm_state::struct_logger::opcode_name(log.op),
+            gas: log.gas,
+            gas_cost: log.gas_cost,
+            depth: log.depth,
+            error: log.error,
+            stack: log.stack.map(|stack| {
+                stack
+                    .iter()
+                    .map(|item| U256::from_big_endian(item.as_bytes()))
+                    .collect()
+            }),
+            memory: log
+                .memory
+                .map(|memory| memory.chunks(32).map(|word| Bytes(word.to_vec())).collect()),
+            storage: log.storage,
+        }
+    }
 }
 
+/// Frame of `callTracer`.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct RPCBlockTrace {
-    pub struct_logs: Vec<RPCStep>,
+pub struct RPCCallFrame {
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub from: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    pub gas: U256,
+    pub gas_used: U256,
+    pub input: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<RPCCallFrame>,
 }
 
+impl RPCCallFrame {
+    /// Build call tree from flat list of traces, ordered as in `trace_*` methods.
+    pub fn from_traces(traces: Vec<evm_state::executor::Trace>) -> Option<Self> {
+        let mut root: Option<Self> = None;
+        for trace in traces {
+            let trace_address = trace.trace_address.clone();
+            let frame = Self::from_trace(trace);
+            match trace_address.split_last() {
+                None => root = Some(frame),
+                Some((_, parents)) => {
+                    let mut parent = root.as_mut()?;
+                    for idx in parents {
+                        parent = parent.calls.get_mut(*idx)?;
+                    }
+                    parent.calls.push(frame)
+                }
+            }
+        }
+        root
+    }
+
+    fn from_trace(trace: evm_state::executor::Trace) -> Self {
+        let output = trace.result.output.clone();
+        let gas_used = trace.result.gas_used;
+        let contract = trace.result.contract;
+        let (_, error) = trace::Trace::result_from(trace.result);
+        let output = (!output.is_empty()).then(|| Bytes(output));
+        match trace.action {
+            evm_state::executor::Action::Call {
+                code,
+                input,
+                context,
+                gas,
+                call_type,
+            } => {
+                let (call_type, value) = match call_type.unwrap_or(evm_state::CallScheme::Call) {
+                    evm_state::CallScheme::Call => ("CALL", Some(context.apparent_value)),
+                    evm_state::CallScheme::CallCode => ("CALLCODE", Some(context.apparent_value)),
+                    evm_state::CallScheme::DelegateCall => ("DELEGATECALL", None),
+                    evm_state::CallScheme::StaticCall => ("STATICCALL", None),
+                };
+                Self {
+                    call_type: call_type.to_string(),
+                    from: context.caller,
+                    to: Some(code),
+                    value,
+                    gas,
+                    gas_used,
+                    input: input.into(),
+                    output,
+                    error,
+                    calls: vec![],
+                }
+            }
+            evm_state::executor::Action::Create {
+                caller,
+                value,
+                gas,
+                init_code,
+                creation_method,
+            } => {
+                let call_type = match creation_method {
+                    evm_state::CreateScheme::Create2 { .. } => "CREATE2",
+                    _ => "CREATE",
+                };
+                Self {
+                    call_type: call_type.to_string(),
+                    from: caller,
+                    to: contract,
+                    value: Some(value),
+                    gas,
+                    gas_used,
+                    input: init_code.into(),
+                    output,
+                    error,
+                    calls: vec![],
+                }
+            }
+        }
+    }
+}
+
+/// Account state before transaction, used by `prestateTracer`.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct RPCStep {
-    pub depth: usize,
-    pub error: String,
-    pub gas: Gas,
-    pub gas_cost: Gas,
-    pub op: u8,
-    pub pc: usize,
-    pub opcode_pc: usize,
-    pub code_hash: H256,
-    pub address: Address,
-    pub breakpoint_index: Option<usize>,
-    pub breakpoint: Option<String>,
-    pub memory: Option<Vec<Bytes>>,
-    pub stack: Option<Vec<U256>>,
-    pub storage: Option<HashMap<U256, U256>>,
+pub struct RPCPrestateAccount {
+    pub balance: U256,
+    pub nonce: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum RPCTraceResult {
+    StructLogs(RPCTrace),
+    CallTracer(RPCCallFrame),
+    PrestateTracer(BTreeMap<Address, RPCPrestateAccount>),
 }
 
 // #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -320,6 +608,91 @@ pub struct RPCDumpAccountBasic {
     // pub storage: HashMap<U256, U256>,
 }
 
+/// State of evm transaction, sent through the bridge.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RPCBridgeTxState {
+    /// Waiting in bridge mempool.
+    Pooled,
+    /// Native transactions are being sent.
+    Processing,
+    /// Native transaction was sent, waiting for it to land.
+    Deployed,
+    Landed,
+    /// Removed from mempool without deploy, or replaced by other transaction.
+    Dropped,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RPCNativeTxKind {
+    /// Evm transaction, wrapped into single native transaction.
+    Execute,
+    /// Creation of storage account for big transaction.
+    CreateStorage,
+    /// Write of transaction chunk into storage account.
+    WriteStorage,
+    /// Execution of transaction from storage account.
+    ExecuteFromStorage,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RPCNativeSignature {
+    pub signature: String,
+    pub kind: RPCNativeTxKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_account: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RPCRedeployAttempt {
+    /// Unix time in milliseconds.
+    pub timestamp: u64,
+    /// Native signature, that wasn't landed in time.
+    pub signature: String,
+    /// Error of reimport into mempool, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RPCTransactionLifecycle {
+    pub hash: H256,
+    pub state: RPCBridgeTxState,
+    /// Unix time in milliseconds, when transaction was first imported into mempool.
+    pub pooled_at: u64,
+    pub native_signatures: Vec<RPCNativeSignature>,
+    pub redeploy_attempts: Vec<RPCRedeployAttempt>,
+    /// Commitment of last executing native transaction.
+    pub commitment: Option<String>,
+    /// Reason of drop or failure.
+    pub error: Option<String>,
+}
+
+/// Transactions of bridge mempool, keyed by sender and nonce (in decimal), as in geth `txpool_content`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RPCTxPoolContent {
+    pub pending: BTreeMap<Address, BTreeMap<String, RPCTransaction>>,
+    pub queued: BTreeMap<Address, BTreeMap<String, RPCTransaction>>,
+}
+
+/// Short textual summary of every transaction in bridge mempool.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RPCTxPoolInspect {
+    pub pending: BTreeMap<Address, BTreeMap<String, String>>,
+    pub queued: BTreeMap<Address, BTreeMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RPCTxPoolStatus {
+    pub pending: Hex<usize>,
+    pub queued: Hex<usize>,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize, Debug, Clone, Copy)]
 #[serde(untagged)]
 pub enum BlockId {
@@ -530,7 +903,7 @@ pub mod trace {
         }
     }
     impl Trace {
-        fn result_from(result: evm_state::executor::Res) -> (Res, Option<String>) {
+        pub(crate) fn result_from(result: evm_state::executor::Res) -> (Res, Option<String>) {
             // TODO: Add rest errors panic!()/todo!(), and other keywords for better search.
             let error = match result.reason {
                 evm_state::ExitReason::Succeed(_) => None,
@@ -643,11 +1016,15 @@ pub mod trace {
             clear_logs_on_error: bool,
             accept_zero_gas_price_with_native_fee: bool,
             burn_gas_price: u64,
+            hardfork: Option<evm_state::HardforkConfig>,
         ) -> BoxFuture<Result<(Block, Vec<H256>), Error>>;
     }
 }
 
-pub use {bridge::BridgeERPC, chain::ChainERPC, general::GeneralERPC, trace::TraceERPC};
+pub use {
+    bridge::BridgeERPC, chain::ChainERPC, debug::DebugERPC, filter::FilterERPC,
+    general::GeneralERPC, trace::TraceERPC, txpool::TxPoolERPC,
+};
 
 pub mod general {
     use super::*;
@@ -690,7 +1067,10 @@ pub mod general {
         fn hashrate(&self, meta: Self::Metadata) -> Result<U256, Error>;
 
         #[rpc(meta, name = "eth_gasPrice")]
-        fn gas_price(&self, meta: Self::Metadata) -> Result<Gas, Error>;
+        fn gas_price(&self, meta: Self::Metadata) -> BoxFuture<Result<Gas, Error>>;
+
+        #[rpc(meta, name = "eth_maxPriorityFeePerGas")]
+        fn max_priority_fee_per_gas(&self, meta: Self::Metadata) -> BoxFuture<Result<Gas, Error>>;
     }
 }
 
@@ -721,6 +1101,24 @@ pub mod chain {
             block: Option<BlockId>,
         ) -> BoxFuture<Result<H256, Error>>;
 
+        #[rpc(meta, name = "eth_feeHistory")]
+        fn fee_history(
+            &self,
+            meta: Self::Metadata,
+            block_count: Hex<u64>,
+            newest_block: BlockId,
+            reward_percentiles: Option<Vec<f64>>,
+        ) -> BoxFuture<Result<RPCFeeHistory, Error>>;
+
+        #[rpc(meta, name = "eth_getProof")]
+        fn proof(
+            &self,
+            meta: Self::Metadata,
+            address: Address,
+            storage_keys: Vec<H256>,
+            block: Option<BlockId>,
+        ) -> BoxFuture<Result<RPCAccountProof, Error>>;
+
         #[rpc(meta, name = "eth_getTransactionCount")]
         fn transaction_count(
             &self,
@@ -854,6 +1252,45 @@ pub mod chain {
     }
 }
 
+pub mod filter {
+    use super::*;
+
+    #[rpc]
+    pub trait FilterERPC {
+        type Metadata;
+
+        #[rpc(meta, name = "eth_newFilter")]
+        fn new_filter(
+            &self,
+            meta: Self::Metadata,
+            log_filter: RPCLogFilter,
+        ) -> BoxFuture<Result<U256, Error>>;
+
+        #[rpc(meta, name = "eth_newBlockFilter")]
+        fn new_block_filter(&self, meta: Self::Metadata) -> Result<U256, Error>;
+
+        #[rpc(meta, name = "eth_newPendingTransactionFilter")]
+        fn new_pending_transaction_filter(&self, meta: Self::Metadata) -> Result<U256, Error>;
+
+        #[rpc(meta, name = "eth_uninstallFilter")]
+        fn uninstall_filter(&self, meta: Self::Metadata, id: U256) -> Result<bool, Error>;
+
+        #[rpc(meta, name = "eth_getFilterChanges")]
+        fn filter_changes(
+            &self,
+            meta: Self::Metadata,
+            id: U256,
+        ) -> BoxFuture<Result<Either<Vec<H256>, Vec<RPCLog>>, Error>>;
+
+        #[rpc(meta, name = "eth_getFilterLogs")]
+        fn filter_logs(
+            &self,
+            meta: Self::Metadata,
+            id: U256,
+        ) -> BoxFuture<Result<Vec<RPCLog>, Error>>;
+    }
+}
+
 pub mod bridge {
     use super::*;
 
@@ -893,48 +1330,83 @@ pub mod bridge {
 
         #[rpc(meta, name = "eth_getCompilers")]
         fn compilers(&self, meta: Self::Metadata) -> Result<Vec<String>, Error>;
+
+        #[rpc(meta, name = "bridge_sendBundle")]
+        fn send_bundle(
+            &self,
+            meta: Self::Metadata,
+            txs: Vec<Bytes>,
+            meta_keys: Option<Vec<String>>,
+        ) -> BoxFuture<Result<Vec<H256>, Error>>;
+
+        #[rpc(meta, name = "bridge_getTransactionLifecycle")]
+        fn transaction_lifecycle(
+            &self,
+            meta: Self::Metadata,
+            tx_hash: H256,
+        ) -> BoxFuture<Result<Option<RPCTransactionLifecycle>, Error>>;
     }
 }
 
-// #[rpc]
-// pub trait FilterRPC {
-//     #[rpc(meta, name = "eth_newFilter")]
-//     fn new_filter(&self, RPCLogFilter) -> Result<String, Error>;
-//     #[rpc(meta, name = "eth_newBlockFilter")]
-//     fn new_block_filter(&self) -> Result<String, Error>;
-//     #[rpc(meta, name = "eth_newPendingTransactionFilter")]
-//     fn new_pending_transaction_filter(&self) -> Result<String, Error>;
-//     #[rpc(meta, name = "eth_uninstallFilter")]
-//     fn uninstall_filter(&self, String) -> Result<bool, Error>;
-
-//     #[rpc(meta, name = "eth_getFilterChanges")]
-//     fn filter_changes(&self, String) -> Result<Either<Vec<String>, Vec<RPCLog>>, Error>;
-//     #[rpc(meta, name = "eth_getFilterLogs")]
-//     fn filter_logs(&self, String) -> Result<Vec<RPCLog>, Error>;
-// }
+pub mod txpool {
+    use super::*;
 
-// #[rpc]
-// pub trait DebugRPC {
-//     #[rpc(name = "debug_getBlockRlp")]
-//     fn block_rlp(&self, usize) -> Result<Bytes, Error>;
-//     #[rpc(name = "debug_traceTransaction")]
-//     fn trace_transaction(&self, H256, Option<RPCTraceConfig>)
-//                             -> Result<RPCTrace, Error>;
-//     #[rpc(name = "debug_traceBlock")]
-//     fn trace_block(&self, Bytes, Option<RPCTraceConfig>)
-//                     -> Result<RPCBlockTrace, Error>;
-//     #[rpc(name = "debug_traceBlockByNumber")]
-//     fn trace_block_by_number(&self, usize, Option<RPCTraceConfig>)
-//                                 -> Result<RPCBlockTrace, Error>;
-//     #[rpc(name = "debug_traceBlockByHash")]
-//     fn trace_block_by_hash(&self, H256, Option<RPCTraceConfig>)
-//                             -> Result<RPCBlockTrace, Error>;
-//     #[rpc(name = "debug_traceBlockFromFile")]
-//     fn trace_block_from_file(&self, String, Option<RPCTraceConfig>)
-//                                 -> Result<RPCBlockTrace, Error>;
-//     #[rpc(name = "debug_dumpBlock")]
-//     fn dump_block(&self, usize) -> Result<RPCDump, Error>;
-// }
+    #[rpc]
+    pub trait TxPoolERPC {
+        type Metadata;
+
+        #[rpc(meta, name = "txpool_content")]
+        fn content(&self, meta: Self::Metadata) -> Result<RPCTxPoolContent, Error>;
+
+        #[rpc(meta, name = "txpool_inspect")]
+        fn inspect(&self, meta: Self::Metadata) -> Result<RPCTxPoolInspect, Error>;
+
+        #[rpc(meta, name = "txpool_status")]
+        fn status(&self, meta: Self::Metadata) -> Result<RPCTxPoolStatus, Error>;
+    }
+}
+
+pub mod debug {
+    use super::*;
+
+    #[rpc]
+    pub trait DebugERPC {
+        type Metadata;
+
+        #[rpc(meta, name = "debug_traceTransaction")]
+        fn trace_transaction(
+            &self,
+            meta: Self::Metadata,
+            tx_hash: H256,
+            config: Option<RPCTraceConfig>,
+        ) -> BoxFuture<Result<RPCTraceResult, Error>>;
+
+        #[rpc(meta, name = "debug_traceCall")]
+        fn trace_call(
+            &self,
+            meta: Self::Metadata,
+            tx: RPCTransaction,
+            block: Option<BlockId>,
+            config: Option<RPCTraceConfig>,
+        ) -> BoxFuture<Result<RPCTraceResult, Error>>;
+
+        #[rpc(meta, name = "debug_traceBlockByNumber")]
+        fn trace_block_by_number(
+            &self,
+            meta: Self::Metadata,
+            block: BlockId,
+            config: Option<RPCTraceConfig>,
+        ) -> BoxFuture<Result<Vec<RPCBlockTrace>, Error>>;
+
+        #[rpc(meta, name = "debug_traceBlockByHash")]
+        fn trace_block_by_hash(
+            &self,
+            meta: Self::Metadata,
+            block_hash: H256,
+            config: Option<RPCTraceConfig>,
+        ) -> BoxFuture<Result<Vec<RPCBlockTrace>, Error>>;
+    }
+}
 
 // pub fn rpc_loop<P: 'static + Patch + Send>(
 //     state: Arc<Mutex<MinerState>>, addr: &SocketAddr, channel: Sender<bool>
@@ -977,6 +1449,25 @@ impl RPCTransaction {
     }
 
     pub fn from_transaction(tx: evm_state::TransactionInReceipt) -> Result<Self, crate::Error> {
+        let transaction_type = tx.tx_type();
+        let (chain_id, access_list, max_fee_per_gas, max_priority_fee_per_gas) = match &tx {
+            TransactionInReceipt::Typed(tx) => {
+                let (max_fee_per_gas, max_priority_fee_per_gas) = match tx.fee {
+                    TypedTransactionFee::AccessList { .. } => (None, None),
+                    TypedTransactionFee::DynamicFee {
+                        max_priority_fee_per_gas,
+                        max_fee_per_gas,
+                    } => (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)),
+                };
+                (
+                    Some(Hex(tx.chain_id)),
+                    Some(tx.access_list.iter().cloned().map(Into::into).collect()),
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                )
+            }
+            _ => (None, None, None, None),
+        };
         let (hash, to, creates, from, gas_limit, gas_price, input, value, nonce, v, r, s) = match tx
         {
             TransactionInReceipt::Signed(tx) => {
@@ -1050,6 +1541,38 @@ impl RPCTransaction {
                     U256::from(0x1),
                 )
             }
+            TransactionInReceipt::Typed(tx) => {
+                let hash = tx.tx_id_hash();
+                let from = tx.caller().with_context(|_| EvmStateError)?;
+                let gas_limit = tx.gas_limit;
+                let gas_price = tx.fee.max_gas_price();
+                let nonce = tx.nonce;
+                let (to, creates) = match tx.action {
+                    evm_state::transactions::TransactionAction::Call(address) => {
+                        (Some(address), None)
+                    }
+                    evm_state::transactions::TransactionAction::Create => (
+                        None,
+                        Some(
+                            evm_state::transactions::TransactionAction::Create.address(from, nonce),
+                        ),
+                    ),
+                };
+                (
+                    hash,
+                    to,
+                    creates,
+                    from,
+                    gas_limit,
+                    gas_price,
+                    tx.unsigned.input,
+                    tx.unsigned.value,
+                    nonce,
+                    u64::from(tx.signature.odd_y_parity),
+                    tx.signature.r.as_bytes().into(),
+                    tx.signature.s.as_bytes().into(),
+                )
+            }
         };
         Ok(RPCTransaction {
             from: Some(from),
@@ -1067,6 +1590,11 @@ impl RPCTransaction {
             v: Some(Hex(v)),
             r: Some(r),
             s: Some(s),
+            transaction_type: (transaction_type != 0).then(|| Hex(transaction_type)),
+            chain_id,
+            access_list,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
         })
     }
 }
@@ -1077,8 +1605,10 @@ impl RPCReceipt {
         tx_hash: H256,
         block_hash: H256,
         exit_data: Option<Vec<u8>>,
+        burn_gas_price: Gas,
     ) -> Result<Self, crate::Error> {
-        let (from, to, contract_address) = match receipt.transaction {
+        let transaction_type = receipt.transaction.tx_type();
+        let (from, to, contract_address, effective_gas_price) = match receipt.transaction {
             TransactionInReceipt::Signed(tx) => {
                 let from = tx.caller().with_context(|_| EvmStateError)?;
                 let nonce = tx.nonce;
@@ -1093,7 +1623,7 @@ impl RPCReceipt {
                         ),
                     ),
                 };
-                (from, to, creates)
+                (from, to, creates, tx.gas_price)
             }
             TransactionInReceipt::Unsigned(tx) => {
                 let from = tx.caller;
@@ -1110,7 +1640,26 @@ impl RPCReceipt {
                     ),
                 };
 
-                (from, to, creates)
+                (from, to, creates, tx.unsigned_tx.gas_price)
+            }
+            TransactionInReceipt::Typed(tx) => {
+                let from = tx.caller().with_context(|_| EvmStateError)?;
+                let nonce = tx.nonce;
+                let (to, creates) = match tx.action {
+                    evm_state::transactions::TransactionAction::Call(address) => {
+                        (Some(address), None)
+                    }
+                    evm_state::transactions::TransactionAction::Create => (
+                        None,
+                        Some(
+                            evm_state::transactions::TransactionAction::Create.address(from, nonce),
+                        ),
+                    ),
+                };
+                // Base fee is equal to burn gas price.
+                let effective_gas_price = tx.fee.effective_gas_price(burn_gas_price);
+
+                (from, to, creates, effective_gas_price)
             }
         };
 
@@ -1154,6 +1703,8 @@ impl RPCReceipt {
             logs_bloom: receipt.logs_bloom,
             logs,
             status: Hex(status),
+            transaction_type: Hex(transaction_type),
+            effective_gas_price,
             error,
         })
     }
@@ -1265,4 +1816,86 @@ mod test {
             r"{ block_hash:0xdededededededededededededededededededededededededededededededede }"
         );
     }
+
+    #[test]
+    fn legacy_receipt_type_is_quantity() {
+        let secret_key = evm_state::secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+        let tx = evm_state::transactions::UnsignedTransaction {
+            nonce: 0.into(),
+            gas_price: 1.into(),
+            gas_limit: 21000.into(),
+            action: evm_state::transactions::TransactionAction::Call(Address::repeat_byte(0x11)),
+            value: 0.into(),
+            input: vec![],
+        }
+        .sign(&secret_key, Some(evm_state::TEST_CHAIN_ID));
+        let receipt = evm_state::transactions::TransactionReceipt::new(
+            TransactionInReceipt::Signed(tx),
+            21000,
+            1,
+            0,
+            vec![],
+            (
+                evm_state::ExitReason::Succeed(evm_state::ExitSucceed::Stopped),
+                vec![],
+            ),
+        );
+        let receipt = RPCReceipt::new_from_receipt(
+            receipt,
+            H256::repeat_byte(0x22),
+            H256::repeat_byte(0x33),
+            None,
+            1.into(),
+        )
+        .unwrap();
+
+        // Clients expect `"type": "0x0"` for legacy transactions, not an empty hex string.
+        let json = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(json["type"], "0x0");
+        let receipt: RPCReceipt = serde_json::from_value(json).unwrap();
+        assert_eq!(receipt.transaction_type, Hex(0));
+    }
+
+    #[test]
+    fn log_filter_indexable() {
+        let filter = |json: &str| serde_json::from_str::<RPCLogFilter>(json).unwrap();
+        assert!(!filter(r#"{"fromBlock":"0x0"}"#).is_indexable());
+        assert!(!filter(r#"{"address":[],"topics":[null]}"#).is_indexable());
+        assert!(!filter(
+            r#"{"topics":[null,"0x0000000000000000000000000000000000000000000000000000000000000001"]}"#
+        )
+        .is_indexable());
+        assert!(
+            filter(r#"{"address":"0x0000000000000000000000000000000000000001"}"#).is_indexable()
+        );
+        assert!(filter(
+            r#"{"topics":[["0x0000000000000000000000000000000000000000000000000000000000000001"]]}"#
+        )
+        .is_indexable());
+    }
+
+    #[test]
+    fn trace_config_geth_compatible() {
+        let config: RPCTraceConfig = serde_json::from_str(
+            r#"{"tracer":"callTracer","tracerConfig":{"onlyTopCall":true},"timeout":"10s"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.tracer, Some(RPCTracer::CallTracer));
+        assert!(config.tracer_config.only_top_call);
+
+        let config: RPCTraceConfig =
+            serde_json::from_str(r#"{"enableMemory":true,"disableStack":true}"#).unwrap();
+        assert!(config.tracer.is_none());
+        let logger_config = evm_state::struct_logger::StructLoggerConfig::from(&config);
+        assert!(logger_config.enable_memory);
+        assert!(logger_config.disable_stack);
+        assert!(!logger_config.disable_storage);
+
+        let config: RPCTraceConfig =
+            serde_json::from_str(r#"{"enableMemory":true,"disableMemory":true}"#).unwrap();
+        let logger_config = evm_state::struct_logger::StructLoggerConfig::from(&config);
+        assert!(!logger_config.enable_memory);
+
+        assert!(serde_json::from_str::<RPCTraceConfig>(r#"{"tracer":"4byteTracer"}"#).is_err());
+    }
 }
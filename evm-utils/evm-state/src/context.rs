@@ -41,6 +41,10 @@ impl TransactionContext {
 pub enum HardforkConfig {
     Istanbul,
     Frontier,
+    /// EIP-2929 warm/cold access costs, EIP-2930 access lists.
+    Berlin,
+    /// EIP-1559 BASEFEE opcode, EIP-3529 reduced refunds, EIP-3541 reject 0xEF code.
+    London,
 }
 
 impl Default for HardforkConfig {
@@ -96,6 +100,8 @@ impl EvmConfig {
             ..match self.executor_config {
                 HardforkConfig::Istanbul => evm::Config::istanbul(),
                 HardforkConfig::Frontier => evm::Config::frontier(),
+                HardforkConfig::Berlin => evm::Config::berlin(),
+                HardforkConfig::London => evm::Config::london(),
             }
         }
     }
@@ -284,7 +290,11 @@ where
     }
 
     fn block_base_fee_per_gas(&self) -> U256 {
-        U256::zero()
+        // Base fee is observable only since London (BASEFEE opcode).
+        match self.config.executor_config {
+            HardforkConfig::London => self.config.burn_gas_price,
+            _ => U256::zero(),
+        }
     }
 
     fn chain_id(&self) -> U256 {
@@ -99,6 +99,16 @@ pub enum Error {
     ))]
     GasPriceOutOfBounds { gas_price: U256 },
 
+    #[snafu(display(
+        "Max priority fee per gas={} exceed max fee per gas={}",
+        max_priority_fee_per_gas,
+        max_fee_per_gas,
+    ))]
+    PriorityFeeGreaterThanMaxFee {
+        max_priority_fee_per_gas: U256,
+        max_fee_per_gas: U256,
+    },
+
     #[snafu(display("Transaction with this hash already executed, tx_hash={:?}", tx_hash,))]
     DuplicateTx { tx_hash: H256 },
 
@@ -1,11 +1,12 @@
 use {
     crate::{
-        context::{ChainContext, EvmConfig, ExecutorContext, TransactionContext},
+        context::{ChainContext, EvmConfig, ExecutorContext, HardforkConfig, TransactionContext},
         error::*,
         state::{AccountProvider, EvmBackend, Incomming},
         transactions::{
-            Transaction, TransactionAction, TransactionInReceipt, TransactionReceipt,
-            UnsignedTransaction, UnsignedTransactionWithCaller,
+            AccessListItem, Transaction, TransactionAction, TransactionInReceipt,
+            TransactionReceipt, TypedTransaction, TypedTransactionFee, UnsignedTransaction,
+            UnsignedTransactionWithCaller,
         },
         types::H160,
         BlockVersion, CallScheme,
@@ -159,6 +160,8 @@ pub struct FeatureSet {
     unsigned_tx_fix: bool,
     clear_logs_on_error: bool,
     accept_zero_gas_price_with_native_fee: bool,
+    berlin_hardfork: bool,
+    london_hardfork: bool,
 }
 
 impl FeatureSet {
@@ -166,19 +169,27 @@ impl FeatureSet {
         unsigned_tx_fix: bool,
         clear_logs_on_error: bool,
         accept_zero_gas_price_with_native_fee: bool,
+        berlin_hardfork: bool,
+        london_hardfork: bool,
     ) -> Self {
         FeatureSet {
             unsigned_tx_fix,
             clear_logs_on_error,
             accept_zero_gas_price_with_native_fee,
+            berlin_hardfork,
+            london_hardfork,
         }
     }
 
+    /// Enables all execution fixes, but keeps hardfork selection from `EvmConfig`,
+    /// because newer hardforks change gas costs.
     pub fn new_with_all_enabled() -> Self {
         FeatureSet {
             unsigned_tx_fix: true,
             clear_logs_on_error: true,
             accept_zero_gas_price_with_native_fee: true,
+            berlin_hardfork: false,
+            london_hardfork: false,
         }
     }
 
@@ -193,6 +204,25 @@ impl FeatureSet {
     pub fn is_accept_zero_gas_price_with_native_fee_enabled(&self) -> bool {
         self.accept_zero_gas_price_with_native_fee
     }
+
+    pub fn is_berlin_hardfork_enabled(&self) -> bool {
+        self.berlin_hardfork
+    }
+
+    pub fn is_london_hardfork_enabled(&self) -> bool {
+        self.london_hardfork
+    }
+
+    /// Returns latest activated hardfork, or `default` if no newer hardfork was activated.
+    pub fn hardfork_config(&self, default: HardforkConfig) -> HardforkConfig {
+        if self.london_hardfork {
+            HardforkConfig::London
+        } else if self.berlin_hardfork {
+            HardforkConfig::Berlin
+        } else {
+            default
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -226,9 +256,10 @@ impl Executor {
     pub fn with_config(
         evm_backend: EvmBackend<Incomming>,
         chain_context: ChainContext,
-        config: EvmConfig,
+        mut config: EvmConfig,
         feature_set: FeatureSet,
     ) -> Self {
+        config.executor_config = feature_set.hardfork_config(config.executor_config);
         Executor {
             evm_backend,
             chain_context,
@@ -255,6 +286,7 @@ impl Executor {
         action: TransactionAction,
         input: Vec<u8>,
         value: U256,
+        access_list: Vec<AccessListItem>,
         tx_chain_id: Option<u64>,
         tx_hash: H256,
         withdraw_fee: bool,
@@ -347,13 +379,21 @@ impl Executor {
         let state =
             MemoryStackState::new(metadata, &execution_context, clear_logs_on_error_enabled);
         let mut executor = StackExecutor::new_with_precompiles(state, &config, &precompiles);
+        let access_list = AccessListItem::into_evm_access_list(access_list);
         let (exit_reason, exit_data) = match action {
             TransactionAction::Call(addr) => {
                 debug!(
                     "TransactionAction::Call caller  = {}, to = {}.",
                     caller, addr
                 );
-                executor.transact_call(caller, addr, value, input, gas_limit.as_u64(), vec![])
+                executor.transact_call(
+                    caller,
+                    addr,
+                    value,
+                    input,
+                    gas_limit.as_u64(),
+                    access_list,
+                )
             }
             TransactionAction::Create => {
                 let addr = TransactionAction::Create.address(caller, nonce);
@@ -361,7 +401,7 @@ impl Executor {
                     "TransactionAction::Create caller  = {}, to = {:?}.",
                     caller, addr
                 );
-                executor.transact_create(caller, value, input, gas_limit.as_u64(), vec![])
+                executor.transact_create(caller, value, input, gas_limit.as_u64(), access_list)
             }
         };
         let traces = executor.take_traces();
@@ -431,6 +471,7 @@ impl Executor {
             tx.action,
             tx.input.clone(),
             tx.value,
+            vec![],
             Some(chain_id),
             tx_hash,
             withdraw_fee,
@@ -465,6 +506,7 @@ impl Executor {
             action,
             input,
             value,
+            vec![],
             evm_tx.signature.chain_id(),
             tx_hash,
             withdraw_fee,
@@ -476,6 +518,56 @@ impl Executor {
         Ok(result)
     }
 
+    /// Execute EIP-2718 typed transaction (EIP-2930 or EIP-1559).
+    ///
+    /// Velas has no dynamic base fee, `burn_gas_price` is used as base fee,
+    /// so EIP-1559 transaction pays `min(max_fee_per_gas, burn_gas_price + max_priority_fee_per_gas)`.
+    pub fn transaction_execute_typed(
+        &mut self,
+        evm_tx: TypedTransaction,
+        withdraw_fee: bool,
+        precompiles: OwnedPrecompile,
+    ) -> Result<ExecutionResult, Error> {
+        let caller = evm_tx.caller()?; // This method verify signature.
+
+        if let TypedTransactionFee::DynamicFee {
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+        } = evm_tx.fee
+        {
+            ensure!(
+                max_priority_fee_per_gas <= max_fee_per_gas,
+                PriorityFeeGreaterThanMaxFee {
+                    max_priority_fee_per_gas,
+                    max_fee_per_gas,
+                }
+            );
+        }
+        let gas_price = evm_tx
+            .fee
+            .effective_gas_price(self.config.burn_gas_price);
+
+        let tx_hash = evm_tx.tx_id_hash();
+        let result = self.transaction_execute_raw(
+            caller,
+            evm_tx.nonce,
+            gas_price,
+            evm_tx.gas_limit,
+            evm_tx.action,
+            evm_tx.input.clone(),
+            evm_tx.value,
+            evm_tx.access_list.clone(),
+            Some(evm_tx.chain_id),
+            tx_hash,
+            withdraw_fee,
+            precompiles,
+        )?;
+
+        self.register_tx_with_receipt(TransactionInReceipt::Typed(evm_tx), result.clone());
+
+        Ok(result)
+    }
+
     /// Do lowlevel operation with executor, without storing transaction into logs.
     /// Usefull for testing and transfering tokens from evm to solana and back.
     // Used for:
@@ -521,10 +613,7 @@ impl Executor {
 
     // TODO: Handle duplicates, statuses.
     fn register_tx_with_receipt(&mut self, tx: TransactionInReceipt, result: ExecutionResult) {
-        let tx_hash = match &tx {
-            TransactionInReceipt::Signed(tx) => tx.tx_id_hash(),
-            TransactionInReceipt::Unsigned(tx) => tx.tx_id_hash(),
-        };
+        let tx_hash = tx.tx_id_hash();
 
         debug!(
             "Register tx = {} in EVM block = {}",
@@ -582,7 +671,13 @@ impl Executor {
         });
     }
 
-    pub fn register_swap_tx_in_evm(&mut self, mint_address: H160, recipient: H160, amount: U256) {
+    /// Register swap as evm transaction from `mint_address`, returns hash of this transaction.
+    pub fn register_swap_tx_in_evm(
+        &mut self,
+        mint_address: H160,
+        recipient: H160,
+        amount: U256,
+    ) -> H256 {
         let nonce = self.with_executor(OwnedPrecompile::default(), |e| {
             let nonce = e.nonce(mint_address);
             e.state_mut().inc_nonce(mint_address);
@@ -610,7 +705,9 @@ impl Executor {
             tx_id: unsigned_tx.tx_id_hash(),
             traces: Vec::new(),
         };
-        self.register_tx_with_receipt(TransactionInReceipt::Unsigned(unsigned_tx), result)
+        let tx_hash = result.tx_id;
+        self.register_tx_with_receipt(TransactionInReceipt::Unsigned(unsigned_tx), result);
+        tx_hash
     }
 
     /// After "swap from evm" transaction EVM_MINT_ADDRESS will cleanup. Using this method.
@@ -854,6 +951,195 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn execute_typed_txs() {
+        let _logger = simple_logger::SimpleLogger::new()
+            .with_utc_timestamps()
+            .init();
+
+        let chain_id = TEST_CHAIN_ID;
+        let evm_config = EvmConfig {
+            chain_id,
+            ..EvmConfig::default()
+        };
+        let mut executor = Executor::with_config(
+            EvmBackend::default(),
+            Default::default(),
+            evm_config,
+            FeatureSet::new_with_all_enabled(),
+        );
+
+        let code = hex::decode(METACOIN_CODE).unwrap();
+        let alice = Persona::new();
+        let create_tx = crate::UnsignedTypedTransaction {
+            chain_id,
+            nonce: U256::zero(),
+            fee: TypedTransactionFee::AccessList {
+                gas_price: U256::zero(),
+            },
+            gas_limit: U256::from(u64::MAX),
+            action: TransactionAction::Create,
+            value: U256::zero(),
+            input: code,
+            access_list: vec![],
+        }
+        .sign(&alice.secret);
+        let contract = create_tx.address().unwrap();
+        let create_hash = create_tx.tx_id_hash();
+
+        assert!(matches!(
+            executor
+                .transaction_execute_typed(create_tx, true, OwnedPrecompile::default())
+                .unwrap()
+                .exit_reason,
+            ExitReason::Succeed(ExitSucceed::Returned)
+        ));
+        let receipt = executor.get_tx_receipt_by_hash(create_hash).unwrap();
+        assert_eq!(receipt.transaction.tx_type(), crate::ACCESS_LIST_TX_TYPE);
+
+        let call = |nonce: u64, max_priority_fee_per_gas: u64| {
+            crate::UnsignedTypedTransaction {
+                chain_id,
+                nonce: nonce.into(),
+                fee: TypedTransactionFee::DynamicFee {
+                    max_priority_fee_per_gas: max_priority_fee_per_gas.into(),
+                    max_fee_per_gas: U256::zero(),
+                },
+                gas_limit: U256::from(u64::MAX),
+                action: TransactionAction::Call(contract),
+                value: U256::zero(),
+                input: metacoin::GET_BALANCE
+                    .encode_input(&[Token::Address(alice.address())])
+                    .unwrap(),
+                access_list: vec![crate::AccessListItem {
+                    address: contract,
+                    storage_keys: vec![H256::zero()],
+                }],
+            }
+            .sign(&alice.secret)
+        };
+
+        assert!(matches!(
+            executor
+                .transaction_execute_typed(call(1, 1), true, OwnedPrecompile::default())
+                .unwrap_err(),
+            Error::PriorityFeeGreaterThanMaxFee { .. }
+        ));
+
+        let ExecutionResult {
+            exit_reason,
+            exit_data: bytes,
+            ..
+        } = executor
+            .transaction_execute_typed(call(1, 0), true, OwnedPrecompile::default())
+            .unwrap();
+        assert_eq!(exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(
+            metacoin::GET_BALANCE.decode_output(&bytes).unwrap(),
+            vec![Token::Uint(U256::from(INITIAL_BALANCE))]
+        );
+    }
+
+    #[test]
+    fn hardfork_selected_by_feature_set() {
+        let _logger = simple_logger::SimpleLogger::new()
+            .with_utc_timestamps()
+            .init();
+
+        // BASEFEE POP STOP - deploys empty contract, BASEFEE is available since London.
+        let code = hex::decode("485000").unwrap();
+        let alice = Persona::new();
+        let evm_config = EvmConfig::default();
+
+        let mut executor = Executor::with_config(
+            EvmBackend::default(),
+            Default::default(),
+            evm_config,
+            FeatureSet::new_with_all_enabled(),
+        );
+        assert_eq!(executor.config().executor_config, HardforkConfig::Istanbul);
+        assert!(matches!(
+            executor
+                .transaction_execute(alice.create(&code), true, OwnedPrecompile::default())
+                .unwrap()
+                .exit_reason,
+            ExitReason::Error(_)
+        ));
+
+        let feature_set = FeatureSet::new(true, true, true, true, false);
+        assert_eq!(
+            feature_set.hardfork_config(HardforkConfig::Istanbul),
+            HardforkConfig::Berlin
+        );
+        let feature_set = FeatureSet::new(true, true, true, true, true);
+        assert_eq!(
+            feature_set.hardfork_config(HardforkConfig::Istanbul),
+            HardforkConfig::London
+        );
+
+        let mut executor = Executor::with_config(
+            EvmBackend::default(),
+            Default::default(),
+            evm_config,
+            feature_set,
+        );
+        assert_eq!(executor.config().executor_config, HardforkConfig::London);
+        assert!(matches!(
+            executor
+                .transaction_execute(alice.create(&code), true, OwnedPrecompile::default())
+                .unwrap()
+                .exit_reason,
+            ExitReason::Succeed(_)
+        ));
+    }
+
+    #[test]
+    fn struct_logs_of_create() {
+        let _logger = simple_logger::SimpleLogger::new()
+            .with_utc_timestamps()
+            .init();
+
+        // PUSH1 1 PUSH1 0 SSTORE PUSH1 0 PUSH1 0 RETURN
+        let code = hex::decode("600160005560006000f3").unwrap();
+        let alice = Persona::new();
+        let mut executor = Executor::with_config(
+            EvmBackend::default(),
+            Default::default(),
+            EvmConfig::default(),
+            FeatureSet::new_with_all_enabled(),
+        );
+
+        let (result, traces) = crate::struct_logger::trace_struct_logs(Default::default(), || {
+            executor.transaction_execute(alice.create(&code), true, OwnedPrecompile::default())
+        });
+        assert!(matches!(
+            result.unwrap().exit_reason,
+            ExitReason::Succeed(_)
+        ));
+
+        let ops: Vec<_> = traces
+            .logs
+            .iter()
+            .map(|log| crate::struct_logger::opcode_name(log.op))
+            .collect();
+        assert_eq!(
+            ops,
+            ["PUSH1", "PUSH1", "SSTORE", "PUSH1", "PUSH1", "RETURN"]
+        );
+        assert!(traces.logs.iter().all(|log| log.depth == 1));
+        assert_eq!(traces.logs[0].gas_cost, 3);
+        assert_eq!(traces.logs[1].gas, traces.logs[0].gas - 3);
+        assert_eq!(traces.logs[2].stack.as_ref().unwrap().len(), 2);
+
+        let sstore_storage = traces.logs[2].storage.as_ref().unwrap();
+        assert_eq!(
+            sstore_storage.get(&H256::zero()),
+            Some(&H256::from_low_u64_be(1))
+        );
+        assert!(traces.logs[3].storage.is_none());
+        assert_eq!(traces.accessed_storage.len(), 1);
+    }
+
     #[test]
     fn handle_duplicate_txs_unsigned() {
         let _logger = simple_logger::SimpleLogger::new()
@@ -869,7 +1155,7 @@ mod tests {
             EvmBackend::default(),
             Default::default(),
             evm_config,
-            FeatureSet::new(false, true, false),
+            FeatureSet::new(false, true, false, false, false),
         );
 
         let code = hex::decode(METACOIN_CODE).unwrap();
@@ -1478,7 +1764,7 @@ mod tests {
             EvmBackend::default(),
             Default::default(),
             Default::default(),
-            FeatureSet::new(false, true, false),
+            FeatureSet::new(false, true, false, false, false),
         );
 
         let exit_reason = match executor.with_executor(OwnedPrecompile::default(), |e| {
@@ -11,12 +11,13 @@ pub use {
 
 pub mod error;
 pub mod storage;
+pub mod struct_logger;
 pub mod traces;
 pub mod transactions;
 pub mod types;
 
 pub use {
-    context::{ChainContext, EvmConfig},
+    context::{ChainContext, EvmConfig, HardforkConfig},
     ethbloom::Bloom,
     executor::{
         ExecutionResult, Executor, PrecompileCallResult, HELLO_WORLD_ABI, HELLO_WORLD_CODE,
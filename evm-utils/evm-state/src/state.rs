@@ -234,6 +234,15 @@ impl EvmBackend<Incomming> {
             .push((transaction, receipt));
     }
 
+    pub fn executed_transactions_len(&self) -> usize {
+        self.state.executed_transactions.len()
+    }
+
+    /// Forget receipts of all transactions, executed after first `len`.
+    pub fn truncate_transaction_receipts(&mut self, len: usize) {
+        self.state.executed_transactions.truncate(len)
+    }
+
     pub fn get_executed_transactions(&self) -> Vec<H256> {
         self.state
             .executed_transactions
@@ -0,0 +1,593 @@
+//! Cold tier of evm state archive.
+//!
+//! Tiered archive is a gc enabled `Storage`, where every recorded state root is registered
+//! in `SlotsRoots` under its slot. When slot becomes older than hot window, its root is unpinned,
+//! and trie nodes that are no longer reachable from any other registered root are moved
+//! into the cold store, before garbage collector removes them from rocksdb.
+//!
+//! Cold store is a directory with append-only segment files, where every node is compressed
+//! with zstd independently, and a small rocksdb index that maps node hash into its location.
+//! Migrated roots stay readable: trie nodes that are missing in hot storage are read from cold store.
+//!
+//! Roots that are pinned by readers are removed from hot storage only after they are unpinned.
+
+use {
+    super::{
+        account_extractor, delta::node_children, proof::keccak, Error as StorageError,
+        ReferenceCounter, SlotsRoots, Storage, SubStorage, VelasDBCommon, DB,
+    },
+    log::*,
+    primitive_types::H256,
+    rlp::{DecoderError, Rlp},
+    rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, ReadOptions, WriteBatch},
+    std::{
+        collections::{HashMap, HashSet},
+        convert::TryInto,
+        fs::{self, File, OpenOptions},
+        io::{Read, Seek, SeekFrom, Write},
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+    },
+    triedb::{
+        empty_trie_hash,
+        merkle::{nibble::NibbleVec, Branch, Extension, Leaf, MerkleNode, MerkleValue},
+        CachedDatabaseHandle, CachedHandle,
+    },
+};
+
+const INDEX_SUBDIR: &str = "index";
+const ROOTS_COLUMN: &str = "roots";
+const SEGMENT_PREFIX: &str = "segment-";
+const SEGMENT_SUFFIX: &str = ".zst";
+const MAX_SEGMENT_SIZE: u64 = 256 * 1024 * 1024;
+const COMPRESSION_LEVEL: i32 = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ColdError {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Database(#[from] rocksdb::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Unable to decode trie node: {0}")]
+    Decode(#[from] DecoderError),
+    #[error("Corrupted cold index entry for node: {0:?}")]
+    CorruptedIndex(H256),
+    #[error("Archive at {0:?} was created without gc, and cannot be tiered")]
+    NotGcArchive(PathBuf),
+}
+
+/// Position of compressed node in segment files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Location {
+    segment: u32,
+    offset: u64,
+    len: u32,
+}
+
+impl Location {
+    const SIZE: usize = 16;
+
+    fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.segment.to_be_bytes());
+        bytes[4..12].copy_from_slice(&self.offset.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.len.to_be_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::SIZE {
+            return None;
+        }
+        let mut segment = [0; 4];
+        let mut offset = [0; 8];
+        let mut len = [0; 4];
+        segment.copy_from_slice(&bytes[0..4]);
+        offset.copy_from_slice(&bytes[4..12]);
+        len.copy_from_slice(&bytes[12..16]);
+        Some(Self {
+            segment: u32::from_be_bytes(segment),
+            offset: u64::from_be_bytes(offset),
+            len: u32::from_be_bytes(len),
+        })
+    }
+}
+
+struct SegmentWriter {
+    id: u32,
+    file: File,
+    len: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationStats {
+    pub slots: usize,
+    pub roots: usize,
+    pub nodes: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ColdVerification {
+    pub nodes: usize,
+    pub roots: usize,
+    pub bytes: u64,
+    /// Nodes which cannot be read, or whose data doesn't match the hash.
+    pub corrupted: Vec<H256>,
+}
+
+pub struct ColdStore {
+    path: PathBuf,
+    index: rocksdb::DB,
+    writer: Mutex<SegmentWriter>,
+}
+
+impl std::fmt::Debug for ColdStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColdStore")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl ColdStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ColdError> {
+        let path = path.as_ref().to_owned();
+        fs::create_dir_all(&path)?;
+
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let index = rocksdb::DB::open_cf_descriptors(
+            &opts,
+            path.join(INDEX_SUBDIR),
+            vec![ColumnFamilyDescriptor::new(
+                ROOTS_COLUMN,
+                Options::default(),
+            )],
+        )?;
+
+        let id = Self::segments(&path)?.last().copied().unwrap_or_default();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Self::segment_path(&path, id))?;
+        let len = file.metadata()?.len();
+        info!(
+            "Opened evm cold store at {:?}, last segment: {}, len: {}",
+            path, id, len
+        );
+
+        Ok(Self {
+            path,
+            index,
+            writer: Mutex::new(SegmentWriter { id, file, len }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn segment_path(path: &Path, id: u32) -> PathBuf {
+        path.join(format!("{}{:08}{}", SEGMENT_PREFIX, id, SEGMENT_SUFFIX))
+    }
+
+    /// Sorted ids of existing segment files.
+    fn segments(path: &Path) -> Result<Vec<u32>, ColdError> {
+        let mut ids = vec![];
+        for entry in fs::read_dir(path)? {
+            let name = entry?.file_name();
+            let id = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(SEGMENT_PREFIX))
+                .and_then(|name| name.strip_suffix(SEGMENT_SUFFIX))
+                .and_then(|id| id.parse().ok());
+            if let Some(id) = id {
+                ids.push(id)
+            }
+        }
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    pub fn contains(&self, hash: H256) -> Result<bool, ColdError> {
+        Ok(self.index.get_pinned(hash)?.is_some())
+    }
+
+    pub fn get(&self, hash: H256) -> Result<Option<Vec<u8>>, ColdError> {
+        let location = match self.index.get_pinned(hash)? {
+            Some(bytes) => Location::from_bytes(&bytes).ok_or(ColdError::CorruptedIndex(hash))?,
+            None => return Ok(None),
+        };
+        let mut file = File::open(Self::segment_path(&self.path, location.segment))?;
+        file.seek(SeekFrom::Start(location.offset))?;
+        let mut compressed = vec![0; location.len as usize];
+        file.read_exact(&mut compressed)?;
+        Ok(Some(zstd::decode_all(compressed.as_slice())?))
+    }
+
+    /// Appends nodes that are not stored yet, returns number of written nodes and bytes.
+    ///
+    /// Segment is synced before index is updated, so index never points to lost data.
+    fn append(&self, nodes: &[(H256, Vec<u8>)]) -> Result<(usize, u64), ColdError> {
+        let mut writer = self.writer.lock().unwrap();
+        let mut batch = WriteBatch::default();
+        let mut written = (0, 0);
+        for (hash, data) in nodes {
+            if self.contains(*hash)? {
+                continue;
+            }
+            if writer.len >= MAX_SEGMENT_SIZE {
+                writer.file.sync_data()?;
+                let id = writer.id + 1;
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(Self::segment_path(&self.path, id))?;
+                *writer = SegmentWriter { id, file, len: 0 };
+            }
+            let compressed = zstd::encode_all(data.as_slice(), COMPRESSION_LEVEL)?;
+            writer.file.write_all(&compressed)?;
+            let location = Location {
+                segment: writer.id,
+                offset: writer.len,
+                len: compressed.len() as u32,
+            };
+            writer.len += compressed.len() as u64;
+            written.0 += 1;
+            written.1 += compressed.len() as u64;
+            batch.put(hash, location.to_bytes());
+        }
+        writer.file.sync_data()?;
+        self.index.write(batch)?;
+        Ok(written)
+    }
+
+    /// Slot under which migrated root was registered.
+    pub fn root_slot(&self, root: H256) -> Result<Option<u64>, ColdError> {
+        let bytes = match self.index.get_pinned_cf(self.roots_cf(), root)? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+        let slot = bytes
+            .as_ref()
+            .try_into()
+            .map_err(|_| ColdError::CorruptedIndex(root))?;
+        Ok(Some(u64::from_be_bytes(slot)))
+    }
+
+    fn record_root(&self, root: H256, slot: u64) -> Result<(), ColdError> {
+        Ok(self
+            .index
+            .put_cf(self.roots_cf(), root, slot.to_be_bytes())?)
+    }
+
+    fn roots_cf(&self) -> &rocksdb::ColumnFamily {
+        self.index
+            .cf_handle(ROOTS_COLUMN)
+            .expect("Cold index roots column should exist")
+    }
+
+    /// Reads every indexed node, and checks that its data matches the hash.
+    pub fn verify(&self) -> Result<ColdVerification, ColdError> {
+        let mut verification = ColdVerification::default();
+        for item in self.index.iterator(IteratorMode::Start) {
+            let (key, value) = item?;
+            let hash = H256::from_slice(&key);
+            verification.nodes += 1;
+            if let Some(location) = Location::from_bytes(&value) {
+                verification.bytes += location.len as u64;
+            }
+            match self.get(hash) {
+                Ok(Some(data)) if keccak(&data) == hash => {}
+                Ok(_) => verification.corrupted.push(hash),
+                Err(e) => {
+                    warn!("Cannot read cold node {:?}: {}", hash, e);
+                    verification.corrupted.push(hash)
+                }
+            }
+        }
+        for item in self.index.iterator_cf(self.roots_cf(), IteratorMode::Start) {
+            let (key, _) = item?;
+            let root = H256::from_slice(&key);
+            verification.roots += 1;
+            if !self.contains(root)? {
+                verification.corrupted.push(root)
+            }
+        }
+        Ok(verification)
+    }
+}
+
+/// Trie handle, that reads nodes from hot storage, and from cold tier if they were migrated there.
+pub struct TieredDatabaseHandle<'a> {
+    db: &'a DB,
+    cold: Option<&'a ColdStore>,
+}
+
+impl CachedDatabaseHandle for TieredDatabaseHandle<'_> {
+    fn get(&self, key: H256) -> Vec<u8> {
+        let node = self.db.get(key).expect("Error on reading database");
+        node.or_else(|| {
+            self.cold?
+                .get(key)
+                .unwrap_or_else(|e| panic!("Error on reading cold tier: {}", e))
+        })
+        .unwrap_or_else(|| panic!("Value for {} not found in database", key))
+    }
+}
+
+/// Hashes referenced by node: hashed children, and storage roots of accounts in leafs.
+/// Garbage collector counts references the same way.
+fn node_references(data: &[u8]) -> Result<Vec<H256>, ColdError> {
+    let node = MerkleNode::decode(&Rlp::new(data))?;
+    let mut children = vec![];
+    node_children(NibbleVec::default(), &node, &mut children);
+    let mut references: Vec<_> = children.into_iter().map(|(_, hash)| hash).collect();
+    leaf_references(&node, &mut references);
+    Ok(references)
+}
+
+fn leaf_references(node: &MerkleNode, references: &mut Vec<H256>) {
+    match node {
+        MerkleNode::Leaf(Leaf { data, .. }) => references.extend(
+            account_extractor(data)
+                .into_iter()
+                .filter(|root| *root != empty_trie_hash()),
+        ),
+        MerkleNode::Extension(Extension { value, .. }) => inlined_references(value, references),
+        MerkleNode::Branch(Branch { childs, .. }) => {
+            for child in childs.iter() {
+                inlined_references(child, references)
+            }
+        }
+    }
+}
+
+fn inlined_references(value: &MerkleValue, references: &mut Vec<H256>) {
+    if let MerkleValue::Full(node) = value {
+        leaf_references(node, references)
+    }
+}
+
+impl<D: VelasDBCommon> Storage<D> {
+    pub fn cold_tier(&self) -> Option<&ColdStore> {
+        self.cold.as_deref()
+    }
+
+    /// Returns true if state with `root` was moved into cold tier.
+    pub fn is_cold_root(&self, root: H256) -> bool {
+        match self.cold_tier().map(|cold| cold.root_slot(root)) {
+            Some(Ok(slot)) => slot.is_some(),
+            Some(Err(e)) => {
+                warn!("Cannot read cold index for root {:?}: {}", root, e);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Trie node from hot storage, or from cold tier if it was migrated there.
+    pub fn trie_node(&self, hash: H256) -> Result<Option<Vec<u8>>, ColdError> {
+        if let Some(node) = self.db().get_opt(hash, &ReadOptions::default())? {
+            return Ok(Some(node));
+        }
+        match self.cold_tier() {
+            Some(cold) => cold.get(hash),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Storage {
+    /// Opens gc enabled archive, with cold tier at `cold_path`.
+    ///
+    /// Existing archive without reference counters cannot be tiered,
+    /// because its nodes can be shared with roots, that are not registered in `SlotsRoots`.
+    pub fn open_tiered_archive(
+        path: impl AsRef<Path>,
+        cold_path: impl AsRef<Path>,
+    ) -> Result<Self, ColdError> {
+        let path = path.as_ref();
+        let exist_cfs = DB::list_cf(&Options::default(), path).unwrap_or_default();
+        if !exist_cfs.is_empty()
+            && !exist_cfs
+                .iter()
+                .any(|cf| cf == ReferenceCounter::COLUMN_NAME)
+        {
+            return Err(ColdError::NotGcArchive(path.to_owned()));
+        }
+        let mut storage = Self::open_persistent(path, true)?;
+        storage.cold = Some(Arc::new(ColdStore::open(cold_path)?));
+        Ok(storage)
+    }
+
+    /// Trie handle for reading states, including states that were moved into cold tier.
+    pub fn tiered_trie_handle(&self) -> CachedHandle<TieredDatabaseHandle<'_>> {
+        CachedHandle::new(TieredDatabaseHandle {
+            db: self.db(),
+            cold: self.cold_tier(),
+        })
+    }
+
+    /// Moves states of slots registered before `before_slot` into the cold tier.
+    /// At most `max_slots` are processed, starting from the oldest.
+    ///
+    /// Nodes are written into cold tier before they are removed from hot storage,
+    /// and roots that are pinned by readers are removed only after they are unpinned,
+    /// by one of the following calls.
+    pub fn migrate_to_cold(
+        &self,
+        before_slot: u64,
+        max_slots: usize,
+    ) -> Result<MigrationStats, ColdError> {
+        let mut stats = MigrationStats {
+            roots: self.remove_released_roots()?,
+            ..MigrationStats::default()
+        };
+        let cold = match self.cold_tier() {
+            Some(cold) => cold,
+            None => return Ok(stats),
+        };
+
+        let mut slots = vec![];
+        for item in self
+            .db()
+            .iterator_cf(self.cf::<SlotsRoots>(), IteratorMode::Start)
+        {
+            let (key, value) = item?;
+            let mut slot = [0; 8];
+            slot.copy_from_slice(&key[0..8]);
+            let slot = u64::from_be_bytes(slot);
+            if slot >= before_slot || slots.len() >= max_slots {
+                break;
+            }
+            slots.push((slot, H256::from_slice(&value)));
+        }
+
+        for (slot, root) in slots {
+            // Root can be shared by multiple slots, nodes are removed only with the last one.
+            if self.gc_count(root)? <= 1 {
+                let nodes = self.unreachable_nodes(root)?;
+                let (nodes, bytes) = cold.append(&nodes)?;
+                stats.nodes += nodes;
+                stats.bytes += bytes;
+                cold.record_root(root, slot)?;
+            }
+            if let Some(root) = self.purge_slot(slot)? {
+                stats.roots += self.remove_unpinned_roots(vec![root])?;
+            }
+            stats.slots += 1;
+        }
+        Ok(stats)
+    }
+
+    /// Nodes that will be removed by garbage collector, when last reference to `root` is dropped.
+    ///
+    /// Each reference decrements counter of a child, so result can only include more nodes,
+    /// than collector actually removes, but never less.
+    fn unreachable_nodes(&self, root: H256) -> Result<Vec<(H256, Vec<u8>)>, ColdError> {
+        let mut decrements: HashMap<H256, u64> = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![root];
+        let mut nodes = vec![];
+        while let Some(hash) = stack.pop() {
+            if !visited.insert(hash) {
+                continue;
+            }
+            let data = match self.db().get(hash)? {
+                Some(data) => data,
+                None => continue,
+            };
+            for child in node_references(&data)? {
+                let decrement = decrements.entry(child).or_default();
+                *decrement += 1;
+                if self.gc_count(child)? <= *decrement {
+                    stack.push(child);
+                }
+            }
+            nodes.push((hash, data));
+        }
+        Ok(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            empty_trie_hash,
+            types::{Account, AccountState, Maybe},
+        },
+        primitive_types::H160,
+        tempfile::TempDir,
+    };
+
+    fn record_slot(storage: &Storage, slot: u64, parent: H256, nonce: u64) -> H256 {
+        let state = AccountState {
+            nonce: nonce.into(),
+            ..Default::default()
+        };
+        let changes = std::iter::once((
+            H160::from_low_u64_be(nonce),
+            (
+                Maybe::Just(state),
+                std::iter::once((H256::repeat_byte(1), H256::from_low_u64_be(nonce))).collect(),
+            ),
+        ))
+        .collect();
+        let root = storage.flush_changes(parent, changes);
+        storage.register_slot(slot, root, false).unwrap();
+        root
+    }
+
+    #[test]
+    fn migrated_root_is_read_from_cold_tier() {
+        let dir = TempDir::new().unwrap();
+        let storage =
+            Storage::open_tiered_archive(dir.path().join("hot"), dir.path().join("cold")).unwrap();
+
+        let mut roots = vec![];
+        let mut parent = empty_trie_hash();
+        for slot in 0..5 {
+            parent = record_slot(&storage, slot, parent, slot + 1);
+            roots.push(parent);
+        }
+
+        // state of first slot is read during migration
+        let pinned = storage.pin_root(roots[0]).unwrap();
+        let stats = storage.migrate_to_cold(3, 100).unwrap();
+        assert_eq!(stats.slots, 3);
+        assert_eq!(stats.roots, 2);
+        assert!(stats.nodes > 0);
+        assert!(storage.db().get(roots[0]).unwrap().is_some());
+        drop(pinned);
+        // unpinned root is removed by the next migration
+        assert_eq!(storage.migrate_to_cold(3, 100).unwrap().roots, 1);
+        for root in &roots[0..3] {
+            assert!(storage.db().get(root).unwrap().is_none());
+            assert!(storage.is_cold_root(*root));
+            assert!(storage.check_root_exist(*root));
+        }
+        // newer states are untouched
+        let proof = storage
+            .get_proof(roots[4], H160::from_low_u64_be(2), &[])
+            .unwrap();
+        assert_eq!(proof.account.nonce, 2.into());
+
+        let verification = storage.cold_tier().unwrap().verify().unwrap();
+        assert_eq!(verification.nodes, stats.nodes);
+        assert_eq!(verification.roots, 3);
+        assert!(verification.corrupted.is_empty());
+
+        // migrated states are readable without moving them back into hot storage
+        let account: Account = storage
+            .typed_for(roots[1])
+            .get(&H160::from_low_u64_be(2))
+            .unwrap();
+        assert_eq!(account.nonce, 2.into());
+        let proof = storage
+            .get_proof(roots[1], H160::from_low_u64_be(2), &[H256::repeat_byte(1)])
+            .unwrap();
+        assert_eq!(proof.account.nonce, 2.into());
+        assert_eq!(proof.storage_proofs[0].value, 2.into());
+        proof.verify(roots[1]).unwrap();
+        assert!(storage.db().get(roots[1]).unwrap().is_none());
+
+        assert_eq!(storage.migrate_to_cold(3, 100).unwrap().slots, 0);
+    }
+
+    #[test]
+    fn non_gc_archive_cannot_be_tiered() {
+        let dir = TempDir::new().unwrap();
+        drop(Storage::open_persistent(dir.path().join("hot"), false).unwrap());
+        assert!(matches!(
+            Storage::open_tiered_archive(dir.path().join("hot"), dir.path().join("cold")),
+            Err(ColdError::NotGcArchive(_))
+        ));
+    }
+}
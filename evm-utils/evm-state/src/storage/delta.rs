@@ -0,0 +1,401 @@
+//! Difference between two evm states, that is used by incremental snapshots.
+//!
+//! Delta contains trie nodes and codes, that are reachable from the new root, but are not
+//! present at the same position in the base trie. Tries are compared in the same order,
+//! as geth difference iterator does: subtrees with equal hash at the same path are skipped.
+
+use {
+    super::{
+        proof::{keccak, lookup, ProofError},
+        Codes, Storage,
+    },
+    crate::types::{Account, Code},
+    primitive_types::H256,
+    rlp::{DecoderError, Rlp},
+    rocksdb::ReadOptions,
+    serde::{Deserialize, Serialize},
+    std::{cmp::Ordering, collections::BTreeMap},
+    triedb::{
+        empty_trie_hash,
+        merkle::{
+            nibble::{self, Nibble, NibbleVec},
+            Branch, Extension, Leaf, MerkleNode, MerkleValue,
+        },
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeltaError {
+    #[error(transparent)]
+    Database(#[from] rocksdb::Error),
+    #[error("Trie node not found: {0:?}")]
+    NodeNotFound(H256),
+    #[error("Code not found: {0:?}")]
+    CodeNotFound(H256),
+    #[error("Unable to decode trie node or account: {0}")]
+    Decode(#[from] DecoderError),
+    #[error("Hash of node or code doesn't match: {0:?}")]
+    HashMismatch(H256),
+    #[error("Root not found: {0:?}")]
+    RootNotFound(H256),
+}
+
+/// Nodes and codes, that should be added to state with `base_root`, to get state with `root`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDelta {
+    pub base_root: H256,
+    pub root: H256,
+    pub nodes: Vec<(H256, Vec<u8>)>,
+    pub codes: Vec<(H256, Code)>,
+}
+
+impl Storage {
+    /// Collects delta between states with `base_root` and `root`.
+    ///
+    /// Base state can be partially removed from database, such nodes are treated as absent,
+    /// and delta will include whole subtree of the new trie at their position.
+    pub fn state_delta(&self, base_root: H256, root: H256) -> Result<StateDelta, DeltaError> {
+        let mut nodes = BTreeMap::new();
+        let mut codes = BTreeMap::new();
+
+        let mut accounts = vec![];
+        self.trie_difference(base_root, root, &mut nodes, &mut accounts)?;
+        for (key, data) in accounts {
+            let account: Account = rlp::decode(&data)?;
+            let base_account = self.base_account(base_root, key).unwrap_or_default();
+
+            let base_storage_root = if base_account == Account::default() {
+                empty_trie_hash()
+            } else {
+                base_account.storage_root
+            };
+            self.trie_difference(
+                base_storage_root,
+                account.storage_root,
+                &mut nodes,
+                &mut vec![],
+            )?;
+
+            if account.code_hash != Code::empty().hash()
+                && account.code_hash != base_account.code_hash
+            {
+                let code = self
+                    .get::<Codes>(account.code_hash)
+                    .ok_or(DeltaError::CodeNotFound(account.code_hash))?;
+                codes.insert(account.code_hash, code);
+            }
+        }
+
+        Ok(StateDelta {
+            base_root,
+            root,
+            nodes: nodes.into_iter().collect(),
+            codes: codes.into_iter().collect(),
+        })
+    }
+
+    /// Writes nodes and codes of delta into database.
+    ///
+    /// Checks that base state exist, and that every node and code matches its hash.
+    /// Reference counters are not updated, so state should be copied with `copy_and_purge`
+    /// into gc enabled storage.
+    pub fn apply_delta(&self, delta: &StateDelta) -> Result<(), DeltaError> {
+        if !self.check_root_exist(delta.base_root) {
+            return Err(DeltaError::RootNotFound(delta.base_root));
+        }
+        for (hash, node) in &delta.nodes {
+            if keccak(node) != *hash {
+                return Err(DeltaError::HashMismatch(*hash));
+            }
+            self.db().put(hash, node)?;
+        }
+        for (hash, code) in &delta.codes {
+            if code.hash() != *hash {
+                return Err(DeltaError::HashMismatch(*hash));
+            }
+            self.set::<Codes>(*hash, code.clone());
+        }
+        if !self.check_root_exist(delta.root) {
+            return Err(DeltaError::RootNotFound(delta.root));
+        }
+        Ok(())
+    }
+
+    /// Adds hashed nodes of trie `root`, missing in trie `base_root`, into `nodes`,
+    /// and key-values from leafs of these nodes into `leafs`.
+    fn trie_difference(
+        &self,
+        base_root: H256,
+        root: H256,
+        nodes: &mut BTreeMap<H256, Vec<u8>>,
+        leafs: &mut Vec<(H256, Vec<u8>)>,
+    ) -> Result<(), DeltaError> {
+        let mut base = TrieNodes::new(self, base_root, true)?;
+        let mut new = TrieNodes::new(self, root, false)?;
+
+        while let Some(node) = &new.current {
+            let order = base
+                .current
+                .as_ref()
+                .map(|base_node| (cmp_path(&base_node.path, &node.path), base_node.hash));
+            match order {
+                Some((Ordering::Less, _)) => {
+                    base.advance(true)?;
+                    continue;
+                }
+                Some((Ordering::Equal, base_hash)) if base_hash == node.hash => {
+                    base.advance(false)?;
+                    new.advance(false)?;
+                    continue;
+                }
+                Some((Ordering::Equal, _)) => base.advance(true)?,
+                Some((Ordering::Greater, _)) | None => {}
+            }
+
+            let bytes = node
+                .bytes
+                .clone()
+                .ok_or(DeltaError::NodeNotFound(node.hash))?;
+            let merkle = MerkleNode::decode(&Rlp::new(&bytes))?;
+            collect_leafs(node.path.clone(), &merkle, leafs);
+            nodes.insert(node.hash, bytes);
+            new.advance(true)?;
+        }
+        Ok(())
+    }
+
+    fn base_account(&self, base_root: H256, key: H256) -> Option<Account> {
+        let bytes = lookup(base_root, key, |hash| {
+            self.db
+                .get_opt(hash.as_ref(), &ReadOptions::default())?
+                .ok_or(ProofError::NodeNotFound(hash))
+        })
+        .ok()??;
+        rlp::decode(&bytes).ok()
+    }
+}
+
+struct TrieNode {
+    path: NibbleVec,
+    hash: H256,
+    /// Missing node is allowed only in the base trie, its subtree is not visited.
+    bytes: Option<Vec<u8>>,
+}
+
+/// Pre-order iterator over nodes of trie, that are referenced by hash.
+struct TrieNodes<'a> {
+    storage: &'a Storage,
+    allow_missing: bool,
+    stack: Vec<(NibbleVec, H256)>,
+    current: Option<TrieNode>,
+}
+
+impl<'a> TrieNodes<'a> {
+    fn new(storage: &'a Storage, root: H256, allow_missing: bool) -> Result<Self, DeltaError> {
+        let mut nodes = Self {
+            storage,
+            allow_missing,
+            stack: vec![],
+            current: None,
+        };
+        if root != empty_trie_hash() {
+            nodes.stack.push((NibbleVec::default(), root));
+        }
+        nodes.advance(false)?;
+        Ok(nodes)
+    }
+
+    /// Moves to the next node, children of current node are skipped unless `descend` is set.
+    fn advance(&mut self, descend: bool) -> Result<(), DeltaError> {
+        if let Some(TrieNode {
+            path,
+            bytes: Some(bytes),
+            ..
+        }) = self.current.take()
+        {
+            if descend {
+                let mut children = vec![];
+                node_children(path, &MerkleNode::decode(&Rlp::new(&bytes))?, &mut children);
+                self.stack.extend(children.into_iter().rev());
+            }
+        }
+
+        if let Some((path, hash)) = self.stack.pop() {
+            let bytes = self
+                .storage
+                .db
+                .get_opt(hash.as_ref(), &ReadOptions::default())?;
+            if bytes.is_none() && !self.allow_missing {
+                return Err(DeltaError::NodeNotFound(hash));
+            }
+            self.current = Some(TrieNode { path, hash, bytes });
+        }
+        Ok(())
+    }
+}
+
+fn cmp_path(lhs: &[Nibble], rhs: &[Nibble]) -> Ordering {
+    let index = |nibble: &Nibble| -> usize { (*nibble).into() };
+    lhs.iter().map(index).cmp(rhs.iter().map(index))
+}
+
+pub(super) fn node_children(
+    path: NibbleVec,
+    node: &MerkleNode,
+    children: &mut Vec<(NibbleVec, H256)>,
+) {
+    match node {
+        MerkleNode::Leaf(_) => {}
+        MerkleNode::Extension(Extension { nibbles, value }) => {
+            let mut path = path;
+            path.extend_from_slice(nibbles);
+            value_children(path, value, children)
+        }
+        MerkleNode::Branch(Branch { childs, .. }) => {
+            for (index, child) in childs.iter().enumerate() {
+                let mut path = path.clone();
+                path.push(index.into());
+                value_children(path, child, children)
+            }
+        }
+    }
+}
+
+fn value_children(path: NibbleVec, value: &MerkleValue, children: &mut Vec<(NibbleVec, H256)>) {
+    match value {
+        MerkleValue::Empty => {}
+        MerkleValue::Full(node) => node_children(path, node, children),
+        MerkleValue::Hash(hash) => children.push((path, *hash)),
+    }
+}
+
+/// Collects leafs of node, including leafs of inlined children.
+fn collect_leafs(path: NibbleVec, node: &MerkleNode, leafs: &mut Vec<(H256, Vec<u8>)>) {
+    match node {
+        MerkleNode::Leaf(Leaf { nibbles, data }) => {
+            let mut path = path;
+            path.extend_from_slice(nibbles);
+            let key = nibble::into_key(&path);
+            // Secure trie keys are always hashes.
+            if key.len() == H256::len_bytes() {
+                leafs.push((H256::from_slice(&key), data.to_vec()));
+            }
+        }
+        MerkleNode::Extension(Extension { nibbles, value }) => {
+            let mut path = path;
+            path.extend_from_slice(nibbles);
+            inlined_leafs(path, value, leafs)
+        }
+        MerkleNode::Branch(Branch { childs, .. }) => {
+            for (index, child) in childs.iter().enumerate() {
+                let mut path = path.clone();
+                path.push(index.into());
+                inlined_leafs(path, child, leafs)
+            }
+        }
+    }
+}
+
+fn inlined_leafs(path: NibbleVec, value: &MerkleValue, leafs: &mut Vec<(H256, Vec<u8>)>) {
+    if let MerkleValue::Full(node) = value {
+        collect_leafs(path, node, leafs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            storage::copy_and_purge,
+            types::{AccountState, Maybe},
+        },
+        primitive_types::H160,
+        std::collections::HashMap,
+    };
+
+    fn account(
+        i: usize,
+        code: Code,
+        slots: &[H256],
+    ) -> (H160, (Maybe<AccountState>, HashMap<H256, H256>)) {
+        let state = AccountState {
+            nonce: i.into(),
+            balance: (i * 1000).into(),
+            code,
+        };
+        let storage = slots
+            .iter()
+            .map(|slot| (*slot, H256::from_low_u64_be(i as u64 + 1)))
+            .collect();
+        (
+            H160::from_low_u64_be(i as u64),
+            (Maybe::Just(state), storage),
+        )
+    }
+
+    #[test]
+    fn delta_applied_on_top_of_base_state() {
+        let storage = Storage::create_temporary().unwrap();
+        let slots: Vec<H256> = (1..=20).map(H256::repeat_byte).collect();
+
+        let changes = (1..=100)
+            .map(|i| account(i, Code::from(vec![i as u8; 64]), &slots))
+            .collect();
+        let base_root = storage.flush_changes(empty_trie_hash(), changes);
+
+        let changes = (95..=105)
+            .map(|i| account(i * 2, Code::from(vec![0xff, i as u8]), &slots[..3]))
+            .collect();
+        let root = storage.flush_changes(base_root, changes);
+
+        let delta = storage.state_delta(base_root, root).unwrap();
+        assert_eq!((delta.base_root, delta.root), (base_root, root));
+        assert_eq!(delta.codes.len(), 11);
+        let full = storage.state_delta(empty_trie_hash(), root).unwrap();
+        assert!(delta.nodes.len() < full.nodes.len() / 2);
+
+        let restored = Storage::create_temporary().unwrap();
+        copy_and_purge(storage.clone(), &[restored.clone()], base_root).unwrap();
+        assert!(!restored.check_root_exist(root));
+        restored.apply_delta(&delta).unwrap();
+
+        // All nodes of new state should be reachable after delta was applied.
+        let copy = Storage::create_temporary().unwrap();
+        copy_and_purge(restored.clone(), &[copy], root).unwrap();
+        let proof = restored
+            .get_proof(root, H160::from_low_u64_be(200), &[slots[0]])
+            .unwrap();
+        assert_eq!(proof.account.nonce, 200.into());
+        assert_eq!(proof.storage_proofs[0].value, 201.into());
+        assert_eq!(
+            restored.get::<Codes>(proof.account.code_hash),
+            Some(Code::from(vec![0xff, 100]))
+        );
+    }
+
+    #[test]
+    fn delta_rejected_without_base_or_with_wrong_hash() {
+        let storage = Storage::create_temporary().unwrap();
+        let changes = (1..=10).map(|i| account(i, Code::empty(), &[])).collect();
+        let base_root = storage.flush_changes(empty_trie_hash(), changes);
+        let changes = (11..=12).map(|i| account(i, Code::empty(), &[])).collect();
+        let root = storage.flush_changes(base_root, changes);
+        let delta = storage.state_delta(base_root, root).unwrap();
+
+        let empty = Storage::create_temporary().unwrap();
+        assert!(matches!(
+            empty.apply_delta(&delta),
+            Err(DeltaError::RootNotFound(hash)) if hash == base_root
+        ));
+
+        let restored = Storage::create_temporary().unwrap();
+        copy_and_purge(storage, &[restored.clone()], base_root).unwrap();
+        let mut tampered = delta;
+        tampered.nodes[0].1.push(0);
+        assert!(matches!(
+            restored.apply_delta(&tampered),
+            Err(DeltaError::HashMismatch(_))
+        ));
+    }
+}
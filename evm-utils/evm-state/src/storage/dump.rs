@@ -0,0 +1,418 @@
+//! Portable dump of evm state at some root.
+//!
+//! Dump is a zstd compressed stream of bincode encoded records:
+//!
+//! ```text
+//! header:  DumpHeader { magic: b"EVMDUMP\0", version: 1, root }
+//! chunk*:  DumpRecord::Accounts(Vec<DumpAccount>)
+//! footer:  DumpRecord::End { accounts }
+//! ```
+//!
+//! Every chunk contains at most `DUMP_CHUNK_ENTRIES` accounts and storage entries.
+//! Accounts and storage slots are keyed by keccak hash, the same way as in the secure trie,
+//! so state can be rebuilt without knowledge of addresses. Account with big storage is split
+//! into several consecutive records with the same key, and only the first one contains code.
+//! Footer contains number of accounts, to detect truncated dumps.
+
+use {
+    super::{
+        inspectors::{DataInspector, NoopInspector},
+        walker::Walker,
+        ChangedState, Codes, Storage,
+    },
+    crate::types::{Account, AccountState, Code, Maybe},
+    anyhow::{anyhow, ensure, Result},
+    primitive_types::{H256, U256},
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::HashMap,
+        io::{BufReader, Read, Write},
+        sync::Mutex,
+    },
+    triedb::empty_trie_hash,
+};
+
+pub const DUMP_MAGIC: [u8; 8] = *b"EVMDUMP\0";
+pub const DUMP_VERSION: u32 = 1;
+/// Maximum number of accounts and storage entries in single chunk.
+pub const DUMP_CHUNK_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpHeader {
+    pub magic: [u8; 8],
+    pub version: u32,
+    pub root: H256,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpAccount {
+    /// Keccak hash of account address.
+    pub key: H256,
+    pub nonce: U256,
+    pub balance: U256,
+    pub code: Code,
+    /// Storage values, keyed by keccak hash of storage index.
+    pub storage: Vec<(H256, H256)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum DumpRecord {
+    Accounts(Vec<DumpAccount>),
+    End { accounts: u64 },
+}
+
+pub struct DumpWriter<W: Write> {
+    encoder: zstd::Encoder<'static, W>,
+    chunk: Vec<DumpAccount>,
+    chunk_entries: usize,
+    accounts: u64,
+}
+
+impl<W: Write> DumpWriter<W> {
+    pub fn new(writer: W, root: H256) -> Result<Self> {
+        let mut encoder = zstd::Encoder::new(writer, 0)?;
+        let header = DumpHeader {
+            magic: DUMP_MAGIC,
+            version: DUMP_VERSION,
+            root,
+        };
+        bincode::serialize_into(&mut encoder, &header)?;
+        Ok(Self {
+            encoder,
+            chunk: vec![],
+            chunk_entries: 0,
+            accounts: 0,
+        })
+    }
+
+    /// Appends account, storage of account is split into several records if needed.
+    pub fn write_account(&mut self, mut account: DumpAccount) -> Result<()> {
+        let storage = std::mem::take(&mut account.storage);
+        let mut parts = storage.chunks(DUMP_CHUNK_ENTRIES).map(<[_]>::to_vec);
+        account.storage = parts.next().unwrap_or_default();
+
+        let (key, nonce, balance) = (account.key, account.nonce, account.balance);
+        self.push(account)?;
+        for storage in parts {
+            self.push(DumpAccount {
+                key,
+                nonce,
+                balance,
+                code: Code::empty(),
+                storage,
+            })?;
+        }
+        self.accounts += 1;
+        Ok(())
+    }
+
+    /// Writes footer and finishes compression, returns number of written accounts.
+    pub fn finish(mut self) -> Result<u64> {
+        self.flush_chunk()?;
+        let footer = DumpRecord::End {
+            accounts: self.accounts,
+        };
+        bincode::serialize_into(&mut self.encoder, &footer)?;
+        self.encoder.finish()?;
+        Ok(self.accounts)
+    }
+
+    fn push(&mut self, account: DumpAccount) -> Result<()> {
+        self.chunk_entries += 1 + account.storage.len();
+        self.chunk.push(account);
+        if self.chunk_entries >= DUMP_CHUNK_ENTRIES {
+            self.flush_chunk()?;
+        }
+        Ok(())
+    }
+
+    fn flush_chunk(&mut self) -> Result<()> {
+        if !self.chunk.is_empty() {
+            let chunk = DumpRecord::Accounts(std::mem::take(&mut self.chunk));
+            bincode::serialize_into(&mut self.encoder, &chunk)?;
+            self.chunk_entries = 0;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over chunks of accounts in dump.
+pub struct DumpReader<R: Read> {
+    decoder: zstd::Decoder<'static, BufReader<R>>,
+    header: DumpHeader,
+    last_key: Option<H256>,
+    accounts: u64,
+    finished: bool,
+}
+
+impl<R: Read> DumpReader<R> {
+    pub fn new(reader: R) -> Result<Self> {
+        let mut decoder = zstd::Decoder::new(reader)?;
+        let header: DumpHeader = bincode::deserialize_from(&mut decoder)?;
+        ensure!(header.magic == DUMP_MAGIC, "Not an evm state dump");
+        ensure!(
+            header.version == DUMP_VERSION,
+            "Unsupported evm state dump version {}",
+            header.version
+        );
+        Ok(Self {
+            decoder,
+            header,
+            last_key: None,
+            accounts: 0,
+            finished: false,
+        })
+    }
+
+    pub fn root(&self) -> H256 {
+        self.header.root
+    }
+}
+
+impl<R: Read> Iterator for DumpReader<R> {
+    type Item = Result<Vec<DumpAccount>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match bincode::deserialize_from(&mut self.decoder) {
+            Ok(DumpRecord::Accounts(chunk)) => {
+                for account in &chunk {
+                    if self.last_key != Some(account.key) {
+                        self.accounts += 1;
+                        self.last_key = Some(account.key);
+                    }
+                }
+                Some(Ok(chunk))
+            }
+            Ok(DumpRecord::End { accounts }) => {
+                self.finished = true;
+                (accounts != self.accounts).then(|| {
+                    Err(anyhow!(
+                        "Evm state dump is corrupted, expected {} accounts, found {}",
+                        accounts,
+                        self.accounts
+                    ))
+                })
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(anyhow!("Unable to read evm state dump: {}", e)))
+            }
+        }
+    }
+}
+
+/// Collects storage and code of every visited account, and writes them into dump.
+struct AccountsDumper<'a, W: Write> {
+    storage: &'a Storage,
+    writer: Mutex<DumpWriter<W>>,
+}
+
+#[derive(Default)]
+struct StorageCollector(Mutex<Vec<(H256, H256)>>);
+
+impl DataInspector<H256, U256> for StorageCollector {
+    fn inspect_data(&self, key: H256, value: U256) -> Result<()> {
+        let mut bytes = H256::zero();
+        value.to_big_endian(bytes.as_bytes_mut());
+        self.0.lock().unwrap().push((key, bytes));
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> DataInspector<H256, Account> for AccountsDumper<'_, W> {
+    fn inspect_data(&self, key: H256, account: Account) -> Result<()> {
+        let walker = Walker::new_sec_encoding(
+            self.storage.db(),
+            NoopInspector,
+            StorageCollector::default(),
+        );
+        walker.traverse(account.storage_root)?;
+        let mut storage = walker.data_inspector.inner.0.into_inner().unwrap();
+        storage.sort_unstable();
+
+        let code = if account.code_hash == Code::empty().hash() {
+            Code::empty()
+        } else {
+            self.storage
+                .get::<Codes>(account.code_hash)
+                .ok_or_else(|| anyhow!("Code {:?} not found", account.code_hash))?
+        };
+
+        self.writer.lock().unwrap().write_account(DumpAccount {
+            key,
+            nonce: account.nonce,
+            balance: account.balance,
+            code,
+            storage,
+        })
+    }
+}
+
+impl Storage {
+    /// Writes all accounts, codes and storages of state `root` into dump,
+    /// returns number of dumped accounts.
+    pub fn dump_state<W: Write + Send>(&self, root: H256, writer: W) -> Result<u64> {
+        ensure!(self.check_root_exist(root), "Root not found: {:?}", root);
+        let dumper = AccountsDumper {
+            storage: self,
+            writer: Mutex::new(DumpWriter::new(writer, root)?),
+        };
+        let walker = Walker::new_sec_encoding(self.db(), NoopInspector, dumper);
+        walker.traverse(root)?;
+        walker
+            .data_inspector
+            .inner
+            .writer
+            .into_inner()
+            .unwrap()
+            .finish()
+    }
+
+    /// Builds state from dump on top of empty state, and checks that its root matches the dump.
+    ///
+    /// Intermediate roots of every chunk are not removed, so dump should be loaded into
+    /// fresh storage.
+    pub fn load_state_dump<R: Read>(&self, reader: R) -> Result<H256> {
+        let dump = DumpReader::new(reader)?;
+        let expected_root = dump.root();
+
+        let mut root = empty_trie_hash();
+        for chunk in dump {
+            let mut changes: ChangedState = HashMap::new();
+            for account in chunk? {
+                let entry = changes
+                    .entry(account.key)
+                    .or_insert_with(|| (Maybe::Nothing, HashMap::new()));
+                // Code is stored only in the first record of account.
+                let code = match &entry.0 {
+                    Maybe::Just(state) if account.code.is_empty() => state.code.clone(),
+                    _ => account.code,
+                };
+                entry.0 = Maybe::Just(AccountState {
+                    nonce: account.nonce,
+                    balance: account.balance,
+                    code,
+                });
+                entry.1.extend(account.storage);
+            }
+            root = self.flush_changes_hashed(root, changes);
+        }
+        ensure!(
+            root == expected_root,
+            "Loaded state root {:?} doesn't match dump root {:?}",
+            root,
+            expected_root
+        );
+        Ok(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, primitive_types::H160};
+
+    fn fill_storage(storage: &Storage) -> H256 {
+        let slots: Vec<H256> = (1..=20).map(H256::repeat_byte).collect();
+        let changes = (1..=50)
+            .map(|i| {
+                let state = AccountState {
+                    nonce: i.into(),
+                    balance: (i * 1000).into(),
+                    code: Code::from(vec![i as u8; i]),
+                };
+                let account_storage = slots
+                    .iter()
+                    .take(i % 5)
+                    .map(|slot| (*slot, H256::from_low_u64_be(i as u64)))
+                    .collect();
+                (
+                    H160::from_low_u64_be(i as u64),
+                    (Maybe::Just(state), account_storage),
+                )
+            })
+            .collect();
+        storage.flush_changes(empty_trie_hash(), changes)
+    }
+
+    #[test]
+    fn dump_and_load_state() {
+        let storage = Storage::create_temporary().unwrap();
+        let root = fill_storage(&storage);
+
+        let mut dump = vec![];
+        assert_eq!(storage.dump_state(root, &mut dump).unwrap(), 50);
+        assert_eq!(DumpReader::new(dump.as_slice()).unwrap().root(), root);
+
+        let loaded = Storage::create_temporary().unwrap();
+        assert_eq!(loaded.load_state_dump(dump.as_slice()).unwrap(), root);
+        let proof = loaded
+            .get_proof(root, H160::from_low_u64_be(7), &[H256::repeat_byte(2)])
+            .unwrap();
+        assert_eq!(proof.account.balance, 7000.into());
+        assert_eq!(proof.storage_proofs[0].value, 7.into());
+        assert_eq!(
+            loaded.get::<Codes>(proof.account.code_hash),
+            Some(Code::from(vec![7; 7]))
+        );
+    }
+
+    #[test]
+    fn big_storage_is_split_into_records() {
+        let key = H256::repeat_byte(1);
+        let storage: Vec<_> = (1..=DUMP_CHUNK_ENTRIES as u64 * 2)
+            .map(|i| (H256::from_low_u64_be(i), H256::from_low_u64_be(i)))
+            .collect();
+        let mut dump = vec![];
+        let mut writer = DumpWriter::new(&mut dump, H256::zero()).unwrap();
+        writer
+            .write_account(DumpAccount {
+                key,
+                nonce: 1.into(),
+                balance: 2.into(),
+                code: Code::from(vec![1, 2, 3]),
+                storage: storage.clone(),
+            })
+            .unwrap();
+        assert_eq!(writer.finish().unwrap(), 1);
+
+        let records: Vec<_> = DumpReader::new(dump.as_slice())
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|record| record.key == key));
+        assert_eq!(records[0].code, Code::from(vec![1, 2, 3]));
+        assert!(records[1].code.is_empty());
+        let restored: Vec<_> = records.into_iter().flat_map(|r| r.storage).collect();
+        assert_eq!(restored, storage);
+    }
+
+    #[test]
+    fn truncated_or_tampered_dump_rejected() {
+        let storage = Storage::create_temporary().unwrap();
+        let root = fill_storage(&storage);
+        let mut dump = vec![];
+        storage.dump_state(root, &mut dump).unwrap();
+
+        let truncated = &dump[..dump.len() / 2];
+        let loaded = Storage::create_temporary().unwrap();
+        assert!(loaded.load_state_dump(truncated).is_err());
+
+        let mut other = vec![];
+        let mut writer = DumpWriter::new(&mut other, root).unwrap();
+        writer
+            .write_account(DumpAccount {
+                key: H256::repeat_byte(1),
+                ..Default::default()
+            })
+            .unwrap();
+        writer.finish().unwrap();
+        assert!(loaded.load_state_dump(other.as_slice()).is_err());
+    }
+}
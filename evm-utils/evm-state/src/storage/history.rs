@@ -0,0 +1,357 @@
+//! Bounded history of rooted evm states.
+//!
+//! By default, state root of every slot is purged from storage as soon as bank of this slot is dropped.
+//! When history is enabled, roots of rooted slots are kept after their banks are dropped,
+//! until pruning policy decides that they are older than retention window.
+//!
+//! Readers can pin root, to prevent it from being removed while they are reading it.
+//! Removal of root, that was unpinned by last reader, is left to next pruning pass,
+//! so readers never run cleanup on their threads.
+//!
+//! History is kept only in memory. On restart, slots of loaded snapshot are cleaned up,
+//! so retention window starts from zero, and states retained before restart are not available.
+
+use {
+    super::{Result, RootCleanup, Storage},
+    log::*,
+    primitive_types::H256,
+    std::{
+        collections::{BTreeSet, HashMap, HashSet},
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    },
+};
+
+const UNKNOWN_BLOCK: u64 = u64::MAX;
+
+#[derive(Debug, Default)]
+struct PinnedRoots {
+    /// Number of readers of each pinned root.
+    readers: HashMap<H256, usize>,
+    /// Pinned roots, that are no longer referenced by any slot,
+    /// they are removed when last reader unpins them.
+    deferred: HashSet<H256>,
+    /// Roots, whose removal is in progress, they cannot be pinned.
+    removing: HashSet<H256>,
+    /// Deferred roots, that were unpinned by last reader, and wait for next pruning pass.
+    released: Vec<H256>,
+}
+
+#[derive(Debug)]
+pub struct RootedHistory {
+    enabled: AtomicBool,
+    /// Rooted slots, whose banks were dropped, but state is still kept in storage.
+    retained: Mutex<BTreeSet<u64>>,
+    oldest_block: AtomicU64,
+    /// States of slots before this one were purged.
+    pruned_before: AtomicU64,
+    pinned: Mutex<PinnedRoots>,
+}
+
+impl Default for RootedHistory {
+    fn default() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            retained: Mutex::new(BTreeSet::new()),
+            oldest_block: AtomicU64::new(UNKNOWN_BLOCK),
+            pruned_before: AtomicU64::new(0),
+            pinned: Mutex::new(PinnedRoots::default()),
+        }
+    }
+}
+
+/// Keeps state root from being removed by pruning, while it is alive.
+#[derive(Debug)]
+pub struct RootPin {
+    storage: Storage,
+    root: H256,
+}
+
+impl Drop for RootPin {
+    fn drop(&mut self) {
+        let mut pinned = self.storage.history.pinned.lock().unwrap();
+        let readers = pinned
+            .readers
+            .get_mut(&self.root)
+            .expect("pinned root should have readers");
+        *readers -= 1;
+        if *readers > 0 {
+            return;
+        }
+        pinned.readers.remove(&self.root);
+        if pinned.deferred.remove(&self.root) {
+            debug!("Releasing unpinned root {:?}", self.root);
+            pinned.removing.insert(self.root);
+            pinned.released.push(self.root);
+        }
+    }
+}
+
+impl RootedHistory {
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Keep state of rooted slot, after its bank was dropped.
+    /// Returns false if history is disabled, and slot should be purged right away.
+    pub fn retain(&self, slot: u64) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        self.retained.lock().unwrap().insert(slot);
+        true
+    }
+
+    pub fn retained_slots(&self) -> usize {
+        self.retained.lock().unwrap().len()
+    }
+
+    /// Oldest evm block, whose state is guaranteed to be kept.
+    pub fn oldest_block(&self) -> Option<u64> {
+        match self.oldest_block.load(Ordering::Relaxed) {
+            UNKNOWN_BLOCK => None,
+            block => Some(block),
+        }
+    }
+
+    pub fn set_oldest_block(&self, block: u64) {
+        self.oldest_block.store(block, Ordering::Relaxed)
+    }
+
+    /// Returns true if state of `slot` is older than retention window, and was purged.
+    pub fn is_pruned(&self, slot: u64) -> bool {
+        self.is_enabled() && slot < self.pruned_before.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn is_removing(&self, root: H256) -> bool {
+        self.pinned.lock().unwrap().removing.contains(&root)
+    }
+
+    /// Marks roots as removing, pinned roots are deferred until they are unpinned.
+    /// Returns roots that can be removed right away.
+    fn start_removing(&self, roots: Vec<H256>) -> Vec<H256> {
+        let mut pinned = self.pinned.lock().unwrap();
+        let PinnedRoots {
+            readers,
+            deferred,
+            removing,
+        } = &mut *pinned;
+        roots
+            .into_iter()
+            .filter(|root| {
+                if readers.contains_key(root) {
+                    deferred.insert(*root);
+                    false
+                } else {
+                    removing.insert(*root);
+                    true
+                }
+            })
+            .collect()
+    }
+
+    fn take_released(&self) -> Vec<H256> {
+        std::mem::take(&mut self.pinned.lock().unwrap().released)
+    }
+
+    fn finish_removing(&self, roots: &[H256]) {
+        let mut pinned = self.pinned.lock().unwrap();
+        for root in roots {
+            pinned.removing.remove(root);
+        }
+    }
+
+    fn take_before(&self, slot: u64) -> Vec<u64> {
+        let mut retained = self.retained.lock().unwrap();
+        let newer = retained.split_off(&slot);
+        std::mem::replace(&mut *retained, newer)
+            .into_iter()
+            .collect()
+    }
+}
+
+impl Storage {
+    pub fn history(&self) -> &RootedHistory {
+        &self.history
+    }
+
+    /// Returns clone of storage, that keeps state `root` from being removed by pruning,
+    /// while this clone (or any of its clones) is alive.
+    /// Returns `None` if root doesn't exist, or is being removed.
+    pub fn pin_root(&self, root: H256) -> Option<Storage> {
+        {
+            let mut pinned = self.history.pinned.lock().unwrap();
+            if pinned.removing.contains(&root) {
+                return None;
+            }
+            *pinned.readers.entry(root).or_default() += 1;
+        }
+        let unpinned = Storage {
+            pin: None,
+            ..self.clone()
+        };
+        // root is unpinned on drop, if it doesn't exist
+        let pin = Arc::new(RootPin {
+            storage: unpinned.clone(),
+            root,
+        });
+        if !self.check_root_exist(root) {
+            return None;
+        }
+        Some(Storage {
+            pin: Some(pin),
+            ..unpinned
+        })
+    }
+
+    fn remove_roots(&self, roots: Vec<H256>) -> Result<()> {
+        let result = RootCleanup::new(self, roots.clone()).cleanup();
+        self.history.finish_removing(&roots);
+        result
+    }
+
+    /// Cleanup roots that are no longer referenced by any slot,
+    /// pinned roots are removed after they are unpinned.
+    /// Returns number of roots, that were removed right away.
+    pub(super) fn remove_unpinned_roots(&self, roots: Vec<H256>) -> Result<usize> {
+        let roots = self.history.start_removing(roots);
+        let removed = roots.len();
+        self.remove_roots(roots)?;
+        Ok(removed)
+    }
+
+    /// Cleanup deferred roots, that were unpinned by last reader since previous call.
+    /// Returns number of removed roots.
+    pub fn remove_released_roots(&self) -> Result<usize> {
+        let roots = self.history.take_released();
+        let removed = roots.len();
+        if removed > 0 {
+            debug!("Removing {} unpinned roots", removed);
+        }
+        self.remove_roots(roots)?;
+        Ok(removed)
+    }
+
+    /// Purge retained slots older than `before`, and cleanup roots that are no longer referenced.
+    /// Roots that are pinned by readers are removed after they are unpinned.
+    /// Returns number of purged slots and number of removed roots.
+    pub fn purge_retained_slots(&self, before: u64) -> Result<(usize, usize)> {
+        let slots = self.history.take_before(before);
+        self.history
+            .pruned_before
+            .fetch_max(before, Ordering::Relaxed);
+        let mut cleanup_roots = vec![];
+        for slot in &slots {
+            if let Some(root) = self.purge_slot(*slot)? {
+                cleanup_roots.push(root)
+            }
+        }
+        if !slots.is_empty() {
+            debug!(
+                "Purged {} retained slots before {}, removing {} roots",
+                slots.len(),
+                before,
+                cleanup_roots.len()
+            );
+        }
+        let removed_roots = self.remove_unpinned_roots(cleanup_roots)?;
+        Ok((slots.len(), removed_roots))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{empty_trie_hash, types::*},
+        primitive_types::H160,
+    };
+
+    #[test]
+    fn retained_slots_are_purged_by_window() {
+        let storage = Storage::create_temporary_gc().unwrap();
+
+        let mut roots = vec![];
+        for slot in 0..4u64 {
+            let state = AccountState {
+                nonce: (slot + 1).into(),
+                ..Default::default()
+            };
+            let changes = std::iter::once((
+                H160::from_low_u64_be(slot),
+                (Maybe::Just(state), Default::default()),
+            ))
+            .collect();
+            let root = storage.flush_changes(empty_trie_hash(), changes);
+            storage.register_slot(slot, root, false).unwrap();
+            roots.push(root);
+        }
+
+        // history is disabled, nothing should be retained
+        assert!(!storage.history().retain(0));
+
+        storage.history().enable();
+        for slot in 0..4 {
+            assert!(storage.history().retain(slot));
+        }
+        assert_eq!(storage.history().retained_slots(), 4);
+
+        assert_eq!(storage.purge_retained_slots(2).unwrap(), (2, 2));
+        assert_eq!(storage.history().retained_slots(), 2);
+        assert!(!storage.check_root_exist(roots[0]));
+        assert!(!storage.check_root_exist(roots[1]));
+        assert!(storage.check_root_exist(roots[2]));
+        assert!(storage.check_root_exist(roots[3]));
+
+        assert_eq!(storage.purge_retained_slots(2).unwrap(), (0, 0));
+        assert!(storage.history().is_pruned(1));
+        assert!(!storage.history().is_pruned(2));
+    }
+
+    #[test]
+    fn pinned_root_is_removed_after_unpin() {
+        let storage = Storage::create_temporary_gc().unwrap();
+        storage.history().enable();
+
+        let mut roots = vec![];
+        for slot in 0..2u64 {
+            let state = AccountState {
+                nonce: (slot + 1).into(),
+                ..Default::default()
+            };
+            let changes = std::iter::once((
+                H160::from_low_u64_be(slot),
+                (Maybe::Just(state), Default::default()),
+            ))
+            .collect();
+            let root = storage.flush_changes(empty_trie_hash(), changes);
+            storage.register_slot(slot, root, false).unwrap();
+            assert!(storage.history().retain(slot));
+            roots.push(root);
+        }
+
+        let pinned = storage.pin_root(roots[0]).unwrap();
+        let pinned_clone = pinned.clone();
+        drop(pinned);
+
+        // removal of pinned root is deferred
+        assert_eq!(storage.purge_retained_slots(2).unwrap(), (2, 1));
+        assert!(storage.check_root_exist(roots[0]));
+        assert!(!storage.check_root_exist(roots[1]));
+        assert!(storage.pin_root(roots[1]).is_none());
+
+        // unpinned root is left for pruning, and cannot be pinned again
+        drop(pinned_clone);
+        assert!(storage.pin_root(roots[0]).is_none());
+        assert_eq!(storage.remove_released_roots().unwrap(), 1);
+        assert!(!storage.check_root_exist(roots[0]));
+        assert!(storage.pin_root(roots[0]).is_none());
+        assert_eq!(storage.remove_released_roots().unwrap(), 0);
+    }
+}
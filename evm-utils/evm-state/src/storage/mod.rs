@@ -31,11 +31,16 @@ use {
         empty_trie_hash,
         gc::{DatabaseTrieMut, DbCounter, TrieCollection},
         rocksdb::{RocksDatabaseHandle, RocksDatabaseHandleGC, RocksHandle, SyncRocksHandle},
-        FixedSecureTrieMut,
+        CachedHandle, FixedSecureTrieMut,
     },
 };
 
+pub mod cold;
+pub mod delta;
+pub mod dump;
+pub mod history;
 pub mod inspectors;
+pub mod proof;
 pub mod two_modes_enum;
 pub mod walker;
 
@@ -103,6 +108,10 @@ where
     // Location should be second field, because of drop order in Rust.
     location: Location,
     gc_enabled: bool,
+    history: Arc<history::RootedHistory>,
+    cold: Option<Arc<cold::ColdStore>>,
+    /// Root, that is kept from pruning while this storage (or any of its clones) is alive.
+    pin: Option<Arc<history::RootPin>>,
 }
 
 impl<D: VelasDBCommon> Clone for Storage<D> {
@@ -111,6 +120,9 @@ impl<D: VelasDBCommon> Clone for Storage<D> {
             db: Arc::clone(&self.db),
             location: self.location.clone(),
             gc_enabled: self.gc_enabled,
+            history: Arc::clone(&self.history),
+            cold: self.cold.clone(),
+            pin: self.pin.clone(),
         }
     }
 }
@@ -230,12 +242,15 @@ where
     pub fn check_root_exist(&self, root: H256) -> bool {
         if root == empty_trie_hash() {
             true // empty root should exist always
+        } else if self.history.is_removing(root) {
+            // root is partially removed by pruning, unless its nodes were moved into cold tier
+            self.is_cold_root(root)
         } else {
             // only return true if root is retrivable
             matches!(
                 self.db.get_opt(root.as_ref(), &ReadOptions::default()),
                 Ok(Some(_))
-            )
+            ) || self.is_cold_root(root)
         }
     }
 
@@ -303,6 +318,9 @@ impl StorageSecondary {
             db: Arc::new(DbWithClose(db)),
             location,
             gc_enabled,
+            history: Default::default(),
+            cold: None,
+            pin: None,
         })
     }
 }
@@ -346,6 +364,9 @@ impl Storage<OptimisticTransactionDB> {
             db: Arc::new(DbWithClose(db)),
             location,
             gc_enabled,
+            history: Default::default(),
+            cold: None,
+            pin: None,
         })
     }
 
@@ -389,8 +410,9 @@ impl Storage<OptimisticTransactionDB> {
     pub fn typed_for<K: AsRef<[u8]>, V: Encodable + Decodable>(
         &self,
         root: H256,
-    ) -> FixedSecureTrieMut<DatabaseTrieMut<RocksHandle<&DB>>, K, V> {
-        let handle = self.rocksdb_trie_handle();
+    ) -> FixedSecureTrieMut<DatabaseTrieMut<CachedHandle<cold::TieredDatabaseHandle<'_>>>, K, V>
+    {
+        let handle = self.tiered_trie_handle();
 
         FixedSecureTrieMut::new(DatabaseTrieMut::trie_for(handle, root))
     }
@@ -0,0 +1,298 @@
+//! Merkle proofs of accounts and their storage (EIP-1186).
+//!
+//! Proof is a list of rlp encoded trie nodes, referenced by hash, on the path from root to the key.
+//! Nodes, that are shorter than 32 bytes, are inlined into the parent node, and are not included.
+
+use {
+    super::{Storage, VelasDBCommon},
+    crate::types::Account,
+    primitive_types::{H160, H256, U256},
+    rlp::{DecoderError, Rlp},
+    sha3::{Digest, Keccak256},
+    std::collections::HashMap,
+    triedb::{
+        empty_trie_hash,
+        merkle::{
+            nibble::{self, Nibble},
+            Branch, Extension, Leaf, MerkleNode, MerkleValue,
+        },
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProofError {
+    #[error(transparent)]
+    Database(#[from] rocksdb::Error),
+    #[error("Trie node not found: {0:?}")]
+    NodeNotFound(H256),
+    #[error(transparent)]
+    Cold(#[from] super::cold::ColdError),
+    #[error("Unable to decode trie node or value: {0}")]
+    Decode(#[from] DecoderError),
+    #[error("Value of {0} doesn't match proof")]
+    ValueMismatch(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageProof {
+    pub key: H256,
+    /// Zero value means that storage slot is empty.
+    pub value: U256,
+    pub proof: Vec<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountProof {
+    pub address: H160,
+    /// Default account means that account doesn't exist in state.
+    pub account: Account,
+    pub account_proof: Vec<Vec<u8>>,
+    pub storage_proofs: Vec<StorageProof>,
+}
+
+impl AccountProof {
+    /// Checks account and all of its storage slots against state root.
+    pub fn verify(&self, state_root: H256) -> Result<(), ProofError> {
+        let account = verify_proof(state_root, self.address.as_bytes(), &self.account_proof)?
+            .map(|bytes| rlp::decode::<Account>(&bytes))
+            .transpose()?
+            .unwrap_or_default();
+        if account != self.account {
+            return Err(ProofError::ValueMismatch(format!(
+                "account {:?}",
+                self.address
+            )));
+        }
+
+        for storage in &self.storage_proofs {
+            let value = verify_proof(
+                self.account.storage_root,
+                storage.key.as_bytes(),
+                &storage.proof,
+            )?
+            .map(|bytes| rlp::decode::<U256>(&bytes))
+            .transpose()?
+            .unwrap_or_default();
+            if value != storage.value {
+                return Err(ProofError::ValueMismatch(format!(
+                    "storage {:?} of account {:?}",
+                    storage.key, self.address
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Verifies proof of `key` in secure trie with `root`.
+///
+/// Returns value stored under the key, or `None` if proof shows that key is absent.
+pub fn verify_proof(
+    root: H256,
+    key: &[u8],
+    proof: &[impl AsRef<[u8]>],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    let nodes: HashMap<H256, &[u8]> = proof
+        .iter()
+        .map(|node| (keccak(node.as_ref()), node.as_ref()))
+        .collect();
+    lookup(root, keccak(key), |hash| {
+        nodes
+            .get(&hash)
+            .map(|node| node.to_vec())
+            .ok_or(ProofError::NodeNotFound(hash))
+    })
+}
+
+impl<D> Storage<D>
+where
+    D: VelasDBCommon,
+{
+    /// Returns proof of account, and its storage slots, at state with `root`.
+    pub fn get_proof(
+        &self,
+        root: H256,
+        address: H160,
+        storage_keys: &[H256],
+    ) -> Result<AccountProof, ProofError> {
+        let (account, account_proof) = self.trie_proof(root, address.as_bytes())?;
+        let account = account
+            .map(|bytes| rlp::decode::<Account>(&bytes))
+            .transpose()?
+            .unwrap_or_default();
+
+        let storage_proofs = storage_keys
+            .iter()
+            .map(|key| {
+                let (value, proof) = self.trie_proof(account.storage_root, key.as_bytes())?;
+                let value = value
+                    .map(|bytes| rlp::decode::<U256>(&bytes))
+                    .transpose()?
+                    .unwrap_or_default();
+                Ok(StorageProof {
+                    key: *key,
+                    value,
+                    proof,
+                })
+            })
+            .collect::<Result<_, ProofError>>()?;
+
+        Ok(AccountProof {
+            address,
+            account,
+            account_proof,
+            storage_proofs,
+        })
+    }
+
+    fn trie_proof(
+        &self,
+        root: H256,
+        key: &[u8],
+    ) -> Result<(Option<Vec<u8>>, Vec<Vec<u8>>), ProofError> {
+        let mut proof = vec![];
+        let value = lookup(root, keccak(key), |hash| {
+            let node = self
+                .trie_node(hash)?
+                .ok_or(ProofError::NodeNotFound(hash))?;
+            proof.push(node.clone());
+            Ok(node)
+        })?;
+        Ok((value, proof))
+    }
+}
+
+pub(super) fn keccak(data: &[u8]) -> H256 {
+    H256::from_slice(Keccak256::digest(data).as_slice())
+}
+
+enum Step<'a> {
+    Found(Option<Vec<u8>>),
+    Next(H256, &'a [Nibble]),
+}
+
+/// Walks trie from the root to the `key`, fetching every node that referenced by hash.
+pub(super) fn lookup(
+    root: H256,
+    key: H256,
+    mut fetch: impl FnMut(H256) -> Result<Vec<u8>, ProofError>,
+) -> Result<Option<Vec<u8>>, ProofError> {
+    if root == empty_trie_hash() {
+        return Ok(None);
+    }
+    let nibbles = nibble::from_key(key.as_bytes());
+    let mut hash = root;
+    let mut remaining = nibbles.as_slice();
+    loop {
+        let bytes = fetch(hash)?;
+        let node = MerkleNode::decode(&Rlp::new(&bytes))?;
+        match walk_node(&node, remaining) {
+            Step::Found(value) => return Ok(value),
+            Step::Next(next_hash, next_remaining) => {
+                hash = next_hash;
+                remaining = next_remaining;
+            }
+        }
+    }
+}
+
+fn walk_node<'a>(node: &MerkleNode, key: &'a [Nibble]) -> Step<'a> {
+    match node {
+        MerkleNode::Leaf(Leaf { nibbles, data }) => {
+            Step::Found((nibbles.as_slice() == key).then(|| data.to_vec()))
+        }
+        MerkleNode::Extension(Extension { nibbles, value }) => {
+            match key.strip_prefix(nibbles.as_slice()) {
+                Some(rest) => walk_value(value, rest),
+                None => Step::Found(None),
+            }
+        }
+        MerkleNode::Branch(Branch { childs, data }) => match key.split_first() {
+            Some((nibble, rest)) => {
+                let index: usize = (*nibble).into();
+                walk_value(&childs[index], rest)
+            }
+            None => Step::Found(data.as_ref().map(|data| data.to_vec())),
+        },
+    }
+}
+
+fn walk_value<'a>(value: &MerkleValue, key: &'a [Nibble]) -> Step<'a> {
+    match value {
+        MerkleValue::Empty => Step::Found(None),
+        MerkleValue::Full(node) => walk_node(node, key),
+        MerkleValue::Hash(hash) => Step::Next(*hash, key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::types::{AccountState, Code, Maybe},
+        std::collections::HashMap,
+    };
+
+    #[test]
+    fn account_and_storage_proofs() {
+        let storage = Storage::create_temporary().unwrap();
+
+        let addresses: Vec<H160> = (1..=50).map(H160::repeat_byte).collect();
+        let slots: Vec<H256> = (1..=20).map(H256::repeat_byte).collect();
+        let changes = addresses
+            .iter()
+            .enumerate()
+            .map(|(i, address)| {
+                let state = AccountState {
+                    nonce: i.into(),
+                    balance: (i * 1000).into(),
+                    code: Code::empty(),
+                };
+                let storage: HashMap<_, _> = slots
+                    .iter()
+                    .map(|slot| (*slot, H256::from_low_u64_be(i as u64 + 1)))
+                    .collect();
+                (*address, (Maybe::Just(state), storage))
+            })
+            .collect();
+        let root = storage.flush_changes(empty_trie_hash(), changes);
+
+        let missing_slot = H256::repeat_byte(0xff);
+        let proof = storage
+            .get_proof(root, addresses[7], &[slots[3], missing_slot])
+            .unwrap();
+        assert_eq!(proof.account.nonce, 7.into());
+        assert_eq!(proof.account.balance, 7000.into());
+        assert_eq!(proof.storage_proofs[0].value, 8.into());
+        assert_eq!(proof.storage_proofs[1].value, U256::zero());
+        proof.verify(root).unwrap();
+
+        // Proof of absence.
+        let missing = storage
+            .get_proof(root, H160::repeat_byte(0xff), &[slots[0]])
+            .unwrap();
+        assert_eq!(missing.account, Account::default());
+        missing.verify(root).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.account.balance = 1.into();
+        assert!(matches!(
+            tampered.verify(root),
+            Err(ProofError::ValueMismatch(_))
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.storage_proofs[0].value = 1.into();
+        assert!(matches!(
+            tampered.verify(root),
+            Err(ProofError::ValueMismatch(_))
+        ));
+
+        let mut tampered = proof;
+        tampered.account_proof.remove(0);
+        assert!(matches!(
+            tampered.verify(root),
+            Err(ProofError::NodeNotFound(hash)) if hash == root
+        ));
+    }
+}
@@ -0,0 +1,351 @@
+//! Opcode level tracer, compatible with geth `structLogs` output.
+//!
+//! Collects events from `evm-runtime` and `evm-gasometer` tracing hooks,
+//! so it should wrap any call to executor, that runs evm code.
+//! Hooks add overhead to every evm step, so tracer is available only with `tracing` feature,
+//! output types are always available.
+
+use {
+    crate::{H160, H256},
+    evm::Opcode,
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StructLoggerConfig {
+    pub disable_stack: bool,
+    pub disable_storage: bool,
+    pub enable_memory: bool,
+    /// Maximum amount of logs to collect, `None` means unlimited.
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLog {
+    pub pc: usize,
+    pub op: Opcode,
+    /// Gas available before executing opcode.
+    pub gas: u64,
+    pub gas_cost: u64,
+    /// Call depth, starting from 1.
+    pub depth: usize,
+    pub stack: Option<Vec<H256>>,
+    pub memory: Option<Vec<u8>>,
+    /// Storage of current contract, that was accessed during execution, attached on SLOAD/SSTORE.
+    pub storage: Option<BTreeMap<H256, H256>>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StructLoggerResult {
+    pub logs: Vec<StructLog>,
+    /// All storage slots that was touched by SLOAD/SSTORE.
+    pub accessed_storage: BTreeMap<H160, BTreeSet<H256>>,
+}
+
+#[cfg(any(feature = "tracing", test))]
+pub use tracer::trace_struct_logs;
+
+#[cfg(any(feature = "tracing", test))]
+mod tracer {
+    use {
+        super::*,
+        evm::{Capture, ExitReason},
+        evm_gasometer::tracing::{self as gas_tracing, Event as GasEvent, Snapshot},
+        evm_runtime::tracing::{self as step_tracing, Event as StepEvent},
+        std::{cell::RefCell, rc::Rc},
+    };
+
+    #[derive(Debug, Default)]
+    struct StructLogger {
+        config: StructLoggerConfig,
+        result: StructLoggerResult,
+        storage: BTreeMap<H160, BTreeMap<H256, H256>>,
+        depth: usize,
+        // Last step was trapped by CALL/CREATE, and child frame not yet started.
+        pending_call: bool,
+        // Gas of last log wasn't yet recorded by gasometer.
+        pending_gas: bool,
+        // Limit was reached, last step wasn't recorded.
+        skip_step: bool,
+    }
+
+    impl StructLogger {
+        fn new(config: StructLoggerConfig) -> Self {
+            Self {
+                config,
+                ..Default::default()
+            }
+        }
+
+        fn step_event(&mut self, event: StepEvent) {
+            match event {
+                StepEvent::Step {
+                    context,
+                    opcode,
+                    position,
+                    stack,
+                    memory,
+                } => {
+                    let pc = match position {
+                        Ok(pc) => *pc,
+                        Err(_) => return,
+                    };
+                    // Child frame always starts from first opcode,
+                    // calls to precompiles and empty accounts has no steps.
+                    if self.depth == 0 || (self.pending_call && pc == 0) {
+                        self.depth += 1;
+                    }
+                    self.pending_call = false;
+
+                    if matches!(self.config.limit, Some(limit) if self.result.logs.len() >= limit) {
+                        self.skip_step = true;
+                        return;
+                    }
+                    self.skip_step = false;
+                    self.pending_gas = true;
+
+                    let storage = (!self.config.disable_storage
+                        && matches!(opcode, Opcode::SLOAD | Opcode::SSTORE))
+                    .then(|| {
+                        self.storage
+                            .get(&context.address)
+                            .cloned()
+                            .unwrap_or_default()
+                    });
+                    self.result.logs.push(StructLog {
+                        pc,
+                        op: opcode,
+                        gas: 0,
+                        gas_cost: 0,
+                        depth: self.depth,
+                        stack: (!self.config.disable_stack).then(|| stack.data().clone()),
+                        memory: self.config.enable_memory.then(|| memory.data().clone()),
+                        storage,
+                        error: None,
+                    })
+                }
+                StepEvent::StepResult { result, .. } => match result {
+                    Err(Capture::Trap(_)) => self.pending_call = true,
+                    Err(Capture::Exit(reason)) => {
+                        if let ExitReason::Error(error) = reason {
+                            if let Some(log) = self.last_log() {
+                                log.error = Some(format!("{:?}", error));
+                            }
+                        }
+                        // Exit of frame without code, which was never started.
+                        if self.pending_call {
+                            self.pending_call = false;
+                        } else {
+                            self.depth = self.depth.saturating_sub(1);
+                        }
+                    }
+                    Ok(()) => {}
+                },
+                StepEvent::SLoad {
+                    address,
+                    index,
+                    value,
+                }
+                | StepEvent::SStore {
+                    address,
+                    index,
+                    value,
+                } => {
+                    self.result
+                        .accessed_storage
+                        .entry(address)
+                        .or_default()
+                        .insert(index);
+                    let storage = self.storage.entry(address).or_default();
+                    storage.insert(index, value);
+                    let storage = storage.clone();
+                    if let Some(log) = self.last_log() {
+                        if log.storage.is_some() {
+                            log.storage = Some(storage);
+                        }
+                    }
+                }
+            }
+        }
+
+        fn gas_event(&mut self, event: GasEvent) {
+            let (cost, snapshot) = match event {
+                GasEvent::RecordCost { cost, snapshot } => (cost, snapshot),
+                GasEvent::RecordDynamicCost {
+                    gas_cost,
+                    memory_gas,
+                    snapshot,
+                    ..
+                } => {
+                    // Memory gas is recorded as total memory usage of the frame.
+                    let memory_cost = snapshot
+                        .map(|s| memory_gas.saturating_sub(s.memory_gas))
+                        .unwrap_or_default();
+                    (gas_cost + memory_cost, snapshot)
+                }
+                _ => return,
+            };
+            if !self.pending_gas {
+                return;
+            }
+            self.pending_gas = false;
+            if let (Some(snapshot), Some(log)) = (snapshot, self.last_log()) {
+                log.gas = remaining_gas(snapshot);
+                log.gas_cost = cost;
+            }
+        }
+
+        fn last_log(&mut self) -> Option<&mut StructLog> {
+            if self.skip_step {
+                return None;
+            }
+            self.result.logs.last_mut()
+        }
+    }
+
+    fn remaining_gas(snapshot: Snapshot) -> u64 {
+        snapshot
+            .gas_limit
+            .saturating_sub(snapshot.used_gas)
+            .saturating_sub(snapshot.memory_gas)
+    }
+
+    struct StepListener(Rc<RefCell<StructLogger>>);
+
+    impl step_tracing::EventListener for StepListener {
+        fn event(&mut self, event: StepEvent) {
+            self.0.borrow_mut().step_event(event)
+        }
+    }
+
+    struct GasListener(Rc<RefCell<StructLogger>>);
+
+    impl gas_tracing::EventListener for GasListener {
+        fn event(&mut self, event: GasEvent) {
+            self.0.borrow_mut().gas_event(event)
+        }
+    }
+
+    /// Run `f` and collect opcode level logs of every evm execution inside it.
+    pub fn trace_struct_logs<R>(
+        config: StructLoggerConfig,
+        f: impl FnOnce() -> R,
+    ) -> (R, StructLoggerResult) {
+        let logger = Rc::new(RefCell::new(StructLogger::new(config)));
+        let mut step_listener = StepListener(logger.clone());
+        let mut gas_listener = GasListener(logger.clone());
+
+        let result = step_tracing::using(&mut step_listener, || {
+            gas_tracing::using(&mut gas_listener, f)
+        });
+        drop((step_listener, gas_listener));
+
+        let logger = Rc::try_unwrap(logger)
+            .expect("Listeners should be dropped after tracing.")
+            .into_inner();
+        (result, logger.result)
+    }
+}
+
+/// Mnemonic of opcode, in format used by geth.
+pub fn opcode_name(opcode: Opcode) -> String {
+    let name = match opcode.as_u8() {
+        0x00 => "STOP",
+        0x01 => "ADD",
+        0x02 => "MUL",
+        0x03 => "SUB",
+        0x04 => "DIV",
+        0x05 => "SDIV",
+        0x06 => "MOD",
+        0x07 => "SMOD",
+        0x08 => "ADDMOD",
+        0x09 => "MULMOD",
+        0x0a => "EXP",
+        0x0b => "SIGNEXTEND",
+        0x10 => "LT",
+        0x11 => "GT",
+        0x12 => "SLT",
+        0x13 => "SGT",
+        0x14 => "EQ",
+        0x15 => "ISZERO",
+        0x16 => "AND",
+        0x17 => "OR",
+        0x18 => "XOR",
+        0x19 => "NOT",
+        0x1a => "BYTE",
+        0x1b => "SHL",
+        0x1c => "SHR",
+        0x1d => "SAR",
+        0x20 => "SHA3",
+        0x30 => "ADDRESS",
+        0x31 => "BALANCE",
+        0x32 => "ORIGIN",
+        0x33 => "CALLER",
+        0x34 => "CALLVALUE",
+        0x35 => "CALLDATALOAD",
+        0x36 => "CALLDATASIZE",
+        0x37 => "CALLDATACOPY",
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3a => "GASPRICE",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3d => "RETURNDATASIZE",
+        0x3e => "RETURNDATACOPY",
+        0x3f => "EXTCODEHASH",
+        0x40 => "BLOCKHASH",
+        0x41 => "COINBASE",
+        0x42 => "TIMESTAMP",
+        0x43 => "NUMBER",
+        0x44 => "DIFFICULTY",
+        0x45 => "GASLIMIT",
+        0x46 => "CHAINID",
+        0x47 => "SELFBALANCE",
+        0x48 => "BASEFEE",
+        0x50 => "POP",
+        0x51 => "MLOAD",
+        0x52 => "MSTORE",
+        0x53 => "MSTORE8",
+        0x54 => "SLOAD",
+        0x55 => "SSTORE",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x59 => "MSIZE",
+        0x5a => "GAS",
+        0x5b => "JUMPDEST",
+        0x5f => "PUSH0",
+        n @ 0x60..=0x7f => return format!("PUSH{}", n - 0x5f),
+        n @ 0x80..=0x8f => return format!("DUP{}", n - 0x7f),
+        n @ 0x90..=0x9f => return format!("SWAP{}", n - 0x8f),
+        n @ 0xa0..=0xa4 => return format!("LOG{}", n - 0xa0),
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf3 => "RETURN",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xfd => "REVERT",
+        0xfe => "INVALID",
+        0xff => "SELFDESTRUCT",
+        n => return format!("opcode {:#04x} not defined", n),
+    };
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opcode_names() {
+        assert_eq!(opcode_name(Opcode::SSTORE), "SSTORE");
+        assert_eq!(opcode_name(Opcode(0x60)), "PUSH1");
+        assert_eq!(opcode_name(Opcode(0x7f)), "PUSH32");
+        assert_eq!(opcode_name(Opcode(0x8f)), "DUP16");
+        assert_eq!(opcode_name(Opcode(0xa4)), "LOG4");
+        assert_eq!(opcode_name(Opcode(0x0c)), "opcode 0x0c not defined");
+    }
+}
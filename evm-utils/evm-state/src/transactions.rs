@@ -394,10 +394,526 @@ impl From<Transaction> for UnsignedTransaction {
     }
 }
 
+/// EIP-2718 type byte for EIP-2930 (access list) transactions.
+pub const ACCESS_LIST_TX_TYPE: u8 = 0x01;
+/// EIP-2718 type byte for EIP-1559 (dynamic fee) transactions.
+pub const DYNAMIC_FEE_TX_TYPE: u8 = 0x02;
+
+/// Entry of EIP-2930 access list.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct AccessListItem {
+    pub address: Address,
+    pub storage_keys: Vec<H256>,
+}
+
+impl AccessListItem {
+    /// Converts access list into format that is accepted by evm executor.
+    pub fn into_evm_access_list(list: Vec<AccessListItem>) -> Vec<(H160, Vec<H256>)> {
+        list.into_iter()
+            .map(|item| (item.address, item.storage_keys))
+            .collect()
+    }
+}
+
+impl Encodable for AccessListItem {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(2);
+        s.append(&self.address);
+        s.append_list(&self.storage_keys);
+    }
+}
+
+impl Decodable for AccessListItem {
+    fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
+        Ok(Self {
+            address: rlp.val_at(0)?,
+            storage_keys: rlp.list_at(1)?,
+        })
+    }
+}
+
+/// Fee part of typed transaction, its variant defines transaction type.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Copy,
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub enum TypedTransactionFee {
+    /// EIP-2930 transaction, with legacy gas price.
+    AccessList { gas_price: Gas },
+    /// EIP-1559 transaction.
+    DynamicFee {
+        max_priority_fee_per_gas: Gas,
+        max_fee_per_gas: Gas,
+    },
+}
+
+impl TypedTransactionFee {
+    pub fn tx_type(&self) -> u8 {
+        match self {
+            TypedTransactionFee::AccessList { .. } => ACCESS_LIST_TX_TYPE,
+            TypedTransactionFee::DynamicFee { .. } => DYNAMIC_FEE_TX_TYPE,
+        }
+    }
+
+    /// Maximum price per gas that user agree to pay.
+    /// For EIP-1559 transaction it is `max_fee_per_gas`.
+    pub fn max_gas_price(&self) -> Gas {
+        match *self {
+            TypedTransactionFee::AccessList { gas_price } => gas_price,
+            TypedTransactionFee::DynamicFee {
+                max_fee_per_gas, ..
+            } => max_fee_per_gas,
+        }
+    }
+
+    /// Price per gas that will be charged, if block base fee is `base_fee`.
+    pub fn effective_gas_price(&self, base_fee: Gas) -> Gas {
+        match *self {
+            TypedTransactionFee::AccessList { gas_price } => gas_price,
+            TypedTransactionFee::DynamicFee {
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+            } => std::cmp::min(
+                max_fee_per_gas,
+                base_fee.saturating_add(max_priority_fee_per_gas),
+            ),
+        }
+    }
+
+    fn rlp_append(&self, s: &mut RlpStream) {
+        match self {
+            TypedTransactionFee::AccessList { gas_price } => {
+                s.append(gas_price);
+            }
+            TypedTransactionFee::DynamicFee {
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+            } => {
+                s.append(max_priority_fee_per_gas);
+                s.append(max_fee_per_gas);
+            }
+        }
+    }
+
+    // Number of rlp items that fee occupy.
+    fn rlp_items(tx_type: u8) -> Result<usize, DecoderError> {
+        match tx_type {
+            ACCESS_LIST_TX_TYPE => Ok(1),
+            DYNAMIC_FEE_TX_TYPE => Ok(2),
+            _ => Err(DecoderError::Custom("Unknown transaction type")),
+        }
+    }
+
+    fn decode(rlp: &Rlp<'_>, tx_type: u8, offset: usize) -> Result<Self, DecoderError> {
+        Ok(match tx_type {
+            ACCESS_LIST_TX_TYPE => TypedTransactionFee::AccessList {
+                gas_price: rlp.val_at(offset)?,
+            },
+            DYNAMIC_FEE_TX_TYPE => TypedTransactionFee::DynamicFee {
+                max_priority_fee_per_gas: rlp.val_at(offset)?,
+                max_fee_per_gas: rlp.val_at(offset + 1)?,
+            },
+            _ => return Err(DecoderError::Custom("Unknown transaction type")),
+        })
+    }
+}
+
+/// Signature of typed transaction, chain_id is part of transaction, so only parity is stored.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Copy,
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct TypedTransactionSignature {
+    pub odd_y_parity: bool,
+    pub r: H256,
+    pub s: H256,
+}
+
+impl TypedTransactionSignature {
+    pub fn is_valid(&self) -> bool {
+        TransactionSignature {
+            v: 27 + u64::from(self.odd_y_parity),
+            r: self.r,
+            s: self.s,
+        }
+        .is_valid()
+    }
+
+    pub fn to_recoverable_signature(self) -> Result<RecoverableSignature, secp256k1::Error> {
+        let mut sig = [0u8; 64];
+        sig[0..32].copy_from_slice(self.r.as_bytes());
+        sig[32..64].copy_from_slice(self.s.as_bytes());
+
+        RecoverableSignature::from_compact(&sig, RecoveryId::from_i32(self.odd_y_parity as i32)?)
+    }
+}
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+pub struct UnsignedTypedTransaction {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub fee: TypedTransactionFee,
+    pub gas_limit: Gas,
+    pub action: TransactionAction,
+    pub value: U256,
+    pub input: Vec<u8>,
+    pub access_list: Vec<AccessListItem>,
+}
+
+impl UnsignedTypedTransaction {
+    fn payload_rlp_append(&self, s: &mut RlpStream) {
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        self.fee.rlp_append(s);
+        s.append(&self.gas_limit);
+        s.append(&self.action);
+        s.append(&self.value);
+        s.append(&self.input);
+        s.append_list(&self.access_list);
+    }
+
+    fn payload_items(&self) -> usize {
+        match self.fee {
+            TypedTransactionFee::AccessList { .. } => 8,
+            TypedTransactionFee::DynamicFee { .. } => 9,
+        }
+    }
+
+    /// Hash of `type || rlp(payload)`, as defined by EIP-2930 and EIP-1559.
+    pub fn signing_hash(&self) -> H256 {
+        let mut stream = RlpStream::new_list(self.payload_items());
+        self.payload_rlp_append(&mut stream);
+        let mut keccak = Keccak256::new();
+        keccak.update([self.fee.tx_type()]);
+        keccak.update(stream.as_raw());
+        H256::from_slice(keccak.finalize().as_slice())
+    }
+
+    pub fn sign(self, key: &SecretKey) -> TypedTransaction {
+        let hash = self.signing_hash();
+        // hash is always MESSAGE_SIZE bytes.
+        let msg = { Message::from_slice(hash.as_bytes()).unwrap() };
+
+        // SecretKey and Message are always valid.
+        let s = { SECP256K1.sign_recoverable(&msg, key) };
+        let (rid, sig) = { s.serialize_compact() };
+
+        let signature = TypedTransactionSignature {
+            odd_y_parity: rid.to_i32() == 1,
+            r: H256::from_slice(&sig[0..32]),
+            s: H256::from_slice(&sig[32..64]),
+        };
+
+        TypedTransaction {
+            unsigned: self,
+            signature,
+        }
+    }
+}
+
+/// EIP-2718 typed transaction (EIP-2930 or EIP-1559).
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct TypedTransaction {
+    pub unsigned: UnsignedTypedTransaction,
+    pub signature: TypedTransactionSignature,
+}
+
+impl std::ops::Deref for TypedTransaction {
+    type Target = UnsignedTypedTransaction;
+    fn deref(&self) -> &Self::Target {
+        &self.unsigned
+    }
+}
+
+impl TypedTransaction {
+    pub fn tx_type(&self) -> u8 {
+        self.fee.tx_type()
+    }
+
+    pub fn caller(&self) -> Result<Address, Error> {
+        let transaction_hash = self.signing_hash();
+        let sig = self
+            .signature
+            .to_recoverable_signature()
+            .context(UnrecoverableCaller { transaction_hash })?;
+        let public_key = SECP256K1
+            .recover(
+                &Message::from_slice(transaction_hash.as_bytes()).unwrap(),
+                &sig,
+            )
+            .context(UnrecoverableCaller { transaction_hash })?;
+        Ok(addr_from_public_key(&public_key))
+    }
+
+    pub fn address(&self) -> Result<Address, Error> {
+        Ok(self.action.address(self.caller()?, self.nonce))
+    }
+
+    /// Encode transaction into EIP-2718 envelope: `type || rlp(payload, y_parity, r, s)`.
+    pub fn encode_envelope(&self) -> Vec<u8> {
+        // R and S is a u256, and should be encoded as scalar.
+        let r = U256::from_big_endian(self.signature.r.as_bytes());
+        let s = U256::from_big_endian(self.signature.s.as_bytes());
+        let mut stream = RlpStream::new_list(self.payload_items() + 3);
+        self.payload_rlp_append(&mut stream);
+        stream.append(&self.signature.odd_y_parity);
+        stream.append(&r);
+        stream.append(&s);
+
+        let mut bytes = vec![self.tx_type()];
+        bytes.extend_from_slice(stream.as_raw());
+        bytes
+    }
+
+    /// Decode transaction from EIP-2718 envelope.
+    pub fn decode_envelope(bytes: &[u8]) -> Result<Self, DecoderError> {
+        let (&tx_type, payload) = bytes.split_first().ok_or(DecoderError::RlpIsTooShort)?;
+        let fee_items = TypedTransactionFee::rlp_items(tx_type)?;
+        let rlp = Rlp::new(payload);
+        if rlp.payload_info()?.total() != payload.len() {
+            return Err(DecoderError::RlpIsTooBig);
+        }
+        if rlp.item_count()? != fee_items + 10 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        let fee = TypedTransactionFee::decode(&rlp, tx_type, 2)?;
+        let offset = 2 + fee_items;
+
+        let u256_to_h256 = |v: U256| {
+            let mut bytes = [0u8; 32];
+            v.to_big_endian(&mut bytes);
+            H256::from(bytes)
+        };
+        let odd_y_parity: u8 = rlp.val_at(offset + 5)?;
+        if odd_y_parity > 1 {
+            return Err(DecoderError::Custom("Invalid signature parity"));
+        }
+
+        Ok(Self {
+            unsigned: UnsignedTypedTransaction {
+                chain_id: rlp.val_at(0)?,
+                nonce: rlp.val_at(1)?,
+                fee,
+                gas_limit: rlp.val_at(offset)?,
+                action: rlp.val_at(offset + 1)?,
+                value: rlp.val_at(offset + 2)?,
+                input: rlp.val_at(offset + 3)?,
+                access_list: rlp.list_at(offset + 4)?,
+            },
+            signature: TypedTransactionSignature {
+                odd_y_parity: odd_y_parity == 1,
+                r: u256_to_h256(rlp.val_at(offset + 6)?),
+                s: u256_to_h256(rlp.val_at(offset + 7)?),
+            },
+        })
+    }
+
+    pub fn tx_id_hash(&self) -> H256 {
+        H256::from_slice(Keccak256::digest(&self.encode_envelope()).as_slice())
+    }
+}
+
+/// Any transaction that can be received from user in raw form (legacy or EIP-2718 envelope).
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub enum SignedTransaction {
+    Legacy(Transaction),
+    Typed(TypedTransaction),
+}
+
+impl SignedTransaction {
+    /// Decode transaction as it received from `eth_sendRawTransaction`.
+    /// Legacy transaction is rlp list, while typed transaction starts from type byte.
+    pub fn decode_raw(bytes: &[u8]) -> Result<Self, DecoderError> {
+        match bytes.first() {
+            None => Err(DecoderError::RlpIsTooShort),
+            Some(byte) if *byte >= 0xc0 => Ok(SignedTransaction::Legacy(rlp::decode(bytes)?)),
+            Some(_) => Ok(SignedTransaction::Typed(TypedTransaction::decode_envelope(
+                bytes,
+            )?)),
+        }
+    }
+
+    pub fn encode_raw(&self) -> Vec<u8> {
+        match self {
+            SignedTransaction::Legacy(tx) => rlp::encode(tx).to_vec(),
+            SignedTransaction::Typed(tx) => tx.encode_envelope(),
+        }
+    }
+
+    pub fn caller(&self) -> Result<Address, Error> {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.caller(),
+            SignedTransaction::Typed(tx) => tx.caller(),
+        }
+    }
+
+    pub fn address(&self) -> Result<Address, Error> {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.address(),
+            SignedTransaction::Typed(tx) => tx.address(),
+        }
+    }
+
+    pub fn tx_id_hash(&self) -> H256 {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.tx_id_hash(),
+            SignedTransaction::Typed(tx) => tx.tx_id_hash(),
+        }
+    }
+
+    pub fn chain_id(&self) -> Option<u64> {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.signature.chain_id(),
+            SignedTransaction::Typed(tx) => Some(tx.chain_id),
+        }
+    }
+
+    pub fn nonce(&self) -> U256 {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.nonce,
+            SignedTransaction::Typed(tx) => tx.nonce,
+        }
+    }
+
+    /// Gas price for legacy transactions, and maximum fee per gas for typed.
+    pub fn gas_price(&self) -> Gas {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.gas_price,
+            SignedTransaction::Typed(tx) => tx.fee.max_gas_price(),
+        }
+    }
+
+    pub fn gas_limit(&self) -> Gas {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.gas_limit,
+            SignedTransaction::Typed(tx) => tx.gas_limit,
+        }
+    }
+
+    pub fn action(&self) -> TransactionAction {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.action,
+            SignedTransaction::Typed(tx) => tx.action,
+        }
+    }
+
+    pub fn value(&self) -> U256 {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.value,
+            SignedTransaction::Typed(tx) => tx.value,
+        }
+    }
+
+    pub fn input(&self) -> &[u8] {
+        match self {
+            SignedTransaction::Legacy(tx) => &tx.input,
+            SignedTransaction::Typed(tx) => &tx.input,
+        }
+    }
+
+    pub fn is_signature_valid(&self) -> bool {
+        match self {
+            SignedTransaction::Legacy(tx) => tx.signature.is_valid(),
+            SignedTransaction::Typed(tx) => tx.signature.is_valid(),
+        }
+    }
+}
+
+impl From<Transaction> for SignedTransaction {
+    fn from(tx: Transaction) -> Self {
+        Self::Legacy(tx)
+    }
+}
+
+impl From<TypedTransaction> for SignedTransaction {
+    fn from(tx: TypedTransaction) -> Self {
+        Self::Typed(tx)
+    }
+}
+
+impl From<SignedTransaction> for TransactionInReceipt {
+    fn from(tx: SignedTransaction) -> Self {
+        match tx {
+            SignedTransaction::Legacy(tx) => Self::Signed(tx),
+            SignedTransaction::Typed(tx) => Self::Typed(tx),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum TransactionInReceipt {
     Signed(Transaction),
     Unsigned(UnsignedTransactionWithCaller),
+    Typed(TypedTransaction),
 }
 
 impl TransactionInReceipt {
@@ -405,6 +921,32 @@ impl TransactionInReceipt {
         match self {
             TransactionInReceipt::Signed(tx) => tx.nonce,
             TransactionInReceipt::Unsigned(tx) => tx.unsigned_tx.nonce,
+            TransactionInReceipt::Typed(tx) => tx.nonce,
+        }
+    }
+
+    pub fn tx_id_hash(&self) -> H256 {
+        match self {
+            TransactionInReceipt::Signed(tx) => tx.tx_id_hash(),
+            TransactionInReceipt::Unsigned(tx) => tx.tx_id_hash(),
+            TransactionInReceipt::Typed(tx) => tx.tx_id_hash(),
+        }
+    }
+
+    /// EIP-2718 transaction type, legacy and unsigned transactions has type 0.
+    pub fn tx_type(&self) -> u8 {
+        match self {
+            TransactionInReceipt::Typed(tx) => tx.tx_type(),
+            _ => 0,
+        }
+    }
+
+    /// Price per gas that was charged, if block base fee is `base_fee`.
+    pub fn effective_gas_price(&self, base_fee: Gas) -> Gas {
+        match self {
+            TransactionInReceipt::Signed(tx) => tx.gas_price,
+            TransactionInReceipt::Unsigned(tx) => tx.unsigned_tx.gas_price,
+            TransactionInReceipt::Typed(tx) => tx.fee.effective_gas_price(base_fee),
         }
     }
 }
@@ -418,6 +960,10 @@ impl Encodable for TransactionInReceipt {
             TransactionInReceipt::Unsigned(tx) => {
                 s.append_internal(tx);
             }
+            // Typed transaction is stored as rlp string with EIP-2718 envelope inside.
+            TransactionInReceipt::Typed(tx) => {
+                s.append_internal(&tx.encode_envelope());
+            }
         }
     }
 }
@@ -434,8 +980,19 @@ impl From<UnsignedTransactionWithCaller> for TransactionInReceipt {
     }
 }
 
+impl From<TypedTransaction> for TransactionInReceipt {
+    fn from(tx: TypedTransaction) -> Self {
+        Self::Typed(tx)
+    }
+}
+
 impl Decodable for TransactionInReceipt {
     fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
+        if rlp.is_data() {
+            return Ok(TransactionInReceipt::Typed(TypedTransaction::decode_envelope(
+                rlp.data()?,
+            )?));
+        }
         let items = rlp.item_count()?;
         Ok(match items {
             8 => TransactionInReceipt::Unsigned(UnsignedTransactionWithCaller::decode(rlp, false)?),
@@ -599,6 +1156,7 @@ impl TransactionReceipt {
         match &self.transaction {
             TransactionInReceipt::Unsigned(tx) => Some(tx.caller),
             TransactionInReceipt::Signed(tx) => tx.caller().ok(),
+            TransactionInReceipt::Typed(tx) => tx.caller().ok(),
         }
     }
 }
@@ -759,6 +1317,87 @@ mod test {
         assert_eq!(t.signature.chain_id(), Some(69));
     }
 
+    #[test]
+    fn typed_tx_envelope_roundtrip() {
+        let mut rng = secp256k1::rand::thread_rng();
+        let key = SecretKey::new(&mut rng);
+        let public_key = PublicKey::from_secret_key(SECP256K1, &key);
+
+        let fees = [
+            TypedTransactionFee::AccessList {
+                gas_price: U256::from(3000),
+            },
+            TypedTransactionFee::DynamicFee {
+                max_priority_fee_per_gas: U256::from(2),
+                max_fee_per_gas: U256::from(3000),
+            },
+        ];
+        for fee in fees {
+            let tx = UnsignedTypedTransaction {
+                chain_id: 69,
+                nonce: U256::from(42),
+                fee,
+                gas_limit: U256::from(50_000),
+                action: TransactionAction::Call(H160::repeat_byte(2)),
+                value: U256::from(1),
+                input: b"Hello!".to_vec(),
+                access_list: vec![AccessListItem {
+                    address: H160::repeat_byte(3),
+                    storage_keys: vec![H256::repeat_byte(4), H256::zero()],
+                }],
+            }
+            .sign(&key);
+            assert_eq!(addr_from_public_key(&public_key), tx.caller().unwrap());
+            assert!(tx.signature.is_valid());
+
+            let bytes = tx.encode_envelope();
+            assert_eq!(bytes[0], fee.tx_type());
+            let decoded = TypedTransaction::decode_envelope(&bytes).unwrap();
+            assert_eq!(decoded, tx);
+            assert_eq!(decoded.tx_id_hash(), tx.tx_id_hash());
+
+            let raw = SignedTransaction::decode_raw(&bytes).unwrap();
+            assert_eq!(raw, SignedTransaction::Typed(tx.clone()));
+            assert_eq!(raw.encode_raw(), bytes);
+
+            let mut trailing = bytes.clone();
+            trailing.push(0x80);
+            assert_eq!(
+                TypedTransaction::decode_envelope(&trailing),
+                Err(DecoderError::RlpIsTooBig)
+            );
+
+            let in_receipt = TransactionInReceipt::Typed(tx.clone());
+            let receipt_bytes = rlp::encode(&in_receipt);
+            let in_receipt_decoded: TransactionInReceipt = rlp::decode(&receipt_bytes).unwrap();
+            assert_eq!(in_receipt_decoded, in_receipt);
+            assert_eq!(in_receipt_decoded.tx_id_hash(), tx.tx_id_hash());
+        }
+    }
+
+    #[test]
+    fn dynamic_fee_effective_gas_price() {
+        let fee = TypedTransactionFee::DynamicFee {
+            max_priority_fee_per_gas: U256::from(2),
+            max_fee_per_gas: U256::from(10),
+        };
+        assert_eq!(fee.effective_gas_price(U256::from(5)), U256::from(7));
+        assert_eq!(fee.effective_gas_price(U256::from(9)), U256::from(10));
+        assert_eq!(fee.max_gas_price(), U256::from(10));
+    }
+
+    #[test]
+    fn raw_legacy_tx_decoded_as_legacy() {
+        let tx_data = "f864808504a817c800825208943535353535353535353535353535353535353535808025a0044852b2a670ade5407e78fb2863c51de9fcb96542a07186fe3aeda6bb8a116da0044852b2a670ade5407e78fb2863c51de9fcb96542a07186fe3aeda6bb8a116d";
+        let signed = SignedTransaction::decode_raw(&hex::decode(tx_data).unwrap()).unwrap();
+        assert!(matches!(signed, SignedTransaction::Legacy(_)));
+        assert_eq!(
+            signed.caller().unwrap(),
+            Address::from_str("f0f6f18bca1b28cd68e4357452947e021241e9ce").unwrap()
+        );
+        assert!(SignedTransaction::decode_raw(&[0x03, 0xc0]).is_err());
+    }
+
     #[test]
     fn should_serialize_to_borsh_and_back() {
         let mut buf: Vec<u8> = vec![];
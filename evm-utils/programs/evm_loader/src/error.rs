@@ -66,6 +66,12 @@ pub enum EvmError {
 
     #[snafu(display("Precompile error"))]
     PrecompileError,
+
+    #[snafu(display("Transaction batch is empty or exceeds maximum size."))]
+    InvalidBatchSize,
+
+    #[snafu(display("EVM Transaction of batch was reverted."))]
+    BatchTransactionReverted,
 }
 
 impl<E> DecodeError<E> for EvmError {
@@ -1,12 +1,15 @@
 use super::scope::*;
 use borsh::{BorshDeserialize, BorshSerialize};
-use evm_state::{Address, Transaction, UnsignedTransaction};
+use evm_state::{Address, SignedTransaction, Transaction, TypedTransaction, UnsignedTransaction};
 use serde::{Deserialize, Serialize};
 
 pub mod v0;
 
 pub const EVM_INSTRUCTION_BORSH_PREFIX: u8 = 255u8;
 
+/// Maximum count of transactions, that can be executed by single batch instruction.
+pub const MAX_TRANSACTIONS_IN_BATCH: usize = 64;
+
 #[derive(
     BorshSerialize,
     BorshDeserialize,
@@ -84,6 +87,10 @@ pub enum ExecuteTransaction {
         tx: Option<UnsignedTransaction>,
         from: evm::Address,
     },
+    /// EIP-2718 typed transaction (EIP-2930 or EIP-1559).
+    SignedTyped {
+        tx: Option<TypedTransaction>,
+    },
 }
 
 impl ExecuteTransaction {
@@ -92,6 +99,7 @@ impl ExecuteTransaction {
             self,
             ExecuteTransaction::Signed { tx: None }
                 | ExecuteTransaction::ProgramAuthorized { tx: None, .. }
+                | ExecuteTransaction::SignedTyped { tx: None }
         )
     }
 }
@@ -162,6 +170,27 @@ pub enum EvmInstruction {
         tx: ExecuteTransaction,
         fee_type: FeePayerType,
     },
+
+    /// Execute list of EVM transactions atomically.
+    /// If any transaction of batch fails or reverts, whole instruction fails,
+    /// and receipts of its transactions are not saved, so their nonces are not increased.
+    /// Each transaction is executed in its own context and has its own receipt.
+    ///
+    /// Outer args:
+    /// account_key[0] - `[writable]`. EVM state account, used for lock.
+    /// account_key[1] - `[readable]`. Optional argument, used in case tokens swaps from EVM back to native.
+    ///
+    /// Outer args (Big tx case):
+    /// account_key[0] - `[writable]`. EVM state account. used for lock.
+    /// account_key[1] - `[writable]`. Big Transaction data storage.
+    ///
+    /// Inner args:
+    /// txs - transactions of batch, if None batch is taken from Big Transaction data storage.
+    /// fee_type - which side will be used for charging fee: Native or Evm
+    ExecuteTransactionBatch {
+        txs: Option<Vec<SignedTransaction>>,
+        fee_type: FeePayerType,
+    },
 }
 
 impl EvmInstruction {
@@ -183,6 +212,20 @@ impl EvmInstruction {
         }
     }
 
+    pub fn new_execute_typed_tx(tx: TypedTransaction, fee_type: FeePayerType) -> Self {
+        Self::ExecuteTransaction {
+            tx: ExecuteTransaction::SignedTyped { tx: Some(tx) },
+            fee_type,
+        }
+    }
+
+    pub fn new_execute_big_typed_tx(fee_type: FeePayerType) -> Self {
+        Self::ExecuteTransaction {
+            tx: ExecuteTransaction::SignedTyped { tx: None },
+            fee_type,
+        }
+    }
+
     pub fn new_execute_big_tx(fee_type: FeePayerType) -> Self {
         Self::ExecuteTransaction {
             tx: ExecuteTransaction::Signed { tx: None },
@@ -196,6 +239,20 @@ impl EvmInstruction {
             fee_type,
         }
     }
+
+    pub fn new_execute_batch(txs: Vec<SignedTransaction>, fee_type: FeePayerType) -> Self {
+        Self::ExecuteTransactionBatch {
+            txs: Some(txs),
+            fee_type,
+        }
+    }
+
+    pub fn new_execute_big_batch(fee_type: FeePayerType) -> Self {
+        Self::ExecuteTransactionBatch {
+            txs: None,
+            fee_type,
+        }
+    }
 }
 
 impl From<EvmBigTransaction> for v0::EvmBigTransaction {
@@ -241,6 +298,14 @@ impl From<v0::EvmInstruction> for EvmInstruction {
             v0::EvmInstruction::EvmAuthorizedTransaction { from, unsigned_tx } => {
                 Self::new_execute_authorized_tx(unsigned_tx, from, FeePayerType::Evm)
             }
+            v0::EvmInstruction::EvmTypedTransaction { evm_tx } => Self::ExecuteTransaction {
+                tx: ExecuteTransaction::SignedTyped { tx: evm_tx },
+                fee_type: FeePayerType::Evm,
+            },
+            v0::EvmInstruction::EvmTransactionBatch { evm_txs } => Self::ExecuteTransactionBatch {
+                txs: evm_txs,
+                fee_type: FeePayerType::Evm,
+            },
         }
     }
 }
@@ -277,7 +342,13 @@ impl From<EvmInstruction> for v0::EvmInstruction {
                         from,
                     }
                 }
+                ExecuteTransaction::SignedTyped { tx } => {
+                    v0::EvmInstruction::EvmTypedTransaction { evm_tx: tx }
+                }
             },
+            EvmInstruction::ExecuteTransactionBatch { txs, .. } => {
+                v0::EvmInstruction::EvmTransactionBatch { evm_txs: txs }
+            }
         }
     }
 }
@@ -922,4 +993,42 @@ mod test {
             std::io::ErrorKind::InvalidInput,
         );
     }
+
+    #[test]
+    fn test_serialize_evm_instruction_batch() {
+        let tx = Transaction {
+            nonce: evm::U256::from(1),
+            gas_price: evm::U256::from(10),
+            gas_limit: evm::U256::from(100),
+            action: evm::TransactionAction::Call(H160::repeat_byte(0x11)),
+            value: evm::U256::from(5),
+            signature: evm::TransactionSignature {
+                v: 20,
+                r: H256::from_low_u64_be(6),
+                s: H256::from_low_u64_be(6),
+            },
+            input: vec![1, 2, 3],
+        };
+        let data = EvmInstruction::new_execute_batch(
+            vec![tx.clone().into(), tx.into()],
+            FeePayerType::Evm,
+        );
+
+        let mut buf = vec![];
+        BorshSerialize::serialize(&data, &mut buf).unwrap();
+        // instruction tag, option tag, vec length, first transaction tag
+        assert_eq!(buf[..7], [4, 1, 2, 0, 0, 0, 0]);
+        assert_eq!(
+            <EvmInstruction as BorshDeserialize>::deserialize(&mut &buf[..]).unwrap(),
+            data
+        );
+
+        let buf = bincode::serialize(&data).unwrap();
+        assert_eq!(bincode::deserialize::<EvmInstruction>(&buf).unwrap(), data);
+
+        let data = EvmInstruction::new_execute_big_batch(FeePayerType::Native);
+        let mut buf = vec![];
+        BorshSerialize::serialize(&data, &mut buf).unwrap();
+        assert_eq!(buf, [4, 0, 1]);
+    }
 }
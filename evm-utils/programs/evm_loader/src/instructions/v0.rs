@@ -71,4 +71,26 @@ pub enum EvmInstruction {
         from: evm::Address,
         unsigned_tx: evm::UnsignedTransaction,
     },
+
+    /// Execute EIP-2718 typed transaction.
+    /// If `evm_tx` is None, transaction is taken from Big Transaction data storage.
+    ///
+    /// Outer args:
+    /// account_key[0] - `[writable]`. EVM state account, used for lock.
+    /// account_key[1] - `[readable]`. Optional argument, used in case tokens swaps from EVM back to native.
+    ///
+    EvmTypedTransaction {
+        evm_tx: Option<evm::TypedTransaction>,
+    },
+
+    /// Execute list of EVM transactions atomically.
+    /// If `evm_txs` is None, transactions are taken from Big Transaction data storage.
+    ///
+    /// Outer args:
+    /// account_key[0] - `[writable]`. EVM state account, used for lock.
+    /// account_key[1] - `[readable]`. Optional argument, used in case tokens swaps from EVM back to native.
+    ///
+    EvmTransactionBatch {
+        evm_txs: Option<Vec<evm::SignedTransaction>>,
+    },
 }
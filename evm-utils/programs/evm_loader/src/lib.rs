@@ -8,6 +8,7 @@ pub mod instructions;
 pub mod precompiles;
 pub mod processor;
 pub mod solana_extension;
+pub mod swap;
 
 pub static ID: solana_sdk::pubkey::Pubkey = solana_sdk::evm_loader::ID;
 
@@ -94,6 +95,52 @@ pub fn send_raw_tx(
     )
 }
 
+pub fn send_raw_typed_tx(
+    signer: solana::Address,
+    evm_tx: evm::TypedTransaction,
+    gas_collector: Option<solana::Address>,
+    fee_type: FeePayerType,
+) -> solana::Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(solana::evm_state::ID, false),
+        AccountMeta::new(signer, true),
+    ];
+    if let Some(gas_collector) = gas_collector {
+        account_metas.push(AccountMeta::new(gas_collector, false))
+    }
+
+    create_evm_instruction_with_borsh(
+        crate::ID,
+        &EvmInstruction::ExecuteTransaction {
+            tx: ExecuteTransaction::SignedTyped { tx: Some(evm_tx) },
+            fee_type,
+        },
+        account_metas,
+    )
+}
+
+/// Execute all transactions of batch atomically, in single instruction.
+pub fn send_raw_batch(
+    signer: solana::Address,
+    evm_txs: Vec<evm::SignedTransaction>,
+    gas_collector: Option<solana::Address>,
+    fee_type: FeePayerType,
+) -> solana::Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(solana::evm_state::ID, false),
+        AccountMeta::new(signer, true),
+    ];
+    if let Some(gas_collector) = gas_collector {
+        account_metas.push(AccountMeta::new(gas_collector, false))
+    }
+
+    create_evm_instruction_with_borsh(
+        crate::ID,
+        &EvmInstruction::new_execute_batch(evm_txs, fee_type),
+        account_metas,
+    )
+}
+
 pub fn authorized_tx(
     sender: solana::Address,
     unsigned_tx: evm::UnsignedTransaction,
@@ -203,6 +250,52 @@ pub fn big_tx_execute(
         account_metas,
     )
 }
+pub fn big_tx_execute_typed(
+    storage: solana::Address,
+    gas_collector: Option<&solana::Address>,
+    fee_type: FeePayerType,
+) -> solana::Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(solana::evm_state::ID, false),
+        AccountMeta::new(storage, true),
+    ];
+
+    if let Some(gas_collector) = gas_collector {
+        account_metas.push(AccountMeta::new(*gas_collector, false))
+    }
+
+    create_evm_instruction_with_borsh(
+        crate::ID,
+        &EvmInstruction::ExecuteTransaction {
+            tx: ExecuteTransaction::SignedTyped { tx: None },
+            fee_type,
+        },
+        account_metas,
+    )
+}
+
+/// Execute batch of transactions, written to storage as borsh encoded `Vec<evm::SignedTransaction>`.
+pub fn big_tx_execute_batch(
+    storage: solana::Address,
+    gas_collector: Option<&solana::Address>,
+    fee_type: FeePayerType,
+) -> solana::Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(solana::evm_state::ID, false),
+        AccountMeta::new(storage, true),
+    ];
+
+    if let Some(gas_collector) = gas_collector {
+        account_metas.push(AccountMeta::new(*gas_collector, false))
+    }
+
+    create_evm_instruction_with_borsh(
+        crate::ID,
+        &EvmInstruction::new_execute_big_batch(fee_type),
+        account_metas,
+    )
+}
+
 pub fn big_tx_execute_authorized(
     storage: solana::Address,
     from: evm::Address,
@@ -240,6 +333,20 @@ pub fn transfer_native_to_evm_ixs(
     ]
 }
 
+/// Evm executor features, that are active in native `feature_set`.
+pub fn executor_feature_set(
+    feature_set: &solana_sdk::feature_set::FeatureSet,
+) -> evm::executor::FeatureSet {
+    use solana_sdk::feature_set::velas;
+    evm::executor::FeatureSet::new(
+        feature_set.is_active(&velas::unsigned_tx_fix::id()),
+        feature_set.is_active(&velas::clear_logs_on_error::id()),
+        feature_set.is_active(&velas::accept_zero_gas_price_with_native_fee::id()),
+        feature_set.is_active(&velas::evm_berlin_hardfork::id()),
+        feature_set.is_active(&velas::evm_london_hardfork::id()),
+    )
+}
+
 /// Create an account that represent evm locked lamports count.
 pub fn create_state_account(lamports: u64) -> solana_sdk::account::AccountSharedData {
     solana_sdk::account::Account {
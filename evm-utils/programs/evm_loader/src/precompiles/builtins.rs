@@ -184,8 +184,8 @@ type EthToVlxImp = PromiseFunc<
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EthToVlxResult {
-    pubkey: Pubkey,
-    amount: u64,
+    pub pubkey: Pubkey,
+    pub amount: u64,
 }
 
 pub static ETH_TO_VLX_CODE: Lazy<NativeContract<EthToVlxImp, Pubkey>> = Lazy::new(|| {
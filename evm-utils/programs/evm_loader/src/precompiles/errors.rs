@@ -70,10 +70,81 @@ pub enum PrecompileErrors {
 
     #[snafu(display("Incorrect final block indicator flag"))]
     IncorrectBlockIndicator,
+
+    #[snafu(display(
+        "Native program is not allowed to be called from evm, program_id = {}",
+        program_id
+    ))]
+    NativeProgramNotAllowed { program_id: solana::Address },
+
+    #[snafu(display(
+        "Instruction is not allowed to be called from evm, program_id = {}",
+        program_id
+    ))]
+    NativeInstructionNotAllowed { program_id: solana::Address },
+
+    #[snafu(display(
+        "Only evm caller authority can sign native call, account_pk = {}",
+        public_key
+    ))]
+    NativeSignerNotAllowed { public_key: solana::Address },
+
+    #[snafu(display("Account is not a valid stake account, account_pk = {}", public_key))]
+    InvalidStakeAccount { public_key: solana::Address },
+
+    #[snafu(display("Precompile is not payable, value = {}", value))]
+    NonPayable { value: evm::U256 },
+
+    #[snafu(display("Not enough gas to execute precompile, required = {}", required))]
+    OutOfGas { required: u64 },
+
+    #[snafu(display(
+        "Requested data is out of account bounds, offset = {}, length = {}, data_len = {}",
+        offset,
+        length,
+        data_len
+    ))]
+    DataOutOfBounds {
+        offset: u64,
+        length: u64,
+        data_len: usize,
+    },
+
+    #[snafu(display("Requested data length = {} exceeds maximum = {}", length, max))]
+    DataLengthExceeded { length: u64, max: u64 },
+
+    #[snafu(display("Account is not a valid token account, account_pk = {}", public_key))]
+    InvalidTokenAccount { public_key: solana::Address },
+
+    #[snafu(display(
+        "No enough tokens on token account, account_pk = {}, amount = {}",
+        public_key,
+        amount
+    ))]
+    InsufficientTokens {
+        public_key: solana::Address,
+        amount: u64,
+    },
+
+    #[snafu(display(
+        "No enough allowance on token account, account_pk = {}, amount = {}",
+        public_key,
+        amount
+    ))]
+    InsufficientAllowance {
+        public_key: solana::Address,
+        amount: u64,
+    },
+
+    #[snafu(display("Unknown function hash = {}", hash))]
+    UnknownFunction { hash: String },
 }
 
 impl From<PrecompileErrors> for ExitError {
     fn from(rhs: PrecompileErrors) -> Self {
-        ExitError::Other(rhs.to_string().into())
+        match rhs {
+            PrecompileErrors::OutOfGas { .. } => ExitError::OutOfGas,
+            rhs => ExitError::Other(rhs.to_string().into()),
+        }
     }
 }
@@ -4,19 +4,33 @@ use evm_state::{
 };
 use once_cell::sync::Lazy;
 use primitive_types::H160;
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
 
 mod abi_parse;
 mod builtins;
 mod compatibility;
 mod errors;
+mod native_account;
+mod native_call;
+mod spl_facade;
 pub use abi_parse::*;
-pub use builtins::{ETH_TO_VLX_ADDR, ETH_TO_VLX_CODE};
+pub use builtins::{EthToVlxResult, ETH_TO_VLX_ADDR, ETH_TO_VLX_CODE};
 pub use compatibility::build_precompile_map;
 pub use errors::PrecompileErrors;
+pub use native_account::{
+    ACCOUNT_DATA_CODE, ACCOUNT_INFO_CODE, MAX_ACCOUNT_DATA_READ, NATIVE_ACCOUNT_ADDR,
+};
+pub use native_call::{
+    native_call_authority, NativeCallAccount, NativeCallPromise, CALL_NATIVE_CODE,
+    NATIVE_CALL_ADDR, NATIVE_CALL_AUTHORITY_SEED, STAKE_DELEGATION_CODE,
+};
+pub use spl_facade::{spl_token_facade_account, spl_token_facade_address, PendingTokens};
 
 use crate::account_structure::AccountStructure;
-use solana_sdk::keyed_account::KeyedAccount;
+use solana_sdk::{feature_set::FeatureSet, keyed_account::KeyedAccount};
 
 pub type Result<T, Err = PrecompileErrors> = std::result::Result<T, Err>;
 type CallResult = Result<(PrecompileOutput, u64, LogEntry)>;
@@ -44,7 +58,6 @@ impl<'a, 'b> NativeContext<'a, 'b> {
 }
 
 pub struct PrecompileContext<'b> {
-    #[allow(unused)]
     gas_limit: Option<u64>,
     evm_context: &'b Context,
     call_scheme: Option<CallScheme>,
@@ -86,6 +99,40 @@ pub static NATIVE_CONTRACTS: Lazy<HashMap<H160, (NativeBuiltinEval, NativePromis
         assert!(native_contracts
             .insert(*ETH_TO_VLX_ADDR, (eth_to_sol, handle_log))
             .is_none());
+
+        // Single address for all native program calls, function is selected by its hash.
+        let native_call: NativeBuiltinEval = &|function_abi_input, cx| {
+            if function_abi_input.starts_with(&STAKE_DELEGATION_CODE.abi.short_signature()) {
+                (*STAKE_DELEGATION_CODE).eval(function_abi_input, cx)
+            } else {
+                (*CALL_NATIVE_CODE).eval(function_abi_input, cx)
+            }
+        };
+
+        let handle_native_call: NativePromiseHandler = &|accounts, _topics: Vec<H256>, data| {
+            (*CALL_NATIVE_CODE).process_promise(accounts, data)
+        };
+        assert!(native_contracts
+            .insert(*NATIVE_CALL_ADDR, (native_call, handle_native_call))
+            .is_none());
+
+        let native_account: NativeBuiltinEval = &|function_abi_input, cx| {
+            if function_abi_input.starts_with(&ACCOUNT_DATA_CODE.abi.short_signature()) {
+                (*ACCOUNT_DATA_CODE).eval(function_abi_input, cx)
+            } else {
+                (*ACCOUNT_INFO_CODE).eval(function_abi_input, cx)
+            }
+        };
+
+        // View only contract, there is no promises to handle.
+        let handle_native_account: NativePromiseHandler =
+            &|_accounts, _topics: Vec<H256>, _data| Ok(());
+        assert!(native_contracts
+            .insert(
+                *NATIVE_ACCOUNT_ADDR,
+                (native_account, handle_native_account)
+            )
+            .is_none());
         native_contracts
     });
 
@@ -100,24 +147,95 @@ pub static NO_PRECOMPILES: Lazy<HashMap<H160, BuiltinEval>> = Lazy::new(|| HashM
 // Simulation does not have access to real account structure, so only process immutable entrypoints
 pub fn simulation_entrypoint<'a>(
     activate_precompile: PrecompileSet,
+    native_features: NativeFeatures,
     evm_account: &'a KeyedAccount,
     users_accounts: &'a [KeyedAccount],
 ) -> OwnedPrecompile<'a> {
     let accounts = AccountStructure::new(evm_account, users_accounts);
-    entrypoint(accounts, activate_precompile, true)
+    entrypoint(accounts, activate_precompile, true, native_features)
+}
+
+/// Native contracts, that are activated by features.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NativeFeatures {
+    pub native_call: bool,
+    pub native_account: bool,
+    pub spl_token_facade: bool,
+}
+
+impl NativeFeatures {
+    pub fn all() -> Self {
+        Self {
+            native_call: true,
+            native_account: true,
+            spl_token_facade: true,
+        }
+    }
+
+    /// Native contracts, that are active in native `feature_set`.
+    pub fn from_feature_set(feature_set: &FeatureSet) -> Self {
+        use solana_sdk::feature_set::velas;
+        Self {
+            native_call: feature_set.is_active(&velas::evm_native_call_precompile::id()),
+            native_account: feature_set.is_active(&velas::evm_native_account_precompile::id()),
+            spl_token_facade: feature_set.is_active(&velas::evm_spl_token_facade::id()),
+        }
+    }
+
+    fn is_enabled(&self, address: &H160) -> bool {
+        if *address == *NATIVE_CALL_ADDR {
+            self.native_call
+        } else if *address == *NATIVE_ACCOUNT_ADDR {
+            self.native_account
+        } else {
+            true
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PrecompileSet {
     No,
     VelasClassic,
     VelasNext,
 }
 
+impl PrecompileSet {
+    /// Precompiles, that evm loader activates for executor with native `feature_set`.
+    pub fn from_feature_set(support_precompile: bool, feature_set: &FeatureSet) -> Self {
+        let evm_new_precompiles =
+            feature_set.is_active(&solana_sdk::feature_set::velas::evm_new_precompiles::id());
+        match (support_precompile, evm_new_precompiles) {
+            (false, _) => PrecompileSet::No,
+            (true, false) => PrecompileSet::VelasClassic,
+            (true, true) => PrecompileSet::VelasNext,
+        }
+    }
+}
+
 pub fn entrypoint(
     accounts: AccountStructure,
     activate_precompile: PrecompileSet,
     keep_old_errors: bool,
+    native_features: NativeFeatures,
+) -> OwnedPrecompile {
+    entrypoint_with_pending(
+        accounts,
+        activate_precompile,
+        keep_old_errors,
+        native_features,
+        Rc::default(),
+    )
+}
+
+/// Same as `entrypoint`, but SPL token facades see `pending` changes of earlier transactions,
+/// whose native calls are not executed yet.
+pub fn entrypoint_with_pending(
+    accounts: AccountStructure,
+    activate_precompile: PrecompileSet,
+    keep_old_errors: bool,
+    native_features: NativeFeatures,
+    pending: Rc<PendingTokens>,
 ) -> OwnedPrecompile {
     let mut map = BTreeMap::new();
 
@@ -160,7 +278,10 @@ pub fn entrypoint(
         )
     }));
 
-    map.extend(NATIVE_CONTRACTS.iter().map(|(k, (method, _))| {
+    let native_contracts = NATIVE_CONTRACTS
+        .iter()
+        .filter(|(k, _)| native_features.is_enabled(k));
+    map.extend(native_contracts.map(|(k, (method, _))| {
         (
             *k,
             Box::new(
@@ -193,15 +314,70 @@ pub fn entrypoint(
                 >,
         )
     }));
+
+    // Facades exist only for mints, passed to instruction.
+    if native_features.spl_token_facade {
+        map.extend(
+            spl_facade::spl_token_facades(&accounts)
+                .into_iter()
+                .map(|(k, mint)| {
+                    let pending = pending.clone();
+                    (
+                        k,
+                        Box::new(
+                            move |function_abi_input: &[u8],
+                                  gas_left,
+                                  call_scheme,
+                                  cx: &Context,
+                                  _is_static| {
+                                let cx = NativeContext::new(
+                                    keep_old_errors,
+                                    accounts,
+                                    gas_left,
+                                    cx,
+                                    call_scheme,
+                                );
+                                spl_facade::eval(mint, function_abi_input, cx, &pending).map_err(
+                                    |err| {
+                                        let exit_err: ExitError = Into::into(err);
+                                        PrecompileFailure::Error {
+                                            exit_status: exit_err,
+                                        }
+                                    },
+                                )
+                            },
+                        )
+                            as Box<
+                                dyn for<'a, 'b> Fn(
+                                    &[u8],
+                                    Option<u64>,
+                                    Option<CallScheme>,
+                                    &Context,
+                                    bool,
+                                ) -> Result<
+                                    (PrecompileOutput, u64, LogEntry),
+                                    PrecompileFailure,
+                                >,
+                            >,
+                    )
+                }),
+        );
+    }
     OwnedPrecompile { precompiles: map }
 }
 
 pub fn filter_native_logs(accounts: AccountStructure<'_>, logs: &mut Vec<Log>) -> Result<()> {
+    let facades = spl_facade::spl_token_facades(&accounts);
     let tmp_logs = std::mem::take(logs);
     for log in tmp_logs {
         if let Some(c) = NATIVE_CONTRACTS.get(&log.address) {
             let handle_promise = c.1;
             (*handle_promise)(accounts, log.topics, log.data)?
+        } else if let Some(promise) = facades
+            .get(&log.address)
+            .and_then(|_| spl_facade::decode_promise(&log.topics, &log.data))
+        {
+            spl_facade::process_promise(accounts, &promise)?
         } else {
             logs.push(log)
         }
@@ -209,11 +385,37 @@ pub fn filter_native_logs(accounts: AccountStructure<'_>, logs: &mut Vec<Log>) -
     Ok(())
 }
 
+/// Returns swaps to native chain, requested in logs of evm transaction.
+pub fn swaps_to_native(logs: &[Log]) -> Vec<EthToVlxResult> {
+    logs.iter()
+        .filter(|log| log.address == *ETH_TO_VLX_ADDR)
+        .filter_map(|log| ETH_TO_VLX_CODE.decode_promise(&log.data).ok())
+        .collect()
+}
+
+/// Returns native program calls, requested in logs of evm transaction,
+/// including calls of SPL token facades.
+pub fn native_calls(accounts: AccountStructure, logs: &[Log]) -> Vec<NativeCallPromise> {
+    let facades = spl_facade::spl_token_facades(&accounts);
+    logs.iter()
+        .filter_map(|log| {
+            if log.address == *NATIVE_CALL_ADDR {
+                CALL_NATIVE_CODE.decode_promise(&log.data).ok()
+            } else if facades.contains_key(&log.address) {
+                spl_facade::decode_promise(&log.topics, &log.data)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use hex_literal::hex;
     use primitive_types::U256;
     use solana_sdk::account::{ReadableAccount, WritableAccount};
+    use solana_sdk::pubkey::Pubkey;
 
     use crate::scope::evm::lamports_to_gwei;
 
@@ -221,9 +423,15 @@ mod test {
     use evm_state::{ExitError, ExitSucceed};
     use std::str::FromStr;
 
+    const NO_FEATURES: NativeFeatures = NativeFeatures {
+        native_call: false,
+        native_account: false,
+        spl_token_facade: false,
+    };
+
     #[test]
     fn check_num_builtins() {
-        assert_eq!(NATIVE_CONTRACTS.len(), 1);
+        assert_eq!(NATIVE_CONTRACTS.len(), 3);
     }
 
     #[ignore]
@@ -244,7 +452,7 @@ mod test {
             apparent_value: U256::from(1),
         };
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::No, true);
+            let precompiles = entrypoint(accounts, PrecompileSet::No, true, NO_FEATURES);
             assert_eq!(
                 dbg!(precompiles.get(&addr).unwrap()(&input, None, None, &cx, false).unwrap_err()),
                 PrecompileFailure::Error { exit_status: ExitError::Other("Failed to find account, account_pk = 29d2S7vB453rNYFdR5Ycwt7y9haRT5fwVwL9zTmBhfV2".into()) } // equal to 0x111..111 in base58
@@ -276,7 +484,7 @@ mod test {
             apparent_value: U256::from(1),
         };
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::No, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::No, false, NO_FEATURES);
             let precompile_output = dbg!(precompiles.get(&addr).unwrap()(
                 &input, None, None, &cx, false
             ));
@@ -305,7 +513,7 @@ mod test {
             apparent_value: lamports_to_gwei(1),
         };
         AccountStructure::testing(0, |accounts: AccountStructure| {
-            let precompiles = entrypoint(accounts, PrecompileSet::No, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::No, false, NO_FEATURES);
             let user = accounts.first().unwrap();
             let input = hex::decode(format!(
                 "b1d6927a{}",
@@ -350,7 +558,7 @@ mod test {
             apparent_value: lamports_to_gwei(1),
         };
         AccountStructure::testing(0, |accounts: AccountStructure| {
-            let precompiles = entrypoint(accounts, PrecompileSet::No, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::No, false, NO_FEATURES);
             let user = accounts.first().unwrap();
             let input = hex::decode(format!(
                 "b1d6927a{}",
@@ -411,7 +619,7 @@ mod test {
             apparent_value: lamports_to_gwei(1),
         };
         AccountStructure::testing(0, |accounts: AccountStructure| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false, NO_FEATURES);
             let input = [0u8; 0];
             let result = precompiles.get(&addr).unwrap()(&input, None, None, &cx, false).unwrap();
             println!("{}", hex::encode(&result.0.output));
@@ -442,7 +650,7 @@ mod test {
             apparent_value: lamports_to_gwei(1),
         };
         AccountStructure::testing(0, |accounts: AccountStructure| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false, NO_FEATURES);
             let input = [1, 2, 3, 4];
             let result = precompiles.get(&addr).unwrap()(&input, None, None, &cx, false).unwrap();
             println!("{}", hex::encode(&result.0.output));
@@ -464,7 +672,7 @@ mod test {
     fn call_to_identity_disabled() {
         let addr = H160::from_str("0000000000000000000000000000000000000004").unwrap();
         AccountStructure::testing(0, |accounts: AccountStructure| {
-            let precompiles = entrypoint(accounts, PrecompileSet::No, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::No, false, NO_FEATURES);
             assert!(precompiles.get(&addr).is_none());
         })
     }
@@ -479,7 +687,7 @@ mod test {
             apparent_value: lamports_to_gwei(1),
         };
         AccountStructure::testing(0, |accounts: AccountStructure| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false, NO_FEATURES);
             let input = [0u8; 0];
             let result = precompiles.get(&addr).unwrap()(&input, None, None, &cx, false).unwrap();
             println!("{}", hex::encode(&result.0.output));
@@ -508,7 +716,7 @@ mod test {
             apparent_value: lamports_to_gwei(1),
         };
         AccountStructure::testing(0, |accounts: AccountStructure| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false, NO_FEATURES);
             let input = hex!("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad000000000000000000000000000000000000000000000000000000000000001a650acf9d3f5f0a2c799776a1254355d5f4061762a237396a99a0e0e3fc2bcd6729514a0dacb2e623ac4abd157cb18163ff942280db4d5caad66ddf941ba12e03");
 
             let result = precompiles.get(&addr).unwrap()(&input, None, None, &cx, false).unwrap();
@@ -527,7 +735,7 @@ mod test {
             );
         });
         AccountStructure::testing(0, |accounts: AccountStructure| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasClassic, false, NO_FEATURES);
             let input = hex!("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad000000000000000000000000000000000000000000000000000000000000001b650acf9d3f5f0a2c799776a1254355d5f4061762a237396a99a0e0e3fc2bcd6729514a0dacb2e623ac4abd157cb18163ff942280db4d5caad66ddf941ba12e03");
 
             let result = precompiles.get(&addr).unwrap()(&input, None, None, &cx, false).unwrap();
@@ -582,7 +790,7 @@ mod test {
 
         // fermat's little theorem example.
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000000000000000000000000000000000000000000000000000000000001
@@ -614,7 +822,7 @@ mod test {
 
         // second example from EIP: zero base.
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000000000000000000000000000000000000000000000000000000000000
@@ -644,7 +852,7 @@ mod test {
 
         // another example from EIP: zero-padding
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000000000000000000000000000000000000000000000000000000000001
@@ -675,7 +883,7 @@ mod test {
 
         // zero-length modulus.
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000000000000000000000000000000000000000000000000000000000001
@@ -705,7 +913,7 @@ mod test {
         };
 
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000000000000000000000000000000000000000000000000000000000001
@@ -720,7 +928,7 @@ mod test {
         });
 
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 00000000000000000000000000000000000000000000000000000000000000ff
@@ -747,7 +955,7 @@ mod test {
 
         // zero-points additions
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000000000000000000000000000000000000000000000000000000000000
@@ -780,7 +988,7 @@ mod test {
 
         // no input, should not fail
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = [0u8; 0];
 
             let result = precompiles.get(&addr).unwrap()(&input, None, None, &cx, false).unwrap();
@@ -802,7 +1010,7 @@ mod test {
 
         // should fail - point not on curve
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 1111111111111111111111111111111111111111111111111111111111111111
@@ -829,7 +1037,7 @@ mod test {
 
         // zero-point multiplication
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000000000000000000000000000000000000000000000000000000000000
@@ -861,7 +1069,7 @@ mod test {
 
         // should fail - point not on curve
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 1111111111111111111111111111111111111111111111111111111111111111
@@ -887,7 +1095,7 @@ mod test {
 
         // should not fail, because empty input is a valid input of 0 elements
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = [0u8; 0];
 
             let result = precompiles.get(&addr).unwrap()(&input, None, None, &cx, false).unwrap();
@@ -910,7 +1118,7 @@ mod test {
 
         // should fail - point not on curve
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 1111111111111111111111111111111111111111111111111111111111111111
@@ -928,7 +1136,7 @@ mod test {
 
         // should fail - input length is invalid
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 1111111111111111111111111111111111111111111111111111111111111111
@@ -954,7 +1162,7 @@ mod test {
 
         // Test vector 4 and expected output from https://github.com/ethereum/EIPs/blob/master/EIPS/eip-152.md#test-vector-4
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000048c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f
@@ -990,7 +1198,7 @@ mod test {
 
         // Test vector 5 and expected output from https://github.com/ethereum/EIPs/blob/master/EIPS/eip-152.md#test-vector-5
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000c48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f
@@ -1026,7 +1234,7 @@ mod test {
 
         // Test vector 6 and expected output from https://github.com/ethereum/EIPs/blob/master/EIPS/eip-152.md#test-vector-6
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000c48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f
@@ -1062,7 +1270,7 @@ mod test {
 
         // Test vector 7 and expected output from https://github.com/ethereum/EIPs/blob/master/EIPS/eip-152.md#test-vector-7
         AccountStructure::testing(0, |accounts| {
-            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false);
+            let precompiles = entrypoint(accounts, PrecompileSet::VelasNext, false, NO_FEATURES);
             let input = hex!(
                 "
                 0000000148c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f
@@ -1096,4 +1304,501 @@ mod test {
             );
         });
     }
+
+    fn call_native_input(
+        program_id: Pubkey,
+        accounts: &[(Pubkey, bool, bool)],
+        data: &[u8],
+    ) -> Vec<u8> {
+        use ethabi::Token;
+        let accounts = accounts
+            .iter()
+            .map(|(pubkey, is_signer, is_writable)| {
+                Token::Tuple(vec![
+                    Token::FixedBytes(pubkey.to_bytes().to_vec()),
+                    Token::Bool(*is_signer),
+                    Token::Bool(*is_writable),
+                ])
+            })
+            .collect();
+        let mut input = CALL_NATIVE_CODE.abi.short_signature().to_vec();
+        input.extend(ethabi::encode(&[
+            Token::FixedBytes(program_id.to_bytes().to_vec()),
+            Token::Array(accounts),
+            Token::Bytes(data.to_vec()),
+        ]));
+        input
+    }
+
+    #[test]
+    fn call_native_memo() {
+        let memo_program = Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr").unwrap();
+        let caller = H160::repeat_byte(0x11);
+        let authority = native_call_authority(&caller);
+        let cx = Context {
+            address: *NATIVE_CALL_ADDR,
+            caller,
+            apparent_value: U256::zero(),
+        };
+        let input = call_native_input(memo_program, &[(authority, true, false)], b"memo");
+
+        AccountStructure::testing(0, |accounts| {
+            // precompile is not available until native calls are enabled
+            let precompiles = entrypoint(accounts, PrecompileSet::No, false, NO_FEATURES);
+            assert!(precompiles.get(&*NATIVE_CALL_ADDR).is_none());
+
+            let precompiles = entrypoint(accounts, PrecompileSet::No, false, NativeFeatures::all());
+            let (output, gas, logs) =
+                precompiles.get(&*NATIVE_CALL_ADDR).unwrap()(&input, None, None, &cx, false)
+                    .unwrap();
+            assert_eq!(output.exit_status, ExitSucceed::Returned);
+            assert_eq!(gas, 20_000 + 2_600 + 16 * 4);
+
+            let mut logs = log_entry_to_logs(*NATIVE_CALL_ADDR, logs);
+            let calls = native_calls(accounts, &logs);
+            assert_eq!(
+                calls,
+                vec![NativeCallPromise {
+                    authority,
+                    program_id: memo_program,
+                    accounts: vec![NativeCallAccount {
+                        pubkey: authority,
+                        is_signer: true,
+                        is_writable: false,
+                    }],
+                    data: b"memo".to_vec(),
+                }]
+            );
+            let instruction = calls[0].instruction();
+            assert_eq!(instruction.program_id, memo_program);
+            assert_eq!(instruction.data, b"memo");
+
+            // authority and program are not passed to native instruction
+            assert!(filter_native_logs(accounts, &mut logs).is_err());
+
+            // not enough gas
+            assert_eq!(
+                precompiles.get(&*NATIVE_CALL_ADDR).unwrap()(&input, Some(1000), None, &cx, false)
+                    .unwrap_err(),
+                PrecompileFailure::Error {
+                    exit_status: ExitError::OutOfGas
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn call_native_not_allowed() {
+        let token_program =
+            Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        let caller = H160::repeat_byte(0x11);
+        let authority = native_call_authority(&caller);
+        let cx = Context {
+            address: *NATIVE_CALL_ADDR,
+            caller,
+            apparent_value: U256::zero(),
+        };
+
+        AccountStructure::testing(0, |accounts| {
+            let precompiles = entrypoint(accounts, PrecompileSet::No, false, NativeFeatures::all());
+            let call = precompiles.get(&*NATIVE_CALL_ADDR).unwrap();
+
+            // SPL token transfer is allowed
+            let input = call_native_input(token_program, &[(authority, true, false)], &[3]);
+            assert!(call(&input, None, None, &cx, false).is_ok());
+
+            // other SPL token instructions are not
+            let input = call_native_input(token_program, &[(authority, true, false)], &[7]);
+            assert!(call(&input, None, None, &cx, false).is_err());
+
+            // only whitelisted programs can be called
+            let input = call_native_input(crate::ID, &[(authority, true, false)], &[3]);
+            assert!(call(&input, None, None, &cx, false).is_err());
+
+            // only authority of evm caller can sign
+            let other = native_call_authority(&H160::repeat_byte(0x22));
+            let input = call_native_input(token_program, &[(other, true, false)], &[3]);
+            assert!(call(&input, None, None, &cx, false).is_err());
+
+            // delegate call cannot be used to sign with authority of another contract
+            let input = call_native_input(token_program, &[(authority, true, false)], &[3]);
+            assert!(call(&input, None, Some(CallScheme::DelegateCall), &cx, false).is_err());
+        })
+    }
+
+    #[test]
+    fn get_stake_delegation() {
+        use solana_sdk::stake::{
+            program as stake_program,
+            state::{Delegation, Meta, Stake, StakeState},
+        };
+
+        let cx = Context {
+            address: *NATIVE_CALL_ADDR,
+            caller: H160::repeat_byte(0x11),
+            apparent_value: U256::zero(),
+        };
+        let voter_pubkey = Pubkey::new_unique();
+
+        AccountStructure::testing(0, |accounts| {
+            let user = accounts.first().unwrap();
+            let input = format!(
+                "{}{}",
+                hex::encode(STAKE_DELEGATION_CODE.abi.short_signature()),
+                hex::encode(user.unsigned_key().to_bytes())
+            );
+            let input = hex::decode(input).unwrap();
+            let precompiles = entrypoint(accounts, PrecompileSet::No, false, NativeFeatures::all());
+            let call = precompiles.get(&*NATIVE_CALL_ADDR).unwrap();
+
+            // user account is not owned by stake program
+            assert!(call(&input, None, None, &cx, false).is_err());
+
+            {
+                let mut account = user.try_account_ref_mut().unwrap();
+                account.set_owner(stake_program::id());
+                let state = StakeState::Stake(
+                    Meta::default(),
+                    Stake {
+                        delegation: Delegation {
+                            voter_pubkey,
+                            stake: 42,
+                            activation_epoch: 1,
+                            ..Delegation::default()
+                        },
+                        credits_observed: 0,
+                    },
+                );
+                account.set_data(bincode::serialize(&state).unwrap());
+            }
+
+            let (output, gas, logs) = call(&input, None, None, &cx, false).unwrap();
+            assert_eq!(gas, 2_600);
+            assert!(logs.is_empty());
+            assert_eq!(
+                output.output,
+                ethabi::encode(&[
+                    ethabi::Token::Uint(42u64.into()),
+                    ethabi::Token::FixedBytes(voter_pubkey.to_bytes().to_vec()),
+                    ethabi::Token::Uint(1u64.into()),
+                    ethabi::Token::Uint(u64::MAX.into()),
+                ])
+            );
+        })
+    }
+
+    #[test]
+    fn get_native_account() {
+        use ethabi::Token;
+
+        let cx = Context {
+            address: *NATIVE_ACCOUNT_ADDR,
+            caller: H160::repeat_byte(0x11),
+            apparent_value: U256::zero(),
+        };
+
+        AccountStructure::testing(0, |accounts| {
+            let user = accounts.first().unwrap();
+            user.try_account_ref_mut()
+                .unwrap()
+                .set_data((0..100).collect());
+            let features = NativeFeatures {
+                native_account: true,
+                ..NativeFeatures::default()
+            };
+            let precompiles = entrypoint(accounts, PrecompileSet::No, false, features);
+            let call = precompiles.get(&*NATIVE_ACCOUNT_ADDR).unwrap();
+
+            let mut input = ACCOUNT_INFO_CODE.abi.short_signature().to_vec();
+            input.extend(ethabi::encode(&[Token::FixedBytes(
+                user.unsigned_key().to_bytes().to_vec(),
+            )]));
+            let (output, gas, logs) = call(&input, None, None, &cx, false).unwrap();
+            assert_eq!(gas, 2_600);
+            assert!(logs.is_empty());
+            assert_eq!(
+                output.output,
+                ethabi::encode(&[
+                    Token::Uint(1000u64.into()),
+                    Token::FixedBytes(crate::ID.to_bytes().to_vec()),
+                    Token::Bool(false),
+                    Token::Uint(100u64.into()),
+                ])
+            );
+
+            let data_input = |offset: u64, length: u64| {
+                let mut input = ACCOUNT_DATA_CODE.abi.short_signature().to_vec();
+                input.extend(ethabi::encode(&[
+                    Token::FixedBytes(user.unsigned_key().to_bytes().to_vec()),
+                    Token::Uint(offset.into()),
+                    Token::Uint(length.into()),
+                ]));
+                input
+            };
+            let (output, gas, _) = call(&data_input(10, 40), None, None, &cx, false).unwrap();
+            assert_eq!(gas, 2_600 + 3 * 2);
+            assert_eq!(
+                output.output,
+                ethabi::encode(&[Token::Bytes((10..50).collect())])
+            );
+
+            // out of account bounds
+            assert!(call(&data_input(90, 11), None, None, &cx, false).is_err());
+            assert!(call(&data_input(u64::MAX, 1), None, None, &cx, false).is_err());
+            // limited by maximum read
+            assert!(call(
+                &data_input(0, MAX_ACCOUNT_DATA_READ + 1),
+                None,
+                None,
+                &cx,
+                false
+            )
+            .is_err());
+            // account is not passed to instruction
+            let mut input = ACCOUNT_INFO_CODE.abi.short_signature().to_vec();
+            input.extend(ethabi::encode(&[Token::FixedBytes(
+                Pubkey::new_unique().to_bytes().to_vec(),
+            )]));
+            assert!(call(&input, None, None, &cx, false).is_err());
+        })
+    }
+
+    #[test]
+    fn call_spl_token_facade() {
+        use ethabi::{ParamType, Token};
+        use solana_sdk::account::{Account, AccountSharedData};
+        use std::cell::RefCell;
+
+        let token_program =
+            Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        let mint = Pubkey::new_unique();
+        let facade = spl_token_facade_address(&mint);
+        let owner = H160::repeat_byte(0x11);
+        let receiver = H160::repeat_byte(0x22);
+        let spender = H160::repeat_byte(0x33);
+        let address =
+            |address: H160| Token::Address(ethabi::Address::from_slice(address.as_bytes()));
+        let input = |name: &str, params: &[ParamType], tokens: &[Token]| {
+            let mut input = ethabi::short_signature(name, params).to_vec();
+            input.extend(ethabi::encode(tokens));
+            input
+        };
+        let cx = Context {
+            address: facade,
+            caller: owner,
+            apparent_value: U256::zero(),
+        };
+        let spender_cx = Context {
+            caller: spender,
+            ..cx
+        };
+
+        let mut mint_data = vec![0; 82];
+        mint_data[36..44].copy_from_slice(&1_000u64.to_le_bytes());
+        mint_data[44] = 6;
+        mint_data[45] = 1;
+        let mut token_data = vec![0; 165];
+        token_data[0..32].copy_from_slice(&mint.to_bytes());
+        token_data[32..64].copy_from_slice(&native_call_authority(&owner).to_bytes());
+        token_data[64..72].copy_from_slice(&300u64.to_le_bytes());
+        let token_account = |data: Vec<u8>| {
+            RefCell::new(AccountSharedData::from(Account {
+                lamports: 1000,
+                data,
+                owner: token_program,
+                executable: false,
+                rent_epoch: 0,
+            }))
+        };
+
+        let evm_key = Pubkey::new_unique();
+        let evm_account = RefCell::new(crate::create_state_account(0));
+        let evm_state = KeyedAccount::new(&evm_key, false, &evm_account);
+        let mut receiver_data = vec![0; 165];
+        receiver_data[0..32].copy_from_slice(&mint.to_bytes());
+        let keys = [
+            mint,
+            spl_token_facade_account(&mint, &owner),
+            spl_token_facade_account(&mint, &receiver),
+        ];
+        let users_accounts = [
+            token_account(mint_data),
+            token_account(token_data),
+            token_account(receiver_data),
+        ];
+        let users: Vec<_> = keys
+            .iter()
+            .zip(&users_accounts)
+            .map(|(key, account)| KeyedAccount::new(key, false, account))
+            .collect();
+        let accounts = AccountStructure::new(&evm_state, &users);
+
+        let precompiles = entrypoint(accounts, PrecompileSet::No, false, NO_FEATURES);
+        assert!(precompiles.get(&facade).is_none());
+
+        let precompiles = entrypoint(accounts, PrecompileSet::No, false, NativeFeatures::all());
+        let call = precompiles.get(&facade).unwrap();
+
+        let view = |input: Vec<u8>| call(&input, None, None, &cx, false).map(|(o, _, _)| o.output);
+        assert_eq!(
+            view(input("totalSupply", &[], &[])).unwrap(),
+            ethabi::encode(&[Token::Uint(1000u64.into())])
+        );
+        assert_eq!(
+            view(input("decimals", &[], &[])).unwrap(),
+            ethabi::encode(&[Token::Uint(6u64.into())])
+        );
+        assert_eq!(
+            view(input("balanceOf", &[ParamType::Address], &[address(owner)])).unwrap(),
+            ethabi::encode(&[Token::Uint(300u64.into())])
+        );
+        // token account of spender is not passed to instruction
+        assert_eq!(
+            view(input(
+                "balanceOf",
+                &[ParamType::Address],
+                &[address(spender)]
+            ))
+            .unwrap(),
+            ethabi::encode(&[Token::Uint(0u64.into())])
+        );
+
+        let transfer = input(
+            "transfer",
+            &[ParamType::Address, ParamType::Uint(256)],
+            &[address(receiver), Token::Uint(100u64.into())],
+        );
+        let (output, gas, logs) = call(&transfer, None, None, &cx, false).unwrap();
+        assert_eq!(output.output, ethabi::encode(&[Token::Bool(true)]));
+        assert_eq!(gas, 30_000);
+
+        let mut logs = log_entry_to_logs(facade, logs);
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[1].topics.len(), 3);
+        let calls = native_calls(accounts, &logs);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].authority, native_call_authority(&owner));
+        assert_eq!(calls[0].program_id, token_program);
+        assert_eq!(calls[0].data, [&[3][..], &100u64.to_le_bytes()].concat());
+        assert_eq!(
+            calls[0].accounts[1].pubkey,
+            spl_token_facade_account(&mint, &receiver)
+        );
+
+        // accounts of native call are not passed to instruction
+        assert!(filter_native_logs(accounts, &mut logs).is_err());
+
+        // balances include transfer, that is not yet executed by native call
+        assert_eq!(
+            view(input("balanceOf", &[ParamType::Address], &[address(owner)])).unwrap(),
+            ethabi::encode(&[Token::Uint(200u64.into())])
+        );
+        assert_eq!(
+            view(input(
+                "balanceOf",
+                &[ParamType::Address],
+                &[address(receiver)]
+            ))
+            .unwrap(),
+            ethabi::encode(&[Token::Uint(100u64.into())])
+        );
+        let transfer = input(
+            "transfer",
+            &[ParamType::Address, ParamType::Uint(256)],
+            &[address(receiver), Token::Uint(201u64.into())],
+        );
+        assert!(call(&transfer, None, None, &cx, false).is_err());
+
+        // token account of spender is required by transfer
+        let transfer_to_spender = input(
+            "transfer",
+            &[ParamType::Address, ParamType::Uint(256)],
+            &[address(spender), Token::Uint(1u64.into())],
+        );
+        assert!(call(&transfer_to_spender, None, None, &cx, false).is_err());
+
+        let allowance = input(
+            "allowance",
+            &[ParamType::Address, ParamType::Address],
+            &[address(owner), address(spender)],
+        );
+        let transfer_from = |amount: u64| {
+            input(
+                "transferFrom",
+                &[ParamType::Address, ParamType::Address, ParamType::Uint(256)],
+                &[
+                    address(owner),
+                    address(receiver),
+                    Token::Uint(amount.into()),
+                ],
+            )
+        };
+        assert!(call(&transfer_from(10), None, None, &spender_cx, false).is_err());
+
+        let approve = input(
+            "approve",
+            &[ParamType::Address, ParamType::Uint(256)],
+            &[address(spender), Token::Uint(50u64.into())],
+        );
+        call(&approve, None, None, &cx, false).unwrap();
+        assert_eq!(
+            view(allowance.clone()).unwrap(),
+            ethabi::encode(&[Token::Uint(50u64.into())])
+        );
+        assert!(call(&transfer_from(60), None, None, &spender_cx, false).is_err());
+        call(&transfer_from(30), None, None, &spender_cx, false).unwrap();
+        assert_eq!(
+            view(allowance).unwrap(),
+            ethabi::encode(&[Token::Uint(20u64.into())])
+        );
+        assert_eq!(
+            view(input("balanceOf", &[ParamType::Address], &[address(owner)])).unwrap(),
+            ethabi::encode(&[Token::Uint(170u64.into())])
+        );
+
+        // amount is limited to u64
+        let transfer = input(
+            "transfer",
+            &[ParamType::Address, ParamType::Uint(256)],
+            &[address(receiver), Token::Uint(ethabi::Uint::MAX)],
+        );
+        assert!(call(&transfer, None, None, &cx, false).is_err());
+
+        // pending changes of finished transaction are derived from its surviving logs
+        let pending = Rc::new(PendingTokens::default());
+        let precompiles = entrypoint_with_pending(
+            accounts,
+            PrecompileSet::No,
+            false,
+            NativeFeatures::all(),
+            pending.clone(),
+        );
+        let call = precompiles.get(&facade).unwrap();
+        let balance_of = |owner: H160| {
+            let balance_of = input("balanceOf", &[ParamType::Address], &[address(owner)]);
+            call(&balance_of, None, None, &cx, false).unwrap().0.output
+        };
+        let transfer = input(
+            "transfer",
+            &[ParamType::Address, ParamType::Uint(256)],
+            &[address(receiver), Token::Uint(100u64.into())],
+        );
+        call(&transfer, None, None, &cx, false).unwrap();
+        // logs of reverted call are removed by evm
+        pending.finish_transaction(accounts, &[]);
+        assert_eq!(
+            balance_of(owner),
+            ethabi::encode(&[Token::Uint(300u64.into())])
+        );
+        let (_, _, logs) = call(&transfer, None, None, &cx, false).unwrap();
+        pending.finish_transaction(accounts, &log_entry_to_logs(facade, logs));
+        assert_eq!(
+            balance_of(owner),
+            ethabi::encode(&[Token::Uint(200u64.into())])
+        );
+        assert_eq!(
+            balance_of(receiver),
+            ethabi::encode(&[Token::Uint(100u64.into())])
+        );
+    }
 }
@@ -0,0 +1,262 @@
+use snafu::{ensure, ResultExt};
+use std::str::FromStr;
+
+use ethabi::{Function, Param, ParamType, StateMutability, Token, Uint};
+use evm_state::{executor::PrecompileOutput, ExitSucceed};
+use once_cell::sync::Lazy;
+use primitive_types::H160;
+
+use super::abi_parse::ParseTokens;
+use super::builtins::{NativeContract, PromiseFunc};
+use super::errors::*;
+use super::native_call::{check_gas, check_non_payable, parse_pubkey};
+use super::{NativeContext, Result};
+use crate::AccountStructure;
+use solana_sdk::account::ReadableAccount;
+use solana_sdk::keyed_account::KeyedAccount;
+use solana_sdk::pubkey::Pubkey;
+
+// Price of account lookup is equal to cold account access in evm (EIP-2929),
+// and data is priced like memory copy.
+const ACCOUNT_QUERY_GAS: u64 = 2_600;
+const ACCOUNT_DATA_WORD_GAS: u64 = 3;
+/// Maximum size of account data, that can be read by single call.
+pub const MAX_ACCOUNT_DATA_READ: u64 = 10 * 1024;
+
+// 0x56454c41532d434841494e000000000041434354 for better search
+pub static NATIVE_ACCOUNT_ADDR: Lazy<H160> = Lazy::new(|| {
+    H160::from_str(concat!(
+        "56454c41532d434841494e", // 'VELAS-CHAIN'
+        "0000000000",             // just spaces
+        "41434354",               // 'ACCT'
+    ))
+    .expect("Serialization of static data should be determenistic and never fail.")
+});
+
+fn parse_u64(token: &Token) -> Result<u64> {
+    match token {
+        Token::Uint(value) if *value <= Uint::from(u64::MAX) => Ok(value.as_u64()),
+        t => UnexpectedInput {
+            expected: String::from("uint64"),
+            got: t.to_string(),
+        }
+        .fail(),
+    }
+}
+
+/// Only accounts of executing instruction are visible for evm.
+fn find_account<'a>(cx: &'a NativeContext, pubkey: &Pubkey) -> Result<&'a KeyedAccount<'a>> {
+    match cx.accounts.find_user(pubkey) {
+        Some(account) => Ok(account),
+        None => {
+            log::trace!("Account not found pk = {}", pubkey);
+            AccountNotFound {
+                public_key: *pubkey,
+            }
+            .fail()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AccountDataInput {
+    pub pubkey: Pubkey,
+    pub offset: u64,
+    pub length: u64,
+}
+
+impl ParseTokens for AccountDataInput {
+    fn parse(inputs: Vec<Token>) -> Result<Self> {
+        ensure!(
+            inputs.len() == 3,
+            ParamsCountMismatch {
+                expected: 3_usize,
+                got: inputs.len()
+            }
+        );
+        Ok(AccountDataInput {
+            pubkey: parse_pubkey(&inputs[0])?,
+            offset: parse_u64(&inputs[1])?,
+            length: parse_u64(&inputs[2])?,
+        })
+    }
+}
+
+type AccountInfoImp = PromiseFunc<
+    fn(Pubkey, NativeContext) -> Result<(PrecompileOutput, u64, Vec<()>)>,
+    fn(AccountStructure, ()) -> Result<()>,
+    Pubkey,
+    (),
+>;
+
+type AccountDataImp = PromiseFunc<
+    fn(AccountDataInput, NativeContext) -> Result<(PrecompileOutput, u64, Vec<()>)>,
+    fn(AccountStructure, ()) -> Result<()>,
+    AccountDataInput,
+    (),
+>;
+
+// View functions don't produce any promise.
+fn no_promise(_accounts: AccountStructure, _promise: ()) -> Result<()> {
+    Ok(())
+}
+
+pub static ACCOUNT_INFO_CODE: Lazy<NativeContract<AccountInfoImp, Pubkey>> = Lazy::new(|| {
+    #[allow(deprecated)]
+    let abi = Function {
+        name: String::from("getAccountInfo"),
+        inputs: vec![Param {
+            name: String::from("account"),
+            kind: ParamType::FixedBytes(32),
+            internal_type: Some(String::from("NativeAddress")),
+        }],
+        outputs: vec![
+            Param {
+                name: String::from("lamports"),
+                kind: ParamType::Uint(64),
+                internal_type: None,
+            },
+            Param {
+                name: String::from("owner"),
+                kind: ParamType::FixedBytes(32),
+                internal_type: Some(String::from("NativeAddress")),
+            },
+            Param {
+                name: String::from("executable"),
+                kind: ParamType::Bool,
+                internal_type: None,
+            },
+            Param {
+                name: String::from("data_len"),
+                kind: ParamType::Uint(64),
+                internal_type: None,
+            },
+        ],
+        constant: Some(true),
+        state_mutability: StateMutability::View,
+    };
+
+    fn implementation(
+        pubkey: Pubkey,
+        cx: NativeContext,
+    ) -> Result<(PrecompileOutput, u64, Vec<()>)> {
+        log::trace!("Precompile ACCOUNT_INFO {}", pubkey);
+        check_non_payable(&cx)?;
+        let gas = check_gas(&cx, ACCOUNT_QUERY_GAS)?;
+
+        let account = find_account(&cx, &pubkey)?
+            .try_account_ref()
+            .with_context(|_| NativeChainInstructionError {})?;
+        let output = ethabi::encode(&[
+            Token::Uint(Uint::from(account.lamports())),
+            Token::FixedBytes(account.owner().to_bytes().to_vec()),
+            Token::Bool(account.executable()),
+            Token::Uint(Uint::from(account.data().len() as u64)),
+        ]);
+
+        Ok((
+            PrecompileOutput {
+                exit_status: ExitSucceed::Returned,
+                output,
+            },
+            gas,
+            vec![],
+        ))
+    }
+
+    let function_hash = abi.short_signature();
+    NativeContract::new(
+        function_hash,
+        abi,
+        PromiseFunc::new(implementation, no_promise),
+    )
+});
+
+pub static ACCOUNT_DATA_CODE: Lazy<NativeContract<AccountDataImp, AccountDataInput>> =
+    Lazy::new(|| {
+        #[allow(deprecated)]
+        let abi = Function {
+            name: String::from("getAccountData"),
+            inputs: vec![
+                Param {
+                    name: String::from("account"),
+                    kind: ParamType::FixedBytes(32),
+                    internal_type: Some(String::from("NativeAddress")),
+                },
+                Param {
+                    name: String::from("offset"),
+                    kind: ParamType::Uint(64),
+                    internal_type: None,
+                },
+                Param {
+                    name: String::from("length"),
+                    kind: ParamType::Uint(64),
+                    internal_type: None,
+                },
+            ],
+            outputs: vec![Param {
+                name: String::from("data"),
+                kind: ParamType::Bytes,
+                internal_type: None,
+            }],
+            constant: Some(true),
+            state_mutability: StateMutability::View,
+        };
+
+        fn implementation(
+            input: AccountDataInput,
+            cx: NativeContext,
+        ) -> Result<(PrecompileOutput, u64, Vec<()>)> {
+            log::trace!("Precompile ACCOUNT_DATA {:?}", input);
+            check_non_payable(&cx)?;
+            let AccountDataInput {
+                pubkey,
+                offset,
+                length,
+            } = input;
+            ensure!(
+                length <= MAX_ACCOUNT_DATA_READ,
+                DataLengthExceeded {
+                    length,
+                    max: MAX_ACCOUNT_DATA_READ
+                }
+            );
+            // Length is bounded, so gas is charged before reading account.
+            let words = (length + 31) / 32;
+            let gas = check_gas(&cx, ACCOUNT_QUERY_GAS + ACCOUNT_DATA_WORD_GAS * words)?;
+
+            let account = find_account(&cx, &pubkey)?
+                .try_account_ref()
+                .with_context(|_| NativeChainInstructionError {})?;
+            let data_len = account.data().len();
+            let data = offset
+                .checked_add(length)
+                .filter(|end| *end <= data_len as u64)
+                .map(|end| &account.data()[offset as usize..end as usize])
+                .ok_or_else(|| {
+                    DataOutOfBounds {
+                        offset,
+                        length,
+                        data_len,
+                    }
+                    .build()
+                })?;
+            let output = ethabi::encode(&[Token::Bytes(data.to_vec())]);
+
+            Ok((
+                PrecompileOutput {
+                    exit_status: ExitSucceed::Returned,
+                    output,
+                },
+                gas,
+                vec![],
+            ))
+        }
+
+        let function_hash = abi.short_signature();
+        NativeContract::new(
+            function_hash,
+            abi,
+            PromiseFunc::new(implementation, no_promise),
+        )
+    });
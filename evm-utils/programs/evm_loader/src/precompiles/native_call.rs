@@ -0,0 +1,443 @@
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use std::str::FromStr;
+
+use ethabi::{Function, Param, ParamType, StateMutability, Token, Uint};
+use evm_state::{executor::PrecompileOutput, CallScheme, ExitSucceed};
+use once_cell::sync::Lazy;
+use primitive_types::H160;
+
+use super::abi_parse::ParseTokens;
+use super::builtins::{NativeContract, PromiseFunc};
+use super::errors::*;
+use super::{NativeContext, Result};
+use crate::AccountStructure;
+use solana_sdk::account::ReadableAccount;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::stake::{self, state::StakeState};
+
+/// Seed of program derived address, that signs native calls on behalf of evm address.
+pub const NATIVE_CALL_AUTHORITY_SEED: &[u8] = b"evm_native_call";
+
+// Gas is charged before call, promise execution is paid by native transaction fee.
+const NATIVE_CALL_BASE_GAS: u64 = 20_000;
+const NATIVE_CALL_ACCOUNT_GAS: u64 = 2_600;
+const NATIVE_CALL_DATA_BYTE_GAS: u64 = 16;
+const STAKE_QUERY_GAS: u64 = 2_600;
+
+pub(super) mod spl_token {
+    solana_sdk::declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+}
+mod spl_token_2022 {
+    solana_sdk::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+mod spl_memo_v1 {
+    solana_sdk::declare_id!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+}
+mod spl_memo {
+    solana_sdk::declare_id!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+}
+
+// Tags of `TokenInstruction::Transfer` and `TokenInstruction::TransferChecked`.
+const SPL_TOKEN_TRANSFER: u8 = 3;
+const SPL_TOKEN_TRANSFER_CHECKED: u8 = 12;
+
+// 0x56454c41532d434841494e00000000004e415456 for better search
+pub static NATIVE_CALL_ADDR: Lazy<H160> = Lazy::new(|| {
+    H160::from_str(concat!(
+        "56454c41532d434841494e", // 'VELAS-CHAIN'
+        "0000000000",             // just spaces
+        "4e415456",               // 'NATV'
+    ))
+    .expect("Serialization of static data should be determenistic and never fail.")
+});
+
+/// Returns address, that signs native calls of contract `caller`.
+pub fn native_call_authority(caller: &H160) -> Pubkey {
+    Pubkey::find_program_address(&[NATIVE_CALL_AUTHORITY_SEED, caller.as_bytes()], &crate::ID).0
+}
+
+/// Check that instruction belongs to whitelisted native program, and can be called from evm.
+fn check_native_instruction(program_id: &Pubkey, data: &[u8]) -> Result<()> {
+    if *program_id == spl_token::id() || *program_id == spl_token_2022::id() {
+        ensure!(
+            matches!(
+                data.first(),
+                Some(&SPL_TOKEN_TRANSFER) | Some(&SPL_TOKEN_TRANSFER_CHECKED)
+            ),
+            NativeInstructionNotAllowed {
+                program_id: *program_id
+            }
+        );
+        return Ok(());
+    }
+    ensure!(
+        *program_id == spl_memo::id() || *program_id == spl_memo_v1::id(),
+        NativeProgramNotAllowed {
+            program_id: *program_id
+        }
+    );
+    Ok(())
+}
+
+pub(super) fn check_gas(cx: &NativeContext, required: u64) -> Result<u64> {
+    if let Some(gas_limit) = cx.precompile_context.gas_limit {
+        ensure!(required <= gas_limit, OutOfGas { required });
+    }
+    Ok(required)
+}
+
+pub(super) fn check_non_payable(cx: &NativeContext) -> Result<()> {
+    let value = cx.precompile_context.evm_context.apparent_value;
+    ensure!(value.is_zero(), NonPayable { value });
+    Ok(())
+}
+
+pub(super) fn parse_pubkey(token: &Token) -> Result<Pubkey> {
+    match token {
+        Token::FixedBytes(bytes) if bytes.len() == 32 => Ok(Pubkey::new(bytes)),
+        t => UnexpectedInput {
+            expected: String::from("bytes32"),
+            got: t.to_string(),
+        }
+        .fail(),
+    }
+}
+
+fn parse_bool(token: &Token) -> Result<bool> {
+    match token {
+        Token::Bool(value) => Ok(*value),
+        t => UnexpectedInput {
+            expected: String::from("bool"),
+            got: t.to_string(),
+        }
+        .fail(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NativeCallAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl ParseTokens for NativeCallAccount {
+    fn parse(inputs: Vec<Token>) -> Result<Self> {
+        ensure!(
+            inputs.len() == 3,
+            ParamsCountMismatch {
+                expected: 3_usize,
+                got: inputs.len()
+            }
+        );
+        Ok(NativeCallAccount {
+            pubkey: parse_pubkey(&inputs[0])?,
+            is_signer: parse_bool(&inputs[1])?,
+            is_writable: parse_bool(&inputs[2])?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct NativeCallInput {
+    pub program_id: Pubkey,
+    pub accounts: Vec<NativeCallAccount>,
+    pub data: Vec<u8>,
+}
+
+impl ParseTokens for NativeCallInput {
+    fn parse(inputs: Vec<Token>) -> Result<Self> {
+        ensure!(
+            inputs.len() == 3,
+            ParamsCountMismatch {
+                expected: 3_usize,
+                got: inputs.len()
+            }
+        );
+        let mut inputs = inputs.into_iter();
+        let program_id = parse_pubkey(&inputs.next().unwrap())?;
+        let accounts = match inputs.next().unwrap() {
+            Token::Array(accounts) => accounts
+                .into_iter()
+                .map(|account| match account {
+                    Token::Tuple(fields) => NativeCallAccount::parse(fields),
+                    t => UnexpectedInput {
+                        expected: String::from("(bytes32,bool,bool)"),
+                        got: t.to_string(),
+                    }
+                    .fail(),
+                })
+                .collect::<Result<_>>()?,
+            t => {
+                return UnexpectedInput {
+                    expected: String::from("(bytes32,bool,bool)[]"),
+                    got: t.to_string(),
+                }
+                .fail()
+            }
+        };
+        let data = match inputs.next().unwrap() {
+            Token::Bytes(data) => data,
+            t => {
+                return UnexpectedInput {
+                    expected: String::from("bytes"),
+                    got: t.to_string(),
+                }
+                .fail()
+            }
+        };
+        Ok(NativeCallInput {
+            program_id,
+            accounts,
+            data,
+        })
+    }
+}
+
+/// Call of native program, that will be invoked by evm loader after evm transaction succeed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NativeCallPromise {
+    /// Program derived address of evm caller, the only account that is signed by evm loader.
+    pub authority: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<NativeCallAccount>,
+    pub data: Vec<u8>,
+}
+
+impl NativeCallPromise {
+    pub fn instruction(&self) -> Instruction {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect();
+        Instruction::new_with_bytes(self.program_id, &self.data, accounts)
+    }
+}
+
+type NativeCallImp = PromiseFunc<
+    fn(NativeCallInput, NativeContext) -> Result<(PrecompileOutput, u64, Vec<NativeCallPromise>)>,
+    fn(AccountStructure, NativeCallPromise) -> Result<()>,
+    NativeCallInput,
+    NativeCallPromise,
+>;
+
+pub static CALL_NATIVE_CODE: Lazy<NativeContract<NativeCallImp, NativeCallInput>> =
+    Lazy::new(|| {
+        #[allow(deprecated)]
+        let abi = Function {
+            name: String::from("callNative"),
+            inputs: vec![
+                Param {
+                    name: String::from("program_id"),
+                    kind: ParamType::FixedBytes(32),
+                    internal_type: Some(String::from("NativeAddress")),
+                },
+                Param {
+                    name: String::from("accounts"),
+                    kind: ParamType::Array(Box::new(ParamType::Tuple(vec![
+                        ParamType::FixedBytes(32),
+                        ParamType::Bool,
+                        ParamType::Bool,
+                    ]))),
+                    internal_type: Some(String::from("struct NativeAccountMeta[]")),
+                },
+                Param {
+                    name: String::from("data"),
+                    kind: ParamType::Bytes,
+                    internal_type: None,
+                },
+            ],
+            outputs: vec![],
+            constant: Some(false),
+            state_mutability: StateMutability::NonPayable,
+        };
+
+        fn implementation(
+            input: NativeCallInput,
+            cx: NativeContext,
+        ) -> Result<(PrecompileOutput, u64, Vec<NativeCallPromise>)> {
+            log::trace!("Precompile CALL_NATIVE {:?}", input);
+
+            // Caller of precompile is only known for direct calls.
+            if !matches!(
+                cx.precompile_context.call_scheme,
+                None | Some(CallScheme::Call)
+            ) || cx.precompile_context.evm_context.address != *NATIVE_CALL_ADDR
+            {
+                return InvalidCallScheme {
+                    scheme: cx.precompile_context.call_scheme,
+                }
+                .fail();
+            }
+            check_non_payable(&cx)?;
+            check_native_instruction(&input.program_id, &input.data)?;
+
+            let authority = native_call_authority(&cx.precompile_context.evm_context.caller);
+            if let Some(account) = input
+                .accounts
+                .iter()
+                .find(|account| account.is_signer && account.pubkey != authority)
+            {
+                return NativeSignerNotAllowed {
+                    public_key: account.pubkey,
+                }
+                .fail();
+            }
+
+            let gas = check_gas(
+                &cx,
+                NATIVE_CALL_BASE_GAS
+                    + NATIVE_CALL_ACCOUNT_GAS * input.accounts.len() as u64
+                    + NATIVE_CALL_DATA_BYTE_GAS * input.data.len() as u64,
+            )?;
+
+            Ok((
+                PrecompileOutput {
+                    exit_status: ExitSucceed::Returned,
+                    output: vec![],
+                },
+                gas,
+                vec![NativeCallPromise {
+                    authority,
+                    program_id: input.program_id,
+                    accounts: input.accounts,
+                    data: input.data,
+                }],
+            ))
+        }
+
+        // Native call itself is invoked by processor, there we only check that all accounts are provided.
+        fn handle_promise(accounts: AccountStructure, promise: NativeCallPromise) -> Result<()> {
+            log::trace!("Promise handle CALL_NATIVE {:?}", promise);
+            check_native_instruction(&promise.program_id, &promise.data)?;
+            let keys = std::iter::once(&promise.program_id)
+                .chain(promise.accounts.iter().map(|account| &account.pubkey));
+            for key in keys {
+                if accounts.evm.unsigned_key() != key && accounts.find_user(key).is_none() {
+                    log::trace!("Account not found pk = {}", key);
+                    return AccountNotFound { public_key: *key }.fail();
+                }
+            }
+            Ok(())
+        }
+
+        let function_hash = abi.short_signature();
+        NativeContract::new(
+            function_hash,
+            abi,
+            PromiseFunc::new(implementation, handle_promise),
+        )
+    });
+
+type StakeDelegationImp = PromiseFunc<
+    fn(Pubkey, NativeContext) -> Result<(PrecompileOutput, u64, Vec<()>)>,
+    fn(AccountStructure, ()) -> Result<()>,
+    Pubkey,
+    (),
+>;
+
+pub static STAKE_DELEGATION_CODE: Lazy<NativeContract<StakeDelegationImp, Pubkey>> =
+    Lazy::new(|| {
+        #[allow(deprecated)]
+        let abi = Function {
+            name: String::from("getStakeDelegation"),
+            inputs: vec![Param {
+                name: String::from("stake_account"),
+                kind: ParamType::FixedBytes(32),
+                internal_type: Some(String::from("NativeAddress")),
+            }],
+            outputs: vec![
+                Param {
+                    name: String::from("stake"),
+                    kind: ParamType::Uint(256),
+                    internal_type: None,
+                },
+                Param {
+                    name: String::from("voter"),
+                    kind: ParamType::FixedBytes(32),
+                    internal_type: Some(String::from("NativeAddress")),
+                },
+                Param {
+                    name: String::from("activation_epoch"),
+                    kind: ParamType::Uint(64),
+                    internal_type: None,
+                },
+                Param {
+                    name: String::from("deactivation_epoch"),
+                    kind: ParamType::Uint(64),
+                    internal_type: None,
+                },
+            ],
+            constant: Some(true),
+            state_mutability: StateMutability::View,
+        };
+
+        // Read only query, stake account should be passed to native instruction.
+        fn implementation(
+            pubkey: Pubkey,
+            cx: NativeContext,
+        ) -> Result<(PrecompileOutput, u64, Vec<()>)> {
+            log::trace!("Precompile STAKE_DELEGATION {}", pubkey);
+            check_non_payable(&cx)?;
+            let gas = check_gas(&cx, STAKE_QUERY_GAS)?;
+
+            let user = if let Some(account) = cx.accounts.find_user(&pubkey) {
+                account
+            } else {
+                log::trace!("Account not found pk = {}", pubkey);
+                return AccountNotFound { public_key: pubkey }.fail();
+            };
+            let account = user
+                .try_account_ref()
+                .with_context(|_| NativeChainInstructionError {})?;
+            ensure!(
+                account.owner() == &stake::program::id(),
+                InvalidStakeAccount { public_key: pubkey }
+            );
+            let state: StakeState = bincode::deserialize(account.data())
+                .map_err(|_| InvalidStakeAccount { public_key: pubkey }.build())?;
+
+            let (stake, voter, activation_epoch, deactivation_epoch) = match state {
+                StakeState::Stake(_, stake) => (
+                    stake.delegation.stake,
+                    stake.delegation.voter_pubkey,
+                    stake.delegation.activation_epoch,
+                    stake.delegation.deactivation_epoch,
+                ),
+                _ => (0, Pubkey::default(), 0, 0),
+            };
+            let output = ethabi::encode(&[
+                Token::Uint(Uint::from(stake)),
+                Token::FixedBytes(voter.to_bytes().to_vec()),
+                Token::Uint(Uint::from(activation_epoch)),
+                Token::Uint(Uint::from(deactivation_epoch)),
+            ]);
+
+            Ok((
+                PrecompileOutput {
+                    exit_status: ExitSucceed::Returned,
+                    output,
+                },
+                gas,
+                vec![],
+            ))
+        }
+
+        fn handle_promise(_accounts: AccountStructure, _promise: ()) -> Result<()> {
+            Ok(())
+        }
+
+        let function_hash = abi.short_signature();
+        NativeContract::new(
+            function_hash,
+            abi,
+            PromiseFunc::new(implementation, handle_promise),
+        )
+    });
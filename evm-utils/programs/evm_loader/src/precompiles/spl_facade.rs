@@ -0,0 +1,657 @@
+//! ERC-20 facade over SPL token mints.
+//!
+//! Every SPL mint, passed to evm instruction, is visible in evm as ERC-20 contract
+//! at deterministic address (see `spl_token_facade_address`).
+//! Balance of evm address is stored in associated token account of its native call authority,
+//! so tokens can be moved between native and evm without any bridge.
+//!
+//! SPL token account has only one delegate, so `approve` replaces allowance of previous spender.
+//!
+//! Facade address is a hash of mint, so facade exists only if its mint is passed to instruction.
+//! Otherwise its address is an empty account, and calls to it succeed without any output,
+//! like calls to any other account without code.
+//!
+//! Transfers are executed as native calls after evm, so balances and allowances,
+//! requested by earlier calls of the same instruction, are kept in `PendingTokens`.
+//! Changes of finished transactions are derived from their surviving promise logs,
+//! so changes of reverted calls are dropped together with their logs.
+
+use sha3::{Digest, Keccak256};
+use snafu::{ensure, ResultExt};
+use std::{cell::RefCell, collections::BTreeMap};
+
+use ethabi::{Function, Param, ParamType, StateMutability, Token, Uint};
+use evm_state::{
+    executor::{LogEntry, PrecompileOutput},
+    CallScheme, ExitSucceed, Log, H256,
+};
+use once_cell::sync::Lazy;
+use primitive_types::H160;
+
+use super::errors::*;
+use super::native_call::{
+    check_gas, check_non_payable, native_call_authority, spl_token, NativeCallAccount,
+    NativeCallPromise,
+};
+use super::{CallResult, NativeContext, Result};
+use crate::AccountStructure;
+use solana_sdk::account::ReadableAccount;
+use solana_sdk::pubkey::Pubkey;
+
+mod spl_associated_token_account {
+    solana_sdk::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+}
+
+const FACADE_ADDR_PREFIX: &[u8] = b"SPL";
+
+const ERC20_VIEW_GAS: u64 = 2_600;
+const ERC20_CALL_GAS: u64 = 30_000;
+
+// Layout of `spl_token::state::Mint`.
+const MINT_LEN: usize = 82;
+const MINT_SUPPLY_OFFSET: usize = 36;
+const MINT_DECIMALS_OFFSET: usize = 44;
+const MINT_IS_INITIALIZED_OFFSET: usize = 45;
+
+// Layout of `spl_token::state::Account`.
+const TOKEN_ACCOUNT_LEN: usize = 165;
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const TOKEN_ACCOUNT_DELEGATE_OFFSET: usize = 72;
+const TOKEN_ACCOUNT_DELEGATED_AMOUNT_OFFSET: usize = 121;
+
+// Tags of `TokenInstruction::Transfer` and `TokenInstruction::Approve`.
+const SPL_TOKEN_TRANSFER: u8 = 3;
+const SPL_TOKEN_APPROVE: u8 = 4;
+
+static TRANSFER_EVENT: Lazy<H256> =
+    Lazy::new(|| H256::from_slice(&Keccak256::digest(b"Transfer(address,address,uint256)")));
+static APPROVAL_EVENT: Lazy<H256> =
+    Lazy::new(|| H256::from_slice(&Keccak256::digest(b"Approval(address,address,uint256)")));
+
+/// Evm address of ERC-20 facade for SPL token `mint`.
+/// Mint is hashed, and prefixed with 'SPL' bytes.
+pub fn spl_token_facade_address(mint: &Pubkey) -> H160 {
+    let hash = Keccak256::digest(&mint.to_bytes());
+    let mut address = H160::from(H256::from_slice(hash.as_slice()));
+    address.as_bytes_mut()[0..FACADE_ADDR_PREFIX.len()].copy_from_slice(FACADE_ADDR_PREFIX);
+    address
+}
+
+/// Token account, that holds facade balance of evm `owner`.
+pub fn spl_token_facade_account(mint: &Pubkey, owner: &H160) -> Pubkey {
+    let authority = native_call_authority(owner);
+    Pubkey::find_program_address(
+        &[authority.as_ref(), spl_token::id().as_ref(), mint.as_ref()],
+        &spl_associated_token_account::id(),
+    )
+    .0
+}
+
+/// Returns facades of all SPL mints, passed to instruction.
+/// Mints that are not passed to instruction have no facade.
+pub fn spl_token_facades(accounts: &AccountStructure) -> BTreeMap<H160, Pubkey> {
+    accounts
+        .users
+        .iter()
+        .filter(|user| {
+            user.try_account_ref()
+                .map(|account| {
+                    account.owner() == &spl_token::id()
+                        && account.data().len() == MINT_LEN
+                        && account.data()[MINT_IS_INITIALIZED_OFFSET] == 1
+                })
+                .unwrap_or(false)
+        })
+        .map(|user| {
+            (
+                spl_token_facade_address(user.unsigned_key()),
+                *user.unsigned_key(),
+            )
+        })
+        .collect()
+}
+
+struct Erc20Abi {
+    total_supply: Function,
+    decimals: Function,
+    balance_of: Function,
+    allowance: Function,
+    transfer: Function,
+    approve: Function,
+    transfer_from: Function,
+}
+
+#[allow(deprecated)]
+fn function(
+    name: &str,
+    inputs: Vec<(&str, ParamType)>,
+    outputs: Vec<ParamType>,
+    state_mutability: StateMutability,
+) -> Function {
+    let param = |(name, kind): (&str, ParamType)| Param {
+        name: String::from(name),
+        kind,
+        internal_type: None,
+    };
+    Function {
+        name: String::from(name),
+        inputs: inputs.into_iter().map(param).collect(),
+        outputs: outputs.into_iter().map(|kind| param(("", kind))).collect(),
+        constant: Some(state_mutability == StateMutability::View),
+        state_mutability,
+    }
+}
+
+static ERC20_ABI: Lazy<Erc20Abi> = Lazy::new(|| {
+    use ParamType::{Address, Bool, Uint};
+    use StateMutability::{NonPayable, View};
+    Erc20Abi {
+        total_supply: function("totalSupply", vec![], vec![Uint(256)], View),
+        decimals: function("decimals", vec![], vec![Uint(8)], View),
+        balance_of: function("balanceOf", vec![("owner", Address)], vec![Uint(256)], View),
+        allowance: function(
+            "allowance",
+            vec![("owner", Address), ("spender", Address)],
+            vec![Uint(256)],
+            View,
+        ),
+        transfer: function(
+            "transfer",
+            vec![("to", Address), ("value", Uint(256))],
+            vec![Bool],
+            NonPayable,
+        ),
+        approve: function(
+            "approve",
+            vec![("spender", Address), ("value", Uint(256))],
+            vec![Bool],
+            NonPayable,
+        ),
+        transfer_from: function(
+            "transferFrom",
+            vec![("from", Address), ("to", Address), ("value", Uint(256))],
+            vec![Bool],
+            NonPayable,
+        ),
+    }
+});
+
+fn parse_address(token: &Token) -> Result<H160> {
+    match token {
+        Token::Address(address) => Ok(H160::from_slice(address.as_bytes())),
+        t => UnexpectedInput {
+            expected: String::from("address"),
+            got: t.to_string(),
+        }
+        .fail(),
+    }
+}
+
+fn parse_amount(token: &Token) -> Result<u64> {
+    match token {
+        Token::Uint(value) if *value <= Uint::from(u64::MAX) => Ok(value.as_u64()),
+        t => UnexpectedInput {
+            expected: String::from("uint64"),
+            got: t.to_string(),
+        }
+        .fail(),
+    }
+}
+
+fn address_topic(address: &H160) -> H256 {
+    H256::from(*address)
+}
+
+fn amount_data(amount: u64) -> Vec<u8> {
+    ethabi::encode(&[Token::Uint(Uint::from(amount))])
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn read_delegate(data: &[u8]) -> Option<(Pubkey, u64)> {
+    // COption<Pubkey> is stored as u32 tag and pubkey.
+    let delegate = &data[TOKEN_ACCOUNT_DELEGATE_OFFSET..][..36];
+    (delegate[0..4] == [1, 0, 0, 0]).then(|| {
+        (
+            Pubkey::new(&delegate[4..]),
+            read_u64(data, TOKEN_ACCOUNT_DELEGATED_AMOUNT_OFFSET),
+        )
+    })
+}
+
+/// Data of token account, passed to instruction.
+fn token_account_data(accounts: &AccountStructure, pubkey: &Pubkey) -> Option<Vec<u8>> {
+    let account = accounts.find_user(pubkey)?.try_account_ref().ok()?;
+    (account.owner() == &spl_token::id() && account.data().len() == TOKEN_ACCOUNT_LEN)
+        .then(|| account.data().to_vec())
+}
+
+/// Balances and allowances, changed by facade calls.
+#[derive(Debug, Default, Clone)]
+struct TokenChanges {
+    balances: BTreeMap<Pubkey, u64>,
+    delegates: BTreeMap<Pubkey, Option<(Pubkey, u64)>>,
+}
+
+impl TokenChanges {
+    fn amount(&self, pubkey: &Pubkey, data: &[u8]) -> u64 {
+        self.balances
+            .get(pubkey)
+            .copied()
+            .unwrap_or_else(|| read_u64(data, TOKEN_ACCOUNT_AMOUNT_OFFSET))
+    }
+
+    fn delegate(&self, pubkey: &Pubkey, data: &[u8]) -> Option<(Pubkey, u64)> {
+        self.delegates
+            .get(pubkey)
+            .copied()
+            .unwrap_or_else(|| read_delegate(data))
+    }
+
+    /// Apply change, requested by promise of facade log.
+    fn apply_promise(&mut self, accounts: &AccountStructure, promise: &NativeCallPromise) {
+        let (tag, amount) = match promise.data.as_slice() {
+            [tag, amount @ ..] if amount.len() == 8 => (*tag, read_u64(amount, 0)),
+            _ => return,
+        };
+        match (tag, promise.accounts.as_slice()) {
+            (SPL_TOKEN_TRANSFER, [source, destination, ..]) => {
+                let (source_data, destination_data) = match (
+                    token_account_data(accounts, &source.pubkey),
+                    token_account_data(accounts, &destination.pubkey),
+                ) {
+                    (Some(source), Some(destination)) => (source, destination),
+                    _ => return,
+                };
+                // Spender, that doesn't own source account, spends its allowance.
+                let owner = &source_data[TOKEN_ACCOUNT_OWNER_OFFSET..][..32];
+                if owner != promise.authority.as_ref() {
+                    let left = self
+                        .delegate(&source.pubkey, &source_data)
+                        .map(|(delegate, allowance)| (delegate, allowance.saturating_sub(amount)))
+                        .filter(|(_, left)| *left > 0);
+                    self.delegates.insert(source.pubkey, left);
+                }
+                if source.pubkey != destination.pubkey {
+                    let source_amount = self.amount(&source.pubkey, &source_data);
+                    let destination_amount = self.amount(&destination.pubkey, &destination_data);
+                    self.balances
+                        .insert(source.pubkey, source_amount.saturating_sub(amount));
+                    self.balances.insert(
+                        destination.pubkey,
+                        destination_amount.saturating_add(amount),
+                    );
+                }
+            }
+            (SPL_TOKEN_APPROVE, [source, delegate, ..]) => {
+                self.delegates
+                    .insert(source.pubkey, Some((delegate.pubkey, amount)));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// State of token accounts, changed by facade calls, but not yet by native calls.
+///
+/// Within transaction in progress, changes of reverted inner calls stay visible until
+/// transaction finishes, native call is still the final check of every transfer.
+#[derive(Debug, Default)]
+pub struct PendingTokens {
+    /// Changes of finished transactions, derived from their surviving logs.
+    committed: RefCell<TokenChanges>,
+    /// Changes visible to transaction in progress.
+    current: RefCell<TokenChanges>,
+}
+
+impl PendingTokens {
+    /// Finish transaction, and keep only changes requested by its surviving `logs`.
+    /// Logs of reverted calls are removed by evm, so their changes are dropped.
+    pub fn finish_transaction(&self, accounts: AccountStructure, logs: &[Log]) {
+        let facades = spl_token_facades(&accounts);
+        let mut committed = self.committed.borrow_mut();
+        let promises = logs
+            .iter()
+            .filter(|log| facades.contains_key(&log.address))
+            .filter_map(|log| decode_promise(&log.topics, &log.data));
+        for promise in promises {
+            committed.apply_promise(&accounts, &promise);
+        }
+        *self.current.borrow_mut() = committed.clone();
+    }
+}
+
+/// Facade token account, with pending changes applied.
+struct TokenAccount {
+    pubkey: Pubkey,
+    amount: u64,
+    delegate: Option<(Pubkey, u64)>,
+}
+
+struct Facade<'a, 'b, 'c> {
+    mint: Pubkey,
+    address: H160,
+    cx: NativeContext<'a, 'b>,
+    pending: &'c PendingTokens,
+}
+
+impl<'a, 'b, 'c> Facade<'a, 'b, 'c> {
+    fn mint_data<T>(&self, read: impl FnOnce(&[u8]) -> T) -> Result<T> {
+        let mint = self.cx.accounts.find_user(&self.mint).ok_or_else(|| {
+            AccountNotFound {
+                public_key: self.mint,
+            }
+            .build()
+        })?;
+        let account = mint
+            .try_account_ref()
+            .with_context(|_| NativeChainInstructionError {})?;
+        Ok(read(account.data()))
+    }
+
+    /// Read facade token account of evm `owner`, returns `None` if it is not passed to instruction.
+    fn token_account(&self, owner: &H160) -> Result<Option<TokenAccount>> {
+        let pubkey = spl_token_facade_account(&self.mint, owner);
+        let user = match self.cx.accounts.find_user(&pubkey) {
+            Some(user) => user,
+            None => return Ok(None),
+        };
+        let account = user
+            .try_account_ref()
+            .with_context(|_| NativeChainInstructionError {})?;
+        ensure!(
+            account.owner() == &spl_token::id()
+                && account.data().len() == TOKEN_ACCOUNT_LEN
+                && account.data()[0..32] == self.mint.to_bytes(),
+            InvalidTokenAccount { public_key: pubkey }
+        );
+        let data = account.data();
+        let changes = self.pending.current.borrow();
+        Ok(Some(TokenAccount {
+            pubkey,
+            amount: changes.amount(&pubkey, data),
+            delegate: changes.delegate(&pubkey, data),
+        }))
+    }
+
+    /// Same as `token_account`, but account is required by native call.
+    fn required_token_account(&self, owner: &H160) -> Result<TokenAccount> {
+        self.token_account(owner)?.ok_or_else(|| {
+            AccountNotFound {
+                public_key: spl_token_facade_account(&self.mint, owner),
+            }
+            .build()
+        })
+    }
+
+    /// State changing functions are only allowed for direct calls, where caller is known.
+    fn caller(&self) -> Result<H160> {
+        let context = &self.cx.precompile_context;
+        if !matches!(context.call_scheme, None | Some(CallScheme::Call))
+            || context.evm_context.address != self.address
+        {
+            return InvalidCallScheme {
+                scheme: context.call_scheme,
+            }
+            .fail();
+        }
+        Ok(context.evm_context.caller)
+    }
+
+    fn promise(&self, authority: &H160, accounts: Vec<(Pubkey, bool)>, data: Vec<u8>) -> Vec<u8> {
+        let authority = native_call_authority(authority);
+        let mut accounts: Vec<_> = accounts
+            .into_iter()
+            .map(|(pubkey, is_writable)| NativeCallAccount {
+                pubkey,
+                is_signer: false,
+                is_writable,
+            })
+            .collect();
+        accounts.push(NativeCallAccount {
+            pubkey: authority,
+            is_signer: true,
+            is_writable: false,
+        });
+        let promise = NativeCallPromise {
+            authority,
+            program_id: spl_token::id(),
+            accounts,
+            data,
+        };
+        bincode::serialize(&promise).expect("Serialization of promise should never fail.")
+    }
+
+    fn transfer(&self, spender: &H160, from: &H160, to: &H160, amount: u64) -> Result<LogEntry> {
+        let source = self.required_token_account(from)?;
+        let destination = self.required_token_account(to)?;
+        ensure!(
+            source.amount >= amount,
+            InsufficientTokens {
+                public_key: source.pubkey,
+                amount
+            }
+        );
+        let mut changes = self.pending.current.borrow_mut();
+        if spender != from {
+            let authority = native_call_authority(spender);
+            let left = match source.delegate {
+                Some((delegate, allowance)) if delegate == authority && allowance >= amount => {
+                    allowance - amount
+                }
+                _ => {
+                    return InsufficientAllowance {
+                        public_key: source.pubkey,
+                        amount,
+                    }
+                    .fail()
+                }
+            };
+            changes
+                .delegates
+                .insert(source.pubkey, (left > 0).then(|| (authority, left)));
+        }
+        if source.pubkey != destination.pubkey {
+            changes
+                .balances
+                .insert(source.pubkey, source.amount - amount);
+            changes.balances.insert(
+                destination.pubkey,
+                destination.amount.saturating_add(amount),
+            );
+        }
+
+        let mut data = vec![SPL_TOKEN_TRANSFER];
+        data.extend_from_slice(&amount.to_le_bytes());
+        let accounts = vec![
+            (spl_token_facade_account(&self.mint, from), true),
+            (spl_token_facade_account(&self.mint, to), true),
+        ];
+        Ok(vec![
+            (vec![], self.promise(spender, accounts, data)),
+            (
+                vec![*TRANSFER_EVENT, address_topic(from), address_topic(to)],
+                amount_data(amount),
+            ),
+        ])
+    }
+
+    fn eval(&self, function_abi_input: &[u8]) -> CallResult {
+        ensure!(
+            function_abi_input.len() >= 4,
+            InputToShort {
+                input_len: function_abi_input.len()
+            }
+        );
+        check_non_payable(&self.cx)?;
+        let (hash, input) = function_abi_input.split_at(4);
+        let abi = &*ERC20_ABI;
+        let function = [
+            &abi.total_supply,
+            &abi.decimals,
+            &abi.balance_of,
+            &abi.allowance,
+            &abi.transfer,
+            &abi.approve,
+            &abi.transfer_from,
+        ]
+        .into_iter()
+        .find(|function| function.short_signature() == hash)
+        .ok_or_else(|| {
+            UnknownFunction {
+                hash: hex::encode(hash),
+            }
+            .build()
+        })?;
+        let tokens = function
+            .decode_input(input)
+            .with_context(|_| FailedToParse {
+                name: function.name.clone(),
+            })?;
+        log::trace!(
+            "Precompile SPL_FACADE mint = {}, function = {}",
+            self.mint,
+            function.name
+        );
+
+        let is_view = function.state_mutability == StateMutability::View;
+        let gas = check_gas(
+            &self.cx,
+            if is_view {
+                ERC20_VIEW_GAS
+            } else {
+                ERC20_CALL_GAS
+            },
+        )?;
+
+        let (output, logs) = match function.name.as_str() {
+            "totalSupply" => {
+                let supply = self.mint_data(|data| read_u64(data, MINT_SUPPLY_OFFSET))?;
+                (vec![Token::Uint(supply.into())], vec![])
+            }
+            "decimals" => {
+                let decimals = self.mint_data(|data| data[MINT_DECIMALS_OFFSET])?;
+                (vec![Token::Uint(u64::from(decimals).into())], vec![])
+            }
+            "balanceOf" => {
+                // Owner without token account has no tokens.
+                let owner = parse_address(&tokens[0])?;
+                let amount = self
+                    .token_account(&owner)?
+                    .map(|account| account.amount)
+                    .unwrap_or(0);
+                (vec![Token::Uint(amount.into())], vec![])
+            }
+            "allowance" => {
+                let owner = parse_address(&tokens[0])?;
+                let spender = native_call_authority(&parse_address(&tokens[1])?);
+                let allowance = self
+                    .token_account(&owner)?
+                    .and_then(|account| account.delegate)
+                    .filter(|(delegate, _)| *delegate == spender)
+                    .map(|(_, allowance)| allowance)
+                    .unwrap_or(0);
+                (vec![Token::Uint(allowance.into())], vec![])
+            }
+            "transfer" => {
+                let from = self.caller()?;
+                let to = parse_address(&tokens[0])?;
+                let amount = parse_amount(&tokens[1])?;
+                (
+                    vec![Token::Bool(true)],
+                    self.transfer(&from, &from, &to, amount)?,
+                )
+            }
+            "approve" => {
+                let owner = self.caller()?;
+                let spender = parse_address(&tokens[0])?;
+                let amount = parse_amount(&tokens[1])?;
+                let account = self.required_token_account(&owner)?;
+                self.pending.current.borrow_mut().delegates.insert(
+                    account.pubkey,
+                    Some((native_call_authority(&spender), amount)),
+                );
+                let mut data = vec![SPL_TOKEN_APPROVE];
+                data.extend_from_slice(&amount.to_le_bytes());
+                let accounts = vec![
+                    (account.pubkey, true),
+                    (native_call_authority(&spender), false),
+                ];
+                let logs = vec![
+                    (vec![], self.promise(&owner, accounts, data)),
+                    (
+                        vec![
+                            *APPROVAL_EVENT,
+                            address_topic(&owner),
+                            address_topic(&spender),
+                        ],
+                        amount_data(amount),
+                    ),
+                ];
+                (vec![Token::Bool(true)], logs)
+            }
+            _ => {
+                let spender = self.caller()?;
+                let from = parse_address(&tokens[0])?;
+                let to = parse_address(&tokens[1])?;
+                let amount = parse_amount(&tokens[2])?;
+                (
+                    vec![Token::Bool(true)],
+                    self.transfer(&spender, &from, &to, amount)?,
+                )
+            }
+        };
+
+        Ok((
+            PrecompileOutput {
+                exit_status: ExitSucceed::Returned,
+                output: ethabi::encode(&output),
+            },
+            gas,
+            logs,
+        ))
+    }
+}
+
+/// Evaluate ERC-20 call to facade of `mint`.
+pub fn eval(
+    mint: Pubkey,
+    function_abi_input: &[u8],
+    cx: NativeContext,
+    pending: &PendingTokens,
+) -> CallResult {
+    let facade = Facade {
+        mint,
+        address: spl_token_facade_address(&mint),
+        cx,
+        pending,
+    };
+    facade.eval(function_abi_input)
+}
+
+/// Decode promise of facade log, events of facade have topics and are kept in receipt.
+pub fn decode_promise(topics: &[H256], data: &[u8]) -> Option<NativeCallPromise> {
+    if !topics.is_empty() {
+        return None;
+    }
+    bincode::deserialize(data).ok()
+}
+
+/// Check that all accounts of native call are passed to instruction.
+pub fn process_promise(accounts: AccountStructure, promise: &NativeCallPromise) -> Result<()> {
+    log::trace!("Promise handle SPL_FACADE {:?}", promise);
+    let keys = std::iter::once(&promise.program_id)
+        .chain(promise.accounts.iter().map(|account| &account.pubkey));
+    for key in keys {
+        if accounts.find_user(key).is_none() {
+            log::trace!("Account not found pk = {}", key);
+            return AccountNotFound { public_key: *key }.fail();
+        }
+    }
+    Ok(())
+}
@@ -1,13 +1,14 @@
 use std::cell::RefMut;
 use std::fmt::Write;
 use std::ops::DerefMut;
+use std::rc::Rc;
 
 use super::account_structure::AccountStructure;
 use super::instructions::{
     EvmBigTransaction, EvmInstruction, ExecuteTransaction, FeePayerType,
-    EVM_INSTRUCTION_BORSH_PREFIX,
+    EVM_INSTRUCTION_BORSH_PREFIX, MAX_TRANSACTIONS_IN_BATCH,
 };
-use super::precompiles;
+use super::precompiles::{self, NativeCallPromise, NativeFeatures};
 use super::scope::*;
 use evm_state::U256;
 use log::*;
@@ -23,6 +24,7 @@ use solana_sdk::instruction::InstructionError;
 use solana_sdk::{keyed_account::KeyedAccount, program_utils::limited_deserialize};
 
 use super::error::EvmError;
+use super::swap::{SwapDirection, SwapEvent};
 use super::tx_chunks::TxChunks;
 
 pub const BURN_ADDR: evm_state::H160 = evm_state::H160::zero();
@@ -108,27 +110,35 @@ impl EvmProcessor {
             _ => limited_deserialize(data)?,
         };
         trace!("Run evm exec with ix = {:?}.", ix);
+        // Batch is all or nothing: on any error receipts of its transactions are discarded,
+        // so runtime, that keeps receipts of failed instruction, doesn't save them or increase nonces.
+        let batch_start = matches!(ix, EvmInstruction::ExecuteTransactionBatch { .. })
+            .then(|| executor.evm_backend.executed_transactions_len());
         let result = match ix {
-            EvmInstruction::EvmBigTransaction(big_tx) => {
-                self.process_big_tx(invoke_context, accounts, big_tx)
-            }
-            EvmInstruction::FreeOwnership {} => self.process_free_ownership(
-                executor,
-                invoke_context,
-                accounts,
-                free_ownership_require_signer,
-            ),
+            EvmInstruction::EvmBigTransaction(big_tx) => self
+                .process_big_tx(invoke_context, accounts, big_tx)
+                .map(|()| Vec::new()),
+            EvmInstruction::FreeOwnership {} => self
+                .process_free_ownership(
+                    executor,
+                    invoke_context,
+                    accounts,
+                    free_ownership_require_signer,
+                )
+                .map(|()| Vec::new()),
             EvmInstruction::SwapNativeToEther {
                 lamports,
                 evm_address,
-            } => self.process_swap_to_evm(
-                executor,
-                invoke_context,
-                accounts,
-                lamports,
-                evm_address,
-                register_swap_tx_in_evm,
-            ),
+            } => self
+                .process_swap_to_evm(
+                    executor,
+                    invoke_context,
+                    accounts,
+                    lamports,
+                    evm_address,
+                    register_swap_tx_in_evm,
+                )
+                .map(|()| Vec::new()),
             EvmInstruction::ExecuteTransaction { tx, fee_type } => self.process_execute_tx(
                 executor,
                 invoke_context,
@@ -137,6 +147,15 @@ impl EvmProcessor {
                 fee_type,
                 borsh_serialization_used,
             ),
+            EvmInstruction::ExecuteTransactionBatch { txs, fee_type } => self
+                .process_execute_batch(
+                    executor,
+                    invoke_context,
+                    accounts,
+                    txs,
+                    fee_type,
+                    borsh_serialization_used,
+                ),
         };
 
         if register_swap_tx_in_evm {
@@ -144,8 +163,11 @@ impl EvmProcessor {
         }
 
         // When old error handling, manually convert EvmError to InstructionError
-        result.or_else(|error| {
+        let native_calls = result.or_else(|error| {
             ic_msg!(invoke_context, "Execution error: {}", error);
+            if let Some(len) = batch_start {
+                executor.evm_backend.truncate_transaction_receipts(len);
+            }
 
             let err = if !new_error_handling {
                 use EvmError::*;
@@ -166,7 +188,7 @@ impl EvmProcessor {
                     MissingRequiredSignature => InstructionError::MissingRequiredSignature,
                     SwapInsufficient => InstructionError::InsufficientFunds,
                     BorrowingFailed => InstructionError::AccountBorrowFailed,
-                    RevertTransaction => return Ok(()), // originally revert was not an error
+                    RevertTransaction => return Ok(Vec::new()), // originally revert was not an error
                     // future error would be just invalid errors.
                     _ => InstructionError::InvalidError,
                 }
@@ -175,7 +197,25 @@ impl EvmProcessor {
             };
 
             Err(err)
-        })
+        })?;
+
+        // Native calls requested by evm contracts are invoked only after evm transaction succeed.
+        for call in native_calls {
+            ic_msg!(
+                invoke_context,
+                "Invoking native program {} from evm, authority: {}",
+                call.program_id,
+                call.authority
+            );
+            if let Err(error) = invoke_context.native_invoke(call.instruction(), &[call.authority])
+            {
+                if let Some(len) = batch_start {
+                    executor.evm_backend.truncate_transaction_receipts(len);
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
     }
 
     fn process_execute_tx(
@@ -186,7 +226,7 @@ impl EvmProcessor {
         tx: ExecuteTransaction,
         fee_type: FeePayerType,
         borsh_used: bool,
-    ) -> Result<(), EvmError> {
+    ) -> Result<Vec<NativeCallPromise>, EvmError> {
         let is_big = tx.is_big();
         let keep_old_errors = true;
         // TODO: Add logic for fee collector
@@ -213,6 +253,8 @@ impl EvmProcessor {
             }
         }
 
+        let native_features = Self::native_features(invoke_context);
+
         let withdraw_fee_from_evm = fee_type.is_evm();
         let mut tx_gas_price;
         let result = match tx {
@@ -239,7 +281,12 @@ impl EvmProcessor {
                 executor.transaction_execute(
                     tx,
                     withdraw_fee_from_evm,
-                    precompiles::entrypoint(accounts, activate_precompile, keep_old_errors),
+                    precompiles::entrypoint(
+                        accounts,
+                        activate_precompile,
+                        keep_old_errors,
+                        native_features,
+                    ),
                 )
             }
             ExecuteTransaction::ProgramAuthorized { tx, from } => {
@@ -279,7 +326,53 @@ impl EvmProcessor {
                     from,
                     tx,
                     withdraw_fee_from_evm,
-                    precompiles::entrypoint(accounts, activate_precompile, keep_old_errors),
+                    precompiles::entrypoint(
+                        accounts,
+                        activate_precompile,
+                        keep_old_errors,
+                        native_features,
+                    ),
+                )
+            }
+            ExecuteTransaction::SignedTyped { tx } => {
+                if !invoke_context
+                    .feature_set
+                    .is_active(&solana_sdk::feature_set::velas::evm_typed_transactions::id())
+                {
+                    ic_msg!(invoke_context, "Typed transactions are not enabled.");
+                    return Err(EvmError::InstructionNotSupportedYet);
+                }
+                let tx: evm::TypedTransaction = match tx {
+                    Some(tx) => tx,
+                    None => Self::get_tx_from_storage(invoke_context, accounts, borsh_used)?,
+                };
+                ic_msg!(
+                    invoke_context,
+                    "Executing typed transaction: type:{}, gas_limit:{}, fee:{:?}, value:{}, action:{:?},",
+                    tx.tx_type(),
+                    tx.gas_limit,
+                    tx.fee,
+                    tx.value,
+                    tx.action
+                );
+                tx_gas_price = tx
+                    .fee
+                    .effective_gas_price(executor.config().burn_gas_price);
+                let activate_precompile = precompile_set(
+                    executor.support_precompile(),
+                    invoke_context
+                        .feature_set
+                        .is_active(&solana_sdk::feature_set::velas::evm_new_precompiles::id()),
+                );
+                executor.transaction_execute_typed(
+                    tx,
+                    withdraw_fee_from_evm,
+                    precompiles::entrypoint(
+                        accounts,
+                        activate_precompile,
+                        keep_old_errors,
+                        native_features,
+                    ),
                 )
             }
         };
@@ -307,6 +400,163 @@ impl EvmProcessor {
         )
     }
 
+    fn native_features(invoke_context: &InvokeContext) -> NativeFeatures {
+        NativeFeatures::from_feature_set(&invoke_context.feature_set)
+    }
+
+    fn process_execute_batch(
+        &self,
+        executor: &mut Executor,
+        invoke_context: &InvokeContext,
+        accounts: AccountStructure,
+        txs: Option<Vec<evm::SignedTransaction>>,
+        fee_type: FeePayerType,
+        borsh_used: bool,
+    ) -> Result<Vec<NativeCallPromise>, EvmError> {
+        if !invoke_context
+            .feature_set
+            .is_active(&solana_sdk::feature_set::velas::evm_transaction_batch::id())
+        {
+            ic_msg!(invoke_context, "Transaction batches are not enabled.");
+            return Err(EvmError::InstructionNotSupportedYet);
+        }
+        // Old error handling keeps state changes of failed instruction, so batch can't be discarded.
+        if !invoke_context
+            .feature_set
+            .is_active(&solana_sdk::feature_set::velas::evm_new_error_handling::id())
+        {
+            ic_msg!(
+                invoke_context,
+                "Transaction batches require new error handling."
+            );
+            return Err(EvmError::InstructionNotSupportedYet);
+        }
+
+        let is_big = txs.is_none();
+        let keep_old_errors = true;
+        let sender = if is_big {
+            accounts.users.get(1)
+        } else {
+            accounts.first()
+        };
+
+        if fee_type.is_native() && sender.is_none() {
+            ic_msg!(invoke_context, "Fee payer is native but no sender providen",);
+            return Err(EvmError::MissingRequiredSignature);
+        }
+
+        let txs: Vec<evm::SignedTransaction> = match txs {
+            Some(txs) => txs,
+            None => {
+                let txs = Self::get_tx_from_storage(invoke_context, accounts, borsh_used)?;
+                let storage = Self::get_big_transaction_storage(invoke_context, &accounts)?;
+                self.cleanup_storage(invoke_context, storage, sender.unwrap_or(accounts.evm))?;
+                txs
+            }
+        };
+        if txs.is_empty() || txs.len() > MAX_TRANSACTIONS_IN_BATCH {
+            ic_msg!(
+                invoke_context,
+                "Invalid batch size: {}, maximum: {}",
+                txs.len(),
+                MAX_TRANSACTIONS_IN_BATCH
+            );
+            return Err(EvmError::InvalidBatchSize);
+        }
+        let typed_transactions_enabled = invoke_context
+            .feature_set
+            .is_active(&solana_sdk::feature_set::velas::evm_typed_transactions::id());
+        if !typed_transactions_enabled
+            && txs
+                .iter()
+                .any(|tx| matches!(tx, evm::SignedTransaction::Typed(_)))
+        {
+            ic_msg!(invoke_context, "Typed transactions are not enabled.");
+            return Err(EvmError::InstructionNotSupportedYet);
+        }
+
+        let activate_precompile = precompiles::PrecompileSet::from_feature_set(
+            executor.support_precompile(),
+            &invoke_context.feature_set,
+        );
+        let native_features = Self::native_features(invoke_context);
+        let withdraw_fee_from_evm = fee_type.is_evm();
+
+        let batch_size = txs.len();
+        let mut native_calls = Vec::new();
+        // Native calls are executed after whole batch.
+        let pending_tokens = Rc::new(precompiles::PendingTokens::default());
+        for (index, tx) in txs.into_iter().enumerate() {
+            ic_msg!(
+                invoke_context,
+                "Executing transaction {} of {} in batch: hash:{:?}, gas_limit:{}, value:{}, action:{:?},",
+                index + 1,
+                batch_size,
+                tx.tx_id_hash(),
+                tx.gas_limit(),
+                tx.value(),
+                tx.action()
+            );
+            let precompiles = precompiles::entrypoint_with_pending(
+                accounts,
+                activate_precompile,
+                keep_old_errors,
+                native_features,
+                pending_tokens.clone(),
+            );
+            let (mut tx_gas_price, result) = match tx {
+                evm::SignedTransaction::Legacy(tx) => (
+                    tx.gas_price,
+                    executor.transaction_execute(tx, withdraw_fee_from_evm, precompiles),
+                ),
+                evm::SignedTransaction::Typed(tx) => (
+                    tx.fee.effective_gas_price(executor.config().burn_gas_price),
+                    executor.transaction_execute_typed(tx, withdraw_fee_from_evm, precompiles),
+                ),
+            };
+            if executor
+                .feature_set
+                .is_accept_zero_gas_price_with_native_fee_enabled()
+                && fee_type.is_native()
+                && tx_gas_price.is_zero()
+            {
+                tx_gas_price = executor.config().burn_gas_price;
+            }
+            // Facade promises are removed from logs, when transaction result is handled.
+            let tx_logs = result
+                .as_ref()
+                .map(|result| result.tx_logs.clone())
+                .unwrap_or_default();
+            let calls = self
+                .handle_transaction_result(
+                    executor,
+                    invoke_context,
+                    accounts,
+                    sender,
+                    tx_gas_price,
+                    result,
+                    withdraw_fee_from_evm,
+                )
+                .map_err(|error| {
+                    ic_msg!(
+                        invoke_context,
+                        "Transaction {} of batch failed: {}",
+                        index + 1,
+                        error
+                    );
+                    // Reverted transaction is not an error for single transaction,
+                    // but it should fail whole batch.
+                    match error {
+                        EvmError::RevertTransaction => EvmError::BatchTransactionReverted,
+                        error => error,
+                    }
+                })?;
+            pending_tokens.finish_transaction(accounts, &tx_logs);
+            native_calls.extend(calls);
+        }
+        Ok(native_calls)
+    }
+
     fn process_free_ownership(
         &self,
         _executor: &mut Executor,
@@ -399,9 +649,23 @@ impl EvmProcessor {
         let evm_account_lamports = evm_account.lamports().saturating_add(lamports);
         evm_account.set_lamports(evm_account_lamports);
         executor.deposit(evm_address, gweis);
-        if register_swap_tx_in_evm {
-            executor.register_swap_tx_in_evm(*precompiles::ETH_TO_VLX_ADDR, evm_address, gweis)
-        }
+        let evm_tx_hash = if register_swap_tx_in_evm {
+            Some(executor.register_swap_tx_in_evm(
+                *precompiles::ETH_TO_VLX_ADDR,
+                evm_address,
+                gweis,
+            ))
+        } else {
+            None
+        };
+        let swap = SwapEvent {
+            direction: SwapDirection::NativeToEvm,
+            native_account: *user.unsigned_key(),
+            evm_address,
+            lamports,
+            evm_tx_hash,
+        };
+        ic_msg!(invoke_context, "{}", swap);
         Ok(())
     }
 
@@ -615,12 +879,14 @@ impl EvmProcessor {
         tx_gas_price: evm_state::U256,
         result: Result<evm_state::ExecutionResult, evm_state::error::Error>,
         withdraw_fee_from_evm: bool,
-    ) -> Result<(), EvmError> {
+    ) -> Result<Vec<NativeCallPromise>, EvmError> {
         let remove_native_logs_after_swap = true;
         let mut result = result.map_err(|e| {
             ic_msg!(invoke_context, "Transaction execution error: {}", e);
             EvmError::InternalExecutorError
         })?;
+        let swaps_to_native = precompiles::swaps_to_native(&result.tx_logs);
+        let native_calls = precompiles::native_calls(accounts, &result.tx_logs);
 
         if remove_native_logs_after_swap {
             executor.modify_tx_logs(result.tx_id, |logs| {
@@ -643,6 +909,30 @@ impl EvmProcessor {
             })?;
         }
 
+        if !swaps_to_native.is_empty() {
+            let caller = executor
+                .get_tx_receipt_by_hash(result.tx_id)
+                .and_then(|receipt| receipt.caller());
+            // Swap with unknown sender would be indexed under zero address, skip it instead.
+            if let Some(caller) = caller {
+                for swap in swaps_to_native {
+                    let swap = SwapEvent {
+                        direction: SwapDirection::EvmToNative,
+                        native_account: swap.pubkey,
+                        evm_address: caller,
+                        lamports: swap.amount,
+                        evm_tx_hash: Some(result.tx_id),
+                    };
+                    ic_msg!(invoke_context, "{}", swap);
+                }
+            } else {
+                ic_msg!(
+                    invoke_context,
+                    "Unable to find caller of tx, swaps to native are not reported"
+                );
+            }
+        }
+
         write!(
             crate::solana_extension::MultilineLogger::new(invoke_context.get_log_collector()),
             "{}",
@@ -708,7 +998,7 @@ impl EvmProcessor {
             );
         }
 
-        Ok(())
+        Ok(native_calls)
     }
 
     /// Ensure that first account is program itself, and it's locked for writes.
@@ -861,16 +1151,7 @@ mod test {
                     self.feature_set
                         .is_active(&solana_sdk::feature_set::velas::burn_fee::id()),
                 ),
-                evm_state::executor::FeatureSet::new(
-                    self.feature_set
-                        .is_active(&solana_sdk::feature_set::velas::unsigned_tx_fix::id()),
-                    self.feature_set
-                        .is_active(&solana_sdk::feature_set::velas::clear_logs_on_error::id()),
-                    self.feature_set.is_active(
-                        &solana_sdk::feature_set::velas::accept_zero_gas_price_with_native_fee::id(
-                        ),
-                    ),
-                ),
+                crate::executor_feature_set(&self.feature_set),
             );
 
             let evm_program = BuiltinProgram {
@@ -1059,6 +1340,178 @@ mod test {
             .is_some())
     }
 
+    #[test]
+    fn execute_typed_tx() {
+        let mut evm_context = EvmMockContext::new(0);
+        evm_context.disable_feature(&solana_sdk::feature_set::velas::burn_fee::id());
+        let secret_key = evm::SecretKey::from_slice(&SECRET_KEY_DUMMY).unwrap();
+
+        let address = secret_key.to_address();
+        evm_context.deposit_evm(address, U256::from(2u32) * 300000u32);
+        let tx_create = evm::UnsignedTypedTransaction {
+            chain_id: CHAIN_ID,
+            nonce: 0u32.into(),
+            fee: evm::TypedTransactionFee::DynamicFee {
+                max_priority_fee_per_gas: 1u32.into(),
+                max_fee_per_gas: 1u32.into(),
+            },
+            gas_limit: 300000u32.into(),
+            action: TransactionAction::Create,
+            value: 0u32.into(),
+            input: hex::decode(evm_state::HELLO_WORLD_CODE).unwrap().to_vec(),
+            access_list: vec![],
+        }
+        .sign(&secret_key);
+        let tx_hash = tx_create.tx_id_hash();
+
+        evm_context
+            .disable_feature(&solana_sdk::feature_set::velas::evm_typed_transactions::id());
+        assert!(evm_context
+            .process_instruction(crate::send_raw_typed_tx(
+                Pubkey::new_unique(),
+                tx_create.clone(),
+                None,
+                FeePayerType::Evm
+            ))
+            .is_err());
+
+        evm_context
+            .feature_set
+            .activate(&solana_sdk::feature_set::velas::evm_typed_transactions::id(), 0);
+        assert!(evm_context
+            .process_instruction(crate::send_raw_typed_tx(
+                Pubkey::new_unique(),
+                tx_create,
+                None,
+                FeePayerType::Evm
+            ))
+            .is_ok());
+        let receipt = evm_context
+            .evm_state
+            .find_transaction_receipt(tx_hash)
+            .unwrap();
+        assert_eq!(receipt.transaction.tx_type(), evm::DYNAMIC_FEE_TX_TYPE);
+    }
+
+    #[test]
+    fn execute_tx_batch() {
+        let mut evm_context = EvmMockContext::new(0);
+        evm_context.disable_feature(&solana_sdk::feature_set::velas::burn_fee::id());
+        let secret_key = evm::SecretKey::from_slice(&SECRET_KEY_DUMMY).unwrap();
+
+        let address = secret_key.to_address();
+        evm_context.deposit_evm(address, U256::from(4u32) * 300000u32);
+        let tx_create = evm::UnsignedTransaction {
+            nonce: 0u32.into(),
+            gas_price: 1u32.into(),
+            gas_limit: 300000u32.into(),
+            action: TransactionAction::Create,
+            value: 0u32.into(),
+            input: hex::decode(evm_state::HELLO_WORLD_CODE).unwrap().to_vec(),
+        }
+        .sign(&secret_key, Some(CHAIN_ID));
+        let tx_call = evm::UnsignedTransaction {
+            nonce: 1u32.into(),
+            gas_price: 1u32.into(),
+            gas_limit: 300000u32.into(),
+            action: TransactionAction::Call(tx_create.address().unwrap()),
+            value: 0u32.into(),
+            input: hex::decode(evm_state::HELLO_WORLD_ABI).unwrap().to_vec(),
+        }
+        .sign(&secret_key, Some(CHAIN_ID));
+        let batch = vec![tx_create.clone().into(), tx_call.clone().into()];
+
+        evm_context.disable_feature(&solana_sdk::feature_set::velas::evm_transaction_batch::id());
+        assert!(evm_context
+            .process_instruction(crate::send_raw_batch(
+                Pubkey::new_unique(),
+                batch.clone(),
+                None,
+                FeePayerType::Evm
+            ))
+            .is_err());
+        evm_context.feature_set.activate(
+            &solana_sdk::feature_set::velas::evm_transaction_batch::id(),
+            0,
+        );
+
+        assert!(evm_context
+            .process_instruction(crate::send_raw_batch(
+                Pubkey::new_unique(),
+                vec![],
+                None,
+                FeePayerType::Evm
+            ))
+            .is_err());
+
+        evm_context
+            .process_instruction(crate::send_raw_batch(
+                Pubkey::new_unique(),
+                batch,
+                None,
+                FeePayerType::Evm,
+            ))
+            .unwrap();
+        for tx_hash in [tx_create.tx_id_hash(), tx_call.tx_id_hash()] {
+            let receipt = evm_context
+                .evm_state
+                .find_transaction_receipt(tx_hash)
+                .unwrap();
+            assert!(matches!(receipt.status, ExitReason::Succeed(_)));
+        }
+
+        // second transaction has invalid nonce, so whole batch should fail
+        let tx_call = evm::UnsignedTransaction {
+            nonce: 2u32.into(),
+            gas_price: 1u32.into(),
+            gas_limit: 300000u32.into(),
+            action: TransactionAction::Call(tx_create.address().unwrap()),
+            value: 0u32.into(),
+            input: hex::decode(evm_state::HELLO_WORLD_ABI).unwrap().to_vec(),
+        }
+        .sign(&secret_key, Some(CHAIN_ID));
+        let tx_invalid = evm::UnsignedTransaction {
+            nonce: 5u32.into(),
+            ..tx_call.clone().into()
+        }
+        .sign(&secret_key, Some(CHAIN_ID));
+        assert!(evm_context
+            .process_instruction(crate::send_raw_batch(
+                Pubkey::new_unique(),
+                vec![tx_call.clone().into(), tx_invalid.into()],
+                None,
+                FeePayerType::Evm
+            ))
+            .is_err());
+        // failed batch leaves no receipts and doesn't increase nonce, so it can be resubmitted
+        assert!(evm_context
+            .evm_state
+            .find_transaction_receipt(tx_call.tx_id_hash())
+            .is_none());
+        assert_eq!(
+            evm_context
+                .evm_state
+                .get_account_state(address)
+                .unwrap()
+                .nonce,
+            U256::from(2u32)
+        );
+
+        evm_context
+            .process_instruction(crate::send_raw_batch(
+                Pubkey::new_unique(),
+                vec![tx_call.clone().into()],
+                None,
+                FeePayerType::Evm,
+            ))
+            .unwrap();
+        let receipt = evm_context
+            .evm_state
+            .find_transaction_receipt(tx_call.tx_id_hash())
+            .unwrap();
+        assert!(matches!(receipt.status, ExitReason::Succeed(_)));
+    }
+
     #[test]
     fn test_big_authorized_tx_execution() {
         let _logger = simple_logger::SimpleLogger::new()
@@ -0,0 +1,233 @@
+//! Swaps between native chain and evm, reported in program logs.
+//!
+//! Every swap processed by evm loader is logged as a single line,
+//! so indexers can find swaps of a transaction without replaying it.
+//! Any program can log the same line, so only lines logged by evm loader itself are trusted.
+//! Logs over collector limit are dropped, so swaps of transaction with truncated logs may be
+//! incomplete, such transactions are detected by `is_log_truncated`.
+
+use std::{fmt, str::FromStr};
+
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+pub const SWAP_LOG_PREFIX: &str = "EvmSwap: ";
+const PROGRAM_LOG_PREFIX: &str = "Program log: ";
+const PROGRAM_PREFIX: &str = "Program ";
+/// Message, that log collector writes instead of messages over its limit.
+const LOG_TRUNCATED: &str = "Log truncated";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapDirection {
+    NativeToEvm,
+    EvmToNative,
+}
+
+impl SwapDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SwapDirection::NativeToEvm => "native_to_evm",
+            SwapDirection::EvmToNative => "evm_to_native",
+        }
+    }
+}
+
+impl FromStr for SwapDirection {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "native_to_evm" => Ok(SwapDirection::NativeToEvm),
+            "evm_to_native" => Ok(SwapDirection::EvmToNative),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapEvent {
+    pub direction: SwapDirection,
+    pub native_account: Pubkey,
+    pub evm_address: H160,
+    pub lamports: u64,
+    /// Evm transaction of swap, `None` if swap wasn't registered in evm block.
+    pub evm_tx_hash: Option<H256>,
+}
+
+impl SwapEvent {
+    /// Parse swap from transaction log message, returns `None` for any other message.
+    pub fn from_log_message(message: &str) -> Option<Self> {
+        let message = message.strip_prefix(PROGRAM_LOG_PREFIX).unwrap_or(message);
+        let fields = message.strip_prefix(SWAP_LOG_PREFIX)?;
+
+        let mut direction = None;
+        let mut native_account = None;
+        let mut evm_address = None;
+        let mut lamports = None;
+        let mut evm_tx_hash = None;
+        for field in fields.split(", ") {
+            let (key, value) = field.split_once('=')?;
+            match key {
+                "direction" => direction = value.parse().ok(),
+                "native" => native_account = value.parse().ok(),
+                "evm" => evm_address = parse_hex(value),
+                "lamports" => lamports = value.parse().ok(),
+                "evm_tx" if value == "none" => evm_tx_hash = Some(None),
+                "evm_tx" => evm_tx_hash = Some(Some(parse_hex(value)?)),
+                _ => return None,
+            }
+        }
+        Some(SwapEvent {
+            direction: direction?,
+            native_account: native_account?,
+            evm_address: evm_address?,
+            lamports: lamports?,
+            evm_tx_hash: evm_tx_hash?,
+        })
+    }
+
+    /// Returns all swaps, reported in transaction logs by evm loader.
+    ///
+    /// Invocation frames are tracked by `Program <id> invoke`, `success` and `failed` messages,
+    /// and swap lines are accepted only inside evm loader frames.
+    pub fn from_log_messages(messages: &[String]) -> Vec<Self> {
+        let evm_loader = crate::ID.to_string();
+        let mut frames = vec![];
+        let mut swaps = vec![];
+        for message in messages {
+            if let Some(log) = message.strip_prefix(PROGRAM_LOG_PREFIX) {
+                if frames.last() == Some(&evm_loader.as_str()) {
+                    swaps.extend(Self::from_log_message(log));
+                }
+            } else if let Some(message) = message.strip_prefix(PROGRAM_PREFIX) {
+                let mut words = message.split(' ');
+                match (words.next(), words.next()) {
+                    (Some(program), Some("invoke")) => frames.push(program),
+                    (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                        frames.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        swaps
+    }
+
+    /// Returns true if some messages of transaction were dropped by log collector,
+    /// and swaps found by `from_log_messages` may be incomplete.
+    pub fn is_log_truncated(messages: &[String]) -> bool {
+        messages.iter().any(|message| message == LOG_TRUNCATED)
+    }
+}
+
+fn parse_hex<T: FromStr>(value: &str) -> Option<T> {
+    value.strip_prefix("0x")?.parse().ok()
+}
+
+impl fmt::Display for SwapEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}direction={}, native={}, evm={:?}, lamports={}, evm_tx=",
+            SWAP_LOG_PREFIX,
+            self.direction.as_str(),
+            self.native_account,
+            self.evm_address,
+            self.lamports
+        )?;
+        match self.evm_tx_hash {
+            Some(hash) => write!(f, "{:?}", hash),
+            None => write!(f, "none"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn swap_log_roundtrip() {
+        let swaps = vec![
+            SwapEvent {
+                direction: SwapDirection::NativeToEvm,
+                native_account: Pubkey::new_unique(),
+                evm_address: H160::repeat_byte(0x11),
+                lamports: 42,
+                evm_tx_hash: Some(H256::repeat_byte(0x22)),
+            },
+            SwapEvent {
+                direction: SwapDirection::EvmToNative,
+                native_account: Pubkey::new_unique(),
+                evm_address: H160::repeat_byte(0x33),
+                lamports: 1_000_000_000,
+                evm_tx_hash: None,
+            },
+        ];
+
+        let mut messages: Vec<String> = swaps
+            .iter()
+            .map(|swap| format!("{}{}", PROGRAM_LOG_PREFIX, swap))
+            .collect();
+        messages.insert(1, "Program log: Execution result:".to_string());
+        messages.push(format!("{}direction=unknown", SWAP_LOG_PREFIX));
+        messages.insert(0, format!("Program {} invoke [1]", crate::ID));
+        messages.push(format!("Program {} success", crate::ID));
+
+        assert_eq!(SwapEvent::from_log_messages(&messages), swaps);
+    }
+
+    #[test]
+    fn swap_log_from_other_program_is_ignored() {
+        let swap = SwapEvent {
+            direction: SwapDirection::NativeToEvm,
+            native_account: Pubkey::new_unique(),
+            evm_address: H160::repeat_byte(0x11),
+            lamports: 42,
+            evm_tx_hash: None,
+        };
+        let spoofer = Pubkey::new_unique();
+        let spoofed = vec![
+            format!("Program {} invoke [1]", spoofer),
+            format!("{}{}", PROGRAM_LOG_PREFIX, swap),
+            format!("Program {} success", spoofer),
+        ];
+        assert_eq!(SwapEvent::from_log_messages(&spoofed), vec![]);
+
+        // swap logged without any invocation frame
+        let unframed = vec![format!("{}{}", PROGRAM_LOG_PREFIX, swap)];
+        assert_eq!(SwapEvent::from_log_messages(&unframed), vec![]);
+
+        // spoofer invoked by evm loader, and evm loader swap after it returns
+        let nested = vec![
+            format!("Program {} invoke [1]", crate::ID),
+            format!("Program {} invoke [2]", spoofer),
+            format!("{}{}", PROGRAM_LOG_PREFIX, swap),
+            format!("Program {} consumed 100 of 200000 compute units", spoofer),
+            format!("Program {} failed: custom program error: 0x0", spoofer),
+            format!("{}{}", PROGRAM_LOG_PREFIX, swap),
+            format!("Program {} success", crate::ID),
+        ];
+        assert_eq!(SwapEvent::from_log_messages(&nested), vec![swap]);
+        assert!(!SwapEvent::is_log_truncated(&nested));
+    }
+
+    #[test]
+    fn truncated_swap_logs_are_detected() {
+        let swap = SwapEvent {
+            direction: SwapDirection::EvmToNative,
+            native_account: Pubkey::new_unique(),
+            evm_address: H160::repeat_byte(0x11),
+            lamports: 42,
+            evm_tx_hash: Some(H256::repeat_byte(0x22)),
+        };
+        let truncated = vec![
+            format!("Program {} invoke [1]", crate::ID),
+            format!("{}{}", PROGRAM_LOG_PREFIX, swap),
+            LOG_TRUNCATED.to_string(),
+        ];
+        assert_eq!(SwapEvent::from_log_messages(&truncated), vec![swap]);
+        assert!(SwapEvent::is_log_truncated(&truncated));
+    }
+}
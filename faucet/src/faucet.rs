@@ -3,11 +3,14 @@
 //! The Solana Faucet builds and sends airdrop transactions,
 //! checking requests against a single-request cap and a per-IP limit
 //! for a given time time_slice.
+//! Airdrops to evm addresses are swapped from the faucet account into evm,
+//! and are limited by the same caps.
 
 use {
     bincode::{deserialize, serialize, serialized_size},
     byteorder::{ByteOrder, LittleEndian},
     crossbeam_channel::{unbounded, Sender},
+    evm_state::H160,
     log::*,
     serde_derive::{Deserialize, Serialize},
     solana_metrics::datapoint_info,
@@ -83,6 +86,31 @@ pub enum FaucetRequest {
         to: Pubkey,
         blockhash: Hash,
     },
+    GetEvmAirdrop {
+        lamports: u64,
+        to: H160,
+        blockhash: Hash,
+    },
+}
+
+impl FaucetRequest {
+    /// Returns size of serialized request, for variant index, that prefixes every request.
+    fn serialized_size_of_variant(variant: u32) -> Option<usize> {
+        let request = match variant {
+            0 => FaucetRequest::GetAirdrop {
+                lamports: u64::default(),
+                to: Pubkey::default(),
+                blockhash: Hash::default(),
+            },
+            1 => FaucetRequest::GetEvmAirdrop {
+                lamports: u64::default(),
+                to: H160::default(),
+                blockhash: Hash::default(),
+            },
+            _ => return None,
+        };
+        Some(serialized_size(&request).unwrap() as usize)
+    }
 }
 
 pub enum FaucetTransaction {
@@ -95,6 +123,7 @@ pub struct Faucet {
     faucet_keypair: Keypair,
     ip_cache: HashMap<IpAddr, u64>,
     address_cache: HashMap<Pubkey, u64>,
+    evm_address_cache: HashMap<H160, u64>,
     pub time_slice: Duration,
     per_time_cap: Option<u64>,
     per_request_cap: Option<u64>,
@@ -139,6 +168,7 @@ impl Faucet {
             faucet_keypair,
             ip_cache: HashMap::new(),
             address_cache: HashMap::new(),
+            evm_address_cache: HashMap::new(),
             time_slice,
             per_time_cap,
             per_request_cap,
@@ -169,10 +199,12 @@ impl Faucet {
     pub fn clear_caches(&mut self) {
         self.ip_cache.clear();
         self.address_cache.clear();
+        self.evm_address_cache.clear();
     }
 
     /// Checks per-request and per-time-ip limits; if both pass, this method returns a signed
-    /// SystemProgram::Transfer transaction from the faucet keypair to the requested recipient. If
+    /// SystemProgram::Transfer transaction from the faucet keypair to the requested recipient,
+    /// or a swap of lamports into evm for an evm recipient. If
     /// the request exceeds this per-request limit, this method returns a signed SPL Memo
     /// transaction with the memo: "request too large; req: <REQUEST> SOL cap: <CAP> SOL"
     pub fn build_airdrop_transaction(
@@ -181,55 +213,75 @@ impl Faucet {
         ip: IpAddr,
     ) -> Result<FaucetTransaction, FaucetError> {
         trace!("build_airdrop_transaction: {:?}", req);
-        match req {
+        let (lamports, blockhash) = match req {
             FaucetRequest::GetAirdrop {
                 lamports,
                 to,
                 blockhash,
             } => {
-                let mint_pubkey = self.faucet_keypair.pubkey();
                 info!(
                     "Requesting airdrop of {} SOL to {:?}",
                     lamports_to_sol(lamports),
                     to
                 );
+                (lamports, blockhash)
+            }
+            FaucetRequest::GetEvmAirdrop {
+                lamports,
+                to,
+                blockhash,
+            } => {
+                info!(
+                    "Requesting evm airdrop of {} SOL to {:?}",
+                    lamports_to_sol(lamports),
+                    to
+                );
+                (lamports, blockhash)
+            }
+        };
+        let mint_pubkey = self.faucet_keypair.pubkey();
+
+        if let Some(cap) = self.per_request_cap {
+            if lamports > cap {
+                let memo = format!(
+                    "{}",
+                    FaucetError::PerRequestCapExceeded(
+                        lamports_to_sol(lamports),
+                        lamports_to_sol(cap),
+                    )
+                );
+                let memo_instruction = Instruction {
+                    program_id: Pubkey::new(&spl_memo::id().to_bytes()),
+                    accounts: vec![],
+                    data: memo.as_bytes().to_vec(),
+                };
+                let message = Message::new(&[memo_instruction], Some(&mint_pubkey));
+                return Ok(FaucetTransaction::Memo((
+                    Transaction::new(&[&self.faucet_keypair], message, blockhash),
+                    memo,
+                )));
+            }
+        }
+        if !ip.is_loopback() && !self.allowed_ips.contains(&ip) {
+            self.check_time_request_limit(lamports, ip)?;
+        }
 
-                if let Some(cap) = self.per_request_cap {
-                    if lamports > cap {
-                        let memo = format!(
-                            "{}",
-                            FaucetError::PerRequestCapExceeded(
-                                lamports_to_sol(lamports),
-                                lamports_to_sol(cap),
-                            )
-                        );
-                        let memo_instruction = Instruction {
-                            program_id: Pubkey::new(&spl_memo::id().to_bytes()),
-                            accounts: vec![],
-                            data: memo.as_bytes().to_vec(),
-                        };
-                        let message = Message::new(&[memo_instruction], Some(&mint_pubkey));
-                        return Ok(FaucetTransaction::Memo((
-                            Transaction::new(&[&self.faucet_keypair], message, blockhash),
-                            memo,
-                        )));
-                    }
-                }
-                if !ip.is_loopback() && !self.allowed_ips.contains(&ip) {
-                    self.check_time_request_limit(lamports, ip)?;
-                }
+        let instructions = match req {
+            FaucetRequest::GetAirdrop { to, .. } => {
                 self.check_time_request_limit(lamports, to)?;
-
-                let transfer_instruction =
-                    system_instruction::transfer(&mint_pubkey, &to, lamports);
-                let message = Message::new(&[transfer_instruction], Some(&mint_pubkey));
-                Ok(FaucetTransaction::Airdrop(Transaction::new(
-                    &[&self.faucet_keypair],
-                    message,
-                    blockhash,
-                )))
+                vec![system_instruction::transfer(&mint_pubkey, &to, lamports)]
             }
-        }
+            FaucetRequest::GetEvmAirdrop { to, .. } => {
+                self.check_time_request_limit(lamports, to)?;
+                solana_evm_loader_program::transfer_native_to_evm_ixs(mint_pubkey, lamports, to)
+            }
+        };
+        let message = Message::new(&instructions, Some(&mint_pubkey));
+        Ok(FaucetTransaction::Airdrop(Transaction::new(
+            &[&self.faucet_keypair],
+            message,
+            blockhash,
+        )))
     }
 
     /// Deserializes a received airdrop request, and returns a serialized transaction
@@ -286,14 +338,38 @@ pub fn request_airdrop_transaction(
         "request_airdrop_transaction: faucet_addr={} id={} lamports={} blockhash={}",
         faucet_addr, id, lamports, blockhash
     );
-
-    let mut stream = TcpStream::connect_timeout(faucet_addr, Duration::new(3, 0))?;
-    stream.set_read_timeout(Some(Duration::new(10, 0)))?;
     let req = FaucetRequest::GetAirdrop {
         lamports,
         blockhash,
         to: *id,
     };
+    request_transaction(faucet_addr, req)
+}
+
+pub fn request_evm_airdrop_transaction(
+    faucet_addr: &SocketAddr,
+    address: &H160,
+    lamports: u64,
+    blockhash: Hash,
+) -> Result<Transaction, FaucetError> {
+    info!(
+        "request_evm_airdrop_transaction: faucet_addr={} address={:?} lamports={} blockhash={}",
+        faucet_addr, address, lamports, blockhash
+    );
+    let req = FaucetRequest::GetEvmAirdrop {
+        lamports,
+        blockhash,
+        to: *address,
+    };
+    request_transaction(faucet_addr, req)
+}
+
+fn request_transaction(
+    faucet_addr: &SocketAddr,
+    req: FaucetRequest,
+) -> Result<Transaction, FaucetError> {
+    let mut stream = TcpStream::connect_timeout(faucet_addr, Duration::new(3, 0))?;
+    stream.set_read_timeout(Some(Duration::new(10, 0)))?;
     let req = serialize(&req).expect("serialize faucet request");
     stream.write_all(&req)?;
 
@@ -408,16 +484,21 @@ async fn process(
     mut stream: TokioTcpStream,
     faucet: Arc<Mutex<Faucet>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut request = vec![
-        0u8;
-        serialized_size(&FaucetRequest::GetAirdrop {
-            lamports: u64::default(),
-            to: Pubkey::default(),
-            blockhash: Hash::default(),
-        })
-        .unwrap() as usize
-    ];
-    while stream.read_exact(&mut request).await.is_ok() {
+    // Requests have different sizes, so variant index is read first.
+    let mut variant = [0u8; 4];
+    while stream.read_exact(&mut variant).await.is_ok() {
+        let request_size =
+            match FaucetRequest::serialized_size_of_variant(LittleEndian::read_u32(&variant)) {
+                Some(request_size) => request_size,
+                None => {
+                    info!("Unknown faucet request: {:?}", variant);
+                    stream.write_all(&ERROR_RESPONSE).await?;
+                    break;
+                }
+            };
+        let mut request = variant.to_vec();
+        request.resize(request_size, 0);
+        stream.read_exact(&mut request[variant.len()..]).await?;
         trace!("{:?}", request);
 
         let response = {
@@ -492,6 +573,25 @@ impl LimitByTime for Pubkey {
     }
 }
 
+impl LimitByTime for H160 {
+    fn check_cache(&self, faucet: &mut Faucet, request_amount: u64) -> u64 {
+        *faucet
+            .evm_address_cache
+            .entry(*self)
+            .and_modify(|total| *total = total.saturating_add(request_amount))
+            .or_insert(request_amount)
+    }
+
+    fn datapoint_info(&self, request_amount: u64, new_total: u64) {
+        datapoint_info!(
+            "faucet-airdrop",
+            ("request_amount", request_amount, i64),
+            ("evm_address", format!("{:?}", self), String),
+            ("new_total", new_total, i64)
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, solana_sdk::system_instruction::SystemInstruction, std::time::Duration};
@@ -649,6 +749,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_faucet_build_evm_airdrop_transaction() {
+        let to = H160::repeat_byte(0x11);
+        let blockhash = Hash::default();
+        let request = FaucetRequest::GetEvmAirdrop {
+            lamports: 2,
+            to,
+            blockhash,
+        };
+        let ip = socketaddr!([127, 0, 0, 1], 0).ip();
+
+        let mint = Keypair::new();
+        let mint_pubkey = mint.pubkey();
+        let mut faucet = Faucet::new(mint, None, Some(2), None);
+
+        if let FaucetTransaction::Airdrop(tx) =
+            faucet.build_airdrop_transaction(request, ip).unwrap()
+        {
+            let expected_message = Message::new(
+                &solana_evm_loader_program::transfer_native_to_evm_ixs(mint_pubkey, 2, to),
+                Some(&mint_pubkey),
+            );
+            assert_eq!(tx.signatures.len(), 1);
+            assert_eq!(tx.message().account_keys, expected_message.account_keys);
+            assert_eq!(tx.message().instructions, expected_message.instructions);
+            assert_eq!(tx.message().recent_blockhash, blockhash);
+        } else {
+            panic!("evm airdrop should succeed");
+        }
+
+        // Per-time cap is tracked by evm address
+        assert!(faucet.build_airdrop_transaction(request, ip).is_err());
+        assert_eq!(faucet.evm_address_cache.get(&to), Some(&4));
+        faucet.clear_caches();
+        assert!(faucet.evm_address_cache.is_empty());
+
+        // Per-request cap
+        let mut faucet = Faucet::new(Keypair::new(), None, None, Some(1));
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request, ip).unwrap(),
+            FaucetTransaction::Memo(_)
+        ));
+    }
+
+    #[test]
+    fn test_faucet_request_serialized_size() {
+        let requests = [
+            FaucetRequest::GetAirdrop {
+                lamports: u64::MAX,
+                to: Pubkey::new_unique(),
+                blockhash: Hash::new_unique(),
+            },
+            FaucetRequest::GetEvmAirdrop {
+                lamports: u64::MAX,
+                to: H160::repeat_byte(0xff),
+                blockhash: Hash::new_unique(),
+            },
+        ];
+        for request in requests {
+            let bytes = serialize(&request).unwrap();
+            let variant = LittleEndian::read_u32(&bytes);
+            assert_eq!(
+                FaucetRequest::serialized_size_of_variant(variant),
+                Some(bytes.len())
+            );
+        }
+        assert_eq!(FaucetRequest::serialized_size_of_variant(2), None);
+    }
+
     #[test]
     fn test_process_faucet_request() {
         let to = solana_sdk::pubkey::new_rand();
@@ -1,5 +1,8 @@
 use {
-    solana_faucet::faucet::{request_airdrop_transaction, run_local_faucet},
+    evm_state::H160,
+    solana_faucet::faucet::{
+        request_airdrop_transaction, request_evm_airdrop_transaction, run_local_faucet,
+    },
     solana_sdk::{
         hash::Hash,
         message::Message,
@@ -24,3 +27,23 @@ fn test_local_faucet() {
     let result = request_airdrop_transaction(&faucet_addr, &to, lamports, blockhash);
     assert_eq!(expected_tx, result.unwrap());
 }
+
+#[test]
+fn test_local_evm_faucet() {
+    let keypair = Keypair::new();
+    let to = H160::repeat_byte(0x11);
+    let lamports = 50;
+    let blockhash = Hash::new_unique();
+    let instructions =
+        solana_evm_loader_program::transfer_native_to_evm_ixs(keypair.pubkey(), lamports, to);
+    let message = Message::new(&instructions, Some(&keypair.pubkey()));
+    let expected_tx = Transaction::new(&[&keypair], message, blockhash);
+
+    let faucet_addr = run_local_faucet(keypair, None);
+
+    // Both kinds of requests are served by the same faucet.
+    let native_to = solana_sdk::pubkey::new_rand();
+    assert!(request_airdrop_transaction(&faucet_addr, &native_to, lamports, blockhash).is_ok());
+    let result = request_evm_airdrop_transaction(&faucet_addr, &to, lamports, blockhash);
+    assert_eq!(expected_tx, result.unwrap());
+}
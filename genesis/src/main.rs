@@ -26,7 +26,7 @@ use {
         fee_calculator::FeeRateGovernor,
         genesis_config::{
             self,
-            evm_genesis::{GethAccountExtractor, OpenEthereumAccountExtractor},
+            evm_genesis::{GethAccountExtractor, OpenEthereumAccountExtractor, StateDumpExtractor},
             ClusterType, GenesisConfig,
         },
         inflation::Inflation,
@@ -407,7 +407,7 @@ fn main() -> Result<(), Box<dyn error::Error>> {
             Arg::with_name("evm-state-format")
                 .long("evm-state-format")
                 .takes_value(true)
-                .help("EVM state json file format [`open-ethereum` or `geth`]")
+                .help("EVM state file format [`open-ethereum`, `geth` or `state-dump`]")
         ).arg(
             Arg::with_name("evm-chain-id")
                 .required(false)
@@ -578,9 +578,14 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         (Some(path), Some(format)) if format == "open-ethereum" => {
             EvmStateJson::OpenEthereum(std::path::Path::new(path))
         }
+        (Some(path), Some(format)) if format == "state-dump" => {
+            EvmStateJson::StateDump(std::path::Path::new(path))
+        }
         (None, _) => EvmStateJson::None,
         _ => {
-            panic!("`evm-state-format` argument value must be `open-ethereum` or `geth`")
+            panic!(
+                "`evm-state-format` argument value must be `open-ethereum`, `geth` or `state-dump`"
+            )
         }
     };
 
@@ -686,6 +691,24 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                 evm_state_balance += pair.unwrap().account.balance;
             }
         }
+        EvmStateJson::StateDump(path) => {
+            info!("Calculating evm state lamports");
+            let dump_extractor = StateDumpExtractor::open_dump(path)
+                .unwrap_or_else(|_| panic!("Unable to open dump at path: `{}`", path.display()));
+            // State dump contains its root, so it can be omitted in arguments.
+            if !matches.is_present("evm-root") {
+                genesis_config.set_evm_root_hash(dump_extractor.root());
+            }
+
+            for pair in dump_extractor {
+                evm_state_balance += pair.unwrap().account.balance;
+            }
+        }
+        EvmStateJson::Accounts(accounts) => {
+            for pair in accounts {
+                evm_state_balance += pair.account.balance;
+            }
+        }
         EvmStateJson::None => {
             info!("No evm state file provided");
         }
@@ -83,6 +83,97 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_1(&'a ReplicaBlockInfo<'a>),
 }
 
+/// Information about an EVM block, produced in a slot.
+/// Hashes, addresses and big numbers are passed as big endian bytes.
+#[derive(Clone, Debug)]
+pub struct ReplicaEvmBlockInfo<'a> {
+    /// The native slot, in which the block was produced.
+    pub slot: u64,
+    pub block_number: u64,
+    pub block_hash: &'a [u8],
+    pub parent_hash: &'a [u8],
+    pub state_root: &'a [u8],
+    pub transactions_root: &'a [u8],
+    pub receipts_root: &'a [u8],
+    pub logs_bloom: &'a [u8],
+    /// The hash of the native block, the EVM block belongs to.
+    pub native_chain_hash: &'a [u8],
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub transaction_count: u64,
+}
+
+/// A wrapper to future-proof ReplicaEvmBlockInfo handling.
+pub enum ReplicaEvmBlockInfoVersions<'a> {
+    V0_0_1(&'a ReplicaEvmBlockInfo<'a>),
+}
+
+/// Log emitted during EVM transaction execution
+#[derive(Clone, Debug)]
+pub struct ReplicaEvmLog<'a> {
+    pub address: &'a [u8],
+    pub topics: Vec<&'a [u8]>,
+    pub data: &'a [u8],
+}
+
+/// Information about an EVM transaction and its receipt
+#[derive(Clone, Debug)]
+pub struct ReplicaEvmTransactionInfo<'a> {
+    pub hash: &'a [u8],
+    pub block_number: u64,
+    /// The position of the transaction in the block, as stored in the receipt.
+    pub index: u64,
+    /// RLP encoded transaction, as it is stored in the receipt.
+    pub transaction: &'a [u8],
+    /// Indicates if the transaction execution succeeded.
+    pub succeed: bool,
+    /// Human readable exit reason of the transaction execution.
+    pub exit_reason: &'a str,
+    pub used_gas: u64,
+    pub logs_bloom: &'a [u8],
+    pub logs: &'a [ReplicaEvmLog<'a>],
+}
+
+/// A wrapper to future-proof ReplicaEvmTransactionInfo handling.
+pub enum ReplicaEvmTransactionInfoVersions<'a> {
+    V0_0_1(&'a ReplicaEvmTransactionInfo<'a>),
+}
+
+/// New state of an EVM account
+#[derive(Clone, Debug)]
+pub struct ReplicaEvmAccountState<'a> {
+    pub nonce: [u8; 32],
+    pub balance: [u8; 32],
+    pub code: &'a [u8],
+}
+
+/// Changes of an EVM account, made by a block
+#[derive(Clone, Debug)]
+pub struct ReplicaEvmAccountChange<'a> {
+    pub address: &'a [u8],
+    /// The new state of the account, `None` if the account was removed.
+    pub state: Option<ReplicaEvmAccountState<'a>>,
+    /// Changed storage slots with their new values.
+    pub storage: Vec<(&'a [u8], &'a [u8])>,
+}
+
+/// Accounts and storage, changed by an EVM block
+#[derive(Clone, Debug)]
+pub struct ReplicaEvmStateDiffInfo<'a> {
+    pub block_number: u64,
+    /// The state root before the changes.
+    pub parent_state_root: &'a [u8],
+    /// The state root after the changes.
+    pub state_root: &'a [u8],
+    pub accounts: &'a [ReplicaEvmAccountChange<'a>],
+}
+
+/// A wrapper to future-proof ReplicaEvmStateDiffInfo handling.
+pub enum ReplicaEvmStateDiffInfoVersions<'a> {
+    V0_0_1(&'a ReplicaEvmStateDiffInfo<'a>),
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 pub enum GeyserPluginError {
@@ -111,6 +202,10 @@ pub enum GeyserPluginError {
     /// Error when updating the transaction.
     #[error("Error updating transaction. Error message: ({msg})")]
     TransactionUpdateError { msg: String },
+
+    /// Error when notifying EVM block, transaction or state changes.
+    #[error("Error updating EVM data. Error message: ({msg})")]
+    EvmUpdateError { msg: String },
 }
 
 /// The current status of a slot
@@ -206,6 +301,32 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called when an EVM block is produced in a slot.
+    #[allow(unused_variables)]
+    fn notify_evm_block(&mut self, block: ReplicaEvmBlockInfoVersions) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for every transaction of an EVM block, after the block itself is notified.
+    #[allow(unused_variables)]
+    fn notify_evm_transaction(
+        &mut self,
+        transaction: ReplicaEvmTransactionInfoVersions,
+        slot: u64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called with EVM accounts and storage, changed by the block, after its transactions are notified.
+    #[allow(unused_variables)]
+    fn notify_evm_state_diff(
+        &mut self,
+        state_diff: ReplicaEvmStateDiffInfoVersions,
+        slot: u64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
@@ -219,4 +340,11 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     fn transaction_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in EVM blocks, transactions and state changes
+    /// Default is false -- if the plugin is interested in
+    /// EVM data, please return true.
+    fn evm_notifications_enabled(&self) -> bool {
+        false
+    }
 }
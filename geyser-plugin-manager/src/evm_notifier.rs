@@ -0,0 +1,211 @@
+/// Module responsible for notifying plugins of EVM blocks, receipts and state changes
+use {
+    crate::{evm_notifier_interface::EvmNotifier, geyser_plugin_manager::GeyserPluginManager},
+    evm_state::{Block, ChangedState, ExitReason, Maybe, H256, U256},
+    log::*,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaEvmAccountChange, ReplicaEvmAccountState, ReplicaEvmBlockInfo,
+        ReplicaEvmBlockInfoVersions, ReplicaEvmLog, ReplicaEvmStateDiffInfo,
+        ReplicaEvmStateDiffInfoVersions, ReplicaEvmTransactionInfo,
+        ReplicaEvmTransactionInfoVersions,
+    },
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_sdk::clock::Slot,
+    std::sync::{Arc, RwLock},
+};
+
+/// Receipt data, that is not stored in the block as is, and should be encoded for plugins.
+struct EncodedReceipt<'a> {
+    transaction: Vec<u8>,
+    exit_reason: String,
+    logs: Vec<ReplicaEvmLog<'a>>,
+}
+
+pub(crate) struct EvmNotifierImpl {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+}
+
+impl EvmNotifier for EvmNotifierImpl {
+    fn notify_evm_block(
+        &self,
+        slot: Slot,
+        block: &Block,
+        state_change: Option<&(H256, ChangedState)>,
+    ) {
+        let mut plugin_manager = self.plugin_manager.write().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+        let mut measure = Measure::start("geyser-plugin-notify-evm-block");
+
+        let block_hash = block.header.hash();
+        let block_info = Self::build_replica_evm_block_info(slot, &block_hash, block);
+
+        let receipts: Vec<_> = block
+            .transactions
+            .iter()
+            .map(|(_, receipt)| EncodedReceipt {
+                transaction: rlp::encode(&receipt.transaction).to_vec(),
+                exit_reason: format!("{:?}", receipt.status),
+                logs: receipt
+                    .logs
+                    .iter()
+                    .map(|log| ReplicaEvmLog {
+                        address: log.address.as_bytes(),
+                        topics: log.topics.iter().map(|topic| topic.as_bytes()).collect(),
+                        data: &log.data,
+                    })
+                    .collect(),
+            })
+            .collect();
+        let transaction_infos: Vec<_> = block
+            .transactions
+            .iter()
+            .zip(&receipts)
+            .map(|((hash, receipt), encoded)| ReplicaEvmTransactionInfo {
+                hash: hash.as_bytes(),
+                block_number: receipt.block_number,
+                index: receipt.index,
+                transaction: &encoded.transaction,
+                succeed: matches!(receipt.status, ExitReason::Succeed(_)),
+                exit_reason: &encoded.exit_reason,
+                used_gas: receipt.used_gas,
+                logs_bloom: receipt.logs_bloom.as_bytes(),
+                logs: &encoded.logs,
+            })
+            .collect();
+
+        let accounts = state_change
+            .map(|(_, changes)| Self::build_replica_evm_account_changes(changes))
+            .unwrap_or_default();
+        let state_diff_info = state_change.map(|(parent_state_root, _)| ReplicaEvmStateDiffInfo {
+            block_number: block.header.block_number,
+            parent_state_root: parent_state_root.as_bytes(),
+            state_root: block.header.state_root.as_bytes(),
+            accounts: &accounts,
+        });
+
+        for plugin in plugin_manager.plugins.iter_mut() {
+            if !plugin.evm_notifications_enabled() {
+                continue;
+            }
+            if let Err(err) =
+                plugin.notify_evm_block(ReplicaEvmBlockInfoVersions::V0_0_1(&block_info))
+            {
+                error!(
+                    "Failed to notify evm block {} at slot {}, error: {} to plugin {}",
+                    block.header.block_number,
+                    slot,
+                    err,
+                    plugin.name()
+                );
+                continue;
+            }
+            for transaction_info in &transaction_infos {
+                if let Err(err) = plugin.notify_evm_transaction(
+                    ReplicaEvmTransactionInfoVersions::V0_0_1(transaction_info),
+                    slot,
+                ) {
+                    error!(
+                        "Failed to notify evm transaction at slot {}, error: {} to plugin {}",
+                        slot,
+                        err,
+                        plugin.name()
+                    );
+                }
+            }
+            if let Some(state_diff_info) = &state_diff_info {
+                if let Err(err) = plugin.notify_evm_state_diff(
+                    ReplicaEvmStateDiffInfoVersions::V0_0_1(state_diff_info),
+                    slot,
+                ) {
+                    error!(
+                        "Failed to notify evm state diff at slot {}, error: {} to plugin {}",
+                        slot,
+                        err,
+                        plugin.name()
+                    );
+                }
+            }
+            trace!(
+                "Successfully notified evm block {} at slot {} to plugin {}",
+                block.header.block_number,
+                slot,
+                plugin.name()
+            );
+        }
+        measure.stop();
+        inc_new_counter_debug!(
+            "geyser-plugin-notify-evm-block-us",
+            measure.as_us() as usize,
+            1000,
+            1000
+        );
+    }
+}
+
+impl EvmNotifierImpl {
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self { plugin_manager }
+    }
+
+    fn build_replica_evm_block_info<'a>(
+        slot: Slot,
+        block_hash: &'a H256,
+        block: &'a Block,
+    ) -> ReplicaEvmBlockInfo<'a> {
+        let header = &block.header;
+        ReplicaEvmBlockInfo {
+            slot,
+            block_number: header.block_number,
+            block_hash: block_hash.as_bytes(),
+            parent_hash: header.parent_hash.as_bytes(),
+            state_root: header.state_root.as_bytes(),
+            transactions_root: header.transactions_root.as_bytes(),
+            receipts_root: header.receipts_root.as_bytes(),
+            logs_bloom: header.logs_bloom.as_bytes(),
+            native_chain_hash: header.native_chain_hash.as_bytes(),
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            timestamp: header.timestamp,
+            transaction_count: block.transactions.len() as u64,
+        }
+    }
+
+    /// Changed accounts, sorted by address.
+    fn build_replica_evm_account_changes(changes: &ChangedState) -> Vec<ReplicaEvmAccountChange> {
+        fn to_big_endian(value: U256) -> [u8; 32] {
+            let mut bytes = [0; 32];
+            value.to_big_endian(&mut bytes);
+            bytes
+        }
+
+        let mut accounts: Vec<_> = changes
+            .iter()
+            .map(|(address, (state, storage))| {
+                let state = match state {
+                    Maybe::Just(state) => {
+                        let code: &Vec<u8> = state.code.as_ref();
+                        Some(ReplicaEvmAccountState {
+                            nonce: to_big_endian(state.nonce),
+                            balance: to_big_endian(state.balance),
+                            code,
+                        })
+                    }
+                    Maybe::Nothing => None,
+                };
+                ReplicaEvmAccountChange {
+                    address: address.as_bytes(),
+                    state,
+                    storage: storage
+                        .iter()
+                        .map(|(index, value)| (index.as_bytes(), value.as_bytes()))
+                        .collect(),
+                }
+            })
+            .collect();
+        accounts.sort_by_key(|account| account.address);
+        accounts
+    }
+}
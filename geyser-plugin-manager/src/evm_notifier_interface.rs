@@ -0,0 +1,19 @@
+use {
+    evm_state::{Block, ChangedState, H256},
+    solana_sdk::clock::Slot,
+    std::sync::{Arc, RwLock},
+};
+
+/// Interface for notifying EVM blocks, their receipts and state changes
+pub trait EvmNotifier {
+    /// Notify the EVM block produced in `slot`.
+    /// `state_change` contains the state root before the block, and accounts changed by the block.
+    fn notify_evm_block(
+        &self,
+        slot: Slot,
+        block: &Block,
+        state_change: Option<&(H256, ChangedState)>,
+    );
+}
+
+pub type EvmNotifierLock = Arc<RwLock<dyn EvmNotifier + Sync + Send>>;
@@ -72,4 +72,14 @@ impl GeyserPluginManager {
         }
         false
     }
+
+    /// Check if there is any plugin interested in EVM data
+    pub fn evm_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.evm_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
 }
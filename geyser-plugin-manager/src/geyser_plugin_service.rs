@@ -3,6 +3,7 @@ use {
         accounts_update_notifier::AccountsUpdateNotifierImpl,
         block_metadata_notifier::BlockMetadataNotifierImpl,
         block_metadata_notifier_interface::BlockMetadataNotifierLock,
+        evm_notifier::EvmNotifierImpl, evm_notifier_interface::EvmNotifierLock,
         geyser_plugin_manager::GeyserPluginManager, slot_status_notifier::SlotStatusNotifierImpl,
         slot_status_observer::SlotStatusObserver, transaction_notifier::TransactionNotifierImpl,
     },
@@ -51,6 +52,7 @@ pub struct GeyserPluginService {
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     transaction_notifier: Option<TransactionNotifierLock>,
     block_metadata_notifier: Option<BlockMetadataNotifierLock>,
+    evm_notifier: Option<EvmNotifierLock>,
 }
 
 impl GeyserPluginService {
@@ -83,6 +85,7 @@ impl GeyserPluginService {
         let account_data_notifications_enabled =
             plugin_manager.account_data_notifications_enabled();
         let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled();
+        let evm_notifications_enabled = plugin_manager.evm_notifications_enabled();
 
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
@@ -103,6 +106,13 @@ impl GeyserPluginService {
                 None
             };
 
+        let evm_notifier: Option<EvmNotifierLock> = if evm_notifications_enabled {
+            let evm_notifier = EvmNotifierImpl::new(plugin_manager.clone());
+            Some(Arc::new(RwLock::new(evm_notifier)))
+        } else {
+            None
+        };
+
         let (slot_status_observer, block_metadata_notifier): (
             Option<SlotStatusObserver>,
             Option<BlockMetadataNotifierLock>,
@@ -129,6 +139,7 @@ impl GeyserPluginService {
             accounts_update_notifier,
             transaction_notifier,
             block_metadata_notifier,
+            evm_notifier,
         })
     }
 
@@ -208,6 +219,10 @@ impl GeyserPluginService {
         self.block_metadata_notifier.clone()
     }
 
+    pub fn get_evm_notifier(&self) -> Option<EvmNotifierLock> {
+        self.evm_notifier.clone()
+    }
+
     pub fn join(self) -> thread::Result<()> {
         if let Some(mut slot_status_observer) = self.slot_status_observer {
             slot_status_observer.join()?;
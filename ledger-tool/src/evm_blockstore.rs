@@ -4,7 +4,7 @@ use solana_clap_utils::input_validators::is_slot;
 
 use solana_ledger::{blockstore::Blockstore, blockstore_db::AccessType};
 use solana_sdk::clock::Slot;
-use std::{path::Path, process::exit, result::Result};
+use std::{path::Path, process::exit, result::Result, sync::atomic::AtomicBool};
 
 pub fn modify_block(
     blockstore: Blockstore,
@@ -37,6 +37,9 @@ pub fn modify_block(
             .into());
         }
     }
+    blockstore
+        .write_evm_logs_index(&block)
+        .expect("Expected database write to succed");
     blockstore
         .write_evm_block_header(&block.header)
         .expect("Expected database write to succed");
@@ -99,6 +102,17 @@ pub fn blocks(
     Ok(())
 }
 
+pub fn backfill_logs_index(blockstore: Blockstore) -> Result<(), Box<dyn std::error::Error>> {
+    let exit = AtomicBool::new(false);
+    let indexed = blockstore.backfill_evm_logs_index(&exit)?;
+    println!(
+        "{} blocks indexed, logs index is complete from block {:?}",
+        indexed,
+        blockstore.read_evm_logs_indexed_from()?
+    );
+    Ok(())
+}
+
 pub trait EvmBlockstoreSubcommand {
     fn evm_blockstore_subcommand(self) -> Self;
 }
@@ -193,7 +207,10 @@ impl EvmBlockstoreSubcommand for App<'_, '_> {
                                 .takes_value(false)
                                 .help("Do not Check next blocks parent_hash"),
                         ),
-                ),
+                )
+                .subcommand(SubCommand::with_name("backfill-logs-index").about(
+                    "Add logs of blocks, stored before logs index was introduced, to the index",
+                )),
         )
     }
 }
@@ -220,6 +237,7 @@ pub fn evm_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'
         }
         ("first-available-block", Some(_arg_matches)) => first_available_block(blockstore),
         ("last-available-block", Some(_arg_matches)) => last_available_block(blockstore),
+        ("backfill-logs-index", Some(_arg_matches)) => backfill_logs_index(blockstore),
         ("block", Some(arg_matches)) => {
             let block_num = value_t_or_exit!(arg_matches, "block", Slot);
             block(blockstore, block_num)
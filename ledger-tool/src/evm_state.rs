@@ -1,4 +1,6 @@
 use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
     path::{Path, PathBuf},
     sync::atomic::AtomicU64,
 };
@@ -17,7 +19,7 @@ use rayon::prelude::*;
 
 use evm_state::{
     storage::cleaner,
-    storage::{inspectors, Storage},
+    storage::{cold::ColdStore, inspectors, Storage},
     H256,
 };
 // use rayon::prelude::*;
@@ -106,6 +108,56 @@ impl EvmStateSubCommand for App<'_, '_> {
                 )
                 .subcommand(
                     SubCommand::with_name("list-roots").about("List roots in gc counter table"),
+                )
+                .subcommand(
+                    SubCommand::with_name("dump")
+                        .about("Dump EVM accounts, code and storage into portable state dump file")
+                        .setting(AppSettings::ArgRequiredElseHelp)
+                        .arg(
+                            Arg::with_name(ROOT_ARG.name)
+                                .long(ROOT_ARG.long)
+                                .required(true)
+                                .takes_value(true)
+                                .help(ROOT_ARG.help),
+                        )
+                        .arg(
+                            Arg::with_name("output")
+                                .long("output")
+                                .required(true)
+                                .takes_value(true)
+                                .help("Path to state dump file"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("load")
+                        .about("Build EVM state in destination RocksDB from state dump file, and verify its root")
+                        .setting(AppSettings::ArgRequiredElseHelp)
+                        .arg(
+                            Arg::with_name("input")
+                                .long("input")
+                                .required(true)
+                                .takes_value(true)
+                                .help("Path to state dump file"),
+                        )
+                        .arg(
+                            Arg::with_name("destination")
+                                .long("destination")
+                                .required(true)
+                                .takes_value(true)
+                                .help("Path to destination RocksDB, should not contain any state"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("verify-cold")
+                        .about("Verify hashes of all nodes stored in cold tier of EVM state archive")
+                        .setting(AppSettings::ArgRequiredElseHelp)
+                        .arg(
+                            Arg::with_name("cold")
+                                .long("cold")
+                                .required(true)
+                                .takes_value(true)
+                                .help("Path to cold tier of EVM state archive"),
+                        ),
                 ),
         )
     }
@@ -226,6 +278,55 @@ pub fn process_evm_state_command(evm_state_path: &Path, matches: &ArgMatches<'_>
 
             println!("Total balance = {:?}", walker.data_inspector.inner.balance)
         }
+        ("dump", Some(matches)) => {
+            let root = value_t_or_exit!(matches, ROOT_ARG.name, H256);
+            let output = value_t_or_exit!(matches, "output", PathBuf);
+
+            let file = BufWriter::new(File::create(&output)?);
+            let accounts = storage.dump_state(root, file)?;
+            println!(
+                "Dumped {} accounts of state {:?} into {}",
+                accounts,
+                root,
+                output.display()
+            );
+        }
+        ("load", Some(matches)) => {
+            let input = value_t_or_exit!(matches, "input", PathBuf);
+            let destination = value_t_or_exit!(matches, "destination", PathBuf);
+
+            ensure!(
+                !destination.exists(),
+                "Destination {} already exists",
+                destination.display()
+            );
+            let destination = Storage::open_persistent(
+                destination,
+                true, // enable gc
+            )?;
+            let root = destination.load_state_dump(BufReader::new(File::open(&input)?))?;
+            println!("Loaded state {:?} from {}", root, input.display());
+        }
+        ("verify-cold", Some(matches)) => {
+            let cold = value_t_or_exit!(matches, "cold", PathBuf);
+
+            let verification = ColdStore::open(&cold)?.verify()?;
+            println!(
+                "Verified {} nodes ({} bytes) of {} roots in {}",
+                verification.nodes,
+                verification.bytes,
+                verification.roots,
+                cold.display()
+            );
+            for hash in &verification.corrupted {
+                println!("Corrupted node {:?}", hash);
+            }
+            ensure!(
+                verification.corrupted.is_empty(),
+                "Found {} corrupted nodes",
+                verification.corrupted.len()
+            );
+        }
         unhandled => panic!("Unhandled {:?}", unhandled),
     }
     Ok(())
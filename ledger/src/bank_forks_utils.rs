@@ -19,7 +19,7 @@ use {
         snapshot_package::AccountsPackageSender,
         snapshot_utils,
     },
-    solana_sdk::genesis_config::GenesisConfig,
+    solana_sdk::{clock::Slot, genesis_config::GenesisConfig},
     std::{fs, path::PathBuf, process, result},
 };
 
@@ -36,7 +36,7 @@ use std::path::Path;
 
 use evm_state::{ChangedState, H256};
 
-pub type EvmStateRecorderSender = crossbeam_channel::Sender<(H256, ChangedState)>;
+pub type EvmStateRecorderSender = crossbeam_channel::Sender<(Slot, H256, ChangedState)>;
 pub type EvmRecorderSender = crossbeam_channel::Sender<evm_state::Block>;
 
 /// Load the banks via genesis or a snapshot then processes all full blocks in blockstore
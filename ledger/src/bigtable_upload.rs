@@ -494,3 +494,177 @@ pub async fn upload_evm_confirmed_blocks(
         Ok(not_confirmed_blocks)
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct EvmBlockGapRepairConfig {
+    /// Only report missing blocks, without uploading them.
+    pub dry_run: bool,
+    pub check_interval: Duration,
+    pub max_blocks_to_check: usize,
+    /// Uploads per check are limited, so repair doesn't delay upload of new blocks for long.
+    pub max_blocks_to_upload: usize,
+    /// Block is skipped after this number of failed uploads, so it doesn't stall repair forever.
+    pub max_upload_attempts: usize,
+}
+
+impl Default for EvmBlockGapRepairConfig {
+    fn default() -> Self {
+        EvmBlockGapRepairConfig {
+            dry_run: false,
+            check_interval: Duration::from_secs(10 * 60),
+            max_blocks_to_check: 100_000,
+            max_blocks_to_upload: 1_000,
+            max_upload_attempts: 5,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EvmBlockGapRepairStats {
+    pub checked: usize,
+    pub missing: usize,
+    pub repaired: usize,
+    /// Missing blocks, that are not confirmed or already purged from blockstore.
+    pub unrecoverable: usize,
+    pub failed: usize,
+    /// First missing block, which upload failed.
+    pub first_failed: Option<evm_state::BlockNum>,
+    /// First missing block, that was not handled because of upload limit or exit.
+    pub next_block: Option<evm_state::BlockNum>,
+}
+
+/// Returns block numbers from `starting_block..=ending_block`, that are absent in sorted `present`.
+fn find_missing_evm_blocks(
+    present: &[evm_state::BlockNum],
+    starting_block: evm_state::BlockNum,
+    ending_block: evm_state::BlockNum,
+) -> Vec<evm_state::BlockNum> {
+    let mut present = present.iter().peekable();
+    (starting_block..=ending_block)
+        .filter(|block_num| {
+            while present.next_if(|present| *present < block_num).is_some() {}
+            present.next_if_eq(&block_num).is_none()
+        })
+        .collect()
+}
+
+/// Finds evm blocks in `starting_block..=ending_block`, that are missing from bigtable,
+/// and uploads at most `max_uploads` of them again from blockstore.
+pub async fn repair_evm_block_gaps(
+    blockstore: Arc<Blockstore>,
+    bigtable: solana_storage_bigtable::LedgerStorage,
+    starting_block: evm_state::BlockNum,
+    ending_block: evm_state::BlockNum,
+    dry_run: bool,
+    max_uploads: usize,
+    exit: Arc<AtomicBool>,
+) -> Result<EvmBlockGapRepairStats, Box<dyn std::error::Error>> {
+    let mut measure = Measure::start("evm block gap repair");
+    let mut stats = EvmBlockGapRepairStats {
+        checked: ending_block.saturating_sub(starting_block) as usize + 1,
+        ..EvmBlockGapRepairStats::default()
+    };
+
+    let mut bigtable_blocks = vec![];
+    let mut start_block = starting_block;
+    while start_block <= ending_block {
+        let mut next_bigtable_blocks = bigtable
+            .get_evm_confirmed_full_blocks_nums(start_block, 1000)
+            .await
+            .map_err(|err| {
+                format!(
+                    "Failed to load list of bigtable evm blocks from {}: {:?}",
+                    start_block, err
+                )
+            })?;
+        match next_bigtable_blocks.last() {
+            Some(last_block) => start_block = last_block + 1,
+            None => break,
+        }
+        bigtable_blocks.append(&mut next_bigtable_blocks);
+    }
+
+    let missing_blocks = find_missing_evm_blocks(&bigtable_blocks, starting_block, ending_block);
+    stats.missing = missing_blocks.len();
+    if !missing_blocks.is_empty() {
+        warn!(
+            "Found {} evm blocks missing in bigtable in the range ({}, {}), dry_run={}",
+            missing_blocks.len(),
+            starting_block,
+            ending_block,
+            dry_run
+        );
+    }
+
+    for block_num in missing_blocks {
+        if exit.load(Ordering::Relaxed) || stats.repaired + stats.failed >= max_uploads {
+            stats.next_block = Some(block_num);
+            break;
+        }
+        let block = match blockstore.get_evm_block(block_num) {
+            Ok((block, true)) => block,
+            Ok((_, false)) => {
+                warn!("Missing evm block {} is not confirmed", block_num);
+                stats.unrecoverable += 1;
+                continue;
+            }
+            Err(err) => {
+                warn!(
+                    "Missing evm block {} can't be loaded from blockstore: {:?}",
+                    block_num, err
+                );
+                stats.unrecoverable += 1;
+                continue;
+            }
+        };
+        if dry_run {
+            info!(
+                "Missing evm block {} can be repaired from blockstore",
+                block_num
+            );
+            continue;
+        }
+        match bigtable.upload_evm_block(block_num, block).await {
+            Ok(()) => {
+                info!("Missing evm block {} uploaded", block_num);
+                stats.repaired += 1;
+            }
+            Err(err) => {
+                error!("upload_evm_block({}) failed: {:?}", block_num, err);
+                stats.failed += 1;
+                stats.first_failed.get_or_insert(block_num);
+            }
+        }
+    }
+
+    measure.stop();
+    datapoint_info!(
+        "bigtable-evm-gap-repair",
+        ("starting_block", starting_block, i64),
+        ("ending_block", ending_block, i64),
+        ("checked", stats.checked, i64),
+        ("missing", stats.missing, i64),
+        ("repaired", stats.repaired, i64),
+        ("unrecoverable", stats.unrecoverable, i64),
+        ("failed", stats.failed, i64),
+        ("dry_run", dry_run, bool),
+        ("elapsed_us", measure.as_us(), i64),
+    );
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_missing_evm_blocks() {
+        assert_eq!(
+            find_missing_evm_blocks(&[3, 4, 7, 9, 12], 3, 10),
+            vec![5, 6, 8, 10]
+        );
+        assert_eq!(find_missing_evm_blocks(&[], 1, 3), vec![1, 2, 3]);
+        assert_eq!(find_missing_evm_blocks(&[0, 1, 2, 3], 1, 3), vec![]);
+        assert_eq!(find_missing_evm_blocks(&[1, 2, 3], 5, 6), vec![5, 6]);
+    }
+}
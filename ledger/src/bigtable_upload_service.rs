@@ -1,16 +1,17 @@
 use {
     crate::{
-        bigtable_upload::{self, ConfirmedBlockUploadConfig},
+        bigtable_upload::{self, ConfirmedBlockUploadConfig, EvmBlockGapRepairConfig},
         blockstore::Blockstore,
     },
     solana_runtime::commitment::BlockCommitmentCache,
     std::{
-        cmp::min,
+        cmp::{max, min},
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
             Arc, RwLock,
         },
         thread::{self, Builder, JoinHandle},
+        time::{Duration, Instant},
     },
     tokio::runtime::Runtime,
 };
@@ -19,6 +20,58 @@ use {
 
 pub struct BigTableUploadService {
     thread: JoinHandle<()>,
+    evm_gap_repair_thread: Option<JoinHandle<()>>,
+}
+
+/// Progress of evm gap repair, kept between checks.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct EvmGapRepairProgress {
+    /// Evm blocks below this cursor were already checked for gaps in bigtable.
+    start: evm_state::BlockNum,
+    /// Block, which upload failed on previous checks, and number of its failed attempts.
+    failed_block: Option<(evm_state::BlockNum, usize)>,
+}
+
+impl EvmGapRepairProgress {
+    /// Moves cursor after repair of `start..=end_block` range.
+    ///
+    /// Range is retried from the first failed block, until it fails `max_upload_attempts` times,
+    /// then the block is skipped.
+    fn advance(
+        &mut self,
+        end_block: evm_state::BlockNum,
+        stats: &bigtable_upload::EvmBlockGapRepairStats,
+        max_upload_attempts: usize,
+    ) {
+        let failed_block = match stats.first_failed {
+            Some(failed_block) => failed_block,
+            None => {
+                self.failed_block = None;
+                self.start = stats.next_block.unwrap_or(end_block + 1);
+                return;
+            }
+        };
+        let attempts = match self.failed_block {
+            Some((block, attempts)) if block == failed_block => attempts + 1,
+            _ => 1,
+        };
+        if attempts >= max_upload_attempts {
+            error!(
+                "bigtable: evm gap repair skips block {} after {} failed uploads",
+                failed_block, attempts
+            );
+            datapoint_error!(
+                "bigtable-evm-gap-repair-skipped",
+                ("block", failed_block, i64),
+                ("attempts", attempts, i64)
+            );
+            self.failed_block = None;
+            self.start = failed_block + 1;
+        } else {
+            self.failed_block = Some((failed_block, attempts));
+            self.start = failed_block;
+        }
+    }
 }
 
 impl BigTableUploadService {
@@ -37,6 +90,7 @@ impl BigTableUploadService {
             block_commitment_cache,
             max_complete_transaction_status_slot,
             ConfirmedBlockUploadConfig::default(),
+            None,
             exit,
         )
     }
@@ -48,9 +102,32 @@ impl BigTableUploadService {
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         config: ConfirmedBlockUploadConfig,
+        evm_gap_repair_config: Option<EvmBlockGapRepairConfig>,
         exit: Arc<AtomicBool>,
     ) -> Self {
         info!("Starting BigTable upload service");
+        // Gaps are searched only below the last evm block, uploaded by upload thread.
+        let uploaded_evm_block = Arc::new(AtomicU64::new(0));
+        let evm_gap_repair_thread = evm_gap_repair_config.map(|evm_gap_repair_config| {
+            let runtime = runtime.clone();
+            let bigtable_ledger_storage = bigtable_ledger_storage.clone();
+            let blockstore = blockstore.clone();
+            let uploaded_evm_block = uploaded_evm_block.clone();
+            let exit = exit.clone();
+            Builder::new()
+                .name("bigtable-evm-gap-repair".to_string())
+                .spawn(move || {
+                    Self::run_evm_gap_repair(
+                        runtime,
+                        bigtable_ledger_storage,
+                        blockstore,
+                        evm_gap_repair_config,
+                        uploaded_evm_block,
+                        exit,
+                    )
+                })
+                .unwrap()
+        });
         let thread = Builder::new()
             .name("bigtable-upload".to_string())
             .spawn(move || {
@@ -61,12 +138,16 @@ impl BigTableUploadService {
                     block_commitment_cache,
                     max_complete_transaction_status_slot,
                     config,
+                    uploaded_evm_block,
                     exit,
                 )
             })
             .unwrap();
 
-        Self { thread }
+        Self {
+            thread,
+            evm_gap_repair_thread,
+        }
     }
 
     fn run(
@@ -76,6 +157,7 @@ impl BigTableUploadService {
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         config: ConfirmedBlockUploadConfig,
+        uploaded_evm_block: Arc<AtomicU64>,
         exit: Arc<AtomicBool>,
     ) {
         let mut start_slot = blockstore.get_first_available_block().unwrap_or_default();
@@ -143,6 +225,7 @@ impl BigTableUploadService {
             match result {
                 Ok(not_confirmed_blocks) => {
                     start_evm_block = end_block - not_confirmed_blocks;
+                    uploaded_evm_block.store(start_evm_block, Ordering::Relaxed);
                 }
                 Err(err) => {
                     warn!("bigtable: upload_evm_confirmed_blocks: {}", err);
@@ -152,7 +235,187 @@ impl BigTableUploadService {
         }
     }
 
+    /// Checks uploaded evm blocks for gaps every `check_interval`, in its own thread,
+    /// so slow bigtable scans don't delay upload of new blocks.
+    fn run_evm_gap_repair(
+        runtime: Arc<Runtime>,
+        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        blockstore: Arc<Blockstore>,
+        config: EvmBlockGapRepairConfig,
+        uploaded_evm_block: Arc<AtomicU64>,
+        exit: Arc<AtomicBool>,
+    ) {
+        let mut progress = EvmGapRepairProgress::default();
+        let mut last_check = Instant::now();
+        while !exit.load(Ordering::Relaxed) {
+            if last_check.elapsed() < config.check_interval {
+                std::thread::sleep(Duration::from_secs(1));
+                continue;
+            }
+            last_check = Instant::now();
+            Self::repair_evm_block_gaps(
+                &runtime,
+                &bigtable_ledger_storage,
+                &blockstore,
+                &config,
+                &mut progress,
+                uploaded_evm_block.load(Ordering::Relaxed),
+                &exit,
+            );
+        }
+    }
+
+    /// Checks the next range of already uploaded evm blocks for gaps in bigtable, and advances
+    /// `progress` past handled blocks.
+    fn repair_evm_block_gaps(
+        runtime: &Runtime,
+        bigtable_ledger_storage: &solana_storage_bigtable::LedgerStorage,
+        blockstore: &Arc<Blockstore>,
+        config: &EvmBlockGapRepairConfig,
+        progress: &mut EvmGapRepairProgress,
+        uploaded_evm_block: evm_state::BlockNum,
+        exit: &Arc<AtomicBool>,
+    ) {
+        // Blocks purged from blockstore can't be repaired anyway.
+        match blockstore.get_first_available_evm_block() {
+            Ok(evm_state::BlockNum::MAX) => return,
+            Ok(first_available_block) => {
+                progress.start = max(progress.start, first_available_block)
+            }
+            Err(err) => {
+                warn!("bigtable: evm gap repair: {}", err);
+                return;
+            }
+        }
+        if uploaded_evm_block <= progress.start {
+            return;
+        }
+        let end_block = min(
+            uploaded_evm_block - 1,
+            progress
+                .start
+                .saturating_add((config.max_blocks_to_check as u64).saturating_sub(1)),
+        );
+
+        let result = runtime.block_on(bigtable_upload::repair_evm_block_gaps(
+            blockstore.clone(),
+            bigtable_ledger_storage.clone(),
+            progress.start,
+            end_block,
+            config.dry_run,
+            config.max_blocks_to_upload,
+            exit.clone(),
+        ));
+
+        match result {
+            Ok(stats) => {
+                info!(
+                    "bigtable: evm gap repair ({}, {}): {:?}",
+                    progress.start, end_block, stats
+                );
+                progress.advance(end_block, &stats, config.max_upload_attempts);
+            }
+            Err(err) => warn!("bigtable: repair_evm_block_gaps: {}", err),
+        }
+    }
+
     pub fn join(self) -> thread::Result<()> {
+        if let Some(evm_gap_repair_thread) = self.evm_gap_repair_thread {
+            evm_gap_repair_thread.join()?;
+        }
         self.thread.join()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::bigtable_upload::EvmBlockGapRepairStats};
+
+    #[test]
+    fn test_evm_gap_repair_progress() {
+        let next = |stats| {
+            let mut progress = EvmGapRepairProgress {
+                start: 10,
+                failed_block: None,
+            };
+            progress.advance(19, &stats, 3);
+            progress.start
+        };
+        let stats = EvmBlockGapRepairStats {
+            checked: 10,
+            ..EvmBlockGapRepairStats::default()
+        };
+        assert_eq!(next(stats), 20);
+
+        // failed uploads are retried from the first failed block
+        let failed = EvmBlockGapRepairStats {
+            missing: 2,
+            repaired: 1,
+            failed: 1,
+            first_failed: Some(12),
+            ..stats
+        };
+        assert_eq!(next(failed), 12);
+
+        // dry run doesn't upload anything, but advances
+        let dry_run = EvmBlockGapRepairStats {
+            missing: 2,
+            ..stats
+        };
+        assert_eq!(next(dry_run), 20);
+
+        // unrecoverable blocks are not retried
+        let unrecoverable = EvmBlockGapRepairStats {
+            missing: 2,
+            unrecoverable: 2,
+            ..stats
+        };
+        assert_eq!(next(unrecoverable), 20);
+
+        // upload limit or exit continues from the first missing block, that was not handled
+        let limited = EvmBlockGapRepairStats {
+            missing: 3,
+            repaired: 2,
+            next_block: Some(15),
+            ..stats
+        };
+        assert_eq!(next(limited), 15);
+    }
+
+    #[test]
+    fn test_evm_gap_repair_skips_failing_block() {
+        let failed = |first_failed| EvmBlockGapRepairStats {
+            checked: 10,
+            missing: 1,
+            failed: 1,
+            first_failed: Some(first_failed),
+            ..EvmBlockGapRepairStats::default()
+        };
+        let mut progress = EvmGapRepairProgress {
+            start: 10,
+            failed_block: None,
+        };
+        progress.advance(19, &failed(12), 3);
+        progress.advance(21, &failed(12), 3);
+        assert_eq!(
+            progress,
+            EvmGapRepairProgress {
+                start: 12,
+                failed_block: Some((12, 2)),
+            }
+        );
+
+        // another block failed, counter starts again
+        progress.advance(21, &failed(13), 3);
+        assert_eq!(progress.failed_block, Some((13, 1)));
+        progress.advance(22, &failed(13), 3);
+        progress.advance(22, &failed(13), 3);
+        assert_eq!(
+            progress,
+            EvmGapRepairProgress {
+                start: 14,
+                failed_block: None,
+            }
+        );
+    }
+}
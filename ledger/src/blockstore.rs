@@ -6,8 +6,8 @@ use {
         ancestor_iterator::AncestorIterator,
         blockstore_db::{
             columns as cf, AccessType, BlockstoreOptions, Column, ColumnName, Database,
-            IteratorDirection, IteratorMode, LedgerColumn, LedgerColumnOptions, Result,
-            ShredStorageType, WriteBatch,
+            EvmLogsIndexKey, EvmSwapAccount, EvmSwapsIndexKey, IteratorDirection, IteratorMode,
+            LedgerColumn, LedgerColumnOptions, Result, ShredStorageType, WriteBatch,
         },
         blockstore_meta::*,
         leader_schedule_cache::LeaderScheduleCache,
@@ -20,8 +20,9 @@ use {
     },
     bincode::deserialize,
     crossbeam_channel::{bounded, Receiver, Sender, TrySendError},
-    evm::H256,
+    evm::{H160, H256},
     evm_state as evm,
+    itertools::Itertools,
     log::*,
     rayon::{
         iter::{IntoParallelRefIterator, ParallelIterator},
@@ -29,6 +30,7 @@ use {
     },
     rocksdb::DBRawIterator,
     solana_entry::entry::{create_ticks, Entry},
+    solana_evm_loader_program::swap::SwapEvent,
     solana_measure::measure::Measure,
     solana_metrics::{datapoint_debug, datapoint_error},
     solana_rayon_threadlimit::get_thread_count,
@@ -36,7 +38,10 @@ use {
     solana_sdk::{
         clock::{Slot, UnixTimestamp, DEFAULT_TICKS_PER_SECOND, MS_PER_TICK},
         genesis_config::{
-            evm_genesis::{GethAccountExtractor, OpenEthereumAccountExtractor},
+            evm_genesis::{
+                AccountPair, GethAccountExtractor, MemoryAccountsExtractor,
+                OpenEthereumAccountExtractor, StateDumpExtractor,
+            },
             GenesisConfig, DEFAULT_GENESIS_ARCHIVE, DEFAULT_GENESIS_FILE,
         },
         hash::Hash,
@@ -103,6 +108,12 @@ pub const MAX_TURBINE_DELAY_IN_TICKS: u64 = MAX_TURBINE_PROPAGATION_IN_MS / MS_P
 // (32K shreds per slot * 4 TX per shred * 2.5 slots per sec)
 pub const MAX_DATA_SHREDS_PER_SLOT: usize = 32_768;
 
+/// Number of evm blocks, which logs blooms are combined into single record of `EvmLogsBloom` column.
+pub const EVM_LOGS_BLOOM_RANGE: evm::BlockNum = 1024;
+// `EvmLogsBloom` record, that contain first evm block covered by logs index.
+const EVM_LOGS_INDEXED_FROM_KEY: u64 = u64::MAX;
+const EVM_LOGS_BACKFILL_BATCH: usize = 1000;
+
 pub type CompletedSlotsSender = Sender<Vec<Slot>>;
 pub type CompletedSlotsReceiver = Receiver<Vec<Slot>>;
 type CompletedRanges = Vec<(u32, u32)>;
@@ -113,6 +124,13 @@ pub struct SignatureInfosForAddress {
     pub found_before: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmSwapWithSignature {
+    pub slot: Slot,
+    pub signature: Signature,
+    pub swap: SwapEvent,
+}
+
 #[derive(Clone, Copy)]
 /// Controls how `blockstore::purge_slots` purges the data.
 pub enum PurgeType {
@@ -191,6 +209,10 @@ pub struct Blockstore {
     evm_transactions_cf: LedgerColumn<cf::EvmTransactionReceipts>,
     evm_blocks_by_hash_cf: LedgerColumn<cf::EvmHeaderIndexByHash>,
     evm_blocks_by_slot_cf: LedgerColumn<cf::EvmHeaderIndexBySlot>,
+    evm_logs_index_cf: LedgerColumn<cf::EvmLogsIndex>,
+    evm_logs_bloom_cf: LedgerColumn<cf::EvmLogsBloom>,
+    evm_logs_bloom_lock: Mutex<()>,
+    evm_swaps_cf: LedgerColumn<cf::EvmSwaps>,
 }
 
 pub struct IndexMetaWorkingSetEntry {
@@ -598,6 +620,9 @@ impl Blockstore {
         let evm_transactions_cf = db.column();
         let evm_blocks_by_hash_cf = db.column();
         let evm_blocks_by_slot_cf = db.column();
+        let evm_logs_index_cf = db.column();
+        let evm_logs_bloom_cf = db.column();
+        let evm_swaps_cf = db.column();
         let optimistic_slots_cf = db.column();
 
         let db = Arc::new(db);
@@ -663,6 +688,10 @@ impl Blockstore {
             evm_transactions_cf,
             evm_blocks_by_hash_cf,
             evm_blocks_by_slot_cf,
+            evm_logs_index_cf,
+            evm_logs_bloom_cf,
+            evm_logs_bloom_lock: Mutex::<()>::default(),
+            evm_swaps_cf,
         };
         if initialize_transaction_status_index {
             blockstore.initialize_transaction_status_index()?;
@@ -3636,6 +3665,300 @@ impl Blockstore {
         )?;
         Ok(())
     }
+
+    /// Add logs of evm block to `EvmLogsIndex`, and its bloom to summary of block range.
+    pub fn write_evm_logs_index(&self, block: &evm::Block) -> Result<()> {
+        let block_num = block.header.block_number;
+        self.write_evm_logs_index_inner(block)?;
+        if self.read_evm_logs_indexed_from()?.is_none() {
+            self.write_evm_logs_indexed_from(block_num)?;
+        }
+        Ok(())
+    }
+
+    fn write_evm_logs_index_inner(&self, block: &evm::Block) -> Result<()> {
+        let block_num = block.header.block_number;
+        let mut keys = HashSet::new();
+        for (_hash, receipt) in &block.transactions {
+            for log in &receipt.logs {
+                let topic = log.topics.first().copied().unwrap_or_default();
+                keys.insert((log.address, topic));
+                keys.insert((log.address, H256::zero()));
+                keys.insert((H160::zero(), topic));
+            }
+        }
+
+        let _lock = self.evm_logs_bloom_lock.lock().unwrap();
+        let mut write_batch = self.db.batch()?;
+        for (address, topic) in keys {
+            write_batch.put_bytes::<cf::EvmLogsIndex>(
+                EvmLogsIndexKey {
+                    address,
+                    topic,
+                    block_num,
+                },
+                &[],
+            )?;
+        }
+        let range_key = evm_logs_bloom_range(block_num).1;
+        let mut bloom = self.read_evm_logs_bloom(range_key)?.unwrap_or_default();
+        bloom.accrue_bloom(&block.header.logs_bloom);
+        write_batch.put_bytes::<cf::EvmLogsBloom>(range_key, bloom.as_bytes())?;
+        self.db.write(write_batch)
+    }
+
+    fn read_evm_logs_bloom(&self, range_key: evm::BlockNum) -> Result<Option<evm::Bloom>> {
+        Ok(self
+            .evm_logs_bloom_cf
+            .get_bytes(range_key)?
+            .filter(|bytes| bytes.len() == 256)
+            .map(|bytes| evm::Bloom::from_slice(&bytes)))
+    }
+
+    /// Returns first evm block, starting from which all blocks are covered by logs index.
+    pub fn read_evm_logs_indexed_from(&self) -> Result<Option<evm::BlockNum>> {
+        Ok(self
+            .evm_logs_bloom_cf
+            .get_bytes(EVM_LOGS_INDEXED_FROM_KEY)?
+            .and_then(|bytes| bytes.as_slice().try_into().ok())
+            .map(u64::from_be_bytes))
+    }
+
+    fn write_evm_logs_indexed_from(&self, block_num: evm::BlockNum) -> Result<()> {
+        self.evm_logs_bloom_cf
+            .put_bytes(EVM_LOGS_INDEXED_FROM_KEY, &block_num.to_be_bytes())
+    }
+
+    /// Add logs of evm blocks, that was stored before logs index was introduced.
+    ///
+    /// Blocks are processed from newest to oldest, so the index stays usable during backfill.
+    /// Returns number of indexed blocks.
+    pub fn backfill_evm_logs_index(&self, exit: &AtomicBool) -> Result<usize> {
+        let first_block = self.get_first_available_evm_block()?;
+        let indexed_from = match self.read_evm_logs_indexed_from()? {
+            Some(indexed_from) => indexed_from,
+            None => match self.get_last_available_evm_block()? {
+                Some(last_block) => last_block + 1,
+                None => return Ok(0),
+            },
+        };
+
+        let mut indexed = 0;
+        for block_num in (first_block..indexed_from).rev() {
+            if exit.load(Ordering::Relaxed) {
+                break;
+            }
+            match self.get_evm_block(block_num) {
+                Ok((block, _confirmed)) => self.write_evm_logs_index_inner(&block)?,
+                Err(e) => warn!("Skip indexing logs of evm block {}: {:?}", block_num, e),
+            }
+            indexed += 1;
+            if indexed % EVM_LOGS_BACKFILL_BATCH == 0 || block_num == first_block {
+                self.write_evm_logs_indexed_from(block_num)?;
+                info!("Evm logs index backfilled up to block {}", block_num);
+            }
+        }
+        Ok(indexed)
+    }
+
+    /// Returns sorted evm blocks in range of filter, that contain logs with requested
+    /// address and first topic, at most `limit` blocks are read from index.
+    ///
+    /// `None` is returned when filter can't be answered by index: filter contain neither address,
+    /// nor first topic, or the range wasn't indexed yet.
+    pub fn evm_logs_index_blocks(
+        &self,
+        filter: &evm::LogFilter,
+        limit: Option<usize>,
+    ) -> Result<Option<Vec<evm::BlockNum>>> {
+        if !self.is_evm_logs_indexed(filter)? {
+            return Ok(None);
+        }
+        let prefixes = match evm_logs_index_prefixes(filter) {
+            Some(prefixes) => prefixes,
+            None => return Ok(None),
+        };
+
+        // Every prefix is sorted by block, merge them lazily, to stop reading index at limit.
+        let mut indexes = Vec::with_capacity(prefixes.len());
+        for (address, topic) in prefixes {
+            let to_block = filter.to_block;
+            let index = self
+                .evm_logs_index_cf
+                .iter(IteratorMode::From(
+                    EvmLogsIndexKey {
+                        address,
+                        topic,
+                        block_num: filter.from_block,
+                    },
+                    IteratorDirection::Forward,
+                ))?
+                .map(|(key, _)| key)
+                .take_while(move |key| {
+                    key.address == address && key.topic == topic && key.block_num <= to_block
+                })
+                .map(|key| key.block_num);
+            indexes.push(index);
+        }
+        let blocks = indexes
+            .into_iter()
+            .kmerge()
+            .dedup()
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+        Ok(Some(blocks))
+    }
+
+    /// Returns true if logs for this filter can be found using `evm_logs_index_blocks`.
+    pub fn is_evm_logs_indexed(&self, filter: &evm::LogFilter) -> Result<bool> {
+        let indexed = matches!(
+            self.evm_logs_index_lowest_block()?,
+            Some(indexed_from) if indexed_from <= filter.from_block
+        );
+        Ok(indexed && evm_logs_index_prefixes(filter).is_some())
+    }
+
+    // Older blocks could be purged from ledger together with its index records,
+    // and served from bigtable instead.
+    fn evm_logs_index_lowest_block(&self) -> Result<Option<evm::BlockNum>> {
+        let first_block = self.get_first_available_evm_block()?;
+        Ok(self
+            .read_evm_logs_indexed_from()?
+            .map(|indexed_from| indexed_from.max(first_block)))
+    }
+
+    /// Split blocks range into subranges, which combined logs bloom match any of `masks`.
+    ///
+    /// Ranges without complete bloom summary are always returned.
+    pub fn evm_logs_bloom_ranges(
+        &self,
+        from: evm::BlockNum,
+        to: evm::BlockNum,
+        masks: &[evm::Bloom],
+    ) -> Result<Vec<(evm::BlockNum, evm::BlockNum)>> {
+        let indexed_from = self.evm_logs_index_lowest_block()?;
+        let mut ranges: Vec<(evm::BlockNum, evm::BlockNum)> = Vec::new();
+        let mut start = from;
+        while start <= to {
+            let (range_start, range_end) = evm_logs_bloom_range(start);
+            let end = range_end.min(to);
+            let summary_complete =
+                matches!(indexed_from, Some(indexed_from) if indexed_from <= range_start);
+            let may_match = !summary_complete
+                || match self.read_evm_logs_bloom(range_end)? {
+                    Some(bloom) => masks.iter().any(|mask| bloom.contains_bloom(mask)),
+                    None => false,
+                };
+            if may_match {
+                match ranges.last_mut() {
+                    Some((_, last_end)) if *last_end + 1 == start => *last_end = end,
+                    _ => ranges.push((start, end)),
+                }
+            }
+            if end == u64::MAX {
+                break;
+            }
+            start = end + 1;
+        }
+        Ok(ranges)
+    }
+
+    /// Add swaps of transaction to `EvmSwaps` index, by both native account and evm address.
+    pub fn write_evm_swaps(
+        &self,
+        slot: Slot,
+        signature: Signature,
+        swaps: &[SwapEvent],
+    ) -> Result<()> {
+        if swaps.is_empty() {
+            return Ok(());
+        }
+        let mut write_batch = self.db.batch()?;
+        for (index, swap) in swaps.iter().enumerate() {
+            for account in [
+                EvmSwapAccount::Native(swap.native_account),
+                EvmSwapAccount::Evm(swap.evm_address),
+            ] {
+                write_batch.put::<cf::EvmSwaps>(
+                    EvmSwapsIndexKey {
+                        account,
+                        slot,
+                        signature,
+                        index: index as u32,
+                    },
+                    swap,
+                )?;
+            }
+        }
+        self.db.write(write_batch)
+    }
+
+    /// Returns swaps of account, from newest to oldest.
+    ///
+    /// Only swaps of rooted slots, and of confirmed ancestors of `highest_slot` are returned.
+    /// Listing starts after transaction `before`, swaps of single transaction are never split
+    /// between pages.
+    pub fn get_evm_swaps_for_address(
+        &self,
+        account: EvmSwapAccount,
+        highest_slot: Slot,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<EvmSwapWithSignature>> {
+        datapoint_info!(
+            "blockstore-rpc-api",
+            ("method", "get_evm_swaps_for_address", String)
+        );
+        let last_root = self.last_root();
+        let confirmed_unrooted_slots: Vec<_> = AncestorIterator::new_inclusive(highest_slot, self)
+            .take_while(|&slot| slot > last_root)
+            .collect();
+
+        let start = match before {
+            None => EvmSwapsIndexKey {
+                account,
+                slot: highest_slot,
+                signature: Signature::new(&[u8::MAX; 64]),
+                index: u32::MAX,
+            },
+            Some(before) => match self.get_transaction_status(before, &confirmed_unrooted_slots)? {
+                Some((slot, _)) => EvmSwapsIndexKey {
+                    account,
+                    slot,
+                    signature: before,
+                    index: 0,
+                },
+                None => return Ok(vec![]),
+            },
+        };
+
+        let mut swaps: Vec<EvmSwapWithSignature> = vec![];
+        let index = self
+            .evm_swaps_cf
+            .iter(IteratorMode::From(start, IteratorDirection::Reverse))?;
+        for (key, value) in index {
+            if key.account != account {
+                break;
+            }
+            if Some(key.signature) == before
+                || !(self.is_root(key.slot) || confirmed_unrooted_slots.contains(&key.slot))
+            {
+                continue;
+            }
+            if swaps.len() >= limit
+                && swaps.last().map(|swap| swap.signature) != Some(key.signature)
+            {
+                break;
+            }
+            swaps.push(EvmSwapWithSignature {
+                slot: key.slot,
+                signature: key.signature,
+                swap: deserialize(&value)?,
+            });
+        }
+        Ok(swaps)
+    }
+
     /// Returns the entry vector for the slot starting with `shred_start_index`
     pub fn get_slot_entries(&self, slot: Slot, shred_start_index: u64) -> Result<Vec<Entry>> {
         self.get_slot_entries_with_shred_info(slot, shred_start_index, false)
@@ -4667,6 +4990,10 @@ fn slot_has_updates(slot_meta: &SlotMeta, slot_meta_backup: &Option<SlotMeta>) -
 pub enum EvmStateJson<'a> {
     OpenEthereum(&'a Path),
     Geth(&'a Path),
+    /// Portable dump, that is made by `ledger-tool evm_state dump`.
+    StateDump(&'a Path),
+    /// Accounts, that are already loaded into memory.
+    Accounts(&'a [AccountPair]),
     None,
 }
 
@@ -4691,6 +5018,14 @@ pub fn create_new_ledger(
             let extractor = GethAccountExtractor::open_dump(path).unwrap();
             genesis_config.generate_evm_state_from_dump(ledger_path, extractor)?;
         }
+        EvmStateJson::StateDump(path) => {
+            let extractor = StateDumpExtractor::open_dump(path).unwrap();
+            genesis_config.generate_evm_state_from_dump(ledger_path, extractor)?;
+        }
+        EvmStateJson::Accounts(accounts) => {
+            let extractor = MemoryAccountsExtractor::new(accounts.to_vec());
+            genesis_config.generate_evm_state_from_dump(ledger_path, extractor)?;
+        }
         EvmStateJson::None => genesis_config.generate_evm_state_empty(ledger_path)?,
     }
     genesis_config.write(ledger_path)?;
@@ -5190,6 +5525,39 @@ fn adjust_ulimit_nofile(enforce_ulimit_nofile: bool) -> Result<()> {
     Ok(())
 }
 
+/// Returns first and last block of logs bloom range, containing `block_num`.
+fn evm_logs_bloom_range(block_num: evm::BlockNum) -> (evm::BlockNum, evm::BlockNum) {
+    let range_start = block_num - block_num % EVM_LOGS_BLOOM_RANGE;
+    (
+        range_start,
+        range_start.saturating_add(EVM_LOGS_BLOOM_RANGE - 1),
+    )
+}
+
+/// Returns (address, first topic) keys of `EvmLogsIndex`, that should be scanned for filter.
+fn evm_logs_index_prefixes(filter: &evm::LogFilter) -> Option<Vec<(H160, H256)>> {
+    let topics = match filter.topics.first() {
+        Some(evm::LogFilterTopicEntry::One(topic)) => vec![*topic],
+        Some(evm::LogFilterTopicEntry::Or(topics)) => topics.clone(),
+        Some(evm::LogFilterTopicEntry::Any) | None => vec![],
+    };
+    let prefixes = match (filter.address.is_empty(), topics.is_empty()) {
+        (true, true) => return None,
+        (false, true) => filter
+            .address
+            .iter()
+            .map(|address| (*address, H256::zero()))
+            .collect(),
+        (true, false) => topics.iter().map(|topic| (H160::zero(), *topic)).collect(),
+        (false, false) => filter
+            .address
+            .iter()
+            .flat_map(|address| topics.iter().map(move |topic| (*address, *topic)))
+            .collect(),
+    };
+    Some(prefixes)
+}
+
 #[cfg(test)]
 pub mod tests {
     use {
@@ -5303,6 +5671,248 @@ pub mod tests {
         );
     }
 
+    fn make_evm_block(block_number: evm::BlockNum, logs: Vec<evm::Log>) -> evm::Block {
+        let tx = evm::UnsignedTransactionWithCaller {
+            unsigned_tx: evm::UnsignedTransaction {
+                nonce: block_number.into(),
+                gas_price: 0.into(),
+                gas_limit: 300000.into(),
+                action: evm::TransactionAction::Create,
+                value: 0.into(),
+                input: vec![],
+            },
+            caller: H160::repeat_byte(0xff),
+            chain_id: evm::TEST_CHAIN_ID,
+            signed_compatible: true,
+        };
+        let tx_hash = tx.tx_id_hash();
+        let receipt = evm::TransactionReceipt::new(
+            evm::TransactionInReceipt::Unsigned(tx),
+            21000,
+            block_number,
+            1,
+            logs,
+            (evm::ExitReason::Succeed(evm::ExitSucceed::Stopped), vec![]),
+        );
+        let transactions = vec![(tx_hash, receipt)];
+        let header = evm::BlockHeader::new(
+            H256::zero(),
+            evm::DEFAULT_GAS_LIMIT,
+            H256::zero(),
+            block_number,
+            21000,
+            0,
+            block_number,
+            H256::zero(),
+            transactions.iter(),
+            evm::BlockVersion::VersionConsistentHashes,
+        );
+        evm::Block {
+            header,
+            transactions,
+        }
+    }
+
+    fn write_evm_block(blockstore: &Blockstore, block: evm::Block) {
+        blockstore.write_evm_block_header(&block.header).unwrap();
+        for (hash, tx) in block.transactions {
+            blockstore
+                .write_evm_transaction(
+                    block.header.block_number,
+                    block.header.native_chain_slot,
+                    hash,
+                    tx,
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_evm_logs_index() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let (address1, address2) = (H160::repeat_byte(1), H160::repeat_byte(2));
+        let (topic1, topic2) = (H256::repeat_byte(1), H256::repeat_byte(2));
+        let log = |address, topics| evm::Log {
+            address,
+            topics,
+            data: vec![],
+        };
+        let logs_of_block = |block_num| match block_num {
+            3 => vec![log(address1, vec![topic1])],
+            1500 => vec![log(address2, vec![topic1, topic2]), log(address1, vec![])],
+            _ => vec![],
+        };
+        let filter = |address: Vec<H160>, topics: Vec<evm::LogFilterTopicEntry>| evm::LogFilter {
+            from_block: 0,
+            to_block: 2000,
+            address,
+            topics,
+        };
+
+        // Blocks before logs index was introduced.
+        for block_num in 0..1000 {
+            write_evm_block(
+                &blockstore,
+                make_evm_block(block_num, logs_of_block(block_num)),
+            );
+        }
+        for block_num in 1000..2000 {
+            let block = make_evm_block(block_num, logs_of_block(block_num));
+            blockstore.write_evm_logs_index(&block).unwrap();
+            write_evm_block(&blockstore, block);
+        }
+        assert_eq!(blockstore.read_evm_logs_indexed_from().unwrap(), Some(1000));
+
+        let by_address = filter(vec![address1], vec![]);
+        assert!(!blockstore.is_evm_logs_indexed(&by_address).unwrap());
+        assert_eq!(
+            blockstore.evm_logs_index_blocks(&by_address, None).unwrap(),
+            None
+        );
+        // Summary of range, that wasn't indexed yet, can't be trusted.
+        let masks = filter(vec![H160::repeat_byte(3)], vec![]).bloom_possibilities();
+        assert_eq!(
+            blockstore.evm_logs_bloom_ranges(0, 2000, &masks).unwrap(),
+            vec![(0, 1023)]
+        );
+
+        let exit = AtomicBool::new(false);
+        assert_eq!(blockstore.backfill_evm_logs_index(&exit).unwrap(), 1000);
+        assert_eq!(blockstore.read_evm_logs_indexed_from().unwrap(), Some(0));
+        assert_eq!(blockstore.backfill_evm_logs_index(&exit).unwrap(), 0);
+
+        assert_eq!(
+            blockstore.evm_logs_index_blocks(&by_address, None).unwrap(),
+            Some(vec![3, 1500])
+        );
+        let by_topic = filter(vec![], vec![evm::LogFilterTopicEntry::One(topic1)]);
+        assert_eq!(
+            blockstore.evm_logs_index_blocks(&by_topic, None).unwrap(),
+            Some(vec![3, 1500])
+        );
+        assert_eq!(
+            blockstore
+                .evm_logs_index_blocks(&by_topic, Some(1))
+                .unwrap(),
+            Some(vec![3])
+        );
+        let by_address_and_topic = filter(
+            vec![address1, address2],
+            vec![evm::LogFilterTopicEntry::Or(vec![topic1, topic2])],
+        );
+        assert_eq!(
+            blockstore
+                .evm_logs_index_blocks(&by_address_and_topic, None)
+                .unwrap(),
+            Some(vec![3, 1500])
+        );
+        let by_address_and_topic =
+            filter(vec![address1], vec![evm::LogFilterTopicEntry::One(topic2)]);
+        assert_eq!(
+            blockstore
+                .evm_logs_index_blocks(&by_address_and_topic, None)
+                .unwrap(),
+            Some(vec![])
+        );
+        // Filter by second topic only can't be answered from index.
+        let by_second_topic = filter(
+            vec![],
+            vec![
+                evm::LogFilterTopicEntry::Any,
+                evm::LogFilterTopicEntry::One(topic2),
+            ],
+        );
+        assert!(!blockstore.is_evm_logs_indexed(&by_second_topic).unwrap());
+
+        let masks = by_second_topic.bloom_possibilities();
+        assert_eq!(
+            blockstore.evm_logs_bloom_ranges(0, 2000, &masks).unwrap(),
+            vec![(1024, 2000)]
+        );
+        let masks = filter(vec![H160::repeat_byte(3)], vec![]).bloom_possibilities();
+        assert_eq!(
+            blockstore.evm_logs_bloom_ranges(0, 2000, &masks).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_evm_swaps_for_address() {
+        use solana_evm_loader_program::swap::SwapDirection;
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let native_account = Pubkey::new_unique();
+        let evm_address = H160::repeat_byte(1);
+        let swap = |direction, lamports: u64| SwapEvent {
+            direction,
+            native_account,
+            evm_address,
+            lamports,
+            evm_tx_hash: Some(H256::from_low_u64_be(lamports)),
+        };
+        let signatures: Vec<_> = (0..4u8).map(|i| Signature::new(&[i + 1; 64])).collect();
+        for (slot, signature) in signatures.iter().enumerate() {
+            let slot = slot as Slot;
+            let swaps = if slot == 3 {
+                vec![
+                    swap(SwapDirection::NativeToEvm, 3),
+                    swap(SwapDirection::EvmToNative, 4),
+                ]
+            } else {
+                vec![swap(SwapDirection::NativeToEvm, slot)]
+            };
+            blockstore
+                .write_transaction_status(
+                    slot,
+                    *signature,
+                    vec![],
+                    vec![],
+                    TransactionStatusMeta::default(),
+                )
+                .unwrap();
+            blockstore
+                .write_evm_swaps(slot, *signature, &swaps)
+                .unwrap();
+        }
+        // Slot 2 is not rooted, its swaps should be skipped.
+        blockstore.set_roots([0, 1, 3].iter()).unwrap();
+
+        let lamports = |swaps: Vec<EvmSwapWithSignature>| -> Vec<u64> {
+            swaps.into_iter().map(|swap| swap.swap.lamports).collect()
+        };
+        for account in [
+            EvmSwapAccount::Native(native_account),
+            EvmSwapAccount::Evm(evm_address),
+        ] {
+            // Swaps of single transaction are not split between pages.
+            let page = blockstore
+                .get_evm_swaps_for_address(account, 3, None, 1)
+                .unwrap();
+            assert_eq!(page[0].signature, signatures[3]);
+            assert_eq!(lamports(page), vec![4, 3]);
+
+            let page = blockstore
+                .get_evm_swaps_for_address(account, 3, Some(signatures[3]), 10)
+                .unwrap();
+            assert_eq!(lamports(page), vec![1, 0]);
+
+            let page = blockstore
+                .get_evm_swaps_for_address(account, 3, Some(signatures[1]), 10)
+                .unwrap();
+            assert_eq!(lamports(page), vec![0]);
+        }
+
+        let other = EvmSwapAccount::Evm(H160::repeat_byte(2));
+        assert!(blockstore
+            .get_evm_swaps_for_address(other, 3, None, 10)
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_insert_get_bytes() {
         // Create enough entries to ensure there are at least two shreds created
@@ -1,4 +1,4 @@
-use evm_state::{BlockNum, H256};
+use evm_state::{BlockNum, H160, H256};
 
 pub use rocksdb::Direction as IteratorDirection;
 use {
@@ -106,6 +106,9 @@ const EVM_HEADERS: &str = "evm_headers";
 const EVM_BLOCK_BY_HASH: &str = "evm_block_by_hash";
 const EVM_BLOCK_BY_SLOT: &str = "evm_block_by_slot";
 const EVM_TRANSACTIONS: &str = "evm_transactions";
+const EVM_LOGS_INDEX: &str = "evm_logs_index";
+const EVM_LOGS_BLOOM: &str = "evm_logs_bloom";
+const EVM_SWAPS: &str = "evm_swaps";
 
 #[derive(Error, Debug)]
 pub enum BlockstoreError {
@@ -241,11 +244,50 @@ pub mod columns {
     #[derive(Debug)]
     /// The evm transaction with statuses.
     pub struct EvmTransactionReceipts;
+
+    #[derive(Debug)]
+    /// The evm blocks, that contain logs with specific address and first topic.
+    pub struct EvmLogsIndex;
+
+    #[derive(Debug)]
+    /// The evm logs bloom, combined for range of blocks.
+    pub struct EvmLogsBloom;
+
+    #[derive(Debug)]
+    /// The swaps between native chain and evm, by native account and evm address.
+    pub struct EvmSwaps;
     #[derive(Debug)]
     /// The optimistic slot column
     pub struct OptimisticSlots;
 }
 
+/// Key of `EvmLogsIndex` column.
+///
+/// Zero address or topic is used as wildcard, to support filters without address or topic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EvmLogsIndexKey {
+    pub address: H160,
+    pub topic: H256,
+    pub block_num: BlockNum,
+}
+
+/// Side of swap, by which `EvmSwaps` column is indexed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EvmSwapAccount {
+    Native(Pubkey),
+    Evm(H160),
+}
+
+/// Key of `EvmSwaps` column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvmSwapsIndexKey {
+    pub account: EvmSwapAccount,
+    pub slot: Slot,
+    pub signature: Signature,
+    /// Position of swap in transaction.
+    pub index: u32,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct EvmTransactionReceiptsIndex {
     pub index: u64,
@@ -494,6 +536,9 @@ impl Rocks {
             new_cf_descriptor::<EvmHeaderIndexByHash>(options, oldest_slot, oldest_block_num),
             new_cf_descriptor::<EvmHeaderIndexBySlot>(options, oldest_slot, oldest_block_num),
             new_cf_descriptor::<EvmTransactionReceipts>(options, oldest_slot, oldest_block_num),
+            new_cf_descriptor::<EvmLogsIndex>(options, oldest_slot, oldest_block_num),
+            new_cf_descriptor::<EvmLogsBloom>(options, oldest_slot, oldest_block_num),
+            new_cf_descriptor::<EvmSwaps>(options, oldest_slot, oldest_block_num),
         ]
     }
 
@@ -526,6 +571,9 @@ impl Rocks {
             EvmTransactionReceipts::NAME,
             EvmHeaderIndexByHash::NAME,
             EvmHeaderIndexBySlot::NAME,
+            EvmLogsIndex::NAME,
+            EvmLogsBloom::NAME,
+            EvmSwaps::NAME,
         ]
     }
 
@@ -1229,6 +1277,129 @@ impl ColumnName for columns::EvmTransactionReceipts {
 impl ProtobufColumn for columns::EvmTransactionReceipts {
     type Type = generated_evm::TransactionReceipt;
 }
+
+impl Column for columns::EvmLogsIndex {
+    type Index = EvmLogsIndexKey;
+
+    fn key(
+        EvmLogsIndexKey {
+            address,
+            topic,
+            block_num,
+        }: EvmLogsIndexKey,
+    ) -> Vec<u8> {
+        let mut key = vec![0; 20 + 32 + 8]; // size_of Address + size_of HASH + size_of BlockNum
+        key[0..20].clone_from_slice(address.as_bytes());
+        key[20..52].clone_from_slice(topic.as_bytes());
+        BigEndian::write_u64(&mut key[52..60], block_num);
+        key
+    }
+
+    fn index(key: &[u8]) -> EvmLogsIndexKey {
+        if key.len() != 60 {
+            return Self::as_index(0);
+        }
+        EvmLogsIndexKey {
+            address: H160::from_slice(&key[0..20]),
+            topic: H256::from_slice(&key[20..52]),
+            block_num: BigEndian::read_u64(&key[52..60]),
+        }
+    }
+
+    fn primary_index(index: Self::Index) -> u64 {
+        index.block_num
+    }
+
+    fn as_index(block_num: u64) -> Self::Index {
+        EvmLogsIndexKey {
+            address: H160::zero(),
+            topic: H256::zero(),
+            block_num,
+        }
+    }
+
+    fn slot(_index: Self::Index) -> Slot {
+        unimplemented!()
+    }
+}
+
+impl ColumnName for columns::EvmLogsIndex {
+    const NAME: &'static str = EVM_LOGS_INDEX;
+}
+
+// Indexed by last block of range, to keep ranges that overlap with oldest block on compaction.
+impl SlotColumn for columns::EvmLogsBloom {}
+
+impl ColumnName for columns::EvmLogsBloom {
+    const NAME: &'static str = EVM_LOGS_BLOOM;
+}
+
+impl Column for columns::EvmSwaps {
+    type Index = EvmSwapsIndexKey;
+
+    fn key(
+        EvmSwapsIndexKey {
+            account,
+            slot,
+            signature,
+            index,
+        }: EvmSwapsIndexKey,
+    ) -> Vec<u8> {
+        let mut key = vec![0; 1 + 32 + 8 + 64 + 4]; // tag + size_of Pubkey + size_of Slot + size_of Signature + size_of u32
+        match account {
+            EvmSwapAccount::Native(pubkey) => key[1..33].clone_from_slice(pubkey.as_ref()),
+            EvmSwapAccount::Evm(address) => {
+                key[0] = 1;
+                key[1..21].clone_from_slice(address.as_bytes());
+            }
+        }
+        BigEndian::write_u64(&mut key[33..41], slot);
+        key[41..105].clone_from_slice(signature.as_ref());
+        BigEndian::write_u32(&mut key[105..109], index);
+        key
+    }
+
+    fn index(key: &[u8]) -> EvmSwapsIndexKey {
+        if key.len() != 109 {
+            return Self::as_index(0);
+        }
+        let account = match key[0] {
+            0 => EvmSwapAccount::Native(Pubkey::new(&key[1..33])),
+            _ => EvmSwapAccount::Evm(H160::from_slice(&key[1..21])),
+        };
+        EvmSwapsIndexKey {
+            account,
+            slot: BigEndian::read_u64(&key[33..41]),
+            signature: Signature::new(&key[41..105]),
+            index: BigEndian::read_u32(&key[105..109]),
+        }
+    }
+
+    fn primary_index(index: Self::Index) -> u64 {
+        index.slot
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.slot
+    }
+
+    fn as_index(slot: u64) -> Self::Index {
+        EvmSwapsIndexKey {
+            account: EvmSwapAccount::Native(Pubkey::default()),
+            slot,
+            signature: Signature::default(),
+            index: 0,
+        }
+    }
+}
+
+impl ColumnName for columns::EvmSwaps {
+    const NAME: &'static str = EVM_SWAPS;
+}
+
+impl TypedColumn for columns::EvmSwaps {
+    type Type = solana_evm_loader_program::swap::SwapEvent;
+}
 #[derive(Debug, Clone)]
 pub struct Database {
     backend: Arc<Rocks>,
@@ -1726,7 +1897,11 @@ impl<C: Column + ColumnName> CompactionFilter for PurgedEvmBlockFilter<C> {
     fn filter(&mut self, _level: u32, key: &[u8], _value: &[u8]) -> CompactionDecision {
         use rocksdb::CompactionDecision::*;
 
-        let block_num_in_key = BigEndian::read_u64(&key[..8]);
+        let block_num_in_key = if C::NAME == columns::EvmLogsIndex::NAME {
+            BigEndian::read_u64(&key[key.len().saturating_sub(8)..])
+        } else {
+            BigEndian::read_u64(&key[..8])
+        };
         if block_num_in_key >= self.oldest_block {
             Keep
         } else {
@@ -1792,7 +1967,10 @@ fn get_cf_options<C: 'static + Column + ColumnName>(
     }
 
     if !disable_auto_compactions && !should_exclude_from_compaction(C::NAME) {
-        if C::NAME == columns::EvmBlockHeader::NAME {
+        if C::NAME == columns::EvmBlockHeader::NAME
+            || C::NAME == columns::EvmLogsIndex::NAME
+            || C::NAME == columns::EvmLogsBloom::NAME
+        {
             cf_options.set_compaction_filter_factory(PurgedEvmBlockFilterFactory::<C> {
                 oldest_block: oldest_block_num.clone(),
                 name: CString::new(format!("purged_evm_block_filter_factory({})", C::NAME))
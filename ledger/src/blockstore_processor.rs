@@ -1501,9 +1501,9 @@ pub fn record_evm_block(
 
     if let Some(evm_state_recorder_sender) = evm_state_recorder_sender {
         let state = bank.evm_state_change();
-        if let Some(state) = state {
+        if let Some((root, changes)) = state {
             evm_state_recorder_sender
-                .send(state)
+                .send((bank.slot(), root, changes))
                 .unwrap_or_else(|err| warn!("evm_state_recorder_sender failed: {:?}", err));
         }
     }
@@ -69,6 +69,8 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         ledger_column_options: config.ledger_column_options.clone(),
         enable_quic_servers: config.enable_quic_servers,
         verify_evm_state: config.verify_evm_state,
+        evm_state_retention_slots: config.evm_state_retention_slots,
+        evm_state_archive_hot_slots: config.evm_state_archive_hot_slots,
         jaeger_collector_url: config.jaeger_collector_url.clone(),
     }
 }
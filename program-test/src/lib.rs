@@ -7,9 +7,11 @@ use tokio::task::JoinError;
 use {
     async_trait::async_trait,
     chrono_humanize::{Accuracy, HumanTime, Tense},
+    evm_state::{AccountState, EvmState, H160, H256},
     log::*,
     solana_banks_client::start_client,
     solana_banks_server::banks_server::start_local_server,
+    solana_evm_loader_program::scope::evm::gweis_to_lamports,
     solana_program_runtime::{
         compute_budget::ComputeBudget, ic_msg, invoke_context::ProcessInstructionWithContext,
         stable_log, timings::ExecuteTimings,
@@ -472,6 +474,7 @@ pub struct ProgramTest {
     prefer_bpf: bool,
     use_bpf_jit: bool,
     deactivate_feature_set: HashSet<Pubkey>,
+    evm_accounts: Vec<(H160, AccountState, Vec<(H256, H256)>)>,
 }
 
 impl Default for ProgramTest {
@@ -503,6 +506,7 @@ impl Default for ProgramTest {
             prefer_bpf,
             use_bpf_jit: false,
             deactivate_feature_set: HashSet::default(),
+            evm_accounts: vec![],
         }
     }
 }
@@ -553,6 +557,23 @@ impl ProgramTest {
             .push((address, AccountSharedData::from(account)));
     }
 
+    /// Add an evm account to the test environment
+    ///
+    /// Balance of evm account is backed by lamports of the evm state account.
+    pub fn add_evm_account(&mut self, address: H160, account: AccountState) {
+        self.add_evm_account_with_storage(address, account, vec![]);
+    }
+
+    /// Add an evm account with the provided contract storage to the test environment
+    pub fn add_evm_account_with_storage(
+        &mut self,
+        address: H160,
+        account: AccountState,
+        storage: Vec<(H256, H256)>,
+    ) {
+        self.evm_accounts.push((address, account, storage));
+    }
+
     /// Add an account to the test environment with the account data in the provided `filename`
     pub fn add_account_with_file_data(
         &mut self,
@@ -839,6 +860,30 @@ impl ProgramTest {
             }
             bank.store_account(address, account);
         }
+        if !self.evm_accounts.is_empty() {
+            let mut evm_state_account = bank
+                .get_account(&solana_sdk::evm_state::id())
+                .expect("Evm state account should exist in genesis");
+            {
+                let mut evm_state = bank.evm_state.write().unwrap();
+                let evm_state = match &mut *evm_state {
+                    EvmState::Incomming(evm_state) => evm_state,
+                    EvmState::Committed(_) => {
+                        unreachable!("Expected genesis evm state to be writable.")
+                    }
+                };
+                for (address, account, storage) in self.evm_accounts.iter() {
+                    // Round up, to never have evm balances, that are not backed by lamports.
+                    let (lamports, gweis) = gweis_to_lamports(account.balance);
+                    let lamports = lamports + u64::from(!gweis.is_zero());
+                    evm_state_account.set_lamports(evm_state_account.lamports() + lamports);
+
+                    evm_state.set_account_state(*address, account.clone());
+                    evm_state.ext_storage(*address, storage.iter().copied());
+                }
+            }
+            bank.store_account(&solana_sdk::evm_state::id(), &evm_state_account);
+        }
         bank.set_capitalization();
         if let Some(max_units) = self.compute_max_units {
             bank.set_compute_budget(Some(ComputeBudget {
@@ -0,0 +1,58 @@
+use {
+    solana_evm_loader_program::scope::evm::{self, FromKey},
+    solana_program_test::ProgramTest,
+    solana_sdk::genesis_config::EVM_DEVELOP_CHAIN_ID,
+};
+
+#[tokio::test]
+async fn test_evm_transfer() {
+    // Arrange
+    let secret_key = evm::SecretKey::from_slice(&[1; 32]).unwrap();
+    let sender = secret_key.to_address();
+    let receiver = evm::Address::repeat_byte(0x11);
+    let balance = evm::lamports_to_gwei(1_000_000_000);
+
+    let mut program_test = ProgramTest::default();
+    program_test.add_evm_account(
+        sender,
+        evm::AccountState {
+            balance,
+            ..Default::default()
+        },
+    );
+    let (mut banks_client, payer, _recent_blockhash) = program_test.start().await;
+    assert_eq!(banks_client.get_evm_balance(sender).await.unwrap(), balance);
+
+    let value = evm::lamports_to_gwei(1_000);
+    let tx = evm::UnsignedTransaction {
+        nonce: 0.into(),
+        gas_price: evm::BURN_GAS_PRICE.into(),
+        gas_limit: 300_000.into(),
+        action: evm::TransactionAction::Call(receiver),
+        value,
+        input: vec![],
+    }
+    .sign(&secret_key, Some(EVM_DEVELOP_CHAIN_ID));
+    let tx = evm::SignedTransaction::Legacy(tx);
+
+    // Act
+    let simulation = banks_client
+        .simulate_evm_transaction(tx.clone())
+        .await
+        .unwrap();
+    let hash = banks_client
+        .process_evm_transaction(&payer, tx)
+        .await
+        .unwrap();
+
+    // Assert
+    assert!(matches!(
+        simulation.exit_reason,
+        evm::ExitReason::Succeed(_)
+    ));
+    let receipt = banks_client.get_evm_receipt(hash).await.unwrap().unwrap();
+    assert!(matches!(receipt.status, evm::ExitReason::Succeed(_)));
+    assert_eq!(receipt.used_gas, simulation.used_gas);
+    assert_eq!(banks_client.get_evm_balance(receiver).await.unwrap(), value);
+    assert_eq!(banks_client.get_evm_nonce(sender).await.unwrap(), 1.into());
+}
@@ -0,0 +1,243 @@
+//! Geth compatible `debug_trace*` methods.
+//!
+//! Transactions are replayed on top of parent block state, same as in `trace_replay*` methods,
+//! and only traced transaction is executed with tracer enabled.
+//! Struct logs (default tracer, and `prestateTracer`) are available only if crate is built with
+//! `evm-tracing` feature, `callTracer` works without it.
+
+use {
+    super::{
+        block_by_number, block_to_state_root, call_inner, executor_for_state, parse_meta_keys,
+        replay_preceding_transactions, replay_state_root, transaction_by_hash, TxOutput,
+    },
+    crate::rpc::JsonRpcRequestProcessor,
+    evm_rpc::{
+        debug::DebugERPC, error::Error, BlockId, Bytes, Either, RPCBlockTrace, RPCCallFrame,
+        RPCPrestateAccount, RPCTrace, RPCTraceConfig, RPCTraceResult, RPCTracer, RPCTransaction,
+    },
+    evm_state::{
+        executor::Action,
+        struct_logger::{StructLoggerConfig, StructLoggerResult},
+        AccountProvider, Address, H256,
+    },
+    jsonrpc_core::BoxFuture,
+    solana_runtime::bank::Bank,
+    solana_sdk::pubkey::Pubkey,
+    std::{collections::BTreeSet, sync::Arc},
+    tracing_attributes::instrument,
+};
+
+/// Run `f` and collect opcode level logs of evm execution.
+#[cfg(feature = "evm-tracing")]
+fn struct_logs<R>(
+    config: StructLoggerConfig,
+    f: impl FnOnce() -> R,
+) -> Result<(R, StructLoggerResult), Error> {
+    Ok(evm_state::struct_logger::trace_struct_logs(config, f))
+}
+
+/// Evm tracing hooks are disabled in this build.
+#[cfg(not(feature = "evm-tracing"))]
+fn struct_logs<R>(
+    _config: StructLoggerConfig,
+    _f: impl FnOnce() -> R,
+) -> Result<(R, StructLoggerResult), Error> {
+    Err(Error::Unimplemented {})
+}
+
+fn trace_transaction(
+    executor: &mut evm_state::Executor,
+    bank: &Bank,
+    tx: RPCTransaction,
+    meta_keys: &[Pubkey],
+    config: &RPCTraceConfig,
+) -> Result<RPCTraceResult, Error> {
+    let result = match config.tracer {
+        None => {
+            let (output, logs) = struct_logs(config.into(), || {
+                call_inner(executor, tx, meta_keys.to_vec(), bank)
+            })?;
+            let output = output?;
+            RPCTraceResult::StructLogs(RPCTrace {
+                gas: output.used_gas,
+                failed: !matches!(output.exit_reason, evm_state::ExitReason::Succeed(_)),
+                return_value: output.exit_data.into(),
+                struct_logs: logs.logs.into_iter().map(From::from).collect(),
+            })
+        }
+        Some(RPCTracer::CallTracer) => {
+            let gas_limit = tx.gas;
+            let output = call_inner(executor, tx, meta_keys.to_vec(), bank)?;
+            let used_gas = output.used_gas;
+            let mut frame =
+                RPCCallFrame::from_traces(output.traces).ok_or(Error::ServerError {})?;
+            // Top level frame should include intrinsic gas, like in receipt.
+            frame.gas = gas_limit.unwrap_or(frame.gas);
+            frame.gas_used = used_gas.into();
+            if config.tracer_config.only_top_call {
+                frame.calls.clear();
+            }
+            RPCTraceResult::CallTracer(frame)
+        }
+        Some(RPCTracer::PrestateTracer) => {
+            let pre_state = executor.evm_backend.clone();
+            let mut accounts: BTreeSet<Address> = tx.from.into_iter().chain(tx.to).collect();
+            // Only storage access events are needed.
+            let logger_config = StructLoggerConfig {
+                disable_stack: true,
+                disable_storage: true,
+                enable_memory: false,
+                limit: Some(0),
+            };
+            let (output, logs) = struct_logs(logger_config, || {
+                call_inner(executor, tx, meta_keys.to_vec(), bank)
+            })?;
+            let TxOutput { traces, .. } = output?;
+            for trace in traces {
+                match trace.action {
+                    Action::Call { code, context, .. } => {
+                        accounts.extend([code, context.address, context.caller])
+                    }
+                    Action::Create { caller, .. } => {
+                        accounts.insert(caller);
+                    }
+                }
+                accounts.extend(trace.result.contract);
+            }
+            accounts.extend(logs.accessed_storage.keys());
+
+            let prestate = accounts
+                .into_iter()
+                .map(|address| {
+                    let state = pre_state.get_account_state(address).unwrap_or_default();
+                    let storage = logs
+                        .accessed_storage
+                        .get(&address)
+                        .into_iter()
+                        .flatten()
+                        .map(|index| {
+                            let value = pre_state.get_storage(address, *index).unwrap_or_default();
+                            (*index, value)
+                        })
+                        .collect();
+                    let account = RPCPrestateAccount {
+                        balance: state.balance,
+                        nonce: state.nonce,
+                        code: (!state.code.is_empty()).then(|| Bytes(state.code.into())),
+                        storage,
+                    };
+                    (address, account)
+                })
+                .collect();
+            RPCTraceResult::PrestateTracer(prestate)
+        }
+    };
+    Ok(result)
+}
+
+/// Replay all transactions of block, and trace them one by one.
+async fn trace_block(
+    meta: Arc<JsonRpcRequestProcessor>,
+    block: BlockId,
+    config: RPCTraceConfig,
+) -> Result<Vec<RPCBlockTrace>, Error> {
+    let block = match block_by_number(meta.clone(), block, true).await? {
+        Some(block) => block,
+        None => return Err(Error::BlockNotFound { block }),
+    };
+    let txs = match block.transactions {
+        Either::Right(txs) => txs,
+        _ => return Err(Error::Unimplemented {}),
+    };
+
+    let meta_keys = parse_meta_keys(&config.meta_keys)?;
+    let saved_state = replay_state_root(&meta, block.number.as_u64()).await;
+    let (mut executor, bank) = executor_for_state(&meta, saved_state, false)?;
+
+    let mut result = Vec::new();
+    for tx in txs {
+        let tx_hash = tx.hash.unwrap_or_default();
+        result.push(RPCBlockTrace {
+            tx_hash,
+            result: trace_transaction(&mut executor, &bank, tx, &meta_keys, &config)?,
+        })
+    }
+    Ok(result)
+}
+
+pub struct DebugErpcImpl;
+impl DebugERPC for DebugErpcImpl {
+    type Metadata = Arc<JsonRpcRequestProcessor>;
+
+    #[instrument(skip(self, meta))]
+    fn trace_transaction(
+        &self,
+        meta: Self::Metadata,
+        tx_hash: H256,
+        config: Option<RPCTraceConfig>,
+    ) -> BoxFuture<Result<RPCTraceResult, Error>> {
+        let config = config.unwrap_or_default();
+        Box::pin(async move {
+            let tx = transaction_by_hash(meta.clone(), tx_hash)
+                .await?
+                .ok_or(Error::TransactionNotFound { tx_hash })?;
+            let (tx_block, tx_index) = match (tx.block_number, tx.transaction_index) {
+                (Some(block), Some(index)) => (block.as_u64(), index.0),
+                _ => return Err(Error::TransactionNotFound { tx_hash }),
+            };
+            let meta_keys = parse_meta_keys(&config.meta_keys)?;
+            let (mut executor, bank) =
+                replay_preceding_transactions(&meta, tx_block, tx_index, &meta_keys)
+                    .await?
+                    .ok_or(Error::BlockNotFound {
+                        block: tx_block.into(),
+                    })?;
+            trace_transaction(&mut executor, &bank, tx, &meta_keys, &config)
+        })
+    }
+
+    #[instrument(skip(self, meta))]
+    fn trace_call(
+        &self,
+        meta: Self::Metadata,
+        tx: RPCTransaction,
+        block: Option<BlockId>,
+        config: Option<RPCTraceConfig>,
+    ) -> BoxFuture<Result<RPCTraceResult, Error>> {
+        let config = config.unwrap_or_default();
+        Box::pin(async move {
+            let meta_keys = parse_meta_keys(&config.meta_keys)?;
+            let saved_state = block_to_state_root(block, &meta).await;
+            let (mut executor, bank) = executor_for_state(&meta, saved_state, true)?;
+            trace_transaction(&mut executor, &bank, tx, &meta_keys, &config)
+        })
+    }
+
+    #[instrument(skip(self, meta))]
+    fn trace_block_by_number(
+        &self,
+        meta: Self::Metadata,
+        block: BlockId,
+        config: Option<RPCTraceConfig>,
+    ) -> BoxFuture<Result<Vec<RPCBlockTrace>, Error>> {
+        Box::pin(trace_block(meta, block, config.unwrap_or_default()))
+    }
+
+    #[instrument(skip(self, meta))]
+    fn trace_block_by_hash(
+        &self,
+        meta: Self::Metadata,
+        block_hash: H256,
+        config: Option<RPCTraceConfig>,
+    ) -> BoxFuture<Result<Vec<RPCBlockTrace>, Error>> {
+        Box::pin(async move {
+            let block =
+                meta.get_evm_block_id_by_hash(block_hash)
+                    .await
+                    .ok_or(Error::BlockNotFound {
+                        block: BlockId::BlockHash { block_hash },
+                    })?;
+            trace_block(meta, block.into(), config.unwrap_or_default()).await
+        })
+    }
+}
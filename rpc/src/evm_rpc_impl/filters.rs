@@ -0,0 +1,343 @@
+//! Server side registry for `eth_newFilter` family of methods.
+//!
+//! Filters are kept in memory of rpc node, and removed if client didn't poll them for `timeout`.
+//! Filter ids are random, so clients can't guess and poll filters of each other,
+//! and allocated from the lower half of `u64`, the upper half is reserved for evm-bridge.
+//! Pending transaction filters are not supported, because node has no evm mempool,
+//! they are served by evm-bridge instead.
+
+use {
+    super::{logs_in_range, ChainErpcImpl, MAX_NUM_BLOCKS},
+    crate::rpc::JsonRpcRequestProcessor,
+    dashmap::{mapref::entry::Entry, DashMap},
+    evm_rpc::{
+        chain::ChainERPC,
+        error::{Error, FilterNotFound},
+        filter::FilterERPC,
+        BlockId, Either, RPCLog, RPCLogFilter,
+    },
+    evm_state::{H256, U256},
+    jsonrpc_core::BoxFuture,
+    rand::{thread_rng, Rng},
+    std::{
+        net::IpAddr,
+        sync::Arc,
+        time::{Duration, Instant},
+    },
+};
+
+/// Same as geth default `filterTimeout`.
+pub const DEFAULT_FILTER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+pub const DEFAULT_MAX_INSTALLED_FILTERS: usize = 10_000;
+/// Limit for single client, so one client can't exhaust `DEFAULT_MAX_INSTALLED_FILTERS`.
+pub const DEFAULT_MAX_FILTERS_PER_CLIENT: usize = 100;
+/// Ids starting from this one are allocated by evm-bridge.
+const MAX_FILTER_ID: u64 = 1 << 63;
+
+#[derive(Debug, Clone)]
+pub enum EvmFilterKind {
+    Logs(RPCLogFilter),
+    Blocks,
+}
+
+#[derive(Debug, Clone)]
+struct EvmFilter {
+    kind: EvmFilterKind,
+    /// Last evm block, which changes was already returned to the client.
+    last_block: u64,
+    last_poll: Instant,
+    /// Address of client that installed filter, if it is known.
+    owner: Option<IpAddr>,
+}
+
+#[derive(Debug)]
+pub struct EvmFilterRegistry {
+    filters: DashMap<u64, EvmFilter>,
+    max_filters: usize,
+    max_filters_per_client: usize,
+    timeout: Duration,
+}
+
+impl Default for EvmFilterRegistry {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_MAX_INSTALLED_FILTERS,
+            DEFAULT_MAX_FILTERS_PER_CLIENT,
+            DEFAULT_FILTER_TIMEOUT,
+        )
+    }
+}
+
+impl EvmFilterRegistry {
+    pub fn new(max_filters: usize, max_filters_per_client: usize, timeout: Duration) -> Self {
+        Self {
+            filters: DashMap::new(),
+            max_filters,
+            max_filters_per_client,
+            timeout,
+        }
+    }
+
+    /// Register new filter, that will track changes after `last_block`.
+    ///
+    /// Filters of clients with unknown `owner` are limited only by global limit.
+    pub fn install(
+        &self,
+        kind: EvmFilterKind,
+        last_block: u64,
+        owner: Option<IpAddr>,
+    ) -> Result<u64, Error> {
+        self.remove_expired();
+        if self.filters.len() >= self.max_filters {
+            return Err(Error::FiltersLimitReached {
+                limit: self.max_filters,
+            });
+        }
+        if owner.is_some()
+            && self
+                .filters
+                .iter()
+                .filter(|filter| filter.owner == owner)
+                .count()
+                >= self.max_filters_per_client
+        {
+            return Err(Error::FiltersLimitReached {
+                limit: self.max_filters_per_client,
+            });
+        }
+        let mut rng = thread_rng();
+        loop {
+            let id = rng.gen_range(1, MAX_FILTER_ID);
+            if let Entry::Vacant(entry) = self.filters.entry(id) {
+                entry.insert(EvmFilter {
+                    kind,
+                    last_block,
+                    last_poll: Instant::now(),
+                    owner,
+                });
+                return Ok(id);
+            }
+        }
+    }
+
+    pub fn uninstall(&self, id: u64) -> bool {
+        self.filters.remove(&id).is_some()
+    }
+
+    /// Returns filter and last polled block, and prolongs filter lifetime.
+    pub fn poll(&self, id: u64) -> Option<(EvmFilterKind, u64)> {
+        let mut filter = self.filters.get_mut(&id)?;
+        if filter.last_poll.elapsed() > self.timeout {
+            drop(filter);
+            self.filters.remove(&id);
+            return None;
+        }
+        filter.last_poll = Instant::now();
+        Some((filter.kind.clone(), filter.last_block))
+    }
+
+    pub fn set_last_block(&self, id: u64, last_block: u64) {
+        if let Some(mut filter) = self.filters.get_mut(&id) {
+            filter.last_block = last_block;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    fn remove_expired(&self) {
+        let timeout = self.timeout;
+        self.filters
+            .retain(|_, filter| filter.last_poll.elapsed() <= timeout);
+    }
+}
+
+fn filter_id(id: U256) -> Result<u64, Error> {
+    if id > U256::from(u64::MAX) {
+        return FilterNotFound { id }.fail();
+    }
+    Ok(id.as_u64())
+}
+
+fn fixed_block_num(block: &Option<BlockId>) -> Option<u64> {
+    match block {
+        Some(BlockId::Num(num)) => Some(num.0),
+        _ => None,
+    }
+}
+
+fn last_available_block(meta: &JsonRpcRequestProcessor) -> Result<u64, Error> {
+    meta.get_last_available_evm_block()
+        .ok_or(Error::ArchiveNotSupported)
+}
+
+pub struct FilterErpcImpl;
+impl FilterERPC for FilterErpcImpl {
+    type Metadata = Arc<JsonRpcRequestProcessor>;
+
+    fn new_filter(
+        &self,
+        meta: Self::Metadata,
+        log_filter: RPCLogFilter,
+    ) -> BoxFuture<Result<U256, Error>> {
+        Box::pin(async move {
+            let last_block = last_available_block(&meta)?;
+            // Track logs starting from `from_block`, if it was set explicitly.
+            let last_block = fixed_block_num(&log_filter.from_block)
+                .map(|from| from.saturating_sub(1).min(last_block))
+                .unwrap_or(last_block);
+            let id = meta.evm_filters.install(
+                EvmFilterKind::Logs(log_filter),
+                last_block,
+                meta.client_ip,
+            )?;
+            Ok(id.into())
+        })
+    }
+
+    fn new_block_filter(&self, meta: Self::Metadata) -> Result<U256, Error> {
+        let last_block = last_available_block(&meta)?;
+        let id = meta
+            .evm_filters
+            .install(EvmFilterKind::Blocks, last_block, meta.client_ip)?;
+        Ok(id.into())
+    }
+
+    fn new_pending_transaction_filter(&self, _meta: Self::Metadata) -> Result<U256, Error> {
+        // Node has no evm mempool, pending transactions are tracked by evm-bridge.
+        Err(Error::Unimplemented {})
+    }
+
+    fn uninstall_filter(&self, meta: Self::Metadata, id: U256) -> Result<bool, Error> {
+        Ok(meta.evm_filters.uninstall(filter_id(id)?))
+    }
+
+    fn filter_changes(
+        &self,
+        meta: Self::Metadata,
+        id: U256,
+    ) -> BoxFuture<Result<Either<Vec<H256>, Vec<RPCLog>>, Error>> {
+        Box::pin(async move {
+            let filter_id = filter_id(id)?;
+            let (kind, last_block) = meta
+                .evm_filters
+                .poll(filter_id)
+                .ok_or(Error::FilterNotFound { id })?;
+            let latest = last_available_block(&meta)?;
+            let from = last_block + 1;
+            // Limit amount of blocks processed per request, rest will be returned on next poll.
+            let to = latest.min(last_block + MAX_NUM_BLOCKS);
+
+            let changes = match kind {
+                EvmFilterKind::Logs(log_filter) => {
+                    let to = fixed_block_num(&log_filter.to_block)
+                        .map(|filter_to| filter_to.min(to))
+                        .unwrap_or(to);
+                    if from > to {
+                        return Ok(Either::Right(vec![]));
+                    }
+                    Either::Right(logs_in_range(&meta, log_filter, from, to, None).await?)
+                }
+                EvmFilterKind::Blocks => {
+                    let mut hashes = vec![];
+                    for block_num in from..=to {
+                        match meta.get_evm_block_by_id(block_num).await {
+                            Some((block, _confirmed)) => hashes.push(block.header.hash()),
+                            None => break,
+                        }
+                    }
+                    let to = last_block + hashes.len() as u64;
+                    meta.evm_filters.set_last_block(filter_id, to);
+                    return Ok(Either::Left(hashes));
+                }
+            };
+            meta.evm_filters
+                .set_last_block(filter_id, to.max(last_block));
+            Ok(changes)
+        })
+    }
+
+    fn filter_logs(&self, meta: Self::Metadata, id: U256) -> BoxFuture<Result<Vec<RPCLog>, Error>> {
+        Box::pin(async move {
+            let (kind, _) = meta
+                .evm_filters
+                .poll(filter_id(id)?)
+                .ok_or(Error::FilterNotFound { id })?;
+            match kind {
+                EvmFilterKind::Logs(log_filter) => ChainErpcImpl.logs(meta, log_filter).await,
+                _ => FilterNotFound { id }.fail(),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_limit_and_uninstall() {
+        let registry = EvmFilterRegistry::new(2, 2, DEFAULT_FILTER_TIMEOUT);
+        let first = registry.install(EvmFilterKind::Blocks, 10, None).unwrap();
+        let second = registry.install(EvmFilterKind::Blocks, 10, None).unwrap();
+        assert_ne!(first, second);
+        assert!(first < MAX_FILTER_ID && second < MAX_FILTER_ID);
+        assert!(matches!(
+            registry.install(EvmFilterKind::Blocks, 10, None),
+            Err(Error::FiltersLimitReached { limit: 2 })
+        ));
+
+        assert!(registry.uninstall(first));
+        assert!(!registry.uninstall(first));
+        assert!(registry.poll(first).is_none());
+        registry.install(EvmFilterKind::Blocks, 10, None).unwrap();
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn filters_limit_per_client() {
+        let registry = EvmFilterRegistry::new(10, 2, DEFAULT_FILTER_TIMEOUT);
+        let greedy = Some(IpAddr::from([10, 0, 0, 1]));
+        let other = Some(IpAddr::from([10, 0, 0, 2]));
+
+        let first = registry.install(EvmFilterKind::Blocks, 10, greedy).unwrap();
+        registry.install(EvmFilterKind::Blocks, 10, greedy).unwrap();
+        assert!(matches!(
+            registry.install(EvmFilterKind::Blocks, 10, greedy),
+            Err(Error::FiltersLimitReached { limit: 2 })
+        ));
+        // Other clients are not affected.
+        registry.install(EvmFilterKind::Blocks, 10, other).unwrap();
+        registry.install(EvmFilterKind::Blocks, 10, None).unwrap();
+
+        assert!(registry.uninstall(first));
+        registry.install(EvmFilterKind::Blocks, 10, greedy).unwrap();
+        assert_eq!(registry.len(), 4);
+    }
+
+    #[test]
+    fn filters_poll_and_expire() {
+        let registry = EvmFilterRegistry::new(10, 10, DEFAULT_FILTER_TIMEOUT);
+        let id = registry.install(EvmFilterKind::Blocks, 10, None).unwrap();
+        assert!(matches!(
+            registry.poll(id),
+            Some((EvmFilterKind::Blocks, 10))
+        ));
+        registry.set_last_block(id, 15);
+        assert!(matches!(
+            registry.poll(id),
+            Some((EvmFilterKind::Blocks, 15))
+        ));
+
+        let registry = EvmFilterRegistry::new(10, 10, Duration::from_millis(0));
+        let id = registry.install(EvmFilterKind::Blocks, 10, None).unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(registry.poll(id).is_none());
+        assert!(registry.is_empty());
+    }
+}
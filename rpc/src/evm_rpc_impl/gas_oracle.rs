@@ -0,0 +1,331 @@
+//! Gas price oracle, that suggests fees from effective gas prices of recent evm blocks.
+//!
+//! Evm base fee is constant and equal to `BURN_GAS_PRICE`, so only priority fee is estimated,
+//! the same way as geth does: percentile of cheapest transactions of last blocks.
+
+use {
+    super::{block_parse_confirmed_num, GAS_PRICE},
+    crate::rpc::JsonRpcRequestProcessor,
+    evm_rpc::{error::Error, BlockId, RPCFeeHistory},
+    evm_state::{Block, BlockNum, BURN_GAS_PRICE, U256},
+    lru::LruCache,
+    std::sync::{Arc, Mutex},
+};
+
+/// Maximum number of blocks that can be requested by `eth_feeHistory`.
+pub const MAX_FEE_HISTORY_BLOCKS: u64 = 1024;
+/// Number of recent blocks, that are sampled for priority fee suggestion.
+pub const DEFAULT_ORACLE_BLOCKS: u64 = 20;
+/// Number of cheapest transactions, that are sampled from every block.
+pub const DEFAULT_ORACLE_SAMPLES_PER_BLOCK: usize = 3;
+/// Percentile of sampled priority fees, that is suggested to user.
+pub const DEFAULT_ORACLE_PERCENTILE: f64 = 60.0;
+/// Suggested priority fee never exceed 500 gwei.
+pub const DEFAULT_MAX_PRIORITY_FEE: u64 = 500_000_000_000;
+
+/// Fees paid in single block.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BlockFees {
+    pub gas_used_ratio: f64,
+    /// Priority fee and gas used by every transaction, sorted by priority fee.
+    pub rewards: Vec<(U256, u64)>,
+}
+
+impl BlockFees {
+    pub fn new(block: &Block, base_fee: U256) -> Self {
+        let gas_used_ratio = if block.header.gas_limit == 0 {
+            0.0
+        } else {
+            block.header.gas_used as f64 / block.header.gas_limit as f64
+        };
+        let mut rewards: Vec<_> = block
+            .transactions
+            .iter()
+            .map(|(_, receipt)| {
+                let priority_fee = receipt
+                    .transaction
+                    .effective_gas_price(base_fee)
+                    .saturating_sub(base_fee);
+                (priority_fee, receipt.used_gas)
+            })
+            .collect();
+        rewards.sort_by_key(|(priority_fee, _)| *priority_fee);
+        Self {
+            gas_used_ratio,
+            rewards,
+        }
+    }
+
+    /// Priority fees at requested percentiles, weighted by gas used.
+    ///
+    /// Percentiles should be sorted, empty block has zero rewards.
+    pub fn reward_percentiles(&self, percentiles: &[f64]) -> Vec<U256> {
+        if self.rewards.is_empty() {
+            return vec![U256::zero(); percentiles.len()];
+        }
+        let total_gas: u64 = self.rewards.iter().map(|(_, gas)| gas).sum();
+        let mut index = 0;
+        let mut sum_gas = self.rewards[0].1;
+        percentiles
+            .iter()
+            .map(|percentile| {
+                let threshold = (total_gas as f64 * percentile / 100.0) as u64;
+                while sum_gas < threshold && index < self.rewards.len() - 1 {
+                    index += 1;
+                    sum_gas += self.rewards[index].1;
+                }
+                self.rewards[index].0
+            })
+            .collect()
+    }
+}
+
+/// Value at `percentile` of unsorted `samples`, or zero if there is no samples.
+fn percentile_of(mut samples: Vec<U256>, percentile: f64) -> U256 {
+    if samples.is_empty() {
+        return U256::zero();
+    }
+    samples.sort();
+    let index = ((samples.len() - 1) as f64 * percentile / 100.0) as usize;
+    samples[index]
+}
+
+#[derive(Debug)]
+pub struct EvmGasOracle {
+    blocks: u64,
+    samples_per_block: usize,
+    percentile: f64,
+    max_priority_fee: U256,
+    min_gas_price: U256,
+    /// Last suggested priority fee, and head block it was computed for.
+    last_suggestion: Mutex<Option<(u64, U256)>>,
+    /// Fees of recent confirmed blocks, confirmed blocks never change, so they are safe to reuse.
+    block_fees: Mutex<LruCache<BlockNum, Arc<BlockFees>>>,
+}
+
+impl Default for EvmGasOracle {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_ORACLE_BLOCKS,
+            DEFAULT_ORACLE_PERCENTILE,
+            DEFAULT_MAX_PRIORITY_FEE.into(),
+            solana_evm_loader_program::scope::evm::lamports_to_gwei(GAS_PRICE),
+        )
+    }
+}
+
+impl EvmGasOracle {
+    pub fn new(blocks: u64, percentile: f64, max_priority_fee: U256, min_gas_price: U256) -> Self {
+        Self {
+            blocks,
+            samples_per_block: DEFAULT_ORACLE_SAMPLES_PER_BLOCK,
+            percentile,
+            max_priority_fee,
+            min_gas_price,
+            last_suggestion: Mutex::new(None),
+            block_fees: Mutex::new(LruCache::new(MAX_FEE_HISTORY_BLOCKS as usize)),
+        }
+    }
+
+    pub fn base_fee(&self) -> U256 {
+        BURN_GAS_PRICE.into()
+    }
+
+    /// Fees of block `block_num`, from cache or blockstore.
+    async fn block_fees(
+        &self,
+        meta: &JsonRpcRequestProcessor,
+        block_num: BlockNum,
+    ) -> Option<Arc<BlockFees>> {
+        if let Some(fees) = self.block_fees.lock().unwrap().get(&block_num) {
+            return Some(fees.clone());
+        }
+        let (block, confirmed) = meta.get_evm_block_by_id(block_num).await?;
+        let fees = Arc::new(BlockFees::new(&block, self.base_fee()));
+        if confirmed {
+            self.block_fees.lock().unwrap().put(block_num, fees.clone());
+        }
+        Some(fees)
+    }
+
+    /// Suggest priority fee from cheapest transactions of recent blocks.
+    pub async fn suggest_priority_fee(
+        &self,
+        meta: &JsonRpcRequestProcessor,
+    ) -> Result<U256, Error> {
+        let head = block_parse_confirmed_num(None, meta).await.unwrap_or(0);
+        if let Some((block, priority_fee)) = *self.last_suggestion.lock().unwrap() {
+            if block == head {
+                return Ok(priority_fee);
+            }
+        }
+
+        let mut samples = vec![];
+        let oldest = (head + 1).saturating_sub(self.blocks);
+        for block_num in oldest..=head {
+            if let Some(fees) = self.block_fees(meta, block_num).await {
+                samples.extend(
+                    fees.rewards
+                        .iter()
+                        .take(self.samples_per_block)
+                        .map(|(priority_fee, _)| *priority_fee),
+                );
+            }
+        }
+        let priority_fee = percentile_of(samples, self.percentile).min(self.max_priority_fee);
+
+        *self.last_suggestion.lock().unwrap() = Some((head, priority_fee));
+        Ok(priority_fee)
+    }
+
+    /// Suggest gas price for legacy transactions, it should cover base fee and priority fee.
+    pub async fn suggest_gas_price(&self, meta: &JsonRpcRequestProcessor) -> Result<U256, Error> {
+        let gas_price = self.base_fee() + self.suggest_priority_fee(meta).await?;
+        Ok(gas_price.max(self.min_gas_price))
+    }
+
+    pub async fn fee_history(
+        &self,
+        meta: &JsonRpcRequestProcessor,
+        block_count: u64,
+        newest_block: BlockId,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> Result<RPCFeeHistory, Error> {
+        if let Some(percentiles) = &reward_percentiles {
+            let valid = percentiles.iter().all(|p| (0.0..=100.0).contains(p))
+                && percentiles.windows(2).all(|w| w[0] <= w[1]);
+            if !valid {
+                return Err(Error::InvalidParams {});
+            }
+        }
+
+        let newest = match newest_block {
+            BlockId::BlockHash { block_hash } => meta.get_evm_block_id_by_hash(block_hash).await,
+            block => block_parse_confirmed_num(Some(block), meta).await,
+        }
+        .ok_or(Error::BlockNotFound {
+            block: newest_block,
+        })?;
+        let block_count = block_count.min(MAX_FEE_HISTORY_BLOCKS);
+        if block_count == 0 {
+            return Ok(RPCFeeHistory {
+                oldest_block: U256::zero(),
+                base_fee_per_gas: vec![],
+                gas_used_ratio: vec![],
+                reward: None,
+            });
+        }
+        let oldest = (newest + 1)
+            .saturating_sub(block_count)
+            .max(meta.get_first_available_evm_block().await);
+
+        let mut gas_used_ratio = vec![];
+        let mut reward = vec![];
+        for block_num in oldest..=newest {
+            let fees = self
+                .block_fees(meta, block_num)
+                .await
+                .ok_or(Error::BlockNotFound {
+                    block: block_num.into(),
+                })?;
+            if let Some(percentiles) = &reward_percentiles {
+                reward.push(fees.reward_percentiles(percentiles));
+            }
+            gas_used_ratio.push(fees.gas_used_ratio);
+        }
+
+        Ok(RPCFeeHistory {
+            oldest_block: oldest.into(),
+            base_fee_per_gas: vec![self.base_fee(); gas_used_ratio.len() + 1],
+            gas_used_ratio,
+            reward: reward_percentiles.map(|_| reward),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        evm_state::{BlockHeader, BlockVersion, H256},
+        jsonrpc_core::futures::executor::block_on,
+        solana_client::connection_cache::ConnectionCache,
+        solana_ledger::genesis_utils::create_genesis_config,
+        solana_runtime::bank::Bank,
+        solana_streamer::socket::SocketAddrSpace,
+    };
+
+    fn fees(rewards: &[(u64, u64)]) -> BlockFees {
+        BlockFees {
+            gas_used_ratio: 0.5,
+            rewards: rewards
+                .iter()
+                .map(|(fee, gas)| (U256::from(*fee), *gas))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn reward_percentiles_weighted_by_gas() {
+        let block = fees(&[(1, 21000), (5, 21000), (10, 100000)]);
+        assert_eq!(
+            block.reward_percentiles(&[0.0, 10.0, 25.0, 50.0, 100.0]),
+            vec![1.into(), 1.into(), 5.into(), 10.into(), 10.into()]
+        );
+
+        let empty = fees(&[]);
+        assert_eq!(
+            empty.reward_percentiles(&[10.0, 90.0]),
+            vec![U256::zero(), U256::zero()]
+        );
+    }
+
+    #[test]
+    fn percentile_of_samples() {
+        let samples: Vec<U256> = [7, 1, 3, 9, 5].iter().map(|s| U256::from(*s)).collect();
+        assert_eq!(percentile_of(samples.clone(), 0.0), 1.into());
+        assert_eq!(percentile_of(samples.clone(), 60.0), 5.into());
+        assert_eq!(percentile_of(samples, 100.0), 9.into());
+        assert_eq!(percentile_of(vec![], 60.0), U256::zero());
+    }
+
+    #[test]
+    fn fee_history_caches_confirmed_blocks() {
+        let genesis = create_genesis_config(100);
+        let bank = Arc::new(Bank::new_for_tests(&genesis.genesis_config));
+        let meta = JsonRpcRequestProcessor::new_from_bank(
+            &bank,
+            SocketAddrSpace::Unspecified,
+            Arc::new(ConnectionCache::default()),
+        );
+        for block_num in 0..3 {
+            let header = BlockHeader::new(
+                H256::zero(),
+                400,
+                H256::zero(),
+                block_num,
+                100 * (block_num + 1),
+                0,
+                block_num,
+                H256::zero(),
+                std::iter::empty(),
+                BlockVersion::InitVersion,
+            );
+            meta.blockstore.write_evm_block_header(&header).unwrap();
+        }
+        // Only first two blocks are confirmed.
+        meta.blockstore.set_roots([0, 1].iter()).unwrap();
+
+        let oracle = EvmGasOracle::default();
+        let history = block_on(oracle.fee_history(&meta, 10, 2.into(), Some(vec![50.0]))).unwrap();
+        assert_eq!(history.oldest_block, U256::zero());
+        assert_eq!(history.gas_used_ratio, vec![0.25, 0.5, 0.75]);
+        assert_eq!(history.reward, Some(vec![vec![U256::zero()]; 3]));
+        assert_eq!(history.base_fee_per_gas.len(), 4);
+
+        let cache = oracle.block_fees.lock().unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains(&0) && cache.contains(&1));
+        assert!(!cache.contains(&2));
+    }
+}
@@ -1,8 +1,19 @@
+mod debug;
+mod filters;
+mod gas_oracle;
+
+pub use {
+    debug::DebugErpcImpl,
+    filters::{EvmFilterRegistry, FilterErpcImpl},
+    gas_oracle::EvmGasOracle,
+};
+
 use std::str::FromStr;
 
 use sha3::{Digest, Keccak256};
 use solana_evm_loader_program::processor::BURN_ADDR;
 use solana_sdk::account::{AccountSharedData, ReadableAccount};
+use solana_sdk::clock::Slot;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::keyed_account::KeyedAccount;
 use solana_sdk::pubkey::Pubkey;
@@ -12,16 +23,19 @@ use crate::rpc_health::RpcHealthStatus;
 use evm_rpc::error::EvmStateError;
 use evm_rpc::{
     chain::ChainERPC,
-    error::{into_native_error, BlockNotFound, Error, StateNotFoundForBlock},
+    error::{into_native_error, BlockNotFound, Error, ProofError},
     general::GeneralERPC,
     trace::{TraceERPC, TraceMeta},
-    BlockId, BlockRelId, Bytes, Either, Hex, RPCBlock, RPCLog, RPCLogFilter, RPCReceipt,
-    RPCTopicFilter, RPCTransaction,
+    BlockId, BlockRelId, Bytes, Either, Hex, RPCAccountProof, RPCBlock, RPCFeeHistory, RPCLog,
+    RPCLogFilter, RPCReceipt, RPCTopicFilter, RPCTransaction,
 };
+use evm_state::storage::proof::AccountProof;
 use evm_state::{
-    AccountProvider, AccountState, Address, Block, BlockHeader, Committed, ExecutionResult, Gas,
-    LogFilter, Transaction, TransactionAction, TransactionInReceipt, TransactionReceipt,
-    TransactionSignature, UnsignedTransactionWithCaller, H160, H256, U256,
+    AccessListItem, AccountProvider, AccountState, Address, Block, BlockHeader, Committed,
+    ExecutionResult, Gas, LogFilter, Transaction, TransactionAction, TransactionInReceipt,
+    TransactionReceipt, TransactionSignature, TypedTransaction, TypedTransactionFee,
+    TypedTransactionSignature, UnsignedTransactionWithCaller, UnsignedTypedTransaction, H160,
+    H256, U256,
 };
 use jsonrpc_core::BoxFuture;
 use snafu::ensure;
@@ -39,6 +53,7 @@ pub struct StateRootWithBank {
     pub bank: Option<Arc<Bank>>,
     pub block: BlockId,
     pub block_timestamp: Option<u64>,
+    pub native_slot: Option<Slot>,
 }
 
 impl StateRootWithBank {
@@ -59,13 +74,14 @@ impl StateRootWithBank {
             assert!(evm.last_root() == root, "we store bank with invalid root");
             return Ok(evm.get_account_state(address));
         }
-        let archive_evm_state = meta
-            .evm_state_archive(self.block_timestamp)
-            .ok_or(Error::ArchiveNotSupported)?;
-        ensure!(
-            archive_evm_state.kvs().check_root_exist(root),
-            StateNotFoundForBlock { block: self.block }
-        );
+        let archive_evm_state = meta.evm_state_archive(self.block_timestamp, root)?;
+        if !archive_evm_state.kvs().check_root_exist(root) {
+            return Err(state_not_found(
+                archive_evm_state.kvs(),
+                self.block,
+                self.native_slot,
+            ));
+        }
         Ok(archive_evm_state
             .get_account_state_at(root, address)
             .unwrap_or_default())
@@ -89,17 +105,72 @@ impl StateRootWithBank {
             assert!(evm.last_root() == root, "we store bank with invalid root");
             return Ok(evm.get_storage(address, idx));
         }
-        let archive_evm_state = meta
-            .evm_state_archive(self.block_timestamp)
-            .ok_or(Error::ArchiveNotSupported)?;
-        ensure!(
-            archive_evm_state.kvs().check_root_exist(root),
-            StateNotFoundForBlock { block: self.block }
-        );
+        let archive_evm_state = meta.evm_state_archive(self.block_timestamp, root)?;
+        if !archive_evm_state.kvs().check_root_exist(root) {
+            return Err(state_not_found(
+                archive_evm_state.kvs(),
+                self.block,
+                self.native_slot,
+            ));
+        }
         Ok(archive_evm_state
             .get_storage_at(root, address, idx)
             .unwrap_or_default())
     }
+
+    pub fn get_proof_at(
+        &self,
+        meta: &JsonRpcRequestProcessor,
+        address: H160,
+        storage_keys: &[H256],
+    ) -> Result<AccountProof, Error> {
+        ensure!(
+            self.state_root.is_some(),
+            BlockNotFound { block: self.block }
+        );
+
+        let root = *self.state_root.as_ref().unwrap();
+        if let Some(bank) = &self.bank {
+            let evm = bank.evm_state.read().unwrap();
+
+            assert!(evm.last_root() == root, "we store bank with invalid root");
+            return evm
+                .kvs()
+                .get_proof(root, address, storage_keys)
+                .context(ProofError);
+        }
+        let archive_evm_state = meta.evm_state_archive(self.block_timestamp, root)?;
+        if !archive_evm_state.kvs().check_root_exist(root) {
+            return Err(state_not_found(
+                archive_evm_state.kvs(),
+                self.block,
+                self.native_slot,
+            ));
+        }
+        archive_evm_state
+            .kvs()
+            .get_proof(root, address, storage_keys)
+            .context(ProofError)
+    }
+}
+
+/// Error for block, whose state root is missing in `storage`.
+///
+/// Storage with bounded history reports state as pruned, if block was produced
+/// at native slot, that is older than retention window.
+fn state_not_found(
+    storage: &evm_state::Storage,
+    block: BlockId,
+    native_slot: Option<Slot>,
+) -> Error {
+    let history = storage.history();
+    match native_slot {
+        Some(slot) if history.is_pruned(slot) => Error::StatePruned {
+            block,
+            oldest_block: history.oldest_block().unwrap_or_default(),
+        },
+        _ => Error::StateNotFoundForBlock { block },
+    }
 }
 
 #[instrument(skip(meta))]
@@ -122,6 +193,7 @@ async fn block_to_state_root(
                 bank: Some(bank),
                 block: block_id,
                 block_timestamp: None,
+                native_slot: None,
             };
         }
         BlockId::RelativeId(BlockRelId::Earliest) | BlockId::Num(Hex(0)) => {
@@ -138,29 +210,88 @@ async fn block_to_state_root(
                     bank: None,
                     block: block_id,
                     block_timestamp: None,
+                    native_slot: None,
                 };
             }
         }
     };
+    let header = meta
+        .get_evm_block_by_id(block_num) // TODO: don't request full block.
+        .await
+        .filter(|(b, _)| {
+            // if requested specific block hash, check that block with this hash is not in reorged fork
+            found_block_hash
+                .map(|block_hash| b.header.hash() == block_hash)
+                .unwrap_or(true)
+        })
+        .map(|(b, _)| b.header);
     StateRootWithBank {
-        state_root: meta
-            .get_evm_block_by_id(block_num) // TODO: don't request full block.
-            .await
-            .filter(|(b, _)| {
-                // if requested specific block hash, check that block with this hash is not in reorged fork
-                found_block_hash
-                    .map(|block_hash| b.header.hash() == block_hash)
-                    .unwrap_or(true)
-            })
-            .map(|(b, _)| b.header.state_root),
-
+        state_root: header.as_ref().map(|header| header.state_root),
         bank: None,
         block: block_id,
-        block_timestamp: meta
-            .get_evm_block_by_id(block_num)
-            .await
-            .map(|(block, _)| block.header.timestamp),
+        block_timestamp: header.as_ref().map(|header| header.timestamp),
+        native_slot: header.map(|header| header.native_chain_slot),
+    }
+}
+
+/// State before `block_num`, used to replay transactions of `block_num` with the features,
+/// that were active when that block was produced.
+async fn replay_state_root(meta: &JsonRpcRequestProcessor, block_num: u64) -> StateRootWithBank {
+    let mut saved_state = block_to_state_root(Some(block_num.saturating_sub(1).into()), meta).await;
+    saved_state.native_slot = meta
+        .get_evm_block_by_id(block_num)
+        .await
+        .map(|(block, _)| block.header.native_chain_slot);
+    saved_state
+}
+
+/// Executor with state right before transaction `tx_index` (starts from 1) of block `block_num`.
+/// Preceding transactions of block are replayed with `meta_keys`, same as in `trace_replayTransaction`,
+/// transactions that cannot be replayed are skipped.
+/// Returns `None` if block not found.
+async fn replay_preceding_transactions(
+    meta: &JsonRpcRequestProcessor,
+    block_num: u64,
+    tx_index: usize,
+    meta_keys: &[solana_sdk::pubkey::Pubkey],
+) -> Result<Option<(evm_state::Executor, Arc<Bank>)>, Error> {
+    let block = match meta.get_evm_block_by_id(block_num).await {
+        Some((block, _)) => block,
+        None => return Ok(None),
+    };
+    let block_hash = block.header.hash();
+    let chain_id = meta.bank(None).evm_chain_id;
+
+    let saved_state = replay_state_root(meta, block_num).await;
+    let (mut executor, bank) = executor_for_state(meta, saved_state, false)?;
+    for (hash, receipt) in block
+        .transactions
+        .into_iter()
+        .take(tx_index.saturating_sub(1))
+    {
+        let tx = match RPCTransaction::new_from_receipt(receipt, hash, block_hash, chain_id) {
+            Ok(tx) => tx,
+            Err(e) => {
+                debug!("Skipping preceding tx = {:?}, error = {:?}", hash, e);
+                continue;
+            }
+        };
+        if let Err(e) = call_inner(&mut executor, tx, meta_keys.to_vec(), &bank) {
+            debug!("Skipping preceding tx = {:?}, error = {:?}", hash, e);
+        }
     }
+    Ok(Some((executor, bank)))
+}
+
+fn parse_meta_keys(
+    meta_keys: &Option<Vec<String>>,
+) -> Result<Vec<solana_sdk::pubkey::Pubkey>, Error> {
+    meta_keys
+        .iter()
+        .flatten()
+        .map(|s| solana_sdk::pubkey::Pubkey::from_str(s))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| into_native_error(e, false))
 }
 
 #[instrument(skip(meta))]
@@ -186,6 +317,59 @@ async fn block_parse_confirmed_num(
     }
 }
 
+/// Maximum number of blocks that can be scanned for logs in a single request.
+const MAX_NUM_BLOCKS: u64 = 2000;
+/// Maximum number of logs returned by `eth_getLogs`, index allows to request unbounded block range.
+const MAX_NUM_LOGS: usize = 10_000;
+
+fn to_log_filter(log_filter: RPCLogFilter, from: u64, to: u64) -> LogFilter {
+    LogFilter {
+        address: log_filter
+            .address
+            .map(|k| match k {
+                Either::Left(v) => v,
+                Either::Right(k) => vec![k],
+            })
+            .unwrap_or_default(),
+        topics: log_filter
+            .topics
+            .unwrap_or_else(|| vec![None])
+            .into_iter()
+            .map(RPCTopicFilter::into_topics)
+            .collect(),
+        from_block: from,
+        to_block: to,
+    }
+}
+
+async fn logs_in_range(
+    meta: &JsonRpcRequestProcessor,
+    log_filter: RPCLogFilter,
+    from: u64,
+    to: u64,
+    limit: Option<usize>,
+) -> Result<Vec<RPCLog>, Error> {
+    let filter = to_log_filter(log_filter, from, to);
+    debug!("filter = {:?}", filter);
+
+    let logs = meta.filter_logs(filter, limit).await.map_err(|e| {
+        debug!("filter_logs error = {:?}", e);
+        into_native_error(e, false)
+    })?;
+    if let Some(limit) = limit {
+        if let Some(first_excess) = logs.get(limit) {
+            // Suggest range, that ends before block with first log above limit.
+            let ending = first_excess.block_num.saturating_sub(1).max(from);
+            return Err(Error::TooManyLogs {
+                limit,
+                starting: from,
+                ending,
+            });
+        }
+    }
+    Ok(logs.into_iter().map(|l| l.into()).collect())
+}
+
 pub struct GeneralErpcImpl;
 impl GeneralERPC for GeneralErpcImpl {
     type Metadata = Arc<JsonRpcRequestProcessor>;
@@ -244,10 +428,12 @@ impl GeneralERPC for GeneralErpcImpl {
         Ok(0.into())
     }
 
-    fn gas_price(&self, _meta: Self::Metadata) -> Result<Gas, Error> {
-        Ok(solana_evm_loader_program::scope::evm::lamports_to_gwei(
-            GAS_PRICE,
-        ))
+    fn gas_price(&self, meta: Self::Metadata) -> BoxFuture<Result<Gas, Error>> {
+        Box::pin(async move { meta.evm_gas_oracle.suggest_gas_price(&meta).await })
+    }
+
+    fn max_priority_fee_per_gas(&self, meta: Self::Metadata) -> BoxFuture<Result<Gas, Error>> {
+        Box::pin(async move { meta.evm_gas_oracle.suggest_priority_fee(&meta).await })
     }
 }
 
@@ -299,6 +485,36 @@ impl ChainERPC for ChainErpcImpl {
         })
     }
 
+    #[instrument(skip(self, meta))]
+    fn fee_history(
+        &self,
+        meta: Self::Metadata,
+        block_count: Hex<u64>,
+        newest_block: BlockId,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> BoxFuture<Result<RPCFeeHistory, Error>> {
+        Box::pin(async move {
+            meta.evm_gas_oracle
+                .fee_history(&meta, block_count.0, newest_block, reward_percentiles)
+                .await
+        })
+    }
+
+    #[instrument(skip(self, meta))]
+    fn proof(
+        &self,
+        meta: Self::Metadata,
+        address: Address,
+        storage_keys: Vec<H256>,
+        block: Option<BlockId>,
+    ) -> BoxFuture<Result<RPCAccountProof, Error>> {
+        Box::pin(async move {
+            let state = block_to_state_root(block, &meta).await;
+            let proof = state.get_proof_at(&meta, address, &storage_keys)?;
+            Ok(proof.into())
+        })
+    }
+
     #[instrument(skip(self, meta))]
     fn transaction_count(
         &self,
@@ -486,8 +702,13 @@ impl ChainERPC for ChainErpcImpl {
                                 }
                             })?;
                     let block_hash = block.header.hash();
+                    let burn_gas_price = burn_gas_price_at(&meta, block.header.native_chain_slot);
                     Some(RPCReceipt::new_from_receipt(
-                        receipt, tx_hash, block_hash, None,
+                        receipt,
+                        tx_hash,
+                        block_hash,
+                        None,
+                        burn_gas_price,
                     )?)
                 }
                 None => None,
@@ -549,7 +770,6 @@ impl ChainERPC for ChainErpcImpl {
         log_filter: RPCLogFilter,
     ) -> BoxFuture<Result<Vec<RPCLog>, Error>> {
         Box::pin(async move {
-            const MAX_NUM_BLOCKS: u64 = 2000;
             let block_num = meta
                 .get_last_available_evm_block()
                 .ok_or(Error::ArchiveNotSupported)?;
@@ -559,7 +779,10 @@ impl ChainERPC for ChainErpcImpl {
             let from = block_parse_confirmed_num(log_filter.from_block, &meta)
                 .await
                 .unwrap_or(block_num);
-            if to > from + MAX_NUM_BLOCKS {
+            // Filters by address or topic are answered from logs index, without range limit.
+            if to > from + MAX_NUM_BLOCKS
+                && !meta.is_evm_logs_indexed(&to_log_filter(log_filter.clone(), from, to))
+            {
                 warn!(
                     "Log filter, block range is too big, reducing, to={}, from={}",
                     to, from
@@ -571,30 +794,7 @@ impl ChainERPC for ChainErpcImpl {
                 });
             }
 
-            let filter = LogFilter {
-                address: log_filter
-                    .address
-                    .map(|k| match k {
-                        Either::Left(v) => v,
-                        Either::Right(k) => vec![k],
-                    })
-                    .unwrap_or_default(),
-                topics: log_filter
-                    .topics
-                    .unwrap_or_else(|| vec![None])
-                    .into_iter()
-                    .map(RPCTopicFilter::into_topics)
-                    .collect(),
-                from_block: from,
-                to_block: to,
-            };
-            debug!("filter = {:?}", filter);
-
-            let logs = meta.filter_logs(filter).await.map_err(|e| {
-                debug!("filter_logs error = {:?}", e);
-                into_native_error(e, false)
-            })?;
-            Ok(logs.into_iter().map(|l| l.into()).collect())
+            logs_in_range(&meta, log_filter, from, to, Some(MAX_NUM_LOGS)).await
         })
     }
 
@@ -647,8 +847,9 @@ impl TraceERPC for TraceErpcImpl {
         meta_info: Option<TraceMeta>,
     ) -> BoxFuture<Result<evm_rpc::trace::TraceResultsWithTransactionHash, Error>> {
         Box::pin(async move {
+            let saved_state = block_to_state_root(block, &meta).await;
             Ok(
-                trace_call_many(meta, vec![(tx, traces, meta_info)], block, true)
+                trace_call_many(meta, vec![(tx, traces, meta_info)], saved_state, true)
                     .await?
                     .into_iter()
                     .next()
@@ -664,7 +865,10 @@ impl TraceERPC for TraceErpcImpl {
         tx_traces: Vec<(RPCTransaction, Vec<String>, Option<TraceMeta>)>,
         block: Option<BlockId>,
     ) -> BoxFuture<Result<Vec<evm_rpc::trace::TraceResultsWithTransactionHash>, Error>> {
-        Box::pin(trace_call_many(meta, tx_traces, block, true))
+        Box::pin(async move {
+            let saved_state = block_to_state_root(block, &meta).await;
+            trace_call_many(meta, tx_traces, saved_state, true).await
+        })
     }
 
     #[instrument(skip(self, meta))]
@@ -672,7 +876,7 @@ impl TraceERPC for TraceErpcImpl {
         &self,
         meta: Self::Metadata,
         tx_hash: H256,
-        traces: Vec<String>,
+        _traces: Vec<String>,
         meta_info: Option<TraceMeta>,
     ) -> BoxFuture<Result<Option<evm_rpc::trace::TraceResultsWithTransactionHash>, Error>> {
         let meta_info = meta_info.unwrap_or_default();
@@ -683,34 +887,25 @@ impl TraceERPC for TraceErpcImpl {
                         (Some(block), Some(index)) => (block.as_u64(), index.0),
                         _ => return Ok(None),
                     };
-                    let base_block = tx_block.saturating_sub(1).into();
-                    let tx_traces = match meta.get_evm_block_by_id(tx_block).await {
-                        Some((block, _)) => {
-                            let block_hash = block.header.hash();
-                            let chain_id = meta.bank(None).evm_chain_id;
-                            block
-                                .transactions
-                                .into_iter()
-                                .take(tx_index)
-                                .filter_map(|(hash, receipt)| {
-                                    let tx = RPCTransaction::new_from_receipt(
-                                        receipt, hash, block_hash, chain_id,
-                                    )
-                                    .ok()?;
-                                    let mut meta_info = meta_info.clone();
-                                    meta_info.transaction_hash = tx.hash;
-                                    meta_info.transaction_index = tx.transaction_index.map(|v| v.0);
-                                    meta_info.block_number = tx.block_number;
-                                    meta_info.block_hash = tx.block_hash;
-                                    Some((tx, traces.clone(), Some(meta_info)))
-                                })
-                                .collect()
-                        }
-                        None => return Ok(None),
-                    };
-
-                    let traces = trace_call_many(meta, tx_traces, Some(base_block), false).await?;
-                    Ok(traces.get(tx_index - 1).cloned())
+                    let meta_keys = parse_meta_keys(&meta_info.meta_keys)?;
+                    let (mut executor, bank) =
+                        match replay_preceding_transactions(&meta, tx_block, tx_index, &meta_keys)
+                            .await?
+                        {
+                            Some(replayed) => replayed,
+                            None => return Ok(None),
+                        };
+                    let (transaction_hash, block_hash, block_number) =
+                        (tx.hash, tx.block_hash, tx.block_number);
+                    let output = call_inner(&mut executor, tx, meta_keys, &bank)?;
+                    Ok(Some(evm_rpc::trace::TraceResultsWithTransactionHash {
+                        trace: output.traces.into_iter().map(From::from).collect(),
+                        output: output.exit_data.into(),
+                        transaction_hash,
+                        transaction_index: Some(Hex(tx_index)),
+                        block_hash,
+                        block_number,
+                    }))
                 }
                 Ok(None) => Ok(None),
                 Err(e) => Err(e),
@@ -748,14 +943,8 @@ impl TraceERPC for TraceErpcImpl {
                     (tx, traces.clone(), Some(meta_info))
                 })
                 .collect();
-            // execute on pervious block
-            trace_call_many(
-                meta,
-                transactions,
-                Some(block.number.as_u64().saturating_sub(1).into()),
-                false,
-            )
-            .await
+            let saved_state = replay_state_root(&meta, block.number.as_u64()).await;
+            trace_call_many(meta, transactions, saved_state, false).await
         })
     }
 
@@ -770,11 +959,13 @@ impl TraceERPC for TraceErpcImpl {
         clear_logs_on_error: bool,
         accept_zero_gas_price_with_native_fee: bool,
         burn_gas_price: u64,
+        hardfork: Option<evm_state::HardforkConfig>,
     ) -> BoxFuture<Result<(Block, Vec<H256>), Error>> {
         fn simulate_transaction(
             executor: &mut evm_state::Executor,
             tx: RPCTransaction,
             meta_keys: Vec<solana_sdk::pubkey::Pubkey>,
+            feature_set: &solana_sdk::feature_set::FeatureSet,
         ) -> Result<ExecutionResult, Error> {
             use solana_evm_loader_program::precompiles::*;
             macro_rules! unwrap_or_default {
@@ -795,6 +986,26 @@ impl TraceERPC for TraceErpcImpl {
             let nonce = unwrap_or_default!(tx.nonce);
             let tx_chain_id = executor.chain_id();
             let tx_hash = unwrap_or_default!(tx.hash);
+            let access_list: Vec<AccessListItem> = tx
+                .access_list
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect();
+            let typed_fee = match tx.transaction_type.map(|t| *t) {
+                Some(evm_state::ACCESS_LIST_TX_TYPE) => {
+                    Some(TypedTransactionFee::AccessList { gas_price })
+                }
+                Some(evm_state::DYNAMIC_FEE_TX_TYPE) => Some(TypedTransactionFee::DynamicFee {
+                    max_priority_fee_per_gas: unwrap_or_default!(tx.max_priority_fee_per_gas),
+                    max_fee_per_gas: tx.max_fee_per_gas.unwrap_or(gas_price),
+                }),
+                _ => None,
+            };
+            let gas_price = typed_fee
+                .map(|fee| fee.effective_gas_price(executor.config().burn_gas_price))
+                .unwrap_or(gas_price);
 
             let evm_state_balance = u64::MAX - 1;
 
@@ -861,6 +1072,8 @@ impl TraceERPC for TraceErpcImpl {
             ));
             let evm_keyed_account =
                 KeyedAccount::new(&solana_sdk::evm_state::ID, false, &evm_account);
+            let activate_precompile =
+                PrecompileSet::from_feature_set(executor.support_precompile(), feature_set);
 
             let result = executor
                 .transaction_execute_raw(
@@ -871,11 +1084,13 @@ impl TraceERPC for TraceErpcImpl {
                     action,
                     input.clone(),
                     value,
+                    access_list.clone(),
                     Some(tx_chain_id),
                     tx_hash,
                     true,
                     simulation_entrypoint(
-                        PrecompileSet::VelasClassic,
+                        activate_precompile,
+                        NativeFeatures::from_feature_set(feature_set),
                         &evm_keyed_account,
                         &user_accounts,
                     ),
@@ -889,17 +1104,31 @@ impl TraceERPC for TraceErpcImpl {
             tx.s.ok_or(Error::InvalidParams {})?
                 .to_big_endian(&mut bytes);
             let s = H256::from_slice(&bytes);
+            let v = *tx.v.ok_or(Error::InvalidParams {})?;
+            let typed_transaction = typed_fee.map(|fee| TypedTransaction {
+                unsigned: UnsignedTypedTransaction {
+                    chain_id: tx_chain_id,
+                    nonce,
+                    fee,
+                    gas_limit,
+                    action,
+                    value,
+                    input: input.clone(),
+                    access_list,
+                },
+                signature: TypedTransactionSignature {
+                    odd_y_parity: v == 1,
+                    r,
+                    s,
+                },
+            });
             let transaction = Transaction {
                 nonce,
                 gas_price,
                 gas_limit,
                 action,
                 value,
-                signature: TransactionSignature {
-                    v: *tx.v.ok_or(Error::InvalidParams {})?,
-                    r,
-                    s,
-                },
+                signature: TransactionSignature { v, r, s },
                 input,
             };
 
@@ -932,6 +1161,8 @@ impl TraceERPC for TraceErpcImpl {
                     signed_compatible: true,
                     caller,
                 })
+            } else if let Some(typed_transaction) = typed_transaction {
+                TransactionInReceipt::Typed(typed_transaction)
             } else {
                 TransactionInReceipt::Signed(transaction)
             };
@@ -951,13 +1182,15 @@ impl TraceERPC for TraceErpcImpl {
         }
 
         Box::pin(async move {
-            let mut evm_state = meta
-                .evm_state_archive(Some(block_header.timestamp))
-                .ok_or(Error::ArchiveNotSupported)?
-                .new_incomming_for_root(state_root)
-                .ok_or(Error::StateNotFoundForBlock {
-                    block: BlockId::Num(Hex(block_header.block_number)),
-                })?;
+            let archive = meta.evm_state_archive(Some(block_header.timestamp), state_root)?;
+            let storage = archive.kvs().clone();
+            let mut evm_state = archive.new_incomming_for_root(state_root).ok_or_else(|| {
+                state_not_found(
+                    &storage,
+                    BlockId::Num(Hex(block_header.block_number)),
+                    Some(block_header.native_chain_slot),
+                )
+            })?;
             evm_state.state.block_number = block_header.block_number;
             evm_state.state.timestamp = block_header.timestamp;
             evm_state.state.last_block_hash = block_header.parent_hash;
@@ -966,6 +1199,8 @@ impl TraceERPC for TraceErpcImpl {
                 chain_id: meta.bank(None).evm_chain_id,
                 estimate: false,
                 burn_gas_price: burn_gas_price.into(),
+                // Old blocks are replayed with default (Istanbul) semantics.
+                executor_config: hardfork.unwrap_or_default(),
                 ..Default::default()
             };
 
@@ -973,6 +1208,12 @@ impl TraceERPC for TraceErpcImpl {
                 .try_into()
                 .map_err(|_| Error::InvalidParams {})?;
 
+            // Native contracts, that were active when block was produced.
+            let feature_set = meta
+                .bank(None)
+                .feature_set
+                .at_slot(block_header.native_chain_slot);
+
             let mut warn = vec![];
             debug!("running with evm_state = {:?}", evm_state);
             for (tx, meta_keys) in txs {
@@ -984,6 +1225,8 @@ impl TraceERPC for TraceErpcImpl {
                         unsigned_tx_fix,
                         clear_logs_on_error,
                         accept_zero_gas_price_with_native_fee,
+                        false,
+                        false,
                     ),
                 );
                 debug!("running on executor = {:?}", executor);
@@ -992,7 +1235,7 @@ impl TraceERPC for TraceErpcImpl {
                     .map(|s| solana_sdk::pubkey::Pubkey::from_str(s))
                     .collect::<Result<Vec<Pubkey>, _>>()
                     .map_err(|_| Error::InvalidParams {})?;
-                match simulate_transaction(&mut executor, tx.clone(), meta_keys) {
+                match simulate_transaction(&mut executor, tx.clone(), meta_keys, &feature_set) {
                     Ok(_result) => {
                         evm_state = executor.deconstruct();
                     }
@@ -1069,6 +1312,27 @@ fn call_many(
     saved_state: StateRootWithBank,
     estimate: bool,
 ) -> Result<Vec<TxOutput>, Error> {
+    let (mut executor, bank) = executor_for_state(&meta, saved_state, estimate)?;
+
+    debug!("running evm executor = {:?}", executor);
+    let mut result = Vec::new();
+    for (tx, meta_keys) in txs {
+        result.push(call_inner(
+            &mut executor,
+            tx.clone(),
+            meta_keys.clone(),
+            &bank,
+        )?)
+    }
+    Ok(result)
+}
+
+/// Create executor on top of `saved_state`, and return bank which should be used for simulation.
+fn executor_for_state(
+    meta: &JsonRpcRequestProcessor,
+    saved_state: StateRootWithBank,
+    estimate: bool,
+) -> Result<(evm_state::Executor, Arc<Bank>), Error> {
     // if we already found bank with some root, or we just cannot find state_root - use latest.
     let use_latest_state = saved_state.bank.is_some() || saved_state.state_root.is_none();
     let bank = saved_state
@@ -1085,12 +1349,17 @@ fn call_many(
         }
     } else {
         let root = saved_state.state_root.unwrap();
-        meta.evm_state_archive(saved_state.block_timestamp)
-            .ok_or(Error::ArchiveNotSupported)?
+        let archive = meta.evm_state_archive(saved_state.block_timestamp, root)?;
+        let storage = archive.kvs().clone();
+        archive
             .new_incomming_for_root(root)
-            .ok_or(Error::StateNotFoundForBlock {
-                block: saved_state.block,
-            })?
+            .ok_or_else(|| state_not_found(&storage, saved_state.block, saved_state.native_slot))?
+    };
+
+    // Historical blocks are replayed with features, that were active at the block's native slot.
+    let feature_set = match saved_state.native_slot {
+        Some(slot) if !use_latest_state => Arc::new(bank.feature_set.at_slot(slot)),
+        _ => bank.feature_set.clone(),
     };
 
     let estimate_config = evm_state::EvmConfig {
@@ -1101,32 +1370,23 @@ fn call_many(
 
     //TODO: Hashes actual to saved root
     let last_hashes = bank.evm_hashes();
-    let mut executor = evm_state::Executor::with_config(
+    let executor = evm_state::Executor::with_config(
         evm_state,
         evm_state::ChainContext::new(last_hashes),
         estimate_config,
-        evm_state::executor::FeatureSet::new(
-            bank.feature_set
-                .is_active(&solana_sdk::feature_set::velas::unsigned_tx_fix::id()),
-            bank.feature_set
-                .is_active(&solana_sdk::feature_set::velas::clear_logs_on_error::id()),
-            bank.feature_set.is_active(
-                &solana_sdk::feature_set::velas::accept_zero_gas_price_with_native_fee::id(),
-            ),
-        ),
+        solana_evm_loader_program::executor_feature_set(&feature_set),
     );
+    Ok((executor, bank))
+}
 
-    debug!("running evm executor = {:?}", executor);
-    let mut result = Vec::new();
-    for (tx, meta_keys) in txs {
-        result.push(call_inner(
-            &mut executor,
-            tx.clone(),
-            meta_keys.clone(),
-            &bank,
-        )?)
-    }
-    Ok(result)
+/// Burn gas price (equal to base fee) of evm blocks, that were produced at native `slot`.
+fn burn_gas_price_at(meta: &JsonRpcRequestProcessor, slot: Slot) -> Gas {
+    let bank = meta.bank(Some(CommitmentConfig::processed()));
+    let burn_fee_activated = bank
+        .feature_set
+        .at_slot(slot)
+        .is_active(&solana_sdk::feature_set::velas::burn_fee::id());
+    evm_state::EvmConfig::new(bank.evm_chain_id, burn_fee_activated).burn_gas_price
 }
 
 #[instrument(skip(executor, bank))]
@@ -1148,6 +1408,12 @@ fn call_inner(
     let nonce = tx.nonce.unwrap_or_else(|| executor.nonce(caller));
     let tx_chain_id = executor.chain_id();
     let tx_hash = tx.hash.unwrap_or_else(H256::random);
+    let access_list = tx
+        .access_list
+        .unwrap_or_default()
+        .into_iter()
+        .map(Into::into)
+        .collect();
 
     let evm_state_balance = bank
         .get_account(&solana_sdk::evm_state::id())
@@ -1209,6 +1475,8 @@ fn call_inner(
         evm_state_balance,
     ));
     let evm_keyed_account = KeyedAccount::new(&solana_sdk::evm_state::ID, false, &evm_account);
+    let activate_precompile =
+        PrecompileSet::from_feature_set(executor.support_precompile(), &bank.feature_set);
 
     let evm_state::executor::ExecutionResult {
         exit_reason,
@@ -1225,11 +1493,13 @@ fn call_inner(
             action,
             input,
             value,
+            access_list,
             Some(tx_chain_id),
             tx_hash,
             true,
             simulation_entrypoint(
-                PrecompileSet::VelasClassic,
+                activate_precompile,
+                NativeFeatures::from_feature_set(&bank.feature_set),
                 &evm_keyed_account,
                 &user_accounts,
             ),
@@ -1319,24 +1589,16 @@ async fn transaction_by_hash(
 async fn trace_call_many(
     meta: Arc<JsonRpcRequestProcessor>,
     tx_traces: Vec<(RPCTransaction, Vec<String>, Option<TraceMeta>)>,
-    block: Option<BlockId>,
+    saved_state: StateRootWithBank,
     estimate: bool,
 ) -> Result<Vec<evm_rpc::trace::TraceResultsWithTransactionHash>, Error> {
-    let saved_state = block_to_state_root(block, &meta).await;
-
     let mut txs = Vec::new();
     let mut txs_meta = Vec::new();
 
     // TODO: Handle Vec<String> - traces array, check that it contain "trace" string.
     for (t, _, meta) in tx_traces {
         let meta = meta.unwrap_or_default();
-        let meta_keys = meta
-            .meta_keys
-            .iter()
-            .flatten()
-            .map(|s| solana_sdk::pubkey::Pubkey::from_str(s))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| into_native_error(e, false))?;
+        let meta_keys = parse_meta_keys(&meta)?;
 
         txs.push((t, meta_keys));
         txs_meta.push(meta);
@@ -1357,3 +1619,44 @@ async fn trace_call_many(
     }
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_pruned_only_before_retention_window() {
+        let block = BlockId::Num(Hex(5));
+
+        let storage = evm_state::Storage::create_temporary_gc().unwrap();
+        assert!(matches!(
+            state_not_found(&storage, block, Some(5)),
+            Error::StateNotFoundForBlock { .. }
+        ));
+
+        storage.history().enable();
+        storage.history().set_oldest_block(42);
+        storage.purge_retained_slots(10).unwrap();
+
+        let err = state_not_found(&storage, block, Some(5));
+        assert!(matches!(
+            err,
+            Error::StatePruned {
+                oldest_block: 42,
+                ..
+            }
+        ));
+        let rpc_err = jsonrpc_core::Error::from(err);
+        assert_eq!(rpc_err.code, jsonrpc_core::ErrorCode::ServerError(2011));
+
+        // state of slot inside retention window should exist, so it is not pruned
+        assert!(matches!(
+            state_not_found(&storage, block, Some(10)),
+            Error::StateNotFoundForBlock { .. }
+        ));
+        assert!(matches!(
+            state_not_found(&storage, block, None),
+            Error::StateNotFoundForBlock { .. }
+        ));
+    }
+}
@@ -3,8 +3,11 @@
 use tracing_attributes::instrument;
 use {
     crate::{
-        max_slots::MaxSlots, optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
-        parsed_token_accounts::*, rpc_health::*,
+        evm_rpc_impl::{EvmFilterRegistry, EvmGasOracle},
+        max_slots::MaxSlots,
+        optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
+        parsed_token_accounts::*,
+        rpc_health::*,
     },
     bincode::{config::Options, serialize},
     crossbeam_channel::{unbounded, Receiver, Sender},
@@ -33,11 +36,13 @@ use {
         rpc_response::{Response as RpcResponse, *},
     },
     solana_entry::entry::Entry,
-    solana_faucet::faucet::request_airdrop_transaction,
+    solana_evm_loader_program::swap::SwapDirection,
+    solana_faucet::faucet::{request_airdrop_transaction, request_evm_airdrop_transaction},
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
     solana_ledger::{
-        blockstore::{Blockstore, SignatureInfosForAddress},
-        blockstore_db::BlockstoreError,
+        bigtable_upload::EvmBlockGapRepairConfig,
+        blockstore::{Blockstore, EvmSwapWithSignature, SignatureInfosForAddress},
+        blockstore_db::{BlockstoreError, EvmSwapAccount},
         get_tmp_ledger_path,
         leader_schedule_cache::LeaderScheduleCache,
     },
@@ -101,7 +106,7 @@ use {
         cmp::{max, min},
         collections::{hash_map::HashMap, HashSet},
         convert::TryFrom,
-        net::SocketAddr,
+        net::{IpAddr, SocketAddr},
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -166,6 +171,8 @@ pub struct JsonRpcConfig {
     pub obsolete_v1_7_api: bool,
     pub rpc_scan_and_fix_roots: bool,
     pub max_batch_duration: Option<Duration>,
+    /// Reverse proxies, whose `X-Forwarded-For` header is used to identify clients.
+    pub rpc_trusted_proxies: Vec<IpAddr>,
 }
 
 impl JsonRpcConfig {
@@ -183,6 +190,8 @@ pub struct RpcBigtableConfig {
     pub bigtable_instance_name: String,
     pub bigtable_app_profile_id: String,
     pub timeout: Option<Duration>,
+    /// Check uploaded evm blocks for gaps and repair them from blockstore.
+    pub evm_block_gap_repair: Option<EvmBlockGapRepairConfig>,
 }
 
 impl Default for RpcBigtableConfig {
@@ -194,6 +203,7 @@ impl Default for RpcBigtableConfig {
             bigtable_instance_name,
             bigtable_app_profile_id,
             timeout: None,
+            evm_block_gap_repair: None,
         }
     }
 }
@@ -261,6 +271,10 @@ pub struct JsonRpcRequestProcessor {
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     evm_state_archive: Option<evm_state::Storage>,
     pub batch_state_map: BatchStateMap,
+    pub evm_filters: Arc<EvmFilterRegistry>,
+    pub evm_gas_oracle: Arc<EvmGasOracle>,
+    /// Address of client, that sent current request, if it was forwarded by proxy.
+    pub client_ip: Option<IpAddr>,
 }
 
 impl JsonRpcRequestProcessor {
@@ -390,6 +404,9 @@ impl JsonRpcRequestProcessor {
                 max_complete_transaction_status_slot,
                 evm_state_archive,
                 batch_state_map: Default::default(),
+                evm_filters: Default::default(),
+                evm_gas_oracle: Default::default(),
+                client_ip: None,
             },
             receiver,
         )
@@ -450,6 +467,9 @@ impl JsonRpcRequestProcessor {
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             evm_state_archive: None,
             batch_state_map: Default::default(),
+            evm_filters: Default::default(),
+            evm_gas_oracle: Default::default(),
+            client_ip: None,
         }
     }
 
@@ -476,19 +496,32 @@ impl JsonRpcRequestProcessor {
         &self.evm_state_archive
     }
 
+    /// Returns evm state that can be used to query historical roots.
+    ///
+    /// If node has no archive, but keeps bounded history of rooted states, live storage is used.
+    /// In both cases `root` is pinned, so it is not pruned or moved into cold tier of archive,
+    /// while returned state is alive. States that were already moved into cold tier are read from there.
     pub fn evm_state_archive(
         &self,
         timestamp: Option<u64>,
-    ) -> Option<evm_state::EvmBackend<evm_state::Incomming>> {
+        root: evm_state::H256,
+    ) -> Result<evm_state::EvmBackend<evm_state::Incomming>, evm_rpc::error::Error> {
         // TODO: block_hashes history
-        let arhive = self.evm_state_archive.clone()?;
         let bank = self.bank(Some(CommitmentConfig::processed()));
         let state_ref = bank.evm_state.read().expect("state was poisoned");
+        let arhive = match self.evm_state_archive.clone() {
+            Some(archive) => archive.pin_root(root).unwrap_or(archive),
+            None if state_ref.kvs().history().is_enabled() => {
+                let storage = state_ref.kvs();
+                storage.pin_root(root).unwrap_or_else(|| storage.clone())
+            }
+            None => return Err(evm_rpc::error::Error::ArchiveNotSupported),
+        };
         let timestamp = timestamp.unwrap_or(bank.clock().unix_timestamp as u64);
         match state_ref.new_from_parent(timestamp, true) {
             evm_state::EvmState::Incomming(mut i) => {
                 i.kvs = arhive;
-                Some(i)
+                Ok(i)
             }
             _ => unreachable!(),
         }
@@ -1783,6 +1816,75 @@ impl JsonRpcRequestProcessor {
         }
     }
 
+    pub fn get_evm_swaps_for_address(
+        &self,
+        account: EvmSwapAccount,
+        before: Option<Signature>,
+        limit: usize,
+        config: RpcContextConfig,
+    ) -> Result<Vec<RpcEvmSwap>> {
+        let commitment = config.commitment.unwrap_or_default();
+        check_is_at_least_confirmed(commitment)?;
+
+        if !self.config.enable_rpc_transaction_history {
+            return Err(RpcCustomError::TransactionHistoryNotAvailable.into());
+        }
+
+        let highest_confirmed_root = self
+            .block_commitment_cache
+            .read()
+            .unwrap()
+            .highest_confirmed_root();
+        let highest_slot = if commitment.is_confirmed() {
+            let confirmed_bank = self.get_bank_with_config(config)?;
+            confirmed_bank.slot()
+        } else {
+            let min_context_slot = config.min_context_slot.unwrap_or_default();
+            if highest_confirmed_root < min_context_slot {
+                return Err(RpcCustomError::MinContextSlotNotReached {
+                    context_slot: highest_confirmed_root,
+                }
+                .into());
+            }
+            highest_confirmed_root
+        };
+
+        let swaps = self
+            .blockstore
+            .get_evm_swaps_for_address(account, highest_slot, before, limit)
+            .map_err(|err| Error::invalid_params(format!("{}", err)))?;
+
+        Ok(swaps
+            .into_iter()
+            .map(|item| {
+                let EvmSwapWithSignature {
+                    slot,
+                    signature,
+                    swap,
+                } = item;
+                let confirmation_status = if slot <= highest_confirmed_root {
+                    TransactionConfirmationStatus::Finalized
+                } else {
+                    TransactionConfirmationStatus::Confirmed
+                };
+                RpcEvmSwap {
+                    signature: signature.to_string(),
+                    slot,
+                    block_time: self.blockstore.get_block_time(slot).ok().flatten(),
+                    confirmation_status: Some(confirmation_status),
+                    direction: match swap.direction {
+                        SwapDirection::NativeToEvm => RpcEvmSwapDirection::NativeToEvm,
+                        SwapDirection::EvmToNative => RpcEvmSwapDirection::EvmToNative,
+                    },
+                    native_account: swap.native_account.to_string(),
+                    evm_address: format!("{:?}", swap.evm_address),
+                    lamports: swap.lamports,
+                    evm_tx_hash: swap.evm_tx_hash.map(|hash| format!("{:?}", hash)),
+                }
+            })
+            .collect())
+    }
+
     pub async fn get_first_available_block(&self) -> Slot {
         let slot = self
             .blockstore
@@ -2375,32 +2477,65 @@ impl JsonRpcRequestProcessor {
         Ok(blocks)
     }
 
+    /// Returns logs matching `filter`, if `limit` is set, stops after `limit + 1` logs was found,
+    /// so caller can detect that result was truncated.
     #[instrument(skip(self))]
     pub async fn filter_logs(
         &self,
         filter: evm_state::LogFilter,
+        limit: Option<usize>,
     ) -> solana_ledger::blockstore_db::Result<Vec<evm_state::LogWithLocation>> {
         info!(target: "evm","Starting search for logs with filter = {:?}", filter);
         let mut filter_request_time = Duration::from_millis(0);
         let filter_request = Instant::now();
         let masks = filter.bloom_possibilities();
+        // Every indexed block contain at least one matching log.
+        let blocks_limit = limit.map(|limit| limit + 1);
+        let ranges = match self
+            .blockstore
+            .evm_logs_index_blocks(&filter, blocks_limit)?
+        {
+            Some(blocks) => {
+                let mut ranges: Vec<(evm_state::BlockNum, evm_state::BlockNum)> = Vec::new();
+                for block in blocks {
+                    match ranges.last_mut() {
+                        Some((_, end)) if *end + 1 == block => *end = block,
+                        _ => ranges.push((block, block)),
+                    }
+                }
+                ranges
+            }
+            None => {
+                self.blockstore
+                    .evm_logs_bloom_ranges(filter.from_block, filter.to_block, &masks)?
+            }
+        };
+        debug!(target: "evm", "Blocks ranges that may contain logs = {:?}", ranges);
 
         filter_request_time += filter_request.elapsed();
 
         let mut logs = Vec::new();
-        for block in self
-            .get_evm_blocks_by_ids(filter.from_block, filter.to_block)
-            .await?
-        {
-            let filter_request = Instant::now();
-            logs.extend(Blockstore::filter_block_logs(&block, &masks, &filter)?);
-            filter_request_time += filter_request.elapsed();
+        'ranges: for (from, to) in ranges {
+            for block in self.get_evm_blocks_by_ids(from, to).await? {
+                let filter_request = Instant::now();
+                logs.extend(Blockstore::filter_block_logs(&block, &masks, &filter)?);
+                filter_request_time += filter_request.elapsed();
+                if matches!(limit, Some(limit) if logs.len() > limit) {
+                    break 'ranges;
+                }
+            }
         }
         info!(target: "evm","Filter evm logs, filter_time = {:?}", filter_request_time);
 
         Ok(logs)
     }
 
+    /// Returns true if logs for filter can be found using logs index,
+    /// without walking every block in range.
+    pub fn is_evm_logs_indexed(&self, filter: &evm_state::LogFilter) -> bool {
+        self.blockstore.is_evm_logs_indexed(filter).unwrap_or(false)
+    }
+
     #[instrument(skip(self))]
     pub async fn get_first_available_evm_block(&self) -> u64 {
         let block = self
@@ -2675,6 +2810,12 @@ fn verify_pubkey(input: &str) -> Result<Pubkey> {
         .map_err(|e| Error::invalid_params(format!("Invalid param: {:?}", e)))
 }
 
+fn verify_evm_address(input: &str) -> Result<evm_state::H160> {
+    input
+        .parse()
+        .map_err(|e| Error::invalid_params(format!("Invalid param: {:?}", e)))
+}
+
 fn verify_hash(input: &str) -> Result<Hash> {
     input
         .parse()
@@ -2724,6 +2865,34 @@ fn verify_and_parse_signatures_for_address_params(
     Ok((address, before, until, limit))
 }
 
+/// Parse address of either side of swap: base-58 native pubkey, or `0x` prefixed evm address.
+fn verify_and_parse_evm_swaps_for_address_params(
+    address: String,
+    before: Option<String>,
+    limit: Option<usize>,
+) -> Result<(EvmSwapAccount, Option<Signature>, usize)> {
+    let account = match address.strip_prefix("0x") {
+        Some(evm_address) => EvmSwapAccount::Evm(
+            evm_address
+                .parse()
+                .map_err(|e| Error::invalid_params(format!("Invalid param: {:?}", e)))?,
+        ),
+        None => EvmSwapAccount::Native(verify_pubkey(&address)?),
+    };
+    let before = before
+        .map(|ref before| verify_signature(before))
+        .transpose()?;
+    let limit = limit.unwrap_or(MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT);
+
+    if limit == 0 || limit > MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT {
+        return Err(Error::invalid_params(format!(
+            "Invalid limit; max {}",
+            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT
+        )));
+    }
+    Ok((account, before, limit))
+}
+
 pub(crate) fn check_is_at_least_confirmed(commitment: CommitmentConfig) -> Result<()> {
     if !commitment.is_at_least_confirmed() {
         return Err(Error::invalid_params(
@@ -4006,6 +4175,14 @@ pub mod rpc_full {
             config: Option<RpcSignaturesForAddressConfig>,
         ) -> BoxFuture<Result<Vec<RpcConfirmedTransactionStatusWithSignature>>>;
 
+        #[rpc(meta, name = "getEvmSwapsForAddress")]
+        fn get_evm_swaps_for_address(
+            &self,
+            meta: Self::Metadata,
+            address: String,
+            config: Option<RpcEvmSwapsConfig>,
+        ) -> Result<Vec<RpcEvmSwap>>;
+
         #[rpc(meta, name = "getFirstAvailableBlock")]
         fn get_first_available_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>>;
 
@@ -4166,7 +4343,6 @@ pub mod rpc_full {
             );
 
             let faucet_addr = meta.config.faucet_addr.ok_or_else(Error::invalid_request)?;
-            let pubkey = verify_pubkey(&pubkey_str)?;
 
             let config = config.unwrap_or_default();
             let bank = meta.bank(config.commitment);
@@ -4180,13 +4356,18 @@ pub mod rpc_full {
                 .get_blockhash_last_valid_block_height(&blockhash)
                 .unwrap_or(0);
 
-            let transaction =
-                request_airdrop_transaction(&faucet_addr, &pubkey, lamports, blockhash).map_err(
-                    |err| {
-                        info!("request_airdrop_transaction failed: {:?}", err);
-                        Error::internal_error()
-                    },
-                )?;
+            // Hex addresses are airdropped into evm, by swapping from the faucet account.
+            let transaction = if pubkey_str.starts_with("0x") {
+                let address = verify_evm_address(&pubkey_str)?;
+                request_evm_airdrop_transaction(&faucet_addr, &address, lamports, blockhash)
+            } else {
+                let pubkey = verify_pubkey(&pubkey_str)?;
+                request_airdrop_transaction(&faucet_addr, &pubkey, lamports, blockhash)
+            }
+            .map_err(|err| {
+                info!("request_airdrop_transaction failed: {:?}", err);
+                Error::internal_error()
+            })?;
 
             let wire_transaction = serialize(&transaction).map_err(|err| {
                 info!("request_airdrop: serialize error: {:?}", err);
@@ -4540,6 +4721,36 @@ pub mod rpc_full {
             }
         }
 
+        fn get_evm_swaps_for_address(
+            &self,
+            meta: Self::Metadata,
+            address: String,
+            config: Option<RpcEvmSwapsConfig>,
+        ) -> Result<Vec<RpcEvmSwap>> {
+            debug!(
+                "get_evm_swaps_for_address rpc request received: {:?}",
+                address
+            );
+            let RpcEvmSwapsConfig {
+                before,
+                limit,
+                commitment,
+                min_context_slot,
+            } = config.unwrap_or_default();
+            let (account, before, limit) =
+                verify_and_parse_evm_swaps_for_address_params(address, before, limit)?;
+            meta.get_evm_swaps_for_address(
+                account,
+                before,
+                limit,
+                RpcContextConfig {
+                    commitment,
+                    min_context_slot,
+                    balance_format: None,
+                },
+            )
+        }
+
         fn get_first_available_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>> {
             debug!("get_first_available_block rpc request received");
             Box::pin(async move { Ok(meta.get_first_available_block().await) })
@@ -42,7 +42,7 @@ use {
     solana_storage_bigtable::CredentialType,
     std::{
         collections::HashSet,
-        net::SocketAddr,
+        net::{IpAddr, SocketAddr},
         path::{Path, PathBuf},
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -53,7 +53,7 @@ use {
     tokio_util::codec::{BytesCodec, FramedRead},
 };
 
-use evm_rpc::{ChainERPC, GeneralERPC, TraceERPC};
+use evm_rpc::{ChainERPC, DebugERPC, FilterERPC, GeneralERPC, TraceERPC};
 use tracing_subscriber::{filter::LevelFilter, prelude::*, EnvFilter};
 
 const FULL_SNAPSHOT_REQUEST_PATH: &str = "/snapshot.tar.bz2";
@@ -321,6 +321,38 @@ fn process_rest(bank_forks: &Arc<RwLock<BankForks>>, path: &str) -> Option<Strin
     }
 }
 
+/// Address of client, as reported by one of `trusted_proxies` in `X-Forwarded-For` header.
+///
+/// Each proxy appends address of its peer to the header, so it is walked from the end,
+/// and the first address that is not a trusted proxy is a client.
+/// Addresses before it could be forged by client, and are ignored.
+///
+/// Http server doesn't expose address of peer to meta extractor,
+/// so without trusted proxies client address is unknown.
+pub fn forwarded_client_ip(
+    req: &hyper::Request<hyper::Body>,
+    trusted_proxies: &[IpAddr],
+) -> Option<IpAddr> {
+    if trusted_proxies.is_empty() {
+        return None;
+    }
+    let mut addresses = req
+        .headers()
+        .get_all("x-forwarded-for")
+        .iter()
+        .map(|value| value.to_str().ok())
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .map(|ip| ip.trim().parse::<IpAddr>().ok())
+        .collect::<Vec<_>>();
+    addresses.reverse();
+    addresses
+        .into_iter()
+        .find(|ip| !matches!(ip, Some(ip) if trusted_proxies.contains(ip)))
+        .flatten()
+}
+
 impl JsonRpcService {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -388,6 +420,7 @@ impl JsonRpcService {
                 ref bigtable_instance_name,
                 ref bigtable_app_profile_id,
                 timeout,
+                ref evm_block_gap_repair,
             }) = config.rpc_bigtable_config
             {
                 let bigtable_config = solana_storage_bigtable::LedgerStorageConfig {
@@ -412,6 +445,7 @@ impl JsonRpcService {
                                 block_commitment_cache.clone(),
                                 current_transaction_status_slot.clone(),
                                 ConfirmedBlockUploadConfig::default(),
+                                evm_block_gap_repair.clone(),
                                 exit_bigtable_ledger_upload_service.clone(),
                             )))
                         } else {
@@ -432,6 +466,7 @@ impl JsonRpcService {
             };
 
         let full_api = config.full_api;
+        let trusted_proxies = config.rpc_trusted_proxies.clone();
         let obsolete_v1_7_api = config.obsolete_v1_7_api;
         let (request_processor, receiver) = JsonRpcRequestProcessor::new(
             config,
@@ -513,6 +548,8 @@ impl JsonRpcService {
                 io.extend_with(super::evm_rpc_impl::ChainErpcImpl.to_delegate());
                 io.extend_with(super::evm_rpc_impl::GeneralErpcImpl.to_delegate());
                 io.extend_with(super::evm_rpc_impl::TraceErpcImpl.to_delegate());
+                io.extend_with(super::evm_rpc_impl::FilterErpcImpl.to_delegate());
+                io.extend_with(super::evm_rpc_impl::DebugErpcImpl.to_delegate());
 
                 let request_middleware = RpcRequestMiddleware::new(
                     ledger_path,
@@ -523,7 +560,17 @@ impl JsonRpcService {
                 let meta = Arc::new(request_processor);
                 let server = ServerBuilder::with_meta_extractor(
                     io,
-                    move |_req: &hyper::Request<hyper::Body>| meta.clone(),
+                    move |req: &hyper::Request<hyper::Body>| match forwarded_client_ip(
+                        req,
+                        &trusted_proxies,
+                    ) {
+                        Some(client_ip) => {
+                            let mut meta = (*meta).clone();
+                            meta.client_ip = Some(client_ip);
+                            Arc::new(meta)
+                        }
+                        None => meta.clone(),
+                    },
                 )
                 .event_loop_executor(runtime.handle().clone())
                 .threads(1)
@@ -695,6 +742,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_forwarded_client_ip() {
+        let request = |headers: &[(&str, &str)]| {
+            let mut builder = hyper::Request::builder();
+            for (name, value) in headers {
+                builder = builder.header(*name, *value);
+            }
+            builder.body(hyper::Body::empty()).unwrap()
+        };
+        let client = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let proxies = [
+            IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)),
+        ];
+
+        // Header is ignored, unless proxies are configured.
+        assert_eq!(
+            forwarded_client_ip(&request(&[("X-Forwarded-For", "10.0.0.1")]), &[]),
+            None
+        );
+        assert_eq!(forwarded_client_ip(&request(&[]), &proxies), None);
+        assert_eq!(
+            forwarded_client_ip(&request(&[("X-Forwarded-For", "10.0.0.1")]), &proxies),
+            client
+        );
+        // Trusted proxies are skipped, forged addresses before client are ignored.
+        assert_eq!(
+            forwarded_client_ip(
+                &request(&[("X-Forwarded-For", "1.2.3.4, 10.0.0.1, 127.0.0.2")]),
+                &proxies
+            ),
+            client
+        );
+        assert_eq!(
+            forwarded_client_ip(
+                &request(&[
+                    ("X-Forwarded-For", "1.2.3.4"),
+                    ("X-Forwarded-For", "10.0.0.1, 127.0.0.1")
+                ]),
+                &proxies
+            ),
+            client
+        );
+        assert_eq!(
+            forwarded_client_ip(&request(&[("X-Forwarded-For", "127.0.0.1")]), &proxies),
+            None
+        );
+        assert_eq!(
+            forwarded_client_ip(
+                &request(&[("X-Forwarded-For", "10.0.0.1, garbage, 127.0.0.1")]),
+                &proxies
+            ),
+            None
+        );
+        assert_eq!(
+            forwarded_client_ip(&request(&[("X-Real-IP", "10.0.0.1")]), &proxies),
+            None
+        );
+    }
+
     #[test]
     fn test_is_file_get_path() {
         let bank_forks = create_bank_forks();
@@ -2,6 +2,7 @@ use {
     crate::transaction_notifier_interface::TransactionNotifierLock,
     crossbeam_channel::{Receiver, RecvTimeoutError},
     itertools::izip,
+    solana_evm_loader_program::swap::SwapEvent,
     solana_ledger::{
         blockstore::Blockstore,
         blockstore_processor::{TransactionStatusBatch, TransactionStatusMessage},
@@ -157,6 +158,31 @@ impl TransactionStatusService {
                             loaded_addresses,
                         };
 
+                        let evm_swaps = match &transaction_status_meta {
+                            TransactionStatusMeta {
+                                status: Ok(()),
+                                log_messages: Some(log_messages),
+                                ..
+                            } => {
+                                // Swap lines after truncation are lost, report gap in swaps index.
+                                if SwapEvent::is_log_truncated(log_messages) {
+                                    warn!(
+                                        "Logs of transaction {} in slot {} were truncated, \
+                                         its evm swaps may be incomplete",
+                                        transaction.signature(),
+                                        slot
+                                    );
+                                    datapoint_warn!(
+                                        "evm-swaps-log-truncated",
+                                        ("slot", slot, i64),
+                                        ("signature", transaction.signature().to_string(), String)
+                                    );
+                                }
+                                SwapEvent::from_log_messages(log_messages)
+                            }
+                            _ => vec![],
+                        };
+
                         if let Some(transaction_notifier) = transaction_notifier.as_ref() {
                             transaction_notifier.write().unwrap().notify_transaction(
                                 slot,
@@ -187,6 +213,10 @@ impl TransactionStatusService {
                                     transaction_status_meta,
                                 )
                                 .expect("Expect database write to succeed: TransactionStatus");
+
+                            blockstore
+                                .write_evm_swaps(slot, *transaction.signature(), &evm_swaps)
+                                .expect("Expect database write to succeed: EvmSwaps");
                         }
                     }
                 }
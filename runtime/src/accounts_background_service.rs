@@ -350,7 +350,17 @@ impl AbsRequestHandler {
 
             let evm_state = bank.evm_state.read().unwrap();
             let storage = evm_state.kvs();
+            let rooted = bank
+                .rc
+                .accounts
+                .accounts_db
+                .accounts_index
+                .is_root(pruned_slot);
             let handle_evm_error = move || -> evm_state::storage::Result<()> {
+                // Rooted state is purged later by pruning policy, if history is enabled.
+                if rooted && storage.history().retain(pruned_slot) {
+                    return Ok(());
+                }
                 if let Some(h) = storage.purge_slot(pruned_slot)? {
                     // TODO: Rewrite it as long lived RootCleanupService.
                     let mut cleaner = evm_state::storage::RootCleanup::new(storage, vec![h]);
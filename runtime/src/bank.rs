@@ -3694,6 +3694,11 @@ impl Bank {
             .is_active(&feature_set::velas::burn_fee::id())
     }
 
+    /// Evm executor features, that are activated in this bank.
+    pub fn evm_executor_feature_set(&self) -> evm_state::executor::FeatureSet {
+        solana_evm_loader_program::executor_feature_set(&self.feature_set)
+    }
+
     /// Prepare a transaction batch from a list of legacy transactions. Used for tests only.
     pub fn prepare_batch_for_tests(&self, txs: Vec<Transaction>) -> TransactionBatch {
         let sanitized_txs = txs
@@ -4202,14 +4207,7 @@ impl Bank {
                     state.clone(),
                     evm_state::ChainContext::new(last_hashes),
                     evm_state::EvmConfig::new(self.evm_chain_id, self.evm_burn_fee_activated()),
-                    evm_state::executor::FeatureSet::new(
-                        self.feature_set
-                            .is_active(&solana_sdk::feature_set::velas::unsigned_tx_fix::id()),
-                        self.feature_set
-                            .is_active(&solana_sdk::feature_set::velas::clear_logs_on_error::id()),
-                        self.feature_set
-                            .is_active(&solana_sdk::feature_set::velas::accept_zero_gas_price_with_native_fee::id()),
-                    ),
+                    self.evm_executor_feature_set(),
                 );
                 Some(evm_executor)
             } else {
@@ -7359,7 +7357,12 @@ impl Drop for Bank {
             let evm_state = self.evm_state.read().unwrap();
             let storage = evm_state.kvs();
             let slot = self.slot();
+            let rooted = self.rc.accounts.accounts_db.accounts_index.is_root(slot);
             let handle_evm_error = move || -> evm_state::storage::Result<()> {
+                // Rooted state is purged later by pruning policy, if history is enabled.
+                if rooted && storage.history().retain(slot) {
+                    return Ok(());
+                }
                 if let Some(h) = storage.purge_slot(slot)? {
                     let mut cleaner = evm_state::storage::RootCleanup::new(storage, vec![h]);
                     cleaner.cleanup()?
@@ -1,4 +1,4 @@
-use crate::snapshot_utils::EVM_STATE_DIR;
+use crate::snapshot_utils::{EVM_STATE_DELTA_FILENAME, EVM_STATE_DIR};
 use {
     bzip2::bufread::BzDecoder,
     log::*,
@@ -389,6 +389,11 @@ fn is_valid_snapshot_archive_entry(parts: &[&str], kind: tar::EntryType) -> bool
         (["snapshots", dir, file], GNUSparse) if all_digits(dir) && all_digits(file) => true,
         (["snapshots", dir, file], Regular) if all_digits(dir) && all_digits(file) => true,
         (["snapshots", dir], Directory) if all_digits(dir) => true,
+        (["snapshots", dir, delta], Regular)
+            if *delta == EVM_STATE_DELTA_FILENAME && all_digits(dir) =>
+        {
+            true
+        }
         (["snapshots", dir, evm_state_dir], Directory)
             if *evm_state_dir == EVM_STATE_DIR && all_digits(dir) =>
         {
@@ -517,6 +522,10 @@ mod tests {
             &["snapshots", "3", "3"],
             tar::EntryType::Regular
         ));
+        assert!(is_valid_snapshot_archive_entry(
+            &["snapshots", "3", "evm-state-delta"],
+            tar::EntryType::Regular
+        ));
         assert!(is_valid_snapshot_archive_entry(
             &["version"],
             tar::EntryType::Regular
@@ -550,6 +559,10 @@ mod tests {
             &["snapshots", "0", "aa"],
             tar::EntryType::Regular
         ));
+        assert!(!is_valid_snapshot_archive_entry(
+            &["snapshots", "x0", "evm-state-delta"],
+            tar::EntryType::Regular
+        ));
         assert!(!is_valid_snapshot_archive_entry(
             &["aaaa"],
             tar::EntryType::Regular
@@ -17,6 +17,7 @@ use {
         stakes::Stakes,
     },
     bincode::{self, config::Options, Error},
+    evm_state::storage::delta::StateDelta,
     log::*,
     rayon::prelude::*,
     serde::{de::DeserializeOwned, de::Error as _, Deserialize, Serialize},
@@ -222,6 +223,7 @@ pub(crate) fn bank_from_streams<R>(
     account_secondary_indexes: AccountSecondaryIndexes,
     caching_enabled: bool,
     evm_state_backup_path: &Path,
+    evm_state_delta: Option<StateDelta>,
     skip_purge_verify: bool,
     evm_archive: Option<evm_state::Storage>,
     limit_load_slot_count_from_snapshot: Option<usize>,
@@ -247,6 +249,16 @@ where
                 } else {
                     (None, None)
                 };
+            if let Some(delta) = &evm_state_delta {
+                let full_snapshot_evm_root =
+                    full_snapshot_bank_fields.evm_persist_fields.last_root();
+                if delta.base_root != full_snapshot_evm_root {
+                    return Err(Error::custom(format!(
+                        "Evm state delta base root {:?} doesn't match full snapshot evm root {:?}",
+                        delta.base_root, full_snapshot_evm_root
+                    )));
+                }
+            }
             let snapshot_accounts_db_fields = SnapshotAccountsDbFields {
                 full_snapshot_accounts_db_fields,
                 incremental_snapshot_accounts_db_fields,
@@ -268,6 +280,7 @@ where
                 accounts_db_config,
                 accounts_update_notifier,
                 evm_state_backup_path,
+                evm_state_delta,
                 skip_purge_verify,
                 evm_state_version.support_gc(), // is remote snapshot supported gc?
                 true,                           // enable gc mode in current evm state
@@ -428,6 +441,8 @@ fn reconstruct_bank_from_fields<E>(
     accounts_db_config: Option<AccountsDbConfig>,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     evm_state_backup_path: &Path,
+    // delta from incremental snapshot, that is applied on top of the backup
+    evm_state_delta: Option<StateDelta>,
     skip_purge_verify: bool,
     // true if we restoring from full backup, or from gc
     load_full_backup: bool,
@@ -471,8 +486,9 @@ where
         enable_gc
     );
 
-    // if we force verify, or our gc settings is not equal to settings in snapshot
-    if !skip_purge_verify || enable_gc != load_full_backup {
+    // if we force verify, or our gc settings is not equal to settings in snapshot,
+    // or state should be rebuilt from evm state delta
+    if !skip_purge_verify || enable_gc != load_full_backup || evm_state_delta.is_some() {
         let mut tmp_evm_state_path_parent = evm_state_path.to_path_buf();
         tmp_evm_state_path_parent.pop();
         let tmp_dir = tempfile::TempDir::new_in(tmp_evm_state_path_parent)?;
@@ -487,6 +503,26 @@ where
                 Error::custom(format!("Unable to restore tmp evm backup storage {}", e))
             })?;
 
+        if let Some(delta) = &evm_state_delta {
+            let mut measure = Measure::start("EVM state delta apply");
+            if delta.root != bank_fields.evm_persist_fields.last_root() {
+                return Err(Error::custom(format!(
+                    "Evm state delta root {:?} doesn't match snapshot evm root {:?}",
+                    delta.root,
+                    bank_fields.evm_persist_fields.last_root()
+                )));
+            }
+            src.apply_delta(delta)
+                .map_err(|e| Error::custom(format!("Unable to apply evm state delta {}", e)))?;
+            measure.stop();
+            info!(
+                "{}, nodes = {}, codes = {}",
+                measure,
+                delta.nodes.len(),
+                delta.codes.len()
+            );
+        }
+
         let destination = evm_state::Storage::open_persistent(evm_state_path, enable_gc)
             .map_err(|e| Error::custom(format!("Unable to open destination evm-state {}", e)))?;
 
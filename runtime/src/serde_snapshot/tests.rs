@@ -259,6 +259,7 @@ fn test_bank_serialize_style(evm_version: EvmStateVersion) {
         AccountSecondaryIndexes::default(),
         false,
         evm_copied_backup.path(),
+        None,  // evm state delta
         false, // skip purge verify
         None,  // evm archive
         None,
@@ -381,6 +382,7 @@ fn test_blank_extra_fields() {
         AccountSecondaryIndexes::default(),
         false,
         evm_backup_path.path(),
+        None,
         false,
         None,
         None,
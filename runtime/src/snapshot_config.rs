@@ -40,6 +40,10 @@ pub struct SnapshotConfig {
 
     // Thread niceness adjustment for snapshot packager service
     pub packager_thread_niceness_adj: i8,
+
+    /// Store delta of evm state from the base full snapshot in incremental snapshots,
+    /// instead of full evm state backup. Nodes that don't support delta can't load such snapshots.
+    pub incremental_snapshot_evm_state_delta: bool,
 }
 
 impl Default for SnapshotConfig {
@@ -60,6 +64,7 @@ impl Default for SnapshotConfig {
             accounts_hash_use_index: false,
             accounts_hash_debug_verify: false,
             packager_thread_niceness_adj: 0,
+            incremental_snapshot_evm_state_delta: false,
         }
     }
 }
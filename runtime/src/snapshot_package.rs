@@ -138,6 +138,9 @@ pub struct SnapshotPackage {
     pub snapshot_type: SnapshotType,
     pub evm_root: evm_state::H256,
     pub evm_db: evm_state::Storage,
+    /// Evm root of the base full snapshot, if it is known, incremental snapshot
+    /// contains only delta of evm state from this root.
+    pub evm_base_root: Option<evm_state::H256>,
     pub bank: Arc<Bank>,
 }
 
@@ -181,6 +184,7 @@ impl From<AccountsPackage> for SnapshotPackage {
             snapshot_type: accounts_package.snapshot_type.unwrap(),
             evm_root: accounts_package.evm_root,
             evm_db: accounts_package.evm_db,
+            evm_base_root: None,
             bank: accounts_package.bank,
         }
     }
@@ -60,6 +60,7 @@ pub const SNAPSHOT_ARCHIVE_DOWNLOAD_DIR: &str = "remote";
 pub const DEFAULT_FULL_SNAPSHOT_ARCHIVE_INTERVAL_SLOTS: Slot = 25_000;
 pub const DEFAULT_INCREMENTAL_SNAPSHOT_ARCHIVE_INTERVAL_SLOTS: Slot = 100;
 pub const EVM_STATE_DIR: &str = "evm-state";
+pub const EVM_STATE_DELTA_FILENAME: &str = "evm-state-delta";
 const MAX_SNAPSHOT_DATA_FILE_SIZE: u64 = 32 * 1024 * 1024 * 1024; // 32 GiB
 const MAX_SNAPSHOT_VERSION_FILE_SIZE: u64 = 8; // byte
 const VERSION_STRING_V1_4_0: &str = "1.4.0";
@@ -138,6 +139,14 @@ pub struct BankSnapshotInfo {
     pub evm_state_backup_path: PathBuf,
 }
 
+impl BankSnapshotInfo {
+    /// Incremental snapshot contains this file instead of full evm state backup.
+    pub fn evm_state_delta_path(&self) -> PathBuf {
+        self.evm_state_backup_path
+            .with_file_name(EVM_STATE_DELTA_FILENAME)
+    }
+}
+
 impl PartialOrd for BankSnapshotInfo {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -354,9 +363,42 @@ pub fn archive_snapshot_package(
     }
 
     //
-    // Create evm state backup
+    // Create evm state backup, or evm state delta for incremental snapshot
     //
-    {
+    let evm_base_root = match snapshot_package.snapshot_type {
+        SnapshotType::IncrementalSnapshot(_) => snapshot_package.evm_base_root,
+        SnapshotType::FullSnapshot => None,
+    };
+    if let Some(evm_base_root) = evm_base_root {
+        let slot = snapshot_package.slot();
+        let snapshot_tmpdir = snapshot_package.snapshot_links.path();
+        let evm_delta_path = snapshot_tmpdir
+            .join(slot.to_string())
+            .join(EVM_STATE_DELTA_FILENAME);
+
+        let mut evm_state_delta = Measure::start("evm-state-delta-ms");
+        let delta = snapshot_package
+            .evm_db
+            .state_delta(evm_base_root, snapshot_package.evm_root)
+            .map_err(|e| SnapshotError::EvmStateError(e.into()))?;
+        let consumed_size = serialize_snapshot_data_file(&evm_delta_path, |stream| {
+            bincode::serialize_into(stream, &delta)?;
+            Ok(())
+        })?;
+        evm_state_delta.stop();
+        inc_new_counter_info!("evm-state-delta-ms", evm_state_delta.as_ms() as usize);
+        datapoint_info!(
+            "snapshot-evm-state-delta",
+            ("slot", slot, i64),
+            ("nodes", delta.nodes.len(), i64),
+            ("codes", delta.codes.len(), i64),
+            ("size", consumed_size, i64)
+        );
+        info!(
+            "EVM state delta {} for slot {} from root {:?} at {:?}",
+            evm_state_delta, slot, evm_base_root, evm_delta_path
+        );
+    } else {
         let slot = snapshot_package.slot();
         let snapshot_tmpdir = snapshot_package.snapshot_links.path();
         let snapshot_hardlink_dir = snapshot_tmpdir.join(slot.to_string());
@@ -1598,6 +1640,29 @@ fn rebuild_bank_from_snapshots(
             .map(|paths| paths.snapshot_path.display()),
     );
 
+    // Incremental snapshot contains either full evm state backup, or delta of evm state,
+    // that should be applied on top of the full snapshot evm state.
+    let (evm_state_backup_path, evm_state_delta) = match &incremental_snapshot_root_paths {
+        Some(root_paths) if !root_paths.evm_state_backup_path.exists() => {
+            let evm_delta_path = root_paths.evm_state_delta_path();
+            let delta = deserialize_snapshot_data_file(&evm_delta_path, |stream| {
+                info!("Loading evm state delta from {}", evm_delta_path.display());
+                let delta: evm_state::storage::delta::StateDelta = bincode::options()
+                    .with_limit(MAX_SNAPSHOT_DATA_FILE_SIZE)
+                    .with_fixint_encoding()
+                    .allow_trailing_bytes()
+                    .deserialize_from(stream)?;
+                Ok(delta)
+            })?;
+            (
+                full_snapshot_root_paths.evm_state_backup_path.clone(),
+                Some(delta),
+            )
+        }
+        Some(root_paths) => (root_paths.evm_state_backup_path.clone(), None),
+        None => (full_snapshot_root_paths.evm_state_backup_path.clone(), None),
+    };
+
     let snapshot_root_paths = SnapshotRootPaths {
         full_snapshot_root_file_path: full_snapshot_root_paths.snapshot_path,
         incremental_snapshot_root_file_path: incremental_snapshot_root_paths
@@ -1620,7 +1685,8 @@ fn rebuild_bank_from_snapshots(
             additional_builtins,
             account_secondary_indexes,
             accounts_db_caching_enabled,
-            &full_snapshot_root_paths.evm_state_backup_path,
+            &evm_state_backup_path,
+            evm_state_delta,
             !verify_evm_state,
             evm_archive,
             limit_load_slot_count_from_snapshot,
@@ -3304,6 +3370,146 @@ mod tests {
         assert_eq!(*bank4, roundtrip_bank);
     }
 
+    /// Test roundtrip of incremental snapshot, that contains only evm state delta
+    /// from the evm root of its full snapshot.
+    #[test]
+    fn test_roundtrip_bank_to_and_from_incremental_snapshot_with_evm_state_delta() {
+        solana_logger::setup();
+        let collector = Pubkey::new_unique();
+        let key1 = Keypair::new();
+
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000);
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        bank0.transfer(1, &mint_keypair, &key1.pubkey()).unwrap();
+        while !bank0.is_complete() {
+            bank0.register_tick(&Hash::new_unique());
+        }
+
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let evm_state_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_format = ArchiveFormat::TarZstd;
+
+        let full_snapshot_slot = bank0.slot();
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            bank0.clone(),
+            None,
+            snapshot_archives_dir.path(),
+            snapshot_archive_format,
+            DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+        let full_snapshot_evm_root = bank0.evm_state.read().unwrap().last_root();
+
+        let slot = full_snapshot_slot + 1;
+        let bank1 = Arc::new(Bank::new_from_parent(&bank0, &collector, slot));
+        {
+            // change evm state, so delta is not empty
+            let mut evm_state = bank1.evm_state.write().unwrap();
+            match &mut *evm_state {
+                evm_state::EvmState::Incomming(i) => {
+                    i.set_initial(vec![(
+                        evm_state::H160::repeat_byte(0x11),
+                        evm_state::MemoryAccount {
+                            balance: 1_000_000.into(),
+                            ..Default::default()
+                        },
+                    )]);
+                }
+                _ => panic!("Not exepcetd state"),
+            }
+            evm_state
+                .try_commit(bank1.slot(), bank1.last_blockhash().to_bytes())
+                .unwrap();
+        }
+        bank1.transfer(1, &mint_keypair, &key1.pubkey()).unwrap();
+        while !bank1.is_complete() {
+            bank1.register_tick(&Hash::new_unique());
+        }
+
+        bank1.squash();
+        bank1.force_flush_accounts_cache();
+        bank1.clean_accounts(true, false, Some(full_snapshot_slot));
+        bank1.update_accounts_hash();
+        bank1.rehash();
+
+        let snapshot_version = SnapshotVersion::default();
+        let temp_dir = tempfile::tempdir_in(bank_snapshots_dir.path()).unwrap();
+        let snapshot_storages = bank1.get_snapshot_storages(Some(full_snapshot_slot));
+        let bank_snapshot_info =
+            add_bank_snapshot(&temp_dir, &bank1, &snapshot_storages, snapshot_version).unwrap();
+        let (evm_root, evm_database) = {
+            let evm_state = bank1.evm_state.read().unwrap();
+            (evm_state.last_root(), evm_state.kvs().clone())
+        };
+        assert_ne!(evm_root, full_snapshot_evm_root);
+        let deltas = bank1.src.slot_deltas(&bank1.src.roots());
+        let accounts_package = AccountsPackage::new(
+            bank1.clone(),
+            &bank_snapshot_info,
+            &temp_dir,
+            deltas,
+            snapshot_archives_dir.path(),
+            snapshot_storages,
+            snapshot_archive_format,
+            snapshot_version,
+            None,
+            Some(SnapshotType::IncrementalSnapshot(full_snapshot_slot)),
+            evm_root,
+            evm_database,
+        )
+        .unwrap();
+        let mut snapshot_package = SnapshotPackage::from(accounts_package);
+        snapshot_package.evm_base_root = Some(full_snapshot_evm_root);
+        archive_snapshot_package(
+            &snapshot_package,
+            DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+        let incremental_snapshot_archive_info = IncrementalSnapshotArchiveInfo::new(
+            full_snapshot_slot,
+            snapshot_package.snapshot_archive_info,
+        );
+
+        let (roundtrip_bank, _) = bank_from_snapshot_archives(
+            evm_state_dir.path(),
+            None,
+            true,
+            &[PathBuf::from(accounts_dir.path())],
+            bank_snapshots_dir.path(),
+            &full_snapshot_archive_info,
+            Some(&incremental_snapshot_archive_info),
+            &genesis_config,
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            false,
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            false,
+            false,
+            Some(ACCOUNTS_DB_CONFIG_FOR_TESTING),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(*bank1, roundtrip_bank);
+        let roundtrip_evm_state = roundtrip_bank.evm_state.read().unwrap();
+        assert_eq!(roundtrip_evm_state.last_root(), evm_root);
+        assert_eq!(
+            roundtrip_evm_state
+                .get_account_state(evm_state::H160::repeat_byte(0x11))
+                .unwrap()
+                .balance,
+            evm_state::U256::from(1_000_000)
+        );
+    }
+
     /// Test rebuilding bank from the latest snapshot archives
     #[test]
     fn test_bank_from_latest_snapshot_archives() {
@@ -437,6 +437,34 @@ pub mod velas {
     pub mod clear_logs_on_native_error {
         solana_sdk::declare_id!("BVF8r9JP1is4YworaZsiEk6fCSTiDxvD59Eo9kFyc85F");
     }
+
+    pub mod evm_typed_transactions {
+        solana_sdk::declare_id!("3EFzMXeomV9cAohyTNED3dsS61SarbJ4N2dE4iCZNTsu");
+    }
+
+    pub mod evm_berlin_hardfork {
+        solana_sdk::declare_id!("HxuTyPhCapLnps41Pvbiv1wxAEsRUAi6EywMSVYPPyWo");
+    }
+
+    pub mod evm_london_hardfork {
+        solana_sdk::declare_id!("EhVYXn4v77og42RqaLCEUGVQhz8hgmWzbTSvNq8kVuBZ");
+    }
+
+    pub mod evm_native_call_precompile {
+        solana_sdk::declare_id!("6T7EkzDk1zFJfKQgpvSirG5moMF4iDLP1NsHKFLzbcQc");
+    }
+
+    pub mod evm_native_account_precompile {
+        solana_sdk::declare_id!("AUofnk4krzdXQWXaN7dANk7bSJ1aQGDDqHKLcJqdY7wH");
+    }
+
+    pub mod evm_spl_token_facade {
+        solana_sdk::declare_id!("DGAjdM72UupiK9J5fU9nRQmuEuepN9gmcZbK4YamP27n");
+    }
+
+    pub mod evm_transaction_batch {
+        solana_sdk::declare_id!("4SaVeEGicojxJrQrYHQF1rRUrx1nEdGsrV3khTZF5vSr");
+    }
 }
 
 pub mod allow_votes_to_directly_update_vote_state {
@@ -664,6 +692,13 @@ lazy_static! {
             (velas::evm_instruction_borsh_serialization::id(), "Support for Borsh serialization for EVM instructions."),
             (velas::accept_zero_gas_price_with_native_fee::id(), "Accept evm transactions with native fee and zero gas price."),
             (velas::clear_logs_on_native_error::id(), "Clear evm logs from receipt if native transaction is failed."),
+            (velas::evm_typed_transactions::id(), "Support EIP-2718 typed evm transactions (EIP-2930, EIP-1559)."),
+            (velas::evm_berlin_hardfork::id(), "Execute evm transactions with Berlin hardfork config."),
+            (velas::evm_london_hardfork::id(), "Execute evm transactions with London hardfork config."),
+            (velas::evm_native_call_precompile::id(), "Evm precompile for calls of whitelisted native programs."),
+            (velas::evm_native_account_precompile::id(), "Evm view precompile for native accounts of instruction."),
+            (velas::evm_spl_token_facade::id(), "Evm ERC-20 facade over SPL token mints."),
+            (velas::evm_transaction_batch::id(), "Evm instruction to execute batch of transactions atomically."),
             /*************** ADD NEW FEATURES HERE ***************/
         ]
     ).collect();
@@ -724,6 +759,18 @@ impl FeatureSet {
         self.active.get(feature_id).copied()
     }
 
+    /// Features, that were active at `slot`, features activated later are reported as inactive.
+    pub fn at_slot(&self, slot: Slot) -> Self {
+        let (active, activated_later): (HashMap<_, _>, HashMap<_, _>) = self
+            .active
+            .clone()
+            .into_iter()
+            .partition(|(_, activated_slot)| *activated_slot <= slot);
+        let mut inactive = self.inactive.clone();
+        inactive.extend(activated_later.into_keys());
+        Self { active, inactive }
+    }
+
     /// List of enabled features that trigger full inflation
     pub fn full_inflation_features_enabled(&self) -> HashSet<Pubkey> {
         let mut hash_set = FULL_INFLATION_FEATURE_PAIRS
@@ -834,4 +881,23 @@ mod test {
         feature_set.deactivate(&feature);
         assert!(!feature_set.is_active(&feature));
     }
+
+    #[test]
+    fn test_feature_set_at_slot() {
+        let mut feature_set = FeatureSet::default();
+
+        let early_feature = Pubkey::new_unique();
+        let late_feature = Pubkey::new_unique();
+        feature_set.activate(&early_feature, 10);
+        feature_set.activate(&late_feature, 20);
+
+        let at_slot = feature_set.at_slot(15);
+        assert!(at_slot.is_active(&early_feature));
+        assert!(!at_slot.is_active(&late_feature));
+        assert!(at_slot.inactive.contains(&late_feature));
+
+        let at_slot = feature_set.at_slot(20);
+        assert!(at_slot.is_active(&early_feature));
+        assert_eq!(at_slot.activated_slot(&late_feature), Some(20));
+    }
 }
@@ -439,8 +439,9 @@ pub mod evm_genesis {
 
     pub use geth::GethAccountExtractor;
     pub use open_ethereum::{generate_evm_state_json, OpenEthereumAccountExtractor};
+    pub use state_dump::StateDumpExtractor;
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct AccountPair {
         /// Keccak image of H160 public ethereum key
         pub encoded_key: H256,
@@ -455,6 +456,17 @@ pub mod evm_genesis {
                 account,
             }
         }
+
+        /// Create pair from account with plain address and storage indexes, hashing them as in state trie.
+        pub fn from_address(address: H160, account: MemoryAccount) -> Self {
+            let hash = |key: &[u8]| H256(Keccak256::digest(key).into());
+            let storage = account
+                .storage
+                .into_iter()
+                .map(|(index, value)| (hash(index.as_ref()), value))
+                .collect();
+            Self::new(hash(address.as_ref()), MemoryAccount { storage, ..account })
+        }
     }
 
     pub trait EvmAccountDumpExtractor: Iterator<Item = Result<AccountPair, Error>> {
@@ -486,6 +498,39 @@ pub mod evm_genesis {
         }
     }
 
+    /// Extractor of accounts, that are already loaded into memory.
+    pub struct MemoryAccountsExtractor {
+        accounts: std::vec::IntoIter<AccountPair>,
+    }
+
+    impl MemoryAccountsExtractor {
+        pub fn new(accounts: Vec<AccountPair>) -> Self {
+            Self {
+                accounts: accounts.into_iter(),
+            }
+        }
+    }
+
+    impl EvmAccountDumpExtractor for MemoryAccountsExtractor {
+        type Key = H160;
+
+        fn encode_key(&self, key: Self::Key) -> H256 {
+            H256(Keccak256::digest(key.as_ref()).into())
+        }
+
+        fn read_account(&mut self) -> Result<Option<AccountPair>, Error> {
+            Ok(self.accounts.next())
+        }
+    }
+
+    impl Iterator for MemoryAccountsExtractor {
+        type Item = Result<AccountPair, Error>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.read_account().transpose()
+        }
+    }
+
     pub fn copy_dir(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<(), Error> {
         let mut stack = vec![PathBuf::from(from.as_ref())];
 
@@ -1087,4 +1132,168 @@ pub mod evm_genesis {
             }
         }
     }
+
+    mod state_dump {
+        use {
+            super::*,
+            evm_state::storage::dump::{DumpAccount, DumpReader},
+        };
+
+        /// Extracts accounts from evm state dump, that is made by `ledger-tool evm_state dump`.
+        pub struct StateDumpExtractor<R: std::io::Read> {
+            reader: DumpReader<R>,
+            chunk: std::vec::IntoIter<DumpAccount>,
+            pending: Option<AccountPair>,
+        }
+
+        impl StateDumpExtractor<BufReader<File>> {
+            pub fn open_dump(dump: &Path) -> Result<Self, Error> {
+                Self::new(BufReader::new(File::open(dump)?))
+            }
+        }
+
+        impl<'a> StateDumpExtractor<&'a [u8]> {
+            pub fn from_bytes(dump: &'a [u8]) -> Result<Self, Error> {
+                Self::new(dump)
+            }
+        }
+
+        impl<R: std::io::Read> StateDumpExtractor<R> {
+            fn new(reader: R) -> Result<Self, Error> {
+                let reader = DumpReader::new(reader)
+                    .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+                Ok(Self {
+                    reader,
+                    chunk: vec![].into_iter(),
+                    pending: None,
+                })
+            }
+
+            /// State root, that dump was made for.
+            pub fn root(&self) -> H256 {
+                self.reader.root()
+            }
+
+            fn next_record(&mut self) -> Result<Option<DumpAccount>, Error> {
+                loop {
+                    if let Some(record) = self.chunk.next() {
+                        return Ok(Some(record));
+                    }
+                    match self.reader.next() {
+                        Some(chunk) => {
+                            self.chunk = chunk
+                                .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?
+                                .into_iter()
+                        }
+                        None => return Ok(None),
+                    }
+                }
+            }
+        }
+
+        impl<R: std::io::Read> EvmAccountDumpExtractor for StateDumpExtractor<R> {
+            type Key = H256;
+
+            fn encode_key(&self, key: Self::Key) -> H256 {
+                key
+            }
+
+            fn read_account(&mut self) -> Result<Option<AccountPair>, Error> {
+                // Account with big storage is split into several consecutive records.
+                while let Some(record) = self.next_record()? {
+                    match &mut self.pending {
+                        Some(pair) if pair.encoded_key == record.key => {
+                            pair.account.storage.extend(record.storage)
+                        }
+                        _ => {
+                            let account = MemoryAccount {
+                                nonce: record.nonce,
+                                balance: record.balance,
+                                storage: record.storage.into_iter().collect(),
+                                code: record.code.into(),
+                            };
+                            let pair = AccountPair::new(record.key, account);
+                            if let Some(previous) = self.pending.replace(pair) {
+                                return Ok(Some(previous));
+                            }
+                        }
+                    }
+                }
+                Ok(self.pending.take())
+            }
+        }
+
+        impl<R: std::io::Read> Iterator for StateDumpExtractor<R> {
+            type Item = Result<AccountPair, Error>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.read_account().transpose()
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use {
+                super::*,
+                evm_state::{
+                    storage::dump::{DumpWriter, DUMP_CHUNK_ENTRIES},
+                    Storage,
+                },
+            };
+
+            #[test]
+            fn genesis_from_state_dump() {
+                let big_storage: Vec<_> = (1..=DUMP_CHUNK_ENTRIES as u64 + 10)
+                    .map(|i| (H256::from_low_u64_be(i), H256::from_low_u64_be(i)))
+                    .collect();
+                let accounts = (1..=20u64).map(|i| DumpAccount {
+                    key: H256::from_low_u64_be(i),
+                    nonce: i.into(),
+                    balance: (i * 100).into(),
+                    code: vec![i as u8; 4].into(),
+                    storage: if i == 5 {
+                        big_storage.clone()
+                    } else {
+                        vec![(H256::repeat_byte(1), H256::from_low_u64_be(i))]
+                    },
+                });
+
+                let mut expected = Storage::create_temporary().unwrap();
+                let root = expected.set_initial(
+                    accounts.clone().map(|account| {
+                        let memory_account = MemoryAccount {
+                            nonce: account.nonce,
+                            balance: account.balance,
+                            storage: account.storage.into_iter().collect(),
+                            code: account.code.into(),
+                        };
+                        (account.key, memory_account)
+                    }),
+                    evm_state::empty_trie_hash(),
+                );
+
+                let mut dump = vec![];
+                let mut writer = DumpWriter::new(&mut dump, root).unwrap();
+                for account in accounts {
+                    writer.write_account(account).unwrap();
+                }
+                writer.finish().unwrap();
+
+                let extractor = StateDumpExtractor::from_bytes(&dump).unwrap();
+                assert_eq!(extractor.root(), root);
+                let pairs = extractor.collect::<Result<Vec<_>, _>>().unwrap();
+                assert_eq!(pairs.len(), 20);
+                assert_eq!(pairs[4].account.storage.len(), big_storage.len());
+
+                let mut storage = Storage::create_temporary().unwrap();
+                let pairs = pairs
+                    .into_iter()
+                    .map(|pair| (pair.encoded_key, pair.account));
+                assert_eq!(
+                    storage.set_initial(pairs, evm_state::empty_trie_hash()),
+                    root
+                );
+            }
+        }
+    }
 }
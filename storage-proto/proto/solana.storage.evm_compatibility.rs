@@ -48,6 +48,12 @@ pub struct Transaction {
     #[prost(bytes = "vec", tag = "1")]
     pub rlp_encoded_body: ::prost::alloc::vec::Vec<u8>,
 }
+/// EIP-2718 typed transaction, stored as envelope (`type || rlp(payload)`).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TypedTransaction {
+    #[prost(bytes = "vec", tag = "1")]
+    pub envelope: ::prost::alloc::vec::Vec<u8>,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UnsignedTransactionWithCaller {
     #[prost(bytes = "vec", tag = "1")]
@@ -61,7 +67,7 @@ pub struct UnsignedTransactionWithCaller {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TransactionInReceipt {
-    #[prost(oneof = "transaction_in_receipt::Transaction", tags = "1, 2")]
+    #[prost(oneof = "transaction_in_receipt::Transaction", tags = "1, 2, 3")]
     pub transaction: ::core::option::Option<transaction_in_receipt::Transaction>,
 }
 /// Nested message and enum types in `TransactionInReceipt`.
@@ -72,6 +78,8 @@ pub mod transaction_in_receipt {
         Unsigned(super::UnsignedTransactionWithCaller),
         #[prost(message, tag = "2")]
         Signed(super::Transaction),
+        #[prost(message, tag = "3")]
+        Typed(super::TypedTransaction),
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -163,6 +163,9 @@ impl From<evm_state::TransactionInReceipt> for generated_evm::TransactionInRecei
                 evm_state::TransactionInReceipt::Unsigned(unsigned) => {
                     generated_evm::transaction_in_receipt::Transaction::Unsigned(unsigned.into())
                 }
+                evm_state::TransactionInReceipt::Typed(tx) => {
+                    generated_evm::transaction_in_receipt::Transaction::Typed(tx.into())
+                }
             }),
         }
     }
@@ -182,6 +185,9 @@ impl TryFrom<generated_evm::TransactionInReceipt> for evm_state::TransactionInRe
                 generated_evm::transaction_in_receipt::Transaction::Signed(tx) => {
                     evm_state::TransactionInReceipt::Signed(tx.try_into()?)
                 }
+                generated_evm::transaction_in_receipt::Transaction::Typed(tx) => {
+                    evm_state::TransactionInReceipt::Typed(tx.try_into()?)
+                }
             },
         )
     }
@@ -203,6 +209,22 @@ impl TryFrom<generated_evm::Transaction> for evm_state::Transaction {
     }
 }
 
+impl From<evm_state::TypedTransaction> for generated_evm::TypedTransaction {
+    fn from(tx: evm_state::TypedTransaction) -> Self {
+        Self {
+            envelope: tx.encode_envelope(),
+        }
+    }
+}
+
+impl TryFrom<generated_evm::TypedTransaction> for evm_state::TypedTransaction {
+    type Error = &'static str;
+    fn try_from(tx: generated_evm::TypedTransaction) -> Result<Self, Self::Error> {
+        evm_state::TypedTransaction::decode_envelope(&tx.envelope)
+            .map_err(|_| "Failed to deserialize typed tx envelope")
+    }
+}
+
 impl From<evm_state::UnsignedTransactionWithCaller>
     for generated_evm::UnsignedTransactionWithCaller
 {
@@ -543,6 +565,32 @@ mod tests {
         });
         let tx_serialized: generated_evm::TransactionInReceipt = tx.clone().into();
         assert_eq!(tx, tx_serialized.try_into().unwrap());
+
+        let tx = evm_state::TransactionInReceipt::Typed(evm_state::TypedTransaction {
+            unsigned: evm_state::UnsignedTypedTransaction {
+                chain_id: 0xde2d,
+                nonce: 1.into(),
+                fee: evm_state::TypedTransactionFee::DynamicFee {
+                    max_priority_fee_per_gas: 2.into(),
+                    max_fee_per_gas: 6.into(),
+                },
+                gas_limit: 4.into(),
+                action: evm_state::TransactionAction::Create,
+                value: 23.into(),
+                input: b"123random bytes".to_vec(),
+                access_list: vec![evm_state::AccessListItem {
+                    address: evm_state::H160::random(),
+                    storage_keys: vec![evm_state::H256::random()],
+                }],
+            },
+            signature: evm_state::TypedTransactionSignature {
+                odd_y_parity: true,
+                r: evm_state::H256::random(),
+                s: evm_state::H256::random(),
+            },
+        });
+        let tx_serialized: generated_evm::TransactionInReceipt = tx.clone().into();
+        assert_eq!(tx, tx_serialized.try_into().unwrap());
     }
 
     #[test]
@@ -1,6 +1,9 @@
 #![allow(clippy::integer_arithmetic)]
-use evm_state::Storage;
+use evm_state::{MemoryAccount, Storage, H160, H256, U256};
 use solana_ledger::blockstore::EvmStateJson;
+use solana_sdk::genesis_config::evm_genesis::{
+    AccountPair, GethAccountExtractor, OpenEthereumAccountExtractor,
+};
 use {
     log::*,
     solana_cli_output::CliAccount,
@@ -20,6 +23,7 @@ use {
     },
     solana_ledger::{
         blockstore::create_new_ledger, blockstore_db::LedgerColumnOptions, create_new_tmp_ledger,
+        get_tmp_ledger_path,
     },
     solana_net_utils::PortRange,
     solana_rpc::{rpc::JsonRpcConfig, rpc_pubsub_service::PubSubConfig},
@@ -47,7 +51,7 @@ use {
     },
     solana_streamer::socket::SocketAddrSpace,
     std::{
-        collections::{HashMap, HashSet},
+        collections::{BTreeMap, HashMap, HashSet},
         fs::{remove_dir_all, File},
         io::Read,
         net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -118,6 +122,8 @@ pub struct TestValidatorGenesis {
     pub accounts_db_caching_enabled: bool,
     deactivate_feature_set: HashSet<Pubkey>,
     evm_state_archive_enabled: bool,
+    /// Evm accounts in genesis, keyed by hashed address.
+    evm_accounts: BTreeMap<H256, MemoryAccount>,
 }
 
 impl Default for TestValidatorGenesis {
@@ -146,6 +152,7 @@ impl Default for TestValidatorGenesis {
             accounts_db_caching_enabled: bool::default(),
             deactivate_feature_set: HashSet::<Pubkey>::default(),
             evm_state_archive_enabled: false,
+            evm_accounts: BTreeMap::<H256, MemoryAccount>::default(),
         }
     }
 }
@@ -369,6 +376,103 @@ impl TestValidatorGenesis {
         )
     }
 
+    /// Add an evm account to the test environment
+    pub fn add_evm_account(&mut self, address: H160, account: MemoryAccount) -> &mut Self {
+        self.add_evm_account_pair(AccountPair::from_address(address, account))
+    }
+
+    fn add_evm_account_pair(&mut self, pair: AccountPair) -> &mut Self {
+        self.evm_accounts.insert(pair.encoded_key, pair.account);
+        self
+    }
+
+    /// Add evm accounts from the state dump of geth (`geth dump`).
+    pub fn add_evm_accounts_from_geth_genesis(&mut self, path: &Path) -> &mut Self {
+        let extractor = GethAccountExtractor::open_dump(path).unwrap_or_else(|err| {
+            error!("Unable to open geth dump {}: {}", path.display(), err);
+            solana_core::validator::abort();
+        });
+        self.add_evm_account_pairs(extractor, path)
+    }
+
+    /// Add evm accounts from the state json of open-ethereum.
+    pub fn add_evm_accounts_from_open_ethereum_genesis(&mut self, path: &Path) -> &mut Self {
+        let extractor = OpenEthereumAccountExtractor::open_dump(path).unwrap_or_else(|err| {
+            error!(
+                "Unable to open open-ethereum dump {}: {}",
+                path.display(),
+                err
+            );
+            solana_core::validator::abort();
+        });
+        self.add_evm_account_pairs(extractor, path)
+    }
+
+    fn add_evm_account_pairs<T>(&mut self, pairs: T, path: &Path) -> &mut Self
+    where
+        T: IntoIterator<Item = std::io::Result<AccountPair>>,
+    {
+        for pair in pairs {
+            match pair {
+                Ok(pair) => self.add_evm_account_pair(pair),
+                Err(err) => {
+                    error!(
+                        "Unable to read evm account from {}: {}",
+                        path.display(),
+                        err
+                    );
+                    solana_core::validator::abort();
+                }
+            };
+        }
+        self
+    }
+
+    /// Copy balance, nonce and code of evm accounts from the cluster.
+    ///
+    /// Contract storage cannot be enumerated over RPC, so cloned contracts start with empty storage.
+    pub fn clone_evm_accounts<T>(
+        &mut self,
+        addresses: T,
+        rpc_client: &RpcClient,
+        skip_missing: bool,
+    ) -> &mut Self
+    where
+        T: IntoIterator<Item = H160>,
+    {
+        for address in addresses {
+            info!("Fetching evm account {:?} over RPC...", address);
+            let res = rpc_client.get_evm_balance(&address).and_then(|balance| {
+                let nonce = rpc_client.get_evm_transaction_count(&address)?;
+                let code = rpc_client.get_evm_code(&address)?;
+                Ok(MemoryAccount {
+                    nonce,
+                    balance,
+                    storage: BTreeMap::new(),
+                    code: code.0,
+                })
+            });
+            match res {
+                Ok(account)
+                    if account.balance.is_zero()
+                        && account.nonce.is_zero()
+                        && account.code.is_empty()
+                        && skip_missing =>
+                {
+                    warn!("Could not find evm account {:?}, skipping.", address);
+                }
+                Ok(account) => {
+                    self.add_evm_account(address, account);
+                }
+                Err(err) => {
+                    error!("Failed to fetch evm account {:?}: {}", address, err);
+                    solana_core::validator::abort();
+                }
+            }
+        }
+        self
+    }
+
     /// Add a BPF program to the test environment.
     ///
     /// `program_name` will also used to locate the BPF shared object in the current or fixtures
@@ -551,6 +655,30 @@ impl TestValidator {
             );
         }
 
+        let evm_accounts: Vec<_> = config
+            .evm_accounts
+            .iter()
+            .map(|(encoded_key, account)| AccountPair {
+                encoded_key: *encoded_key,
+                account: account.clone(),
+            })
+            .collect();
+        if !evm_accounts.is_empty() {
+            let evm_balance = evm_accounts
+                .iter()
+                .fold(U256::zero(), |sum, pair| sum + pair.account.balance);
+            // Round up, to never have evm balances, that are not backed by lamports.
+            let (mut evm_state_lamports, change) =
+                solana_evm_loader_program::scope::evm::gweis_to_lamports(evm_balance);
+            if !change.is_zero() {
+                evm_state_lamports += 1;
+            }
+            accounts.insert(
+                solana_sdk::evm_state::ID,
+                solana_evm_loader_program::create_state_account(evm_state_lamports),
+            );
+        }
+
         let mut genesis_config = create_genesis_config_with_leader_ex(
             mint_lamports,
             &mint_address,
@@ -572,6 +700,18 @@ impl TestValidator {
             genesis_config.ticks_per_slot = ticks_per_slot;
         }
 
+        if !evm_accounts.is_empty() {
+            let mut storage = Storage::create_temporary()
+                .map_err(|err| format!("Failed to create evm storage: {}", err))?;
+            let evm_root_hash = storage.set_initial(
+                evm_accounts
+                    .iter()
+                    .map(|pair| (pair.encoded_key, pair.account.clone())),
+                evm_state::empty_trie_hash(),
+            );
+            genesis_config.set_evm_root_hash(evm_root_hash);
+        }
+
         // Remove features tagged to deactivate
         for deactivate_feature_pk in &config.deactivate_feature_set {
             if FEATURE_NAMES.contains_key(deactivate_feature_pk) {
@@ -591,15 +731,23 @@ impl TestValidator {
         }
 
         let ledger_path = match &config.ledger_path {
-            None => create_new_tmp_ledger!(&genesis_config).0,
-            Some(ledger_path) => {
+            None if evm_accounts.is_empty() => create_new_tmp_ledger!(&genesis_config).0,
+            ledger_path => {
+                let ledger_path = &ledger_path
+                    .clone()
+                    .unwrap_or_else(|| get_tmp_ledger_path!());
                 if TestValidatorGenesis::ledger_exists(ledger_path) {
                     return Ok(ledger_path.to_path_buf());
                 }
 
+                let evm_state_json = if evm_accounts.is_empty() {
+                    EvmStateJson::None
+                } else {
+                    EvmStateJson::Accounts(&evm_accounts)
+                };
                 let _ = create_new_ledger(
                     ledger_path,
-                    EvmStateJson::None,
+                    evm_state_json,
                     &genesis_config,
                     config
                         .max_genesis_archive_unpacked_size
@@ -912,6 +1060,72 @@ mod test {
         rpc_client.get_health().await.expect("health");
     }
 
+    #[test]
+    fn preload_evm_accounts() {
+        let cloned_address = H160::repeat_byte(0x11);
+        let (cluster, _payer) = TestValidatorGenesis::default()
+            .add_evm_account(
+                cloned_address,
+                MemoryAccount {
+                    nonce: 3.into(),
+                    balance: 1_000_000_000_000u64.into(),
+                    storage: BTreeMap::new(),
+                    code: vec![0x60, 0x00],
+                },
+            )
+            .start();
+
+        let dump_address = H160::repeat_byte(0x22);
+        let dump_key =
+            AccountPair::from_address(dump_address, MemoryAccount::default()).encoded_key;
+        let dump_dir = tempfile::tempdir().unwrap();
+        let dump_path = dump_dir.path().join("geth_dump.json");
+        std::fs::write(
+            &dump_path,
+            format!(
+                "{{\"root\":\"{:?}\"}}\n\
+                 {{\"key\":\"{:?}\",\"balance\":\"5000000000\",\"nonce\":7,\"code\":\"0x6001\"}}\n",
+                H256::zero(),
+                dump_key
+            ),
+        )
+        .unwrap();
+
+        let (test_validator, _payer) = TestValidatorGenesis::default()
+            .add_evm_accounts_from_geth_genesis(&dump_path)
+            .clone_evm_accounts([cloned_address], &cluster.get_rpc_client(), false)
+            .start();
+        let rpc_client = test_validator.get_rpc_client();
+
+        assert_eq!(
+            rpc_client.get_evm_balance(&dump_address).unwrap(),
+            5_000_000_000u64.into()
+        );
+        assert_eq!(
+            rpc_client.get_evm_transaction_count(&dump_address).unwrap(),
+            7.into()
+        );
+        assert_eq!(
+            rpc_client.get_evm_code(&dump_address).unwrap().0,
+            vec![0x60, 0x01]
+        );
+
+        assert_eq!(
+            rpc_client.get_evm_balance(&cloned_address).unwrap(),
+            1_000_000_000_000u64.into()
+        );
+        assert_eq!(
+            rpc_client
+                .get_evm_transaction_count(&cloned_address)
+                .unwrap(),
+            3.into()
+        );
+        assert_eq!(
+            rpc_client.get_evm_code(&cloned_address).unwrap().0,
+            vec![0x60, 0x00]
+        );
+    }
+
     #[tokio::test]
     #[should_panic]
     async fn document_tokio_panic() {
@@ -98,6 +98,54 @@ pub fn parse_evm(
                 info,
             })
         }
+        EvmInstruction::ExecuteTransaction {
+            tx: ExecuteTransaction::SignedTyped { tx: Some(evm_tx) },
+            fee_type,
+        } => {
+            let transaction = RPCTransaction::from_transaction(evm_tx.into()).map_err(|_| {
+                ParseInstructionError::InstructionKeyMismatch(ParsableProgram::Evm)
+            })?;
+            let info = if instruction.accounts.len() >= 2 {
+                json!({
+                    "bridgeAccount":  account_keys[instruction.accounts[1] as usize].to_string(),
+                    "transaction": transaction,
+                    "feeType": fee_type,
+                })
+            } else {
+                json!({
+                    "transaction": transaction,
+                    "feeType": fee_type,
+                })
+            };
+
+            Ok(ParsedInstructionEnum {
+                instruction_type: "evmTypedTransaction".to_string(),
+                info,
+            })
+        }
+        EvmInstruction::ExecuteTransaction {
+            tx: ExecuteTransaction::SignedTyped { tx: None },
+            fee_type,
+        } => {
+            check_num_stake_accounts(&instruction.accounts, 2)?;
+            let info = if instruction.accounts.len() >= 3 {
+                json!({
+                    "storageAccount": account_keys[instruction.accounts[1] as usize].to_string(),
+                    "bridgeAccount":  account_keys[instruction.accounts[2] as usize].to_string(),
+                    "feeType": fee_type,
+                })
+            } else {
+                json!({
+                    "storageAccount": account_keys[instruction.accounts[1] as usize].to_string(),
+                    "feeType": fee_type,
+                })
+            };
+
+            Ok(ParsedInstructionEnum {
+                instruction_type: "evmBigTypedTransactionExecute".to_string(),
+                info,
+            })
+        }
         EvmInstruction::ExecuteTransaction {
             tx:
                 ExecuteTransaction::ProgramAuthorized {
@@ -174,6 +222,56 @@ pub fn parse_evm(
                 info,
             })
         }
+        EvmInstruction::ExecuteTransactionBatch {
+            txs: Some(evm_txs),
+            fee_type,
+        } => {
+            let transactions = evm_txs
+                .into_iter()
+                .map(|evm_tx| RPCTransaction::from_transaction(evm_tx.into()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ParseInstructionError::InstructionKeyMismatch(ParsableProgram::Evm))?;
+            let info = if instruction.accounts.len() >= 2 {
+                json!({
+                    "bridgeAccount":  account_keys[instruction.accounts[1] as usize].to_string(),
+                    "transactions": transactions,
+                    "feeType": fee_type,
+                })
+            } else {
+                json!({
+                    "transactions": transactions,
+                    "feeType": fee_type,
+                })
+            };
+
+            Ok(ParsedInstructionEnum {
+                instruction_type: "evmTransactionBatch".to_string(),
+                info,
+            })
+        }
+        EvmInstruction::ExecuteTransactionBatch {
+            txs: None,
+            fee_type,
+        } => {
+            check_num_stake_accounts(&instruction.accounts, 2)?;
+            let info = if instruction.accounts.len() >= 3 {
+                json!({
+                    "storageAccount": account_keys[instruction.accounts[1] as usize].to_string(),
+                    "bridgeAccount":  account_keys[instruction.accounts[2] as usize].to_string(),
+                    "feeType": fee_type,
+                })
+            } else {
+                json!({
+                    "storageAccount": account_keys[instruction.accounts[1] as usize].to_string(),
+                    "feeType": fee_type,
+                })
+            };
+
+            Ok(ParsedInstructionEnum {
+                instruction_type: "evmBigTransactionBatchExecute".to_string(),
+                info,
+            })
+        }
     }
 }
 
@@ -1,6 +1,7 @@
 use {
     clap::{crate_name, value_t, value_t_or_exit, values_t_or_exit, App, Arg},
     crossbeam_channel::unbounded,
+    evm_state::H160,
     log::*,
     solana_clap_utils::{
         input_parsers::{pubkey_of, pubkeys_of, value_of},
@@ -325,6 +326,42 @@ fn main() {
                      If the ledger already exists then this parameter is silently ignored",
                 ),
         )
+        .arg(
+            Arg::with_name("clone_evm_account")
+                .long("clone-evm")
+                .value_name("ADDRESS")
+                .takes_value(true)
+                .validator(is_parsable::<H160>)
+                .multiple(true)
+                .requires("json_rpc_url")
+                .help(
+                    "Copy balance, nonce and code of an evm account from the cluster \
+                     referenced by the --url argument. Storage of contracts is not copied. \
+                     If the ledger already exists then this parameter is silently ignored",
+                ),
+        )
+        .arg(
+            Arg::with_name("evm_geth_genesis")
+                .long("evm-geth-genesis")
+                .value_name("FILE")
+                .takes_value(true)
+                .multiple(true)
+                .help(
+                    "Load evm accounts from the state dump of geth. \
+                     If the ledger already exists then this parameter is silently ignored",
+                ),
+        )
+        .arg(
+            Arg::with_name("evm_open_ethereum_genesis")
+                .long("evm-open-ethereum-genesis")
+                .value_name("FILE")
+                .takes_value(true)
+                .multiple(true)
+                .help(
+                    "Load evm accounts from the state json of open-ethereum. \
+                     If the ledger already exists then this parameter is silently ignored",
+                ),
+        )
         .arg(
             Arg::with_name("warp_slot")
                 .required(false)
@@ -558,6 +595,22 @@ fn main() {
         .map(|v| v.into_iter().collect())
         .unwrap_or_default();
 
+    let evm_accounts_to_clone: Vec<H160> = if matches.is_present("clone_evm_account") {
+        values_t_or_exit!(matches, "clone_evm_account", H160)
+    } else {
+        vec![]
+    };
+
+    let evm_geth_genesis: Vec<PathBuf> = matches
+        .values_of("evm_geth_genesis")
+        .map(|values| values.map(PathBuf::from).collect())
+        .unwrap_or_default();
+
+    let evm_open_ethereum_genesis: Vec<PathBuf> = matches
+        .values_of("evm_open_ethereum_genesis")
+        .map(|values| values.map(PathBuf::from).collect())
+        .unwrap_or_default();
+
     let warp_slot = if matches.is_present("warp_slot") {
         Some(match matches.value_of("warp_slot") {
             Some(_) => value_t_or_exit!(matches, "warp_slot", Slot),
@@ -618,6 +671,9 @@ fn main() {
         for (name, long) in &[
             ("bpf_program", "--bpf-program"),
             ("clone_account", "--clone"),
+            ("clone_evm_account", "--clone-evm"),
+            ("evm_geth_genesis", "--evm-geth-genesis"),
+            ("evm_open_ethereum_genesis", "--evm-open-ethereum-genesis"),
             ("account", "--account"),
             ("mint_address", "--mint"),
             ("ticks_per_slot", "--ticks-per-slot"),
@@ -683,6 +739,7 @@ fn main() {
                 String
             ),
             timeout: None,
+            evm_block_gap_repair: None,
         })
     } else {
         None
@@ -732,6 +789,24 @@ fn main() {
         );
     }
 
+    if !evm_accounts_to_clone.is_empty() {
+        genesis.clone_evm_accounts(
+            evm_accounts_to_clone,
+            cluster_rpc_client
+                .as_ref()
+                .expect("bug: --url argument missing?"),
+            false,
+        );
+    }
+
+    for path in &evm_geth_genesis {
+        genesis.add_evm_accounts_from_geth_genesis(path);
+    }
+
+    for path in &evm_open_ethereum_genesis {
+        genesis.add_evm_accounts_from_open_ethereum_genesis(path);
+    }
+
     if let Some(warp_slot) = warp_slot {
         genesis.warp_slot(warp_slot);
     }
@@ -23,6 +23,7 @@ use {
         rpc_config::RpcLeaderScheduleConfig, rpc_request::MAX_MULTIPLE_ACCOUNTS,
     },
     solana_core::{
+        evm_services::DEFAULT_EVM_ARCHIVE_HOT_SLOTS,
         ledger_cleanup_service::{DEFAULT_MAX_LEDGER_SHREDS, DEFAULT_MIN_MAX_LEDGER_SHREDS},
         system_monitor_service::SystemMonitorService,
         tower_storage,
@@ -30,9 +31,12 @@ use {
         validator::{is_snapshot_config_valid, Validator, ValidatorConfig, ValidatorStartProgress},
     },
     solana_gossip::{cluster_info::Node, contact_info::ContactInfo},
-    solana_ledger::blockstore_db::{
-        BlockstoreRecoveryMode, BlockstoreRocksFifoOptions, LedgerColumnOptions, ShredStorageType,
-        DEFAULT_ROCKS_FIFO_SHRED_STORAGE_SIZE_BYTES,
+    solana_ledger::{
+        bigtable_upload::EvmBlockGapRepairConfig,
+        blockstore_db::{
+            BlockstoreRecoveryMode, BlockstoreRocksFifoOptions, LedgerColumnOptions,
+            ShredStorageType, DEFAULT_ROCKS_FIFO_SHRED_STORAGE_SIZE_BYTES,
+        },
     },
     solana_net_utils::VALIDATOR_PORT_RANGE,
     solana_perf::recycler::enable_recycler_warming,
@@ -429,6 +433,7 @@ pub fn main() {
         &format!("{}-{}", VALIDATOR_PORT_RANGE.0, VALIDATOR_PORT_RANGE.1);
     let default_genesis_archive_unpacked_size = &MAX_GENESIS_ARCHIVE_UNPACKED_SIZE.to_string();
     let default_rpc_max_multiple_accounts = &MAX_MULTIPLE_ACCOUNTS.to_string();
+    let default_evm_state_archive_hot_slots = &DEFAULT_EVM_ARCHIVE_HOT_SLOTS.to_string();
 
     let default_rpc_pubsub_max_active_subscriptions =
         PubSubConfig::default().max_active_subscriptions.to_string();
@@ -437,6 +442,14 @@ pub fn main() {
     let default_rpc_pubsub_queue_capacity_bytes =
         PubSubConfig::default().queue_capacity_bytes.to_string();
     let default_send_transaction_service_config = send_transaction_service::Config::default();
+    let default_evm_gap_repair_config = EvmBlockGapRepairConfig::default();
+    let default_bigtable_evm_gap_repair_interval = default_evm_gap_repair_config
+        .check_interval
+        .as_secs()
+        .to_string();
+    let default_bigtable_evm_gap_repair_max_blocks = default_evm_gap_repair_config
+        .max_blocks_to_check
+        .to_string();
     let default_rpc_send_transaction_retry_ms = default_send_transaction_service_config
         .retry_rate_ms
         .to_string();
@@ -538,6 +551,29 @@ pub fn main() {
                 .takes_value(true)
                 .help("Use DIR as evm-state archive location"),
         )
+        .arg(
+            Arg::with_name("evm_state_archive_cold_path")
+                .long("evm-state-archive-cold")
+                .value_name("DIR")
+                .takes_value(true)
+                .requires("evm_state_archive_path")
+                .help("Use DIR as cold tier of evm-state archive. \
+                       States older than hot window are moved there, \
+                       and are read from there on demand. \
+                       Tiering requires archive with gc enabled: \
+                       existing archive created without gc cannot be tiered, \
+                       a new one should be synced instead."),
+        )
+        .arg(
+            Arg::with_name("evm_state_archive_hot_slots")
+                .long("evm-state-archive-hot-slots")
+                .value_name("SLOTS")
+                .takes_value(true)
+                .requires("evm_state_archive_cold_path")
+                .validator(is_parsable::<u64>)
+                .default_value(default_evm_state_archive_hot_slots)
+                .help("Keep evm-state archive of last SLOTS slots in hot tier"),
+        )
         .arg(
             Arg::with_name("evm_state_rpc_port")
                 .long("evm-state-rpc-port")
@@ -580,6 +616,17 @@ pub fn main() {
                 .takes_value(true)
                 .help("Use DIR as evm-state location"),
         )
+        .arg(
+            Arg::with_name("evm_state_retention_slots")
+                .long("evm-state-retention-slots")
+                .value_name("SLOTS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .help("Keep evm state of rooted banks for last SLOTS slots, \
+                       and serve historical evm state requests from it. \
+                       Older states are pruned. History is not persisted, \
+                       so after restart it starts from the loaded snapshot."),
+        )
         .arg(
             Arg::with_name("jaeger_collector_url")
                 .long("jaeger-collector")
@@ -716,6 +763,42 @@ pub fn main() {
                 .takes_value(false)
                 .help("Upload new confirmed blocks into a BigTable instance"),
         )
+        .arg(
+            Arg::with_name("enable_bigtable_evm_gap_repair")
+                .long("enable-bigtable-evm-gap-repair")
+                .requires("enable_bigtable_ledger_upload")
+                .takes_value(false)
+                .help("Periodically check uploaded EVM blocks for gaps in the BigTable instance \
+                       and upload missing blocks again from the local ledger"),
+        )
+        .arg(
+            Arg::with_name("bigtable_evm_gap_repair_dry_run")
+                .long("bigtable-evm-gap-repair-dry-run")
+                .requires("enable_bigtable_evm_gap_repair")
+                .takes_value(false)
+                .help("Only report EVM blocks missing in the BigTable instance, \
+                       without uploading them"),
+        )
+        .arg(
+            Arg::with_name("bigtable_evm_gap_repair_interval")
+                .long("bigtable-evm-gap-repair-interval")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .requires("enable_bigtable_evm_gap_repair")
+                .default_value(&default_bigtable_evm_gap_repair_interval)
+                .help("Number of seconds between checks of uploaded EVM blocks for gaps"),
+        )
+        .arg(
+            Arg::with_name("bigtable_evm_gap_repair_max_blocks")
+                .long("bigtable-evm-gap-repair-max-blocks")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .requires("enable_bigtable_evm_gap_repair")
+                .default_value(&default_bigtable_evm_gap_repair_max_blocks)
+                .help("Maximum number of uploaded EVM blocks checked for gaps at once"),
+        )
         .arg(
             Arg::with_name("enable_cpi_and_log_storage")
                 .long("enable-cpi-and-log-storage")
@@ -913,6 +996,15 @@ pub fn main() {
                    incremental snapshot interval. To set the full snapshot \
                    interval, use --full-snapshot-interval-slots.")
          )
+        .arg(
+            Arg::with_name("incremental_snapshot_evm_state_delta")
+                .long("incremental-snapshot-evm-state-delta")
+                .takes_value(false)
+                .conflicts_with("no_incremental_snapshots")
+                .help("Store only delta of evm state in incremental snapshots. \
+                       Such snapshots are smaller, but cannot be loaded by nodes \
+                       that don't support evm state delta.")
+        )
         .arg(
             Arg::with_name("incremental_snapshot_interval_slots")
                 .long("incremental-snapshot-interval-slots")
@@ -1305,6 +1397,20 @@ pub fn main() {
                 .takes_value(true)
                 .help("Maximum number of seconds to process batched jsonrpc requests."),
         )
+        .arg(
+            Arg::with_name("rpc_trusted_proxy")
+                .long("rpc-trusted-proxy")
+                .value_name("IP_ADDRESS")
+                .validator(is_parsable::<IpAddr>)
+                .takes_value(true)
+                .multiple(true)
+                .help("Address of reverse proxy in front of JSON RPC. \
+                       May be specified multiple times. \
+                       Clients are identified by the last address in X-Forwarded-For header \
+                       that is not a trusted proxy, so RPC port must be reachable only \
+                       through trusted proxies. Without trusted proxies clients are unknown, \
+                       and only global limits are applied to them."),
+        )
         .arg(
             Arg::with_name("rpc_bigtable_instance_name")
                 .long("rpc-bigtable-instance-name")
@@ -2262,10 +2368,14 @@ pub fn main() {
     };
     let evm_state_archive = matches.value_of("evm_state_archive_path").map(|path| {
         info!("Opening evm archive storage");
-        evm_state::Storage::open_persistent(
-            path, false, // gc disabled
-        )
-        .expect("Cannot open evm archive folder")
+        match matches.value_of("evm_state_archive_cold_path") {
+            Some(cold_path) => evm_state::Storage::open_tiered_archive(path, cold_path)
+                .expect("Cannot open tiered evm archive folders"),
+            None => evm_state::Storage::open_persistent(
+                path, false, // gc disabled
+            )
+            .expect("Cannot open evm archive folder"),
+        }
     });
 
     let authorized_voter_keypairs = keypairs_of(&matches, "authorized_voter_keypairs")
@@ -2569,6 +2679,22 @@ pub fn main() {
             timeout: value_t!(matches, "rpc_bigtable_timeout", u64)
                 .ok()
                 .map(Duration::from_secs),
+            evm_block_gap_repair: matches
+                .is_present("enable_bigtable_evm_gap_repair")
+                .then(|| EvmBlockGapRepairConfig {
+                    dry_run: matches.is_present("bigtable_evm_gap_repair_dry_run"),
+                    check_interval: Duration::from_secs(value_t_or_exit!(
+                        matches,
+                        "bigtable_evm_gap_repair_interval",
+                        u64
+                    )),
+                    max_blocks_to_check: value_t_or_exit!(
+                        matches,
+                        "bigtable_evm_gap_repair_max_blocks",
+                        usize
+                    ),
+                    ..EvmBlockGapRepairConfig::default()
+                }),
         })
     } else {
         None
@@ -2638,6 +2764,8 @@ pub fn main() {
             max_batch_duration: value_t!(matches, "max_batch_duration", u64)
                 .ok()
                 .map(Duration::from_secs),
+            rpc_trusted_proxies: values_t!(matches, "rpc_trusted_proxy", IpAddr)
+                .unwrap_or_default(),
         },
         evm_state_rpc_config,
         accountsdb_repl_service_config,
@@ -2730,6 +2858,8 @@ pub fn main() {
         accounts_shrink_ratio,
         enable_quic_servers,
         verify_evm_state: !matches.is_present("no_verify_evm_state"),
+        evm_state_retention_slots: value_t!(matches, "evm_state_retention_slots", Slot).ok(),
+        evm_state_archive_hot_slots: value_t_or_exit!(matches, "evm_state_archive_hot_slots", Slot),
         jaeger_collector_url: value_of(&matches, "jaeger_collector_url"),
         ..ValidatorConfig::default()
     };
@@ -2875,6 +3005,8 @@ pub fn main() {
         accounts_hash_use_index: validator_config.accounts_db_use_index_hash_calculation,
         accounts_hash_debug_verify: validator_config.accounts_db_test_hash_calculation,
         packager_thread_niceness_adj: snapshot_packager_niceness_adj,
+        incremental_snapshot_evm_state_delta: matches
+            .is_present("incremental_snapshot_evm_state_delta"),
     });
 
     validator_config.accounts_hash_interval_slots =